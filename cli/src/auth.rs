@@ -5,7 +5,7 @@ use serde::Serialize;
 
 //---------------------------------------------------------------------------------------------------- Hash
 #[derive(Zeroize,ZeroizeOnDrop,PartialEq,Debug,Serialize)]
-// Contains `Basic <original input in base64>`
+// Contains `Basic <original input in base64>` or `Bearer <token>`
 pub struct Auth(String);
 
 impl Auth {
@@ -14,6 +14,11 @@ impl Auth {
 		Self(rpc::base64::encode_with_authorization_basic_header(input))
 	}
 
+	/// Use a `festivald` bearer token
+	pub fn bearer(token: String) -> Self {
+		Self(format!("Bearer {token}"))
+	}
+
 	pub fn as_str(&self) -> &str {
 		self.0.as_str()
 	}
@@ -44,7 +44,7 @@ pub struct Cli {
 	#[arg(short, long, verbatim_doc_comment, value_name = "URL")]
 	/// URL of the `festivald` to connect to
 	///
-	/// The protocol, IPv4 address, and port of the
+	/// The protocol, IP address, and port of the
 	/// `festivald` that `festival-cli` will connect
 	/// to by default.
 	///
@@ -52,7 +52,13 @@ pub struct Cli {
 	///   - http
 	///   - https
 	///
-	/// IP address must be IPv4.
+	/// IP address can be IPv4 or IPv6 (in brackets).
+	///
+	/// Without a port, the protocol's default is used (80 or 443),
+	/// or 18425 if there's no protocol either.
+	///
+	/// A path is kept, e.g. for `festivald` behind a reverse proxy:
+	/// `https://music.example/festival`
 	///
 	/// Default is: `http://127.0.0.1:18425`
 	festivald: Option<String>,
@@ -99,6 +105,25 @@ pub struct Cli {
 	/// ```
 	authorization: Option<String>,
 
+	#[arg(long, verbatim_doc_comment, value_name = "TOKEN or FILE")]
+	/// Bearer token sent to `festivald`
+	///
+	/// This is a token created by `festivald`'s
+	/// `token_new` method, see here for more info:
+	/// <https://docs.festival.pm/daemon/authorization/tokens.html>
+	///
+	/// It is sent instead of `--authorization`,
+	/// so only 1 of them can be set.
+	///
+	/// A `festivald` with `HTTPS` or `.onion` must be
+	/// used or `festival-cli` will refuse to start.
+	///
+	/// An empty string disables this feature.
+	///
+	/// Alternatively, you can input an absolute PATH
+	/// to a file containing the token.
+	token: Option<String>,
+
 	#[arg(short, long, verbatim_doc_comment)]
 	/// Route connections through a proxy
 	///
@@ -114,6 +139,27 @@ pub struct Cli {
 	/// For example: `festival-cli --proxy socks5://127.0.0.1:9050`
 	proxy: Option<String>,
 
+	#[arg(short, long, verbatim_doc_comment, value_name = "FILE or -")]
+	/// Send a batch of `JSON-RPC` requests from a file (or `stdin`)
+	///
+	/// The input must be a JSON array of `JSON-RPC 2.0` requests,
+	/// which `festivald` will execute in order, e.g:
+	/// ```
+	/// [
+	///   {"jsonrpc":"2.0","method":"clear","params":{"playback":false}},
+	///   {"jsonrpc":"2.0","method":"queue_add_key_album","params":{"key":0,"append":"back","clear":false,"play":true}},
+	///   {"jsonrpc":"2.0","id":0,"method":"volume","params":{"volume":50}}
+	/// ]
+	/// ```
+	///
+	/// Requests without an `id` are notifications, they are
+	/// executed but get no response. The responses for the
+	/// rest are printed as a JSON array, in the same order.
+	///
+	/// Use `-` to read from `stdin`, e.g:
+	/// `cat batch.json | festival-cli --batch -`
+	batch: Option<String>,
+
 	#[arg(short, long, verbatim_doc_comment)]
 	/// Print debug information about the config/request/response
 	debug: bool,
@@ -170,11 +216,11 @@ pub struct Cli {
 
 //---------------------------------------------------------------------------------------------------- Regular CLI argument handling
 impl Cli {
-	pub fn get() -> (Option<ConfigBuilder>, Option<Rpc>, bool, bool) {
+	pub fn get() -> (Option<ConfigBuilder>, Option<Rpc>, Option<String>, bool, bool) {
 		Self::parse().handle_args()
 	}
 
-	fn handle_args(mut self) -> (Option<ConfigBuilder>, Option<Rpc>, bool, bool) {
+	fn handle_args(mut self) -> (Option<ConfigBuilder>, Option<Rpc>, Option<String>, bool, bool) {
 		// Version.
 		if self.version {
 			eprintln!("{FESTIVAL_CLI_SHUKUSAI_COMMIT}\n{COPYRIGHT}");
@@ -256,7 +302,7 @@ impl Cli {
 		let config = self.handle_config();
 
 		// Return.
-		(config, self.rpc, self.debug || self.dry_run, self.dry_run)
+		(config, self.rpc, self.batch, self.debug || self.dry_run, self.dry_run)
 	}
 
 	pub fn handle_config(&mut self) -> Option<ConfigBuilder> {
@@ -299,6 +345,7 @@ impl Cli {
 			self.id             => cb.id,
 			confirm_no_tls_auth => cb.confirm_no_tls_auth,
 			self.authorization  => cb.authorization,
+			self.token          => cb.token,
 			self.proxy          => cb.proxy
 		}
 
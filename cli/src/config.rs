@@ -13,6 +13,7 @@ use crate::constants::{
 };
 use std::{
 	net::{
+		IpAddr,
 		Ipv4Addr,
 		SocketAddrV4,
 	},
@@ -55,6 +56,8 @@ pub struct ConfigBuilder {
 	pub proxy:               Option<String>,
 	pub id:                  Option<String>,
 	pub authorization:	     Option<String>,
+	#[serde(default = "token_default")]
+	pub token:               Option<String>,
 	pub confirm_no_tls_auth: Option<bool>,
 }
 
@@ -66,6 +69,7 @@ impl Default for ConfigBuilder {
 			proxy:               Some("".to_string()),
 			id:                  Some(DEFAULT_ID.into()),
 			authorization:       Some("".to_string()),
+			token:               token_default(),
 			confirm_no_tls_auth: Some(false),
 		}
 	}
@@ -79,6 +83,7 @@ impl ConfigBuilder {
 			proxy,
 			id,
 			authorization,
+			token,
 			confirm_no_tls_auth,
 		} = self;
 
@@ -152,15 +157,17 @@ impl ConfigBuilder {
 			Err(_)  => crate::exit!("invalid [festivald] URL: {}", c.festivald),
 		};
 		let (festivald, ip, protocol, onion) = {
-			let protocol = match uri.scheme_str() {
-				Some("http")  => "http",
-				Some("https") => "https",
+			let (protocol, default_port) = match uri.scheme_str() {
+				Some("http")  => ("http", 80),
+				Some("https") => ("https", 443),
 				Some(x) => crate::exit!("invalid [festivald] URL protocol: {x}, must be HTTP or HTTPS"),
 				None => {
 					debug_print!("missing [festivald] URL protocol, defaulting to [http]");
-					"http"
+					("http", FESTIVAL_CLI_PORT)
 				},
 			};
+			// IPv6 hosts keep their brackets (`[::1]`)
+			// so they can be re-used as-is in the URL below.
 			let (ip, onion) = match uri.host() {
 				Some(ip) => (ip, ip.ends_with(".onion")),
 				None => {
@@ -169,10 +176,13 @@ impl ConfigBuilder {
 				},
 			};
 			let port = uri.port_u16().unwrap_or_else(|| {
-				debug_print!("missing [festivald] URL Port, defaulting to [{FESTIVAL_CLI_PORT}]");
-				FESTIVAL_CLI_PORT
+				debug_print!("missing [festivald] URL Port, defaulting to [{default_port}]");
+				default_port
 			});
-			(format!("{protocol}://{ip}:{port}"), ip, protocol, onion)
+			// Keep the path, `festivald` could be
+			// behind a reverse proxy's `base_path`.
+			let path = uri.path().trim_end_matches('/');
+			(format!("{protocol}://{ip}:{port}{path}"), ip, protocol, onion)
 		};
 
 		// FIXME TODO: testing.
@@ -210,7 +220,43 @@ impl ConfigBuilder {
 			}
 		}
 
-		if onion || ip == "localhost" || ip == "127.0.0.1" {
+		// TOKEN
+		if let Some(s) = token {
+			// Check if it's a PATH or a String.
+			let path = PathBuf::from(&s);
+			let s = if path.is_absolute() && path.exists() {
+				debug_print!("reading file [{}] for [token]", path.display());
+
+				match std::fs::read_to_string(path) {
+					Ok(s) => {
+						match s.lines().next() {
+							Some(s) => s.trim().to_string(),
+							None    => crate::exit!("[token] PATH file is empty"),
+						}
+					},
+					Err(e) => crate::exit!("[token] PATH read error: {e}"),
+				}
+			} else {
+				debug_print!("assuming [token] is a string, not a PATH");
+				s
+			};
+
+			// Skip empty token.
+			if s.is_empty() {
+			// Only 1 `authorization` header can be sent.
+			} else if c.authorization.is_some() {
+				crate::exit!("[authorization] and [token] are both set, only 1 can be used");
+			} else {
+				c.authorization = Some(crate::auth::Auth::bearer(s));
+			}
+		}
+
+		// Strip IPv6 brackets, then check for any loopback (`127.0.0.0/8`, `::1`).
+		let loopback = ip.trim_start_matches('[').trim_end_matches(']')
+			.parse::<IpAddr>()
+			.is_ok_and(|ip| ip.is_loopback());
+
+		if onion || loopback || ip == "localhost" {
 			debug_print!("local/onion address detected, enabling [confirm_no_tls_auth]");
 			c.confirm_no_tls_auth = true;
 		}
@@ -262,11 +308,17 @@ impl ConfigBuilder {
 			cmd.id                  => self.id,
 			cmd.confirm_no_tls_auth => self.confirm_no_tls_auth,
 			cmd.authorization       => self.authorization,
+			cmd.token               => self.token,
 			cmd.proxy               => self.proxy
 		}
 	}
 }
 
+//---------------------------------------------------------------------------------------------------- Serde defaults
+fn token_default() -> Option<String> {
+	Some("".to_string())
+}
+
 //---------------------------------------------------------------------------------------------------- Config
 /// The actual `struct` we will use for the whole program.
 ///
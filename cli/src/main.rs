@@ -8,7 +8,7 @@ mod rpc;
 
 fn main() {
 	// Handle regular CLI arguments (exit if needed).
-	let (config_cmd, rpc, debug, dry_run) = crate::cli::Cli::get();
+	let (config_cmd, rpc, batch, debug, dry_run) = crate::cli::Cli::get();
 
 	// Read config: `festival-cli.toml`.
 	let mut config_builder = crate::config::ConfigBuilder::file_or();
@@ -21,6 +21,14 @@ fn main() {
 	// Build config.
 	let config = config_builder.build(debug);
 
+	// Send a batch of requests, print response.
+	if let Some(batch) = batch {
+		if rpc.is_some() {
+			crate::exit!("--batch cannot be used with a method");
+		}
+		crate::rpc::batch(config, debug, dry_run, &batch);
+	}
+
 	let Some(rpc) = rpc else {
 		crate::exit!("missing method");
 	};
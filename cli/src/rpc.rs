@@ -5,9 +5,8 @@ use zeroize::Zeroize;
 use crate::constants::FESTIVAL_CLI_USER_AGENT;
 
 //---------------------------------------------------------------------------------------------------- Request
-// `exit` is used to prevent destructors from running.
-// We are exiting the program anyway so they don't need to run.
-pub fn request(config: Config, debug: bool, dry_run: bool, rpc: Rpc) -> ! {
+// Create the `POST` request to `festivald`, with the config applied.
+fn post(config: &Config, debug: bool) -> ureq::Request {
 	if debug {
 		eprintln!("\n=================================================> Config\n{}\n", serde_json::to_string_pretty(&config).unwrap());
 	}
@@ -23,18 +22,24 @@ pub fn request(config: Config, debug: bool, dry_run: bool, rpc: Rpc) -> ! {
 	};
 
 	// Add proxy.
-	let agent = match config.proxy {
+	let agent = match &config.proxy {
 		None    => agent,
-		Some(p) => agent.proxy(p.proxy),
+		Some(p) => agent.proxy(p.proxy.clone()),
 	};
 
 	let req = agent.build().post(&config.festivald);
 
 	// Add authorization.
-	let req = match config.authorization {
+	match &config.authorization {
 		None    => req,
 		Some(a) => req.set("authorization", a.as_str()),
-	};
+	}
+}
+
+// `exit` is used to prevent destructors from running.
+// We are exiting the program anyway so they don't need to run.
+pub fn request(config: Config, debug: bool, dry_run: bool, rpc: Rpc) -> ! {
+	let req = post(&config, debug);
 
 	macro_rules! req_resp {
 		($rpc:expr, $debug:expr, $expected_response:ty) => {{
@@ -114,15 +119,18 @@ pub fn request(config: Config, debug: bool, dry_run: bool, rpc: Rpc) -> ! {
 		CollectionHealth(x)       => req_resp!(x, debug, rpc::resp::CollectionHealth),
 		CollectionResourceSize(x) => req_resp!(x, debug, rpc::resp::CollectionResourceSize),
 
+		DaemonBroadcast(x)   => req_resp!(x, debug, rpc::resp::DaemonBroadcast),
 		DaemonConfig(x)      => req_resp!(x, debug, rpc::resp::DaemonConfig),
 		DaemonMethods(x)     => req_resp!(x, debug, rpc::resp::DaemonMethods),
 		DaemonNoAuthRpc(x)   => req_resp!(x, debug, rpc::resp::DaemonNoAuthRpc),
 		DaemonNoAuthRest(x)  => req_resp!(x, debug, rpc::resp::DaemonNoAuthRest),
+		DaemonPermissions(x) => req_resp!(x, debug, rpc::resp::DaemonPermissions),
 		DaemonRemoveCache(x) => req_resp!(x, debug, rpc::resp::DaemonRemoveCache),
 		DaemonSave(x)        => req_resp!(x, debug, rpc::resp::Status),
 		DaemonSeenIps(x)     => req_resp!(x, debug, rpc::resp::DaemonSeenIps),
 		DaemonShutdown(x)    => req_resp!(x, debug, rpc::resp::DaemonShutdown),
 		DaemonState(x)       => req_resp!(x, debug, rpc::resp::DaemonState),
+		DaemonUser(x)        => req_resp!(x, debug, rpc::resp::DaemonUser),
 
 		StateAudio(x)      => req_resp!(x, debug, rpc::resp::StateAudio),
 		StateQueueKey(x)   => req_resp!(x, debug, rpc::resp::StateQueueKey),
@@ -159,6 +167,13 @@ pub fn request(config: Config, debug: bool, dry_run: bool, rpc: Rpc) -> ! {
 		MapAlbumSongs(x)    => req_resp!(x, debug, rpc::resp::MapAlbumSongs),
 		MapAlbumEntries(x)  => req_resp!(x, debug, rpc::resp::MapAlbumEntries),
 
+		GenreList(x)   => req_resp!(x, debug, rpc::resp::GenreList),
+		GenreAlbums(x) => req_resp!(x, debug, rpc::resp::GenreAlbums),
+
+		StatsSong(x)           => req_resp!(x, debug, rpc::resp::StatsSong),
+		StatsMostPlayed(x)     => req_resp!(x, debug, rpc::resp::StatsMostPlayed),
+		StatsRecentlyPlayed(x) => req_resp!(x, debug, rpc::resp::StatsRecentlyPlayed),
+
 		CurrentArtist(x) => req_resp!(x, debug, rpc::resp::CurrentArtist),
 		CurrentAlbum(x)  => req_resp!(x, debug, rpc::resp::CurrentAlbum),
 		CurrentSong(x)   => req_resp!(x, debug, rpc::resp::CurrentSong),
@@ -190,6 +205,7 @@ pub fn request(config: Config, debug: bool, dry_run: bool, rpc: Rpc) -> ! {
 		Volume(x)      => req_resp!(x, debug, rpc::resp::Volume),
 		VolumeUp(x)    => req_resp!(x, debug, rpc::resp::VolumeUp),
 		VolumeDown(x)  => req_resp!(x, debug, rpc::resp::VolumeDown),
+		Crossfade(x)   => req_resp!(x, debug, rpc::resp::Status),
 
 		QueueAddKeyArtist(x)  => req_resp!(x, debug, rpc::resp::Status),
 		QueueAddKeyAlbum(x)   => req_resp!(x, debug, rpc::resp::Status),
@@ -202,6 +218,7 @@ pub fn request(config: Config, debug: bool, dry_run: bool, rpc: Rpc) -> ! {
 		QueueAddRandSong(x)   => req_resp!(x, debug, rpc::resp::QueueAddRandSong),
 		QueueAddRandEntry(x)  => req_resp!(x, debug, rpc::resp::QueueAddRandEntry),
 		QueueAddPlaylist(x)   => req_resp!(x, debug, rpc::resp::Status),
+		QueueAddGenre(x)      => req_resp!(x, debug, rpc::resp::Status),
 		QueueSetIndex(x)      => req_resp!(x, debug, rpc::resp::QueueSetIndex),
 		QueueRemoveRange(x)   => req_resp!(x, debug, rpc::resp::QueueRemoveRange),
 
@@ -216,10 +233,85 @@ pub fn request(config: Config, debug: bool, dry_run: bool, rpc: Rpc) -> ! {
 		PlaylistAddMapArtist(x) => req_resp!(x, debug, rpc::resp::PlaylistAddMapArtist),
 		PlaylistAddMapAlbum(x)  => req_resp!(x, debug, rpc::resp::PlaylistAddMapAlbum),
 		PlaylistAddMapSong(x)   => req_resp!(x, debug, rpc::resp::PlaylistAddMapSong),
+		PlaylistAddGenre(x)     => req_resp!(x, debug, rpc::resp::PlaylistAddGenre),
 		PlaylistSingle(x)       => req_resp!(x, debug, rpc::resp::PlaylistSingle),
 		PlaylistBrief(x)        => req_resp!(x, debug, rpc::resp::PlaylistBrief),
 		PlaylistFull(x)         => req_resp!(x, debug, rpc::resp::PlaylistFull),
+		PlaylistSmartNew(x)     => req_resp!(x, debug, rpc::resp::PlaylistSmartNew),
+		PlaylistSmartList(x)    => req_resp!(x, debug, rpc::resp::PlaylistSmartList),
+		PlaylistImport(x)       => req_resp!(x, debug, rpc::resp::PlaylistImport),
+		PlaylistExport(x)       => req_resp!(x, debug, rpc::resp::PlaylistExport),
+		TokenNew(x)             => req_resp!(x, debug, rpc::resp::TokenNew),
+		TokenList(x)            => req_resp!(x, debug, rpc::resp::TokenList),
+		TokenRevoke(x)          => req_resp!(x, debug, rpc::resp::TokenRevoke),
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Batch
+// Send a `JSON-RPC` batch (an array of requests) read from
+// `path` (or `stdin` if `-`), and print the response as-is.
+//
+// Unlike `request()`, the responses aren't type-checked,
+// as the batch can contain any mix of methods.
+pub fn batch(config: Config, debug: bool, dry_run: bool, path: &str) -> ! {
+	let req = post(&config, debug);
+
+	// Read the batch.
+	let string = if path == "-" {
+		let mut s = String::new();
+		if let Err(e) = std::io::Read::read_to_string(&mut std::io::stdin(), &mut s) {
+			crate::exit!("stdin: {e}");
+		}
+		s
+	} else {
+		match std::fs::read_to_string(path) {
+			Ok(s)  => s,
+			Err(e) => crate::exit!("{path}: {e}"),
+		}
+	};
+
+	// Make sure it's a non-empty array of objects,
+	// so mistakes are caught before being sent.
+	let batch = match serde_json::from_str::<Vec<serde_json::Map<String, serde_json::Value>>>(&string) {
+		Ok(b) if b.is_empty() => crate::exit!("batch is empty"),
+		Ok(b)  => b,
+		Err(e) => crate::exit!("batch must be a JSON array of requests: {e}"),
+	};
+
+	if debug {
+		eprintln!("=================================================> Batch ({} requests)\n{}\n", batch.len(), serde_json::to_string_pretty(&batch).unwrap());
+	}
+
+	if dry_run {
+		eprintln!("=================================================> Aborting due to dry run");
+		std::process::exit(0);
 	}
+
+	// Send request.
+	let resp = match req.send_json(batch) {
+		Ok(s)  => s,
+		Err(e) => crate::exit!("{e}"),
+	};
+
+	if debug {
+		eprintln!("=================================================> Response Info\n{{");
+		eprintln!(r#"  "status": "{}","#, resp.status());
+		eprintln!(r#"  "status-text": "{}","#, resp.status_text());
+		eprintln!(r#"  "url": "{}""#, resp.get_url());
+		eprintln!("}}\n");
+	}
+
+	// Only notifications were sent.
+	if resp.status() == 204 {
+		std::process::exit(0);
+	}
+
+	match resp.into_string() {
+		Ok(s)  => println!("{s}"),
+		Err(e) => crate::exit!("{e}"),
+	}
+
+	std::process::exit(0);
 }
 
 //---------------------------------------------------------------------------------------------------- TESTS
@@ -314,7 +406,7 @@ mod tests {
 		// Load the fake `Collection`.
 		use disk::Bincode2;
 		Collection::mkdir().unwrap();
-		std::fs::copy("../assets/shukusai/state/collection3_real.bin", Collection::absolute_path().unwrap()).unwrap();
+		std::fs::copy("../assets/shukusai/state/collection9_real.bin", Collection::absolute_path().unwrap()).unwrap();
 
 		// Spawn `festivald`.
 		std::process::Command::new(&festivald)
@@ -505,7 +597,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -516,7 +614,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -527,7 +631,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "flac",
@@ -538,7 +648,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "m4a",
@@ -549,7 +665,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_2"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "song_6",
@@ -560,7 +682,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_2"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -571,7 +699,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_3"
+        ],
+        "composers": [],
+        "featured": []
       }
     ],
     "sort_artist_lexi": [
@@ -864,6 +998,30 @@ r#"{
       1,
       0
     ]
+,
+    "sort_artist_genre": [
+      0,
+      1,
+      2
+    ],
+    "sort_artist_genre_rev": [
+      0,
+      1,
+      2
+    ],
+    "sort_album_genre": [
+      0,
+      1,
+      2,
+      3
+    ],
+    "sort_album_genre_rev": [
+      0,
+      1,
+      2,
+      3
+    ],
+    "genres": []
   },
   "id": 0
 }"#,
@@ -1055,7 +1213,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -1066,7 +1230,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -1077,7 +1247,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "flac",
@@ -1088,7 +1264,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "m4a",
@@ -1099,7 +1281,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_2"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "song_6",
@@ -1110,7 +1298,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_2"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -1121,7 +1315,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_3"
+        ],
+        "composers": [],
+        "featured": []
       }
     ]
   },
@@ -1146,19 +1346,44 @@ r#"{
 //			"",
 //			"",
 
+			DaemonBroadcast => rpc::resp::DaemonBroadcast,
+			"",
+r#"{
+  "jsonrpc": "2.0",
+  "result": {
+    "enabled": false,
+    "format": "opus",
+    "bitrate": 192,
+    "listeners": 0,
+    "total_listeners": 0
+  },
+  "id": 0
+}"#,
+
 			DaemonConfig => rpc::resp::DaemonConfig,
 			"",
 r#"{
   "jsonrpc": "2.0",
   "result": {
-    "ip": "127.0.0.1",
+    "ip": [
+      "127.0.0.1"
+    ],
     "port": 18425,
     "max_connections": null,
     "exclusive_ips": [
       "127.0.0.1"
     ],
+    "trusted_proxies": null,
+    "trusted_proxy_header": "x-forwarded-for",
+    "base_path": null,
     "sleep_on_fail": 3000,
+    "rate_limit_cheap": null,
+    "rate_limit_expensive": null,
+    "lockout_attempts": null,
+    "lockout_time": 300,
     "collection_paths": [],
+    "collection_watch": false,
+    "collection_path_templates": [],
     "tls": false,
     "certificate": null,
     "key": null,
@@ -1170,8 +1395,17 @@ r#"{
     "watch": false,
     "cache_clean": true,
     "cache_time": 3600,
+    "transcode_cache_size": 1000,
     "restore_audio_state": false,
     "previous_threshold": 3,
+    "crossfade": 0,
+    "crossfade_curve": "equal_power",
+    "replay_gain": "off",
+    "broadcast": false,
+    "broadcast_format": "opus",
+    "broadcast_bitrate": 192,
+    "output": "pulseaudio",
+    "output_wav_path": null,
     "media_controls": false,
     "authorization": false,
     "confirm_no_tls_auth": false,
@@ -1187,7 +1421,7 @@ r#"{
 r#"{
   "jsonrpc": "2.0",
   "result": {
-    "len": 109,
+    "len": 127,
     "methods": [
       "collection_new",
       "collection_brief",
@@ -1202,15 +1436,18 @@ r#"{
       "collection_perf",
       "collection_health",
       "collection_resource_size",
+      "daemon_broadcast",
       "daemon_config",
       "daemon_methods",
       "daemon_no_auth_rpc",
       "daemon_no_auth_rest",
+      "daemon_permissions",
       "daemon_remove_cache",
       "daemon_save",
       "daemon_seen_ips",
       "daemon_shutdown",
       "daemon_state",
+      "daemon_user",
       "state_audio",
       "state_queue_key",
       "state_queue_song",
@@ -1243,6 +1480,11 @@ r#"{
       "map_artist_entries",
       "map_album_songs",
       "map_album_entries",
+      "genre_list",
+      "genre_albums",
+      "stats_song",
+      "stats_most_played",
+      "stats_recently_played",
       "current_artist",
       "current_album",
       "current_song",
@@ -1271,6 +1513,7 @@ r#"{
       "volume",
       "volume_up",
       "volume_down",
+      "crossfade",
       "queue_add_key_artist",
       "queue_add_key_album",
       "queue_add_key_song",
@@ -1282,6 +1525,7 @@ r#"{
       "queue_add_rand_song",
       "queue_add_rand_entry",
       "queue_add_playlist",
+      "queue_add_genre",
       "queue_set_index",
       "queue_remove_range",
       "playlist_new",
@@ -1295,9 +1539,17 @@ r#"{
       "playlist_add_map_artist",
       "playlist_add_map_album",
       "playlist_add_map_song",
+      "playlist_add_genre",
       "playlist_single",
       "playlist_brief",
-      "playlist_full"
+      "playlist_full",
+      "playlist_smart_new",
+      "playlist_smart_list",
+      "playlist_import",
+      "playlist_export",
+      "token_new",
+      "token_list",
+      "token_revoke"
     ]
   },
   "id": 0
@@ -1325,6 +1577,10 @@ r#"{
   "id": 0
 }"#,
 
+			DaemonPermissions => rpc::resp::DaemonPermissions,
+			"",
+			"",
+
 			DaemonRemoveCache => rpc::resp::DaemonRemoveCache,
 			"",
 r#"{
@@ -1349,6 +1605,19 @@ r#"{
 			"",
 			"", // Contains variable data, not reliable on CI.
 
+			DaemonUser => rpc::resp::DaemonUser,
+			"",
+r#"{
+  "jsonrpc": "2.0",
+  "result": {
+    "authenticated": false,
+    "user": null,
+    "role": null,
+    "token": null
+  },
+  "id": 0
+}"#,
+
 			StateAudio => rpc::resp::StateAudio,
 			"",
 r#"{
@@ -1506,7 +1775,13 @@ r#"{
       "track": 1,
       "disc": null,
       "mime": "",
-      "extension": ""
+      "extension": "",
+      "track_artist": null,
+      "artists": [
+        "artist_1"
+      ],
+      "composers": [],
+      "featured": []
     }
   },
   "id": 0
@@ -1574,7 +1849,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -1585,7 +1866,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -1596,7 +1883,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "flac",
@@ -1607,7 +1900,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       }
     ]
   },
@@ -1658,7 +1957,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -1669,7 +1974,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       }
     ]
   },
@@ -1786,7 +2097,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -1797,7 +2114,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       }
     ]
   },
@@ -1870,7 +2193,13 @@ r#"{
       "track": 2,
       "disc": null,
       "mime": "",
-      "extension": ""
+      "extension": "",
+      "track_artist": null,
+      "artists": [
+        "artist_1"
+      ],
+      "composers": [],
+      "featured": []
     }
   },
   "id": 0
@@ -1938,7 +2267,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -1949,7 +2284,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -1960,7 +2301,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "flac",
@@ -1971,7 +2318,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       }
     ]
   },
@@ -1998,7 +2351,13 @@ r#"{
         "track": 1,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       },
       {
         "title": "mp3",
@@ -2009,7 +2368,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       }
     ]
   },
@@ -2020,6 +2385,59 @@ r#"{
 			ureq::json!({"artist":"artist_1","album":"album_1"}),
 			r#""#, // Skip
 
+			GenreList => rpc::resp::GenreList,
+			"",
+r#"{
+  "jsonrpc": "2.0",
+  "result": {
+    "len": 0,
+    "genres": []
+  },
+  "id": 0
+}"#,
+
+			// Skipped, no genres in the test `Collection`.
+//			GenreAlbums => rpc::resp::GenreAlbums,
+//			ureq::json!({"genre":"rock"}),
+//			"",
+
+			// Nothing has been played yet.
+			StatsSong => rpc::resp::StatsSong,
+			ureq::json!({"key":0}),
+r#"{
+  "jsonrpc": "2.0",
+  "result": {
+    "play_count": 0,
+    "skip_count": 0,
+    "first_played": 0,
+    "last_played": 0,
+    "listened": 0
+  },
+  "id": 0
+}"#,
+
+			StatsMostPlayed => rpc::resp::StatsMostPlayed,
+			ureq::json!({"len":null}),
+r#"{
+  "jsonrpc": "2.0",
+  "result": {
+    "len": 0,
+    "songs": []
+  },
+  "id": 0
+}"#,
+
+			StatsRecentlyPlayed => rpc::resp::StatsRecentlyPlayed,
+			ureq::json!({"len":10}),
+r#"{
+  "jsonrpc": "2.0",
+  "result": {
+    "len": 0,
+    "songs": []
+  },
+  "id": 0
+}"#,
+
 			// Skipped, nothing is set so these
 			// will all be an `error`.
 			//
@@ -2093,7 +2511,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       }
     ]
   },
@@ -2167,7 +2591,13 @@ r#"{
         "track": 2,
         "disc": null,
         "mime": "",
-        "extension": ""
+        "extension": "",
+        "track_artist": null,
+        "artists": [
+          "artist_1"
+        ],
+        "composers": [],
+        "featured": []
       }
     ]
   },
@@ -2302,6 +2732,14 @@ r#"{
 			ureq::json!({"down":5}),
 			"",
 
+			Crossfade => rpc::resp::Status,
+			ureq::json!({"seconds":5,"curve":"equal_power"}),
+r#"{
+  "jsonrpc": "2.0",
+  "result": null,
+  "id": 0
+}"#,
+
 			QueueAddKeyArtist => rpc::resp::Status,
 			ureq::json!({"key":0,"append":"back","clear":false,"play":false}),
 r#"{
@@ -2369,6 +2807,11 @@ r#"{
 			// Skipped, no playlists object.
 //			QueueAddPlaylist => rpc::resp::Status,
 //			ureq::json!({"append":"back","clear":false,"play":false}),
+//			"",
+
+			// Skipped, no genres in the test `Collection`.
+//			QueueAddGenre => rpc::resp::Status,
+//			ureq::json!({"genre":"rock","append":"back","clear":false,"play":false}),
 //			"",
 
 			QueueSetIndex => rpc::resp::QueueSetIndex,
@@ -2490,6 +2933,11 @@ r#"{
   "id": 0
 }"#,
 
+			// Skipped, no genres in the test `Collection`.
+//			PlaylistAddGenre => rpc::resp::PlaylistAddGenre,
+//			ureq::json!({"genre":"rock","playlist":"hello","append":"back"}),
+//			"",
+
 			PlaylistSingle => rpc::resp::PlaylistSingle,
 			ureq::json!({"playlist":"hello"}),
 r#"{
@@ -2855,6 +3303,69 @@ r#"{
   "id": 0
 }"#,
 
+			// No genres in the test `Collection`, so this is empty.
+			PlaylistSmartNew => rpc::resp::PlaylistSmartNew,
+			ureq::json!({"playlist":"smart","rules":[{"genre":"rock"}],"limit":null,"sort":null}),
+r#"{
+  "jsonrpc": "2.0",
+  "result": {
+    "existed": false,
+    "len": 0
+  },
+  "id": 0
+}"#,
+
+			PlaylistSmartList => rpc::resp::PlaylistSmartList,
+			"",
+r#"{
+  "jsonrpc": "2.0",
+  "result": {
+    "len": 1,
+    "playlists": {
+      "smart": {
+        "rules": [
+          {
+            "genre": "rock"
+          }
+        ],
+        "limit": null,
+        "sort": "lexi"
+      }
+    }
+  },
+  "id": 0
+}"#,
+
+			PlaylistExport => rpc::resp::PlaylistExport,
+			ureq::json!({"playlist":"smart","format":"m3u8"}),
+r#"{
+  "jsonrpc": "2.0",
+  "result": {
+    "format": "m3u8",
+    "len": 0,
+    "data": "#EXTM3U\n#PLAYLIST:smart\n"
+  },
+  "id": 0
+}"#,
+
+			TokenNew => rpc::resp::TokenNew,
+			ureq::json!({"label":"kiosk","methods":["toggle"],"expire":60}),
+			"", // Skipped, contains variable data.
+
+			TokenList => rpc::resp::TokenList,
+			"",
+			"", // Skipped, contains variable data.
+
+			TokenRevoke => rpc::resp::TokenRevoke,
+			ureq::json!({"id":"0000000000000000"}),
+r#"{
+  "jsonrpc": "2.0",
+  "result": {
+    "existed": false
+  },
+  "id": 0
+}"#,
+
 			// Saved until last.
 			DaemonShutdown => rpc::resp::DaemonShutdown,
 			"",
@@ -0,0 +1,245 @@
+//---------------------------------------------------------------------------------------------------- Use
+use log::{info,warn,debug,trace};
+use once_cell::sync::OnceCell;
+use hyper::{
+	Response,
+	body::{Body,Bytes},
+};
+use benri::{
+	atomic_load,
+	atomic_add,
+	atomic_sub,
+	lock,
+};
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use crossbeam::channel::Receiver;
+use tokio::sync::broadcast::{
+	Sender,
+	error::RecvError,
+};
+use shukusai::audio::BroadcastPcm;
+use crate::resp;
+use crate::transcode::{Encode,Format,Transcode};
+
+//---------------------------------------------------------------------------------------------------- Constants
+// How many chunks of PCM `Audio` can queue before it starts dropping them.
+const PCM_QUEUE: usize = 64;
+
+// How many encoded chunks a listener can fall behind
+// before it skips ahead to the live position.
+const ENCODED_QUEUE: usize = 256;
+
+//---------------------------------------------------------------------------------------------------- Statics
+// Where the encoded audio gets sent to, every `/stream` listener subscribes to this.
+//
+// This is only set if `broadcast` is enabled.
+static ENCODED: OnceCell<(Sender<Bytes>, Format)> = OnceCell::new();
+
+// The headers of the current encoder's stream (Ogg/Opus has some),
+// they are sent to new listeners before they join in the middle.
+static HEADER: Mutex<Bytes> = Mutex::new(Bytes::new());
+
+// Used in `daemon_broadcast` RPC call.
+pub static TOTAL_LISTENERS: AtomicU64 = AtomicU64::new(0);
+static LISTENERS: AtomicU64 = AtomicU64::new(0);
+
+#[inline(always)]
+/// Get current `/stream` listener count.
+pub fn listeners() -> u64 {
+	atomic_load!(LISTENERS)
+}
+
+/// Held by each `/stream` listener, it increments the listener counters.
+/// Upon drop, the current listener count is decremented.
+struct ListenerToken(());
+
+impl ListenerToken {
+	fn new() -> Self {
+		atomic_add!(TOTAL_LISTENERS, 1);
+		atomic_add!(LISTENERS, 1);
+		Self(())
+	}
+}
+
+impl Drop for ListenerToken {
+	fn drop(&mut self) {
+		atomic_sub!(LISTENERS, 1);
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Init
+// INVARIANT: must be called once and only once,
+// _before_ `Kernel::spawn()` so that `Audio` sees it.
+//
+// Spawns the encoder thread.
+//
+// `config` refuses to start with `broadcast` enabled
+// if `festivald` was built without `format`'s encoder.
+pub fn init(format: Format, bitrate: u16) {
+	let (to_encoder, from_audio) = crossbeam::channel::bounded::<BroadcastPcm>(PCM_QUEUE);
+	let (to_listeners, _) = tokio::sync::broadcast::channel::<Bytes>(ENCODED_QUEUE);
+
+	// SAFETY: we only set these here.
+	ENCODED.set((to_listeners.clone(), format)).unwrap();
+	shukusai::audio::BROADCAST.set(to_encoder).unwrap();
+
+	let transcode = Transcode { format, bitrate };
+	if let Err(e) = std::thread::Builder::new()
+		.name("Broadcast".into())
+		.spawn(move || encode(transcode, from_audio, to_listeners))
+	{
+		crate::exit!("could not spawn broadcast thread: {e}");
+	}
+
+	info!("Broadcast ... {format} @ {bitrate}kbps");
+}
+
+//---------------------------------------------------------------------------------------------------- Encoder
+// Copy the encoded bytes to every listener.
+fn send(to_listeners: &Sender<Bytes>, out: Vec<u8>) {
+	if !out.is_empty() {
+		// This only errors if there are no listeners, which is fine.
+		let _ = to_listeners.send(Bytes::from(out));
+	}
+}
+
+// The encoder thread.
+//
+// This receives the PCM `Audio` is playing, encodes it
+// and sends it to all the `/stream` listeners.
+//
+// The encoders are the same ones `/transcode` uses.
+fn encode(
+	transcode:    Transcode,
+	from_audio:   Receiver<BroadcastPcm>,
+	to_listeners: Sender<Bytes>,
+) {
+	// The current `(rate, channels)` of the PCM,
+	// the encoder must be re-created if these change.
+	let mut spec:    Option<(u32, u16)>      = None;
+	let mut encoder: Option<Box<dyn Encode>> = None;
+
+	// Re-used buffer, only used for downmixing.
+	let mut pcm: Vec<f32> = vec![];
+
+	while let Ok(chunk) = from_audio.recv() {
+		// No one is listening, don't bother encoding.
+		if to_listeners.receiver_count() == 0 {
+			continue;
+		}
+
+		if spec != Some((chunk.rate, chunk.channels)) {
+			// Flush the old encoder's leftover frames.
+			if let Some(mut e) = encoder.take() {
+				match e.finish() {
+					Ok(out) => send(&to_listeners, out),
+					Err(e)  => warn!("Broadcast - flush error: {e}"),
+				}
+			}
+
+			spec = Some((chunk.rate, chunk.channels));
+
+			// A new encoder starts a new stream, with
+			// its own headers (for Ogg, a chained stream).
+			let channels = chunk.channels.clamp(1, 2) as usize;
+			match crate::transcode::new_encoder(transcode, chunk.rate, channels) {
+				Ok(mut e) => {
+					debug!("Broadcast - new encoder: {}Hz, {channels} channel(s)", chunk.rate);
+					let header = Bytes::from(e.encode(&[]).unwrap_or_default());
+					*lock!(HEADER) = header.clone();
+					if !header.is_empty() {
+						let _ = to_listeners.send(header);
+					}
+					encoder = Some(e);
+				},
+				Err(e) => warn!("Broadcast - encoder error: {e}, rate: {}, channels: {channels}", chunk.rate),
+			}
+		}
+
+		let Some(e) = encoder.as_mut() else {
+			continue;
+		};
+
+		// Only keep the front left/right of anything above stereo.
+		let channels = chunk.channels.max(1) as usize;
+		let samples: &[f32] = if channels <= 2 {
+			&chunk.samples
+		} else {
+			pcm.clear();
+			for frame in chunk.samples.chunks_exact(channels) {
+				pcm.extend_from_slice(&frame[..2]);
+			}
+			&pcm
+		};
+
+		match e.encode(samples) {
+			Ok(out) => send(&to_listeners, out),
+			Err(e)  => warn!("Broadcast - encode error: {e}"),
+		}
+	}
+
+	debug!("Broadcast - audio channel disconnected, exiting");
+}
+
+//---------------------------------------------------------------------------------------------------- `/stream`
+// A never-ending body of what `festivald` is currently playing.
+pub async fn stream() -> Result<Response<Body>, anyhow::Error> {
+	let Some((encoded, format)) = ENCODED.get() else {
+		return Ok(resp::forbidden("Broadcast is disabled"));
+	};
+
+	// Subscribe before getting the header, so a new
+	// encoder's header can't be missed in-between.
+	let mut from_encoder = encoded.subscribe();
+	let header = lock!(HEADER).clone();
+	let (mut sender, body) = Body::channel();
+
+	tokio::spawn(async move {
+		let _token = ListenerToken::new();
+
+		if !header.is_empty() && sender.send_data(header).await.is_err() {
+			return;
+		}
+
+		loop {
+			match from_encoder.recv().await {
+				Ok(bytes) => {
+					// The listener disconnected.
+					if sender.send_data(bytes).await.is_err() {
+						break;
+					}
+				},
+				// The listener is too slow, it will skip
+				// ahead to the oldest audio still buffered.
+				Err(RecvError::Lagged(n)) => trace!("Broadcast - listener lagged by {n} chunks"),
+				Err(RecvError::Closed)    => break,
+			}
+		}
+	});
+
+	Ok(resp::rest_live(body, format.mime()))
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn token() {
+		assert_eq!(std::mem::size_of::<ListenerToken>(), 0);
+
+		// Other tests may hold tokens in parallel, only check
+		// that the total went up by (at least) 2, it never goes down.
+		let total = atomic_load!(TOTAL_LISTENERS);
+
+		{
+			let _a = ListenerToken::new();
+			let _b = ListenerToken::new();
+			assert!(listeners() >= 2);
+		} // drop
+
+		assert!(atomic_load!(TOTAL_LISTENERS) >= total + 2);
+	}
+}
@@ -16,7 +16,7 @@ use std::num::NonZeroUsize;
 use disk::{Bincode2, Json, Plain, Toml};
 use const_format::formatcp;
 use std::process::exit;
-use std::net::Ipv4Addr;
+use std::net::IpAddr;
 use std::path::PathBuf;
 use crate::config::ConfigBuilder;
 
@@ -41,8 +41,14 @@ pub struct Cli {
 	command: Option<Command>,
 
 	#[arg(long, verbatim_doc_comment)]
-	/// The IPv4 address `festivald` will bind to [default: 127.0.0.1]
-	ip: Option<std::net::Ipv4Addr>,
+	/// The IPv4/IPv6 address `festivald` will bind to [default: 127.0.0.1]
+	///
+	/// To listen on multiple addresses, use this flag per IP.
+	/// `::` will (on most systems) accept both IPv4 and IPv6,
+	/// unless an IPv4 address is also given, e.g: `--ip 0.0.0.0 --ip ::`
+	///
+	/// Example: `festivald --ip 127.0.0.1 --ip ::1`
+	ip: Option<Vec<IpAddr>>,
 
 	#[arg(long, verbatim_doc_comment)]
 	/// The port `festivald` will bind to [default: 18425]
@@ -68,13 +74,59 @@ pub struct Cli {
 	///
 	/// `festivald` will only serve connections coming
 	/// from these IPs. If there's no value given or
-	/// any of the values is "0.0.0.0", `festivald`
+	/// any of the values is "0.0.0.0" or "::", `festivald`
 	/// will serve all IP ranges.
 	///
+	/// CIDR ranges are also accepted, e.g: `192.168.0.0/16`, `fd00::/8`.
+	///
 	/// To allow multiple IPs, use this flag per IP.
 	///
-	/// Example: `festivald --exclusive-ip 127.0.0.1 --exclusive-ip 192.168.2.1`
-	exclusive_ip: Option<Vec<Ipv4Addr>>,
+	/// Example: `festivald --exclusive-ip 127.0.0.1 --exclusive-ip 192.168.0.0/16`
+	exclusive_ip: Option<Vec<crate::ip::IpNet>>,
+
+	#[arg(long, verbatim_doc_comment, value_name = "IP")]
+	/// Trust the `--trusted-proxy-header` header from these IPs
+	///
+	/// If `festivald` is behind a reverse proxy (nginx, Caddy, etc),
+	/// every connection comes from the proxy's IP. Connections from
+	/// these IPs will instead use the client IP in the proxy's
+	/// `--trusted-proxy-header` header for `exclusive_ips`,
+	/// rate limits, lockouts and logs.
+	///
+	/// Headers from any other IP are ignored, so only
+	/// list proxies you control.
+	///
+	/// CIDR ranges are also accepted, e.g: `10.0.0.0/8`.
+	///
+	/// To trust multiple proxies, use this flag per IP.
+	///
+	/// Example: `festivald --trusted-proxy 127.0.0.1 --trusted-proxy ::1`
+	trusted_proxy: Option<Vec<crate::ip::IpNet>>,
+
+	#[arg(long, verbatim_doc_comment, value_name = "x-forwarded-for|forwarded")]
+	/// The header trusted proxies put the client IP in
+	///
+	/// Only this header is read, the other one is ignored,
+	/// as most proxies pass it through from the client untouched,
+	/// so pick the one your proxy actually sets (or overwrites).
+	///
+	/// `x-forwarded-for` is `X-Forwarded-For` (the default),
+	/// `forwarded` is the RFC 7239 `Forwarded` header.
+	trusted_proxy_header: Option<crate::ip::ProxyHeader>,
+
+	#[arg(long, verbatim_doc_comment, value_name = "PATH")]
+	/// Serve everything under this URL path prefix
+	///
+	/// If `festivald` is behind a reverse proxy at a sub-path,
+	/// e.g: `https://music.example/festival/`, set this to
+	/// `/festival` and the JSON-RPC endpoint, REST endpoints,
+	/// `/event` and documentation will all be under it, e.g:
+	/// `/festival/rand/song`.
+	///
+	/// An empty string or `/` means the root.
+	///
+	/// Example: `festivald --base-path /festival`
+	base_path: Option<String>,
 
 	#[arg(long, verbatim_doc_comment, requires = "certificate", requires = "key")]
 	/// Enable HTTPS
@@ -216,7 +268,6 @@ pub struct Cli {
 	/// This includes:
 	///   - Authentication failure
 	///   - IPs not in the `exclusive_ips` list
-	///   - IPv6 connections
 	///
 	/// If 0, `festivald` will immediately respond. This may
 	/// not be wanted to due potential DoS and timing attacks.
@@ -225,6 +276,45 @@ pub struct Cli {
 	/// to 0 (unless you don't trust your local network?).
 	sleep_on_fail: Option<u64>,
 
+	#[arg(long, verbatim_doc_comment, value_name = "REQ_PER_MIN")]
+	/// Rate limit for cheap requests, in requests per minute
+	///
+	/// Each client gets a bucket of this many requests that
+	/// refills at this rate, e.g `60` allows a burst of 60
+	/// requests, then 1 request per second.
+	///
+	/// Authenticated clients are limited per user,
+	/// everyone else is limited per IP.
+	///
+	/// Cheap requests are every JSON-RPC method and
+	/// REST resource that isn't expensive.
+	///
+	/// 0 means no limit.
+	rate_limit_cheap: Option<u32>,
+
+	#[arg(long, verbatim_doc_comment, value_name = "REQ_PER_MIN")]
+	/// Rate limit for expensive requests, in requests per minute
+	///
+	/// This is the same as `--rate-limit-cheap`, but with
+	/// a separate bucket for expensive requests, e.g:
+	/// `collection_new`, `collection_full`, REST ZIPs.
+	///
+	/// 0 means no limit.
+	rate_limit_expensive: Option<u32>,
+
+	#[arg(long, verbatim_doc_comment, value_name = "ATTEMPTS")]
+	/// Lock out an IP after this many failed authorizations in a row
+	///
+	/// Locked out IPs receive `429 Too Many Requests`
+	/// for everything until `--lockout-time` has passed.
+	///
+	/// 0 means never lock out.
+	lockout_attempts: Option<u32>,
+
+	#[arg(long, verbatim_doc_comment, value_name = "SECONDS")]
+	/// How many seconds a locked out IP stays locked out
+	lockout_time: Option<u64>,
+
 	#[arg(long, verbatim_doc_comment, value_name = "PATH")]
 	/// Default PATHs to use for the `Collection`
 	///
@@ -242,6 +332,39 @@ pub struct Cli {
 	/// Example: `festivald --collection-path /my/path/1 --collection-path /my/path/2`
 	collection_path: Vec<PathBuf>,
 
+	#[arg(long, verbatim_doc_comment, default_value_t = false)]
+	/// Watch the `Collection` PATHs for changes
+	///
+	/// With this enabled, `festivald` will watch the `--collection-path`
+	/// directories and automatically update the `Collection` when audio
+	/// files are added, removed, renamed or re-tagged.
+	///
+	/// Only the changed files are scanned, unchanged
+	/// `Song`'s are re-used from the current `Collection`.
+	collection_watch: bool,
+
+	#[arg(long, verbatim_doc_comment, value_name = "TEMPLATE")]
+	/// Infer metadata from the folder structure of untagged files
+	///
+	/// If a file is missing its artist or album tag (or has no
+	/// tags at all), its PATH is matched against these templates
+	/// and the first match fills in the missing metadata.
+	///
+	/// Each `/` separated part of the template matches a directory,
+	/// the last part matches the file name (without extension).
+	///
+	/// The available fields are:
+	/// `{artist}` (required), `{album}` (required),
+	/// `{release}`, `{track}` and `{title}`.
+	///
+	/// Files with metadata inferred from their PATH
+	/// are listed by the `collection_health` method.
+	///
+	/// To set multiple templates, use this flag per template.
+	///
+	/// Example: `festivald --collection-path-template "{artist}/{album} ({release})/{track} - {title}"`
+	collection_path_template: Vec<String>,
+
 	#[arg(long, verbatim_doc_comment)]
 	/// Enable/disable inlined resources for the REST API.
 	///
@@ -305,6 +428,18 @@ pub struct Cli {
 	/// This does nothing if `--disable-cache-clean` is passed.
 	cache_time: Option<u64>,
 
+	#[arg(long, verbatim_doc_comment, value_name = "MEGABYTES")]
+	/// Set the `/transcode` cache size limit
+	///
+	/// Transcoded `Song`'s are kept on disk so that the same
+	/// request doesn't need to be encoded again.
+	///
+	/// Once the cache grows past this many megabytes, the
+	/// oldest transcoded files are removed to make room.
+	///
+	/// `0` means no limit.
+	transcode_cache_size: Option<u64>,
+
 	#[arg(long, verbatim_doc_comment)]
 	/// Reset threshold for the `previous` JSON-RPC method
 	///
@@ -330,6 +465,84 @@ pub struct Cli {
 	/// always go back if `threshold` is not specified.
 	previous_threshold: Option<u32>,
 
+	#[arg(long, verbatim_doc_comment, value_name = "SECONDS")]
+	/// Crossfade between `Song`'s for this many seconds
+	///
+	/// The last `SECONDS` of the current `Song` will be
+	/// mixed with the first `SECONDS` of the next `Song`.
+	///
+	/// This is skipped for consecutive `Song`'s in the
+	/// same `Album`, so gapless albums stay gapless.
+	///
+	/// `0` disables crossfading, the maximum is `30`.
+	crossfade: Option<u8>,
+
+	#[arg(long, verbatim_doc_comment, value_name = "linear|equal_power")]
+	/// The volume curve used while crossfading
+	///
+	/// `linear` fades the volume linearly, `equal_power`
+	/// keeps the perceived loudness constant throughout.
+	crossfade_curve: Option<shukusai::audio::CrossfadeCurve>,
+
+	#[arg(long, verbatim_doc_comment, value_name = "off|track|album")]
+	/// Loudness normalization (ReplayGain) mode
+	///
+	/// `track` normalizes each `Song` to the same loudness.
+	/// `album` normalizes each `Album` to the same loudness,
+	/// keeping the differences between its `Song`'s intact.
+	///
+	/// The gain is read from `REPLAYGAIN_*` or `R128_*` tags,
+	/// or calculated when the `Collection` is created.
+	///
+	/// Gain is never applied in a way that would clip.
+	replay_gain: Option<shukusai::audio::ReplayGain>,
+
+	#[arg(long, verbatim_doc_comment, default_value_t = false)]
+	/// Enable the live HTTP audio broadcast
+	///
+	/// The audio `festivald` plays will be encoded into
+	/// `--broadcast-format` and served at the `/stream` REST endpoint.
+	///
+	/// Any amount of clients can listen at once, they
+	/// will all hear the same queue in sync.
+	///
+	/// This does not need an audio device, if `festivald` cannot
+	/// open one, it will play and broadcast the audio anyway.
+	broadcast: bool,
+
+	#[arg(long, verbatim_doc_comment, value_name = "opus|mp3|aac")]
+	/// The audio format of the broadcast
+	///
+	/// `opus` is Opus in an Ogg container, and is always available.
+	/// `mp3` and `aac` require `festivald` to be built with
+	/// the `mp3` and `aac` features respectively.
+	broadcast_format: Option<crate::transcode::Format>,
+
+	#[arg(long, verbatim_doc_comment, value_name = "KBPS")]
+	/// The bitrate of the broadcast in kbps
+	///
+	/// Must be one of: 64, 96, 112, 128, 160, 192, 224, 256, 320.
+	broadcast_bitrate: Option<u16>,
+
+	#[arg(long, verbatim_doc_comment, value_name = "OUTPUT")]
+	/// The audio output to play to
+	///
+	/// `pulseaudio` (Linux) or `cpal` (Windows/macOS) is the audio device.
+	/// `null` plays into nothing, in real-time (headless servers, CI).
+	/// `wav` writes everything played into a WAV file, in real-time.
+	///
+	/// The default is the audio device.
+	output: Option<String>,
+
+	#[arg(long, verbatim_doc_comment, value_name = "PATH")]
+	/// The WAV file the `wav` output writes to
+	///
+	/// If audio with a different sample rate or channel count is played,
+	/// a new file with a numbered suffix is started, e.g: `festival.1.wav`.
+	///
+	/// The default is `festival.wav` in the OS audio directory.
+	output_wav_path: Option<PathBuf>,
+
 	#[arg(long, verbatim_doc_comment, default_value_t = false)]
 	/// Disable audio state restoration
 	///
@@ -439,6 +652,17 @@ pub struct Cli {
 	/// Print all the JSON-RPC methods available
 	methods: bool,
 
+	#[arg(long, verbatim_doc_comment)]
+	/// Hash a password for the `[users]` config, print it, and exit
+	///
+	/// The password is prompted for if this is a terminal,
+	/// else a single line is read from STDIN, e.g:
+	/// `festivald --hash-password < password.txt`
+	///
+	/// The output is what goes into a user's `password` field, e.g:
+	/// `alice = { password = "<OUTPUT>", role = "admin" }`
+	hash_password: bool,
+
 	#[arg(short, long)]
 	/// Print version
 	version: bool,
@@ -656,6 +880,13 @@ impl Cli {
 			exit(0);
 		}
 
+		//-------------------------------------------------- Hash password
+		if self.hash_password {
+			let password = Self::read_password();
+			println!("{}", rpc::hash::Password::new(password).encode());
+			exit(0);
+		}
+
 		//-------------------------------------------------- Sub-commands
 		self.handle_command();
 
@@ -677,6 +908,42 @@ impl Cli {
 		}
 	}
 
+	// Read the password for `--hash-password`.
+	//
+	// This is never taken as an argument, so it
+	// doesn't end up in shell history or `ps` output.
+	fn read_password() -> String {
+		use std::io::IsTerminal;
+		use zeroize::Zeroize;
+
+		let result = if std::io::stdin().is_terminal() {
+			rpassword::prompt_password("Password: ").and_then(|mut p| {
+				let mut confirm = rpassword::prompt_password("Confirm password: ")?;
+				if p != confirm {
+					p.zeroize();
+					confirm.zeroize();
+					eprintln!("{BIN} error: passwords do not match");
+					exit(1);
+				}
+				confirm.zeroize();
+				Ok(p)
+			})
+		} else {
+			let mut p = String::new();
+			std::io::stdin().read_line(&mut p).map(|_| {
+				let len = p.trim_end_matches(&['\r', '\n'][..]).len();
+				p.truncate(len);
+				p
+			})
+		};
+
+		match result {
+			Ok(p) if !p.is_empty() => p,
+			Ok(_)  => { eprintln!("{BIN} error: password must not be empty"); exit(1); },
+			Err(e) => { eprintln!("{BIN} error: could not read password: {e}"); exit(1); },
+		}
+	}
+
 	pub fn handle_signal(&self, s: &Signal) -> ! {
 		fn handle<T>(result: Result<T, anyhow::Error>) {
 			if let Err(e) = result {
@@ -720,6 +987,8 @@ impl Cli {
 		let mut direct_download     = self.direct_download.then_some(self.direct_download);
 		let mut confirm_no_tls_auth = self.confirm_no_tls_auth.then_some(self.confirm_no_tls_auth);
 		let mut no_auth_docs        = self.no_auth_docs.then_some(self.no_auth_docs);
+		let mut broadcast           = self.broadcast.then_some(self.broadcast);
+		let mut collection_watch    = self.collection_watch.then_some(self.collection_watch);
 
 		// `disable_*` negation.
 		let mut docs                = self.disable_docs.then_some(!self.disable_docs);
@@ -755,7 +1024,9 @@ impl Cli {
 			}
 		}
 
-		let mut exclusive_ips = vec_to_some_btreeset!(self.exclusive_ip, std::net::Ipv4Addr);
+		let mut ip            = self.ip.take().map(crate::ip::Ips::from);
+		let mut exclusive_ips = vec_to_some_btreeset!(self.exclusive_ip, crate::ip::IpNet);
+		let mut trusted_proxies = vec_to_some_btreeset!(self.trusted_proxy, crate::ip::IpNet);
 		let mut no_auth_rpc   = vec_to_some_btreeset!(self.no_auth_rpc, rpc::Method);
 		let mut no_auth_rest  = vec_to_some_btreeset!(self.no_auth_rest, rpc::resource::Resource);
 
@@ -765,6 +1036,12 @@ impl Cli {
 			Some(std::mem::take(&mut self.collection_path))
 		};
 
+		let mut collection_path_templates = if self.collection_path_template.is_empty() {
+			None
+		} else {
+			Some(std::mem::take(&mut self.collection_path_template))
+		};
+
 		let mut log_level = self.log_level.clone();
 
 		macro_rules! if_some {
@@ -781,12 +1058,21 @@ impl Cli {
 		}
 
 		if_some! {
-			self.ip                 => cb.ip,
+			ip                      => cb.ip,
 			self.port               => cb.port,
 			self.max_connections    => cb.max_connections,
 			exclusive_ips           => cb.exclusive_ips,
+			trusted_proxies         => cb.trusted_proxies,
+			self.trusted_proxy_header => cb.trusted_proxy_header,
+			self.base_path          => cb.base_path,
 			self.sleep_on_fail      => cb.sleep_on_fail,
+			self.rate_limit_cheap   => cb.rate_limit_cheap,
+			self.rate_limit_expensive => cb.rate_limit_expensive,
+			self.lockout_attempts   => cb.lockout_attempts,
+			self.lockout_time       => cb.lockout_time,
 			collection_paths        => cb.collection_paths,
+			collection_watch        => cb.collection_watch,
+			collection_path_templates => cb.collection_path_templates,
 			tls                     => cb.tls,
 			self.certificate        => cb.certificate,
 			self.key                => cb.key,
@@ -798,8 +1084,17 @@ impl Cli {
 			watch                   => cb.watch,
 			cache_clean             => cb.cache_clean,
 			self.cache_time         => cb.cache_time,
+			self.transcode_cache_size => cb.transcode_cache_size,
 			restore_audio_state     => cb.restore_audio_state,
 			self.previous_threshold => cb.previous_threshold,
+			self.crossfade          => cb.crossfade,
+			self.crossfade_curve    => cb.crossfade_curve,
+			self.replay_gain        => cb.replay_gain,
+			broadcast               => cb.broadcast,
+			self.broadcast_format   => cb.broadcast_format,
+			self.broadcast_bitrate  => cb.broadcast_bitrate,
+			self.output             => cb.output,
+			self.output_wav_path    => cb.output_wav_path,
 			media_controls          => cb.media_controls,
 			self.authorization      => cb.authorization,
 			confirm_no_tls_auth     => cb.confirm_no_tls_auth,
@@ -11,6 +11,9 @@ use crate::constants::{
 	FESTIVALD_PORT,
 	FESTIVALD_CONFIG,
 };
+use crate::ip::{Ips,IpNet,ProxyHeader};
+use crate::transcode::Format;
+use crate::user::{Role,User,UserConfig,USERS};
 use strum::{
 	AsRefStr,
 	Display,
@@ -21,13 +24,14 @@ use strum::{
 	IntoStaticStr,
 };
 use std::net::{
+	IpAddr,
 	Ipv4Addr,
-	SocketAddrV4,
 };
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap,BTreeSet};
 use std::path::PathBuf;
 use once_cell::sync::OnceCell;
 use shukusai::constants::DASH;
+use shukusai::audio::{CrossfadeCurve,CROSSFADE_MAX_SECONDS,ReplayGain,OUTPUT_DEVICE};
 
 //---------------------------------------------------------------------------------------------------- Statics
 static CONFIG: OnceCell<Config> = OnceCell::new();
@@ -42,9 +46,18 @@ pub fn config() -> &'static Config {
 // SAFETY: This does not get initialized if there's no `authorization` config.
 // This is okay because we will only ever use `.get()`.
 pub static AUTH: OnceCell<rpc::hash::Hash> = OnceCell::new();
+// The username part of the `authorization` config, set alongside `AUTH`.
+pub static AUTH_USER: OnceCell<String> = OnceCell::new();
 
 //---------------------------------------------------------------------------------------------------- Constants
 const LOG_DEFAULT: log::LevelFilter = log::LevelFilter::Error;
+/// The MP3 bitrates (kbps) the `/stream` broadcast can be encoded at.
+pub const BROADCAST_BITRATES: [u16; 9] = [64, 96, 112, 128, 160, 192, 224, 256, 320];
+const BROADCAST_BITRATE_DEFAULT: u16 = 192;
+// Megabytes.
+const TRANSCODE_CACHE_SIZE_DEFAULT: u64 = 1000;
+// Seconds.
+const LOCKOUT_TIME_DEFAULT: u64 = 300;
 
 //---------------------------------------------------------------------------------------------------- ConfigBuilder
 /// The `struct` that maps value directly from the disk.
@@ -54,11 +67,25 @@ const LOG_DEFAULT: log::LevelFilter = log::LevelFilter::Error;
 disk::toml!(ConfigBuilder, disk::Dir::Config, FESTIVAL, FRONTEND_SUB_DIR, "festivald");
 #[derive(Clone,Debug,PartialEq,Eq,Serialize,Deserialize)]
 pub struct ConfigBuilder {
-	pub ip:                  Option<Ipv4Addr>,
+	pub ip:                  Option<Ips>,
 	pub port:                Option<u16>,
 	pub max_connections:     Option<u64>,
-	pub exclusive_ips:       Option<BTreeSet<Ipv4Addr>>,
+	pub exclusive_ips:       Option<BTreeSet<IpNet>>,
+	#[serde(default = "trusted_proxies_default")]
+	pub trusted_proxies:     Option<BTreeSet<IpNet>>,
+	#[serde(default = "trusted_proxy_header_default")]
+	pub trusted_proxy_header: Option<ProxyHeader>,
+	#[serde(default = "base_path_default")]
+	pub base_path:           Option<String>,
 	pub sleep_on_fail:       Option<u64>,
+	#[serde(default = "rate_limit_default")]
+	pub rate_limit_cheap:    Option<u32>,
+	#[serde(default = "rate_limit_default")]
+	pub rate_limit_expensive: Option<u32>,
+	#[serde(default = "lockout_attempts_default")]
+	pub lockout_attempts:    Option<u32>,
+	#[serde(default = "lockout_time_default")]
+	pub lockout_time:        Option<u64>,
 	pub collection_paths:    Option<Vec<PathBuf>>,
 	pub tls:                 Option<bool>,
 	pub certificate:         Option<PathBuf>,
@@ -70,25 +97,60 @@ pub struct ConfigBuilder {
 	pub log_level:           Option<log::LevelFilter>,
 	pub restore_audio_state: Option<bool>,
 	pub previous_threshold:  Option<u32>,
+	// These were added after `v1.0.0`, old config files
+	// without them should still be equal to the default.
+	#[serde(default = "crossfade_default")]
+	pub crossfade:           Option<u8>,
+	#[serde(default = "crossfade_curve_default")]
+	pub crossfade_curve:     Option<CrossfadeCurve>,
+	#[serde(default = "replay_gain_default")]
+	pub replay_gain:         Option<ReplayGain>,
+	#[serde(default = "broadcast_default")]
+	pub broadcast:           Option<bool>,
+	#[serde(default = "broadcast_format_default")]
+	pub broadcast_format:    Option<Format>,
+	#[serde(default = "broadcast_bitrate_default")]
+	pub broadcast_bitrate:   Option<u16>,
+	#[serde(default = "output_default")]
+	pub output:              Option<String>,
+	#[serde(default = "output_wav_path_default")]
+	pub output_wav_path:     Option<PathBuf>,
 	pub watch:               Option<bool>,
 	pub cache_clean:         Option<bool>,
 	pub cache_time:          Option<u64>,
+	#[serde(default = "transcode_cache_size_default")]
+	pub transcode_cache_size: Option<u64>,
+	#[serde(default = "collection_watch_default")]
+	pub collection_watch:    Option<bool>,
+	#[serde(default = "collection_path_templates_default")]
+	pub collection_path_templates: Option<Vec<String>>,
 	pub media_controls:      Option<bool>,
 	pub authorization:	     Option<String>,
 	pub confirm_no_tls_auth: Option<bool>,
 	pub no_auth_rpc:         Option<BTreeSet<rpc::Method>>,
 	pub no_auth_rest:        Option<BTreeSet<rpc::resource::Resource>>,
 	pub no_auth_docs:        Option<bool>,
+	#[serde(default = "roles_default")]
+	pub roles:               Option<BTreeMap<String, Role>>,
+	#[serde(default = "users_default")]
+	pub users:               Option<BTreeMap<String, UserConfig>>,
 }
 
 impl Default for ConfigBuilder {
 	fn default() -> Self {
 		Self {
-			ip:                  Some(Ipv4Addr::LOCALHOST),
+			ip:                  Some(Ips::One(IpAddr::V4(Ipv4Addr::LOCALHOST))),
 			port:                Some(FESTIVALD_PORT),
 			max_connections:     Some(0),
 			exclusive_ips:       Some(BTreeSet::new()),
+			trusted_proxies:     trusted_proxies_default(),
+			trusted_proxy_header: trusted_proxy_header_default(),
+			base_path:           base_path_default(),
 			sleep_on_fail:       Some(3000),
+			rate_limit_cheap:    rate_limit_default(),
+			rate_limit_expensive: rate_limit_default(),
+			lockout_attempts:    lockout_attempts_default(),
+			lockout_time:        lockout_time_default(),
 			collection_paths:    Some(vec![]),
 			tls:                 Some(false),
 			certificate:         Some(PathBuf::from("")),
@@ -100,15 +162,28 @@ impl Default for ConfigBuilder {
 			log_level:           Some(LOG_DEFAULT),
 			restore_audio_state: Some(true),
 			previous_threshold:  Some(3),
+			crossfade:           crossfade_default(),
+			crossfade_curve:     crossfade_curve_default(),
+			replay_gain:         replay_gain_default(),
+			broadcast:           broadcast_default(),
+			broadcast_format:    broadcast_format_default(),
+			broadcast_bitrate:   broadcast_bitrate_default(),
+			output:              output_default(),
+			output_wav_path:     output_wav_path_default(),
 			watch:               Some(true),
 			cache_clean:         Some(true),
 			cache_time:          Some(3600),
+			transcode_cache_size: transcode_cache_size_default(),
+			collection_watch:    collection_watch_default(),
+			collection_path_templates: collection_path_templates_default(),
 			media_controls:      Some(true),
 			authorization:       Some("".to_string()),
 			confirm_no_tls_auth: Some(false),
 			no_auth_rpc:         Some(BTreeSet::new()),
 			no_auth_rest:        Some(BTreeSet::new()),
 			no_auth_docs:        Some(false),
+			roles:               roles_default(),
+			users:               users_default(),
 		}
 	}
 }
@@ -122,7 +197,14 @@ impl ConfigBuilder {
 			port,
 			max_connections,
 			exclusive_ips,
+			trusted_proxies,
+			trusted_proxy_header,
+			base_path,
 			sleep_on_fail,
+			rate_limit_cheap,
+			rate_limit_expensive,
+			lockout_attempts,
+			lockout_time,
 			collection_paths,
 			tls,
 			certificate,
@@ -134,15 +216,28 @@ impl ConfigBuilder {
 			log_level,
 			restore_audio_state,
 			previous_threshold,
+			crossfade,
+			crossfade_curve,
+			replay_gain,
+			broadcast,
+			broadcast_format,
+			broadcast_bitrate,
+			output,
+			output_wav_path,
 			watch,
 			cache_clean,
 			cache_time,
+			transcode_cache_size,
+			collection_watch,
+			collection_path_templates,
 			media_controls,
 			authorization,
 			confirm_no_tls_auth,
 			no_auth_rpc,
 			no_auth_rest,
 			no_auth_docs,
+			roles,
+			users,
 		} = self;
 
 		macro_rules! get {
@@ -170,11 +265,18 @@ impl ConfigBuilder {
 		}
 
 		let mut c = Config {
-			ip:                  get!(ip,                  "ip",                  Ipv4Addr::LOCALHOST),
+			ip:                  get!(ip,                  "ip",                  Ips::One(IpAddr::V4(Ipv4Addr::LOCALHOST))).into_vec(),
 			port:                get!(port,                "port",                FESTIVALD_PORT),
 			max_connections:     sum!(max_connections,     "max_connections",     None::<u64>),
-			exclusive_ips:       sum!(exclusive_ips,       "exclusive_ips",       None::<BTreeSet<Ipv4Addr>>),
+			exclusive_ips:       sum!(exclusive_ips,       "exclusive_ips",       None::<BTreeSet<IpNet>>),
+			trusted_proxies:     sum!(trusted_proxies,     "trusted_proxies",     None::<BTreeSet<IpNet>>),
+			trusted_proxy_header: get!(trusted_proxy_header, "trusted_proxy_header", ProxyHeader::XForwardedFor),
+			base_path:           sum!(base_path,           "base_path",           None::<String>),
 			sleep_on_fail:       sum!(sleep_on_fail,       "sleep_on_fail",       Some(3000)),
+			rate_limit_cheap:    sum!(rate_limit_cheap,    "rate_limit_cheap",    None::<u32>),
+			rate_limit_expensive: sum!(rate_limit_expensive, "rate_limit_expensive", None::<u32>),
+			lockout_attempts:    sum!(lockout_attempts,    "lockout_attempts",    None::<u32>),
+			lockout_time:        get!(lockout_time,        "lockout_time",        LOCKOUT_TIME_DEFAULT),
 			collection_paths:    get!(collection_paths,    "collection_paths",    if let Some(p) = dirs::audio_dir() { vec![p] } else { Vec::<PathBuf>::with_capacity(0) }),
 			tls:                 get!(tls,                 "tls",                 false),
 			certificate:         sum!(certificate,         "certificate",         None::<PathBuf>),
@@ -186,16 +288,33 @@ impl ConfigBuilder {
 			log_level:           get!(log_level,           "log_level",           LOG_DEFAULT),
 			restore_audio_state: get!(restore_audio_state, "restore_audio_state", true),
 			previous_threshold:  get!(previous_threshold,  "previous_threshold",  3),
+			crossfade:           get!(crossfade,           "crossfade",           0),
+			crossfade_curve:     get!(crossfade_curve,     "crossfade_curve",     CrossfadeCurve::new()),
+			replay_gain:         get!(replay_gain,         "replay_gain",         ReplayGain::new()),
+			broadcast:           get!(broadcast,           "broadcast",           false),
+			broadcast_format:    get!(broadcast_format,    "broadcast_format",    Format::Opus),
+			broadcast_bitrate:   get!(broadcast_bitrate,   "broadcast_bitrate",   BROADCAST_BITRATE_DEFAULT),
+			output:              get!(output,              "output",              OUTPUT_DEVICE.to_string()),
+			output_wav_path:     sum!(output_wav_path,     "output_wav_path",     None::<PathBuf>),
 			watch:               get!(watch,               "watch",               true),
 			cache_clean:         get!(cache_clean,         "cache_clean",         true),
 			cache_time:          get!(cache_time,          "cache_time",          3600),
+			transcode_cache_size: get!(transcode_cache_size, "transcode_cache_size", TRANSCODE_CACHE_SIZE_DEFAULT),
+			collection_watch:    get!(collection_watch,    "collection_watch",    false),
+			collection_path_templates: get!(collection_path_templates, "collection_path_templates", Vec::<String>::with_capacity(0)),
 			media_controls:      get!(media_controls,      "media_controls",      true),
 			confirm_no_tls_auth: get!(confirm_no_tls_auth, "confirm_no_tls_auth", false),
 			no_auth_rpc:         sum!(no_auth_rpc,         "no_auth_rpc",         None::<BTreeSet<rpc::Method>>),
 			no_auth_rest:        sum!(no_auth_rest,        "no_auth_rest",        None::<BTreeSet<rpc::resource::Resource>>),
 			no_auth_docs:        get!(no_auth_docs,        "no_auth_docs",        false),
+			roles:               get!(roles,               "roles",               BTreeMap::<String, Role>::new()),
+			users:               BTreeMap::new(),
 		};
 
+		if c.ip.is_empty() {
+			crate::exit!("[ip] must contain at least 1 IP address");
+		}
+
 		if c.max_connections == Some(0) {
 			c.max_connections = None;
 		}
@@ -204,6 +323,50 @@ impl ConfigBuilder {
 			c.sleep_on_fail = None;
 		}
 
+		if c.rate_limit_cheap == Some(0) {
+			c.rate_limit_cheap = None;
+		}
+
+		if c.rate_limit_expensive == Some(0) {
+			c.rate_limit_expensive = None;
+		}
+
+		if c.lockout_attempts == Some(0) {
+			c.lockout_attempts = None;
+		}
+
+		if c.crossfade > CROSSFADE_MAX_SECONDS {
+			warn!("[crossfade] is greater than [{CROSSFADE_MAX_SECONDS}], using [{CROSSFADE_MAX_SECONDS}]");
+			c.crossfade = CROSSFADE_MAX_SECONDS;
+		}
+
+		if c.broadcast && !c.broadcast_format.available() {
+			crate::exit!("[broadcast_format] [{0}] requires festivald to be built with the `{0}` feature", c.broadcast_format);
+		}
+
+		if !BROADCAST_BITRATES.contains(&c.broadcast_bitrate) {
+			warn!("[broadcast_bitrate] [{}] is not one of {BROADCAST_BITRATES:?}, using [{BROADCAST_BITRATE_DEFAULT}]", c.broadcast_bitrate);
+			c.broadcast_bitrate = BROADCAST_BITRATE_DEFAULT;
+		}
+
+		if c.output.is_empty() {
+			c.output = OUTPUT_DEVICE.to_string();
+		} else if !shukusai::audio::outputs().contains(&c.output.as_str()) {
+			crate::exit!("[output] [{}] is not one of {:?}", c.output, shukusai::audio::outputs());
+		}
+
+		if let Some(ref path) = c.output_wav_path {
+			if path.as_os_str().is_empty() {
+				c.output_wav_path = None;
+			}
+		}
+
+		for template in c.collection_path_templates.iter() {
+			if let Err(e) = shukusai::template::PathTemplate::new(template) {
+				crate::exit!("[collection_path_templates] {e}");
+			}
+		}
+
 		if c.filename_separator.contains("/") {
 			crate::exit!("[filename_separator] cannot contain '/', it is the PATH separator for ZIPs");
 		}
@@ -217,11 +380,33 @@ impl ConfigBuilder {
 //		c.no_auth_rest = Some([rpc::resource::Resource::Song].into());
 
 		if let Some(ref hs) = c.exclusive_ips {
-			if hs.is_empty() ||  hs.contains(&Ipv4Addr::UNSPECIFIED) {
+			if hs.is_empty() || hs.iter().any(IpNet::is_unspecified) {
 				c.exclusive_ips = None;
 			}
 		}
 
+		if let Some(ref hs) = c.trusted_proxies {
+			if hs.is_empty() {
+				c.trusted_proxies = None;
+			}
+		}
+
+		// Normalize to `/path`, without a trailing `/`.
+		if let Some(ref path) = c.base_path {
+			let trimmed = path.trim().trim_matches('/');
+			if trimmed.is_empty() {
+				c.base_path = None;
+			} else if
+				!trimmed.bytes().all(|b| b.is_ascii_graphic()) ||
+				trimmed.contains(['?', '#', '%']) ||
+				trimmed.split('/').any(|s| s.is_empty() || s == "." || s == "..")
+			{
+				crate::exit!("[base_path] [{path}] is not a valid URL path, e.g: \"/festival\"");
+			} else {
+				c.base_path = Some(format!("/{trimmed}"));
+			}
+		}
+
 		if let Some(ref cert) = c.certificate {
 			if cert.as_os_str().is_empty() {
 				warn!("TLS certificate is empty PATH, ignoring");
@@ -240,6 +425,10 @@ impl ConfigBuilder {
 			}
 		}
 
+		// Only bound to localhost, TLS isn't needed for authorization.
+		let local = c.ip.iter().all(IpAddr::is_loopback);
+		let tls   = c.tls && c.certificate.is_some() && c.key.is_some();
+
 		// AUTHORIZATION
 		if let Some(s) = authorization {
 			// Check if it's a PATH or a String.
@@ -265,11 +454,16 @@ impl ConfigBuilder {
 			} else if s.split_once(":").is_none() {
 				crate::exit!("[authorization] field is not in `USERNAME:PASSWORD` format");
 			// Reject if TLS is not enabled.
-			} else if !c.confirm_no_tls_auth && c.ip != Ipv4Addr::LOCALHOST && (!c.tls || c.certificate.is_none() || c.key.is_none()) {
+			} else if !c.confirm_no_tls_auth && !local && !tls {
 				crate::exit!("[authorization] field was provided but TLS is not enabled, exiting for safety");
 			} else {
-				if c.ip == Ipv4Addr::LOCALHOST {
-					info!("[authorization] is enabled, TLS is not, but we're binding on [localhost], allowing");
+				if local && !tls {
+					info!("[authorization] is enabled, TLS is not, but we're only binding on [localhost], allowing");
+				}
+
+				// SAFETY: unwrap is okay, we only set `AUTH_USER` here.
+				if let Some((user, _)) = s.split_once(":") {
+					AUTH_USER.set(user.to_string()).unwrap();
 				}
 
 				// Base64 encode before hashing.
@@ -286,11 +480,49 @@ impl ConfigBuilder {
 			warn!("missing config [authorization], skipping");
 		}
 
+		// USERS
+		for (name, role) in c.roles.iter() {
+			if role.all && (!role.rpc.is_empty() || !role.rest.is_empty()) {
+				warn!("[roles.{name}] has `all = true`, ignoring its `rpc` and `rest` lists");
+			}
+		}
+
+		let users = users.unwrap_or_default();
+		if !users.is_empty() {
+			if !c.confirm_no_tls_auth && !local && !tls {
+				crate::exit!("[users] was provided but TLS is not enabled, exiting for safety");
+			} else if local && !tls {
+				info!("[users] is enabled, TLS is not, but we're only binding on [localhost], allowing");
+			}
+
+			let mut map = BTreeMap::new();
+			for (name, user) in users.into_iter() {
+				if name.is_empty() || name.contains(':') {
+					crate::exit!("[users] username [{name}] must not be empty or contain ':'");
+				}
+				if AUTH_USER.get() == Some(&name) {
+					crate::exit!("[users.{name}] has the same username as [authorization]");
+				}
+				if !c.roles.contains_key(&user.role) {
+					crate::exit!("[users.{name}] role [{}] does not exist in [roles]", user.role);
+				}
+				let Some(password) = rpc::hash::Password::decode(&user.password) else {
+					crate::exit!("[users.{name}] password is not an Argon2 hash, create one with `festivald --hash-password`");
+				};
+
+				c.users.insert(name.clone(), user.role.clone());
+				map.insert(name, User::new(password, user.role));
+			}
+
+			// SAFETY: unwrap is okay, we only set `USERS` here.
+			USERS.set(map).unwrap();
+		}
+
 		info!("{DASH} Configuration");
 		for line in format!("{c:#?}").lines() {
 			info!("{line}");
 		}
-		info!("Authorization: {}", AUTH.get().is_some());
+		info!("Authorization: {}", crate::user::enabled());
 		info!("{DASH} Configuration");
 
 		// SAFETY: unwrap is okay, we only set `CONFIG` here.
@@ -354,7 +586,14 @@ impl ConfigBuilder {
 			cmd.port                => self.port,
 			cmd.max_connections     => self.max_connections,
 			cmd.exclusive_ips       => self.exclusive_ips,
+			cmd.trusted_proxies     => self.trusted_proxies,
+			cmd.trusted_proxy_header => self.trusted_proxy_header,
+			cmd.base_path           => self.base_path,
 			cmd.sleep_on_fail       => self.sleep_on_fail,
+			cmd.rate_limit_cheap    => self.rate_limit_cheap,
+			cmd.rate_limit_expensive => self.rate_limit_expensive,
+			cmd.lockout_attempts    => self.lockout_attempts,
+			cmd.lockout_time        => self.lockout_time,
 			cmd.collection_paths    => self.collection_paths,
 			cmd.tls                 => self.tls,
 			cmd.certificate         => self.certificate,
@@ -366,9 +605,20 @@ impl ConfigBuilder {
 			cmd.log_level           => self.log_level,
 			cmd.restore_audio_state => self.restore_audio_state,
 			cmd.previous_threshold  => self.previous_threshold,
+			cmd.crossfade           => self.crossfade,
+			cmd.crossfade_curve     => self.crossfade_curve,
+			cmd.replay_gain         => self.replay_gain,
+			cmd.broadcast           => self.broadcast,
+			cmd.broadcast_format    => self.broadcast_format,
+			cmd.broadcast_bitrate   => self.broadcast_bitrate,
+			cmd.output              => self.output,
+			cmd.output_wav_path     => self.output_wav_path,
 			cmd.watch               => self.watch,
 			cmd.cache_clean         => self.cache_clean,
 			cmd.cache_time          => self.cache_time,
+			cmd.transcode_cache_size => self.transcode_cache_size,
+			cmd.collection_watch    => self.collection_watch,
+			cmd.collection_path_templates => self.collection_path_templates,
 			cmd.media_controls      => self.media_controls,
 			cmd.authorization       => self.authorization,
 			cmd.confirm_no_tls_auth => self.confirm_no_tls_auth
@@ -376,6 +626,83 @@ impl ConfigBuilder {
 	}
 }
 
+//---------------------------------------------------------------------------------------------------- Serde defaults
+const fn trusted_proxies_default() -> Option<BTreeSet<IpNet>> {
+	Some(BTreeSet::new())
+}
+
+const fn trusted_proxy_header_default() -> Option<ProxyHeader> {
+	Some(ProxyHeader::XForwardedFor)
+}
+
+const fn base_path_default() -> Option<String> {
+	Some(String::new())
+}
+
+const fn rate_limit_default() -> Option<u32> {
+	Some(0)
+}
+
+const fn lockout_attempts_default() -> Option<u32> {
+	Some(0)
+}
+
+const fn lockout_time_default() -> Option<u64> {
+	Some(LOCKOUT_TIME_DEFAULT)
+}
+
+const fn crossfade_default() -> Option<u8> {
+	Some(0)
+}
+
+const fn crossfade_curve_default() -> Option<CrossfadeCurve> {
+	Some(CrossfadeCurve::new())
+}
+
+const fn replay_gain_default() -> Option<ReplayGain> {
+	Some(ReplayGain::new())
+}
+
+const fn broadcast_default() -> Option<bool> {
+	Some(false)
+}
+
+const fn broadcast_format_default() -> Option<Format> {
+	Some(Format::Opus)
+}
+
+const fn broadcast_bitrate_default() -> Option<u16> {
+	Some(BROADCAST_BITRATE_DEFAULT)
+}
+
+fn output_default() -> Option<String> {
+	Some("".to_string())
+}
+
+fn output_wav_path_default() -> Option<PathBuf> {
+	Some(PathBuf::from(""))
+}
+
+const fn transcode_cache_size_default() -> Option<u64> {
+	Some(TRANSCODE_CACHE_SIZE_DEFAULT)
+}
+
+const fn collection_watch_default() -> Option<bool> {
+	Some(false)
+}
+
+const fn collection_path_templates_default() -> Option<Vec<String>> {
+	Some(Vec::new())
+}
+
+const fn roles_default() -> Option<BTreeMap<String, Role>> {
+	Some(BTreeMap::new())
+}
+
+const fn users_default() -> Option<BTreeMap<String, UserConfig>> {
+	Some(BTreeMap::new())
+}
+
 //---------------------------------------------------------------------------------------------------- Config
 /// The actual `struct` we will use for the whole program.
 ///
@@ -384,11 +711,18 @@ impl ConfigBuilder {
 disk::toml!(Config, disk::Dir::Config, FESTIVAL, FRONTEND_SUB_DIR, "festivald");
 #[derive(Clone,Debug,PartialEq,Eq,Serialize,Deserialize)]
 pub struct Config {
-	pub ip:                  std::net::Ipv4Addr,
+	pub ip:                  Vec<IpAddr>,
 	pub port:                u16,
 	pub max_connections:     Option<u64>,
-	pub exclusive_ips:       Option<BTreeSet<Ipv4Addr>>,
+	pub exclusive_ips:       Option<BTreeSet<IpNet>>,
+	pub trusted_proxies:     Option<BTreeSet<IpNet>>,
+	pub trusted_proxy_header: ProxyHeader,
+	pub base_path:           Option<String>,
 	pub sleep_on_fail:       Option<u64>,
+	pub rate_limit_cheap:    Option<u32>,
+	pub rate_limit_expensive: Option<u32>,
+	pub lockout_attempts:    Option<u32>,
+	pub lockout_time:        u64,
 	pub collection_paths:    Vec<PathBuf>,
 	pub tls:                 bool,
 	pub certificate:         Option<PathBuf>,
@@ -400,14 +734,28 @@ pub struct Config {
 	pub log_level:           log::LevelFilter,
 	pub restore_audio_state: bool,
 	pub previous_threshold:  u32,
+	pub crossfade:           u8,
+	pub crossfade_curve:     CrossfadeCurve,
+	pub replay_gain:         ReplayGain,
+	pub broadcast:           bool,
+	pub broadcast_format:    Format,
+	pub broadcast_bitrate:   u16,
+	pub output:              String,
+	pub output_wav_path:     Option<PathBuf>,
 	pub watch:               bool,
 	pub cache_clean:         bool,
 	pub cache_time:          u64,
+	pub transcode_cache_size: u64,
+	pub collection_watch:    bool,
+	pub collection_path_templates: Vec<String>,
 	pub media_controls:      bool,
 	pub confirm_no_tls_auth: bool,
 	pub no_auth_rpc:         Option<BTreeSet<rpc::Method>>,
 	pub no_auth_rest:        Option<BTreeSet<rpc::resource::Resource>>,
 	pub no_auth_docs:        bool,
+	pub roles:               BTreeMap<String, Role>,
+	// Username -> role name, the passwords are only kept in `USERS`.
+	pub users:               BTreeMap<String, String>,
 }
 
 //---------------------------------------------------------------------------------------------------- TESTS
@@ -0,0 +1,386 @@
+// Server-Sent Events for the `/event` endpoint.
+//
+// A single task polls the shared state `Kernel`/`Audio` write
+// to, and turns any changes into `rpc::event::Event`'s which
+// get sent to every connected client.
+//
+// New `Collection`'s are sent by the `Router` directly,
+// right after it swaps the `CollectionPtr`.
+
+//---------------------------------------------------------------------------------------------------- Use
+use anyhow::anyhow;
+use log::{error,info,warn,debug,trace};
+use once_cell::sync::OnceCell;
+use hyper::{
+	Response,
+	body::{Body,Bytes},
+};
+use http::request::Parts;
+use std::net::SocketAddr;
+use std::time::Duration;
+use std::hash::{Hash,Hasher};
+use std::collections::hash_map::DefaultHasher;
+use tokio::sync::broadcast::{
+	Sender,
+	error::RecvError,
+};
+use shukusai::{
+	collection::Collection,
+	state::{
+		AudioState,
+		ResetState,
+		AUDIO_STATE,
+		PLAYLISTS,
+		RESET_STATE,
+	},
+};
+use rpc::event::Event;
+use crate::{
+	ptr::CollectionPtr,
+	resp,
+};
+
+//---------------------------------------------------------------------------------------------------- Constants
+// How often the shared state is checked for changes.
+const POLL: Duration = Duration::from_millis(100);
+
+// How long a client can go without any events before
+// a comment is sent, so that proxies don't close the connection.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+// How many events a client can fall behind before it
+// skips them and gets sent the current state instead.
+const EVENT_QUEUE: usize = 256;
+
+// `text/event-stream` MIME.
+const MIME_EVENT_STREAM: &str = "text/event-stream";
+
+//---------------------------------------------------------------------------------------------------- Statics
+// Where all events get sent to, every `/event` client subscribes to this.
+static EVENTS: OnceCell<Sender<Event>> = OnceCell::new();
+
+//---------------------------------------------------------------------------------------------------- Init
+// Spawn the task that turns state changes into events.
+//
+// This must be called from within the `tokio` runtime.
+pub fn init() {
+	let (to_clients, _) = tokio::sync::broadcast::channel(EVENT_QUEUE);
+
+	if EVENTS.set(to_clients.clone()).is_err() {
+		warn!("Event - already initialized");
+		return;
+	}
+
+	tokio::task::spawn(poll(to_clients));
+}
+
+// Send an event to all clients.
+fn send(to_clients: &Sender<Event>, event: Event) {
+	trace!("Event - {event:?}");
+	// This only errors if there are no clients, which is fine.
+	let _ = to_clients.send(event);
+}
+
+// A new `Collection` is being used.
+//
+// This must not hold onto the `Arc`, see `collection_new()`.
+pub fn collection(collection: &Collection) {
+	if let Some(to_clients) = EVENTS.get() {
+		send(to_clients, collection_event(collection));
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Poll
+// The parts of the state we compare against.
+struct Snapshot {
+	audio:     AudioState,
+	playlists: u64,
+	reset:     ResetState,
+}
+
+impl Snapshot {
+	async fn new() -> Self {
+		// The locks must be dropped before the next `.await`.
+		let audio = crate::rpc::audio_state_low_priority_lock().await.clone();
+		let reset = reset_state_low_priority_lock().await.clone();
+
+		Self {
+			audio,
+			playlists: playlists_hash(),
+			reset,
+		}
+	}
+
+	// Update to the current state, returns the events for what changed.
+	//
+	// The queue can be big, so it is only cloned if it actually changed.
+	async fn update(&mut self) -> Vec<Event> {
+		let mut events = vec![];
+
+		// The lock must be dropped before the next `.await`.
+		{
+			let b = crate::rpc::audio_state_low_priority_lock().await;
+			let a = &mut self.audio;
+
+			if a.song != b.song || a.queue_idx != b.queue_idx || a.runtime != b.runtime {
+				events.push(song_event(&b));
+			}
+			if a.elapsed != b.elapsed {
+				events.push(elapsed_event(&b));
+			}
+			if a.playing != b.playing {
+				events.push(Event::Playing(rpc::event::Playing { playing: b.playing }));
+			}
+			if a.volume != b.volume {
+				events.push(Event::Volume(rpc::event::Volume { volume: b.volume.inner() }));
+			}
+			if a.repeat != b.repeat {
+				events.push(Event::Repeat(rpc::event::Repeat { repeat: b.repeat }));
+			}
+			// Compare the length first, it's cheaper.
+			if a.queue.len() != b.queue.len() || a.queue != b.queue {
+				events.push(queue_event(&b));
+				a.queue.clone_from(&b.queue);
+			}
+
+			a.queue_idx = b.queue_idx;
+			a.playing   = b.playing;
+			a.song      = b.song;
+			a.elapsed   = b.elapsed;
+			a.runtime   = b.runtime;
+			a.repeat    = b.repeat;
+			a.volume    = b.volume;
+		}
+
+		let playlists = playlists_hash();
+		if self.playlists != playlists {
+			self.playlists = playlists;
+			events.push(playlists_event());
+		}
+
+		let reset = reset_state_low_priority_lock().await.clone();
+		if self.reset != reset {
+			events.push(reset_event(&reset));
+			self.reset = reset;
+		}
+
+		events
+	}
+}
+
+async fn poll(to_clients: Sender<Event>) {
+	// `None` while nobody is listening, new clients
+	// get the `current()` state when they connect.
+	let mut snapshot: Option<Snapshot> = None;
+
+	loop {
+		tokio::time::sleep(POLL).await;
+
+		// Nobody is listening, don't bother.
+		if to_clients.receiver_count() == 0 {
+			snapshot = None;
+			continue;
+		}
+
+		match snapshot.as_mut() {
+			Some(s) => {
+				for event in s.update().await {
+					send(&to_clients, event);
+				}
+			},
+			None => snapshot = Some(Snapshot::new().await),
+		}
+	}
+}
+
+// Same as `audio_state_low_priority_lock()`, for `RESET_STATE`.
+async fn reset_state_low_priority_lock() -> std::sync::RwLockReadGuard<'static, ResetState> {
+	loop {
+		if let Ok(lock) = RESET_STATE.try_read() {
+			return lock;
+		}
+
+		tokio::time::sleep(Duration::from_millis(1)).await;
+	}
+}
+
+// `Playlists` can be big, so only a hash is kept around.
+fn playlists_hash() -> u64 {
+	let mut hasher = DefaultHasher::new();
+	PLAYLISTS.read().hash(&mut hasher);
+	hasher.finish()
+}
+
+//---------------------------------------------------------------------------------------------------- Events
+fn song_event(a: &AudioState) -> Event {
+	Event::Song(rpc::event::Song {
+		song_key:  a.song,
+		queue_idx: a.queue_idx,
+		runtime:   a.runtime.inner(),
+	})
+}
+
+fn elapsed_event(a: &AudioState) -> Event {
+	Event::Elapsed(rpc::event::Elapsed {
+		elapsed: a.elapsed.inner(),
+		runtime: a.runtime.inner(),
+	})
+}
+
+fn queue_event(a: &AudioState) -> Event {
+	Event::Queue(rpc::event::Queue {
+		len:  a.queue.len(),
+		keys: a.queue.iter().copied().collect(),
+	})
+}
+
+fn playlists_event() -> Event {
+	let playlists: Vec<String> = PLAYLISTS.read().keys().map(|s| s.to_string()).collect();
+
+	Event::Playlists(rpc::event::Playlists {
+		len: playlists.len(),
+		playlists,
+	})
+}
+
+fn reset_event(r: &ResetState) -> Event {
+	Event::Reset(rpc::event::Reset {
+		resetting: r.resetting,
+		percent:   r.percent.inner(),
+		phase:     r.phase,
+		specific:  r.specific.to_string(),
+	})
+}
+
+fn collection_event(c: &Collection) -> Event {
+	Event::Collection(rpc::event::Collection {
+		empty:        c.empty,
+		timestamp:    c.timestamp,
+		count_artist: c.count_artist.inner(),
+		count_album:  c.count_album.inner(),
+		count_song:   c.count_song.inner(),
+		count_art:    c.count_art.inner(),
+	})
+}
+
+// Every event needed to know the current state,
+// sent when a client connects (or falls behind).
+async fn current(collection: &'static CollectionPtr) -> Vec<Event> {
+	let audio = crate::rpc::audio_state_low_priority_lock().await.clone();
+	let reset = reset_state_low_priority_lock().await.clone();
+
+	vec![
+		collection_event(&collection.arc()),
+		reset_event(&reset),
+		queue_event(&audio),
+		song_event(&audio),
+		elapsed_event(&audio),
+		Event::Playing(rpc::event::Playing { playing: audio.playing }),
+		Event::Volume(rpc::event::Volume { volume: audio.volume.inner() }),
+		Event::Repeat(rpc::event::Repeat { repeat: audio.repeat }),
+		playlists_event(),
+	]
+}
+
+//---------------------------------------------------------------------------------------------------- `/event`
+// Format an event as an SSE message.
+fn sse(event: &Event) -> Option<Bytes> {
+	match event.data() {
+		Ok(data) => Some(Bytes::from(format!("event: {}\ndata: {data}\n\n", event.as_ref()))),
+		Err(e)   => {
+			error!("Event - serialization error: {e}");
+			None
+		},
+	}
+}
+
+// A never-ending `text/event-stream` body of `Event`'s.
+//
+// Authorization works like JSON-RPC: a client only receives
+// the events whose equivalent method (`Event::method()`) it is
+// allowed to call, i.e. it's in its role or in `no_auth_rpc`.
+pub async fn handle(
+	parts:      Parts,
+	addr:       SocketAddr,
+	collection: &'static CollectionPtr,
+) -> Result<Response<Body>, anyhow::Error> {
+	let Some(events) = EVENTS.get() else {
+		return Ok(resp::server_err("Events are not initialized"));
+	};
+
+	// Reject if not a single event is allowed.
+	let caller = crate::user::caller(&parts).await;
+	if !Event::METHODS.iter().any(|m| caller.rpc(m)) {
+		if !caller.anonymous() {
+			return Ok(resp::forbidden("Forbidden"));
+		}
+		crate::limit::fail(&addr, &parts);
+		if crate::seen::seen(&addr).await {
+			crate::router::sleep_on_fail().await;
+		}
+		return Ok(resp::unauthorized("Unauthorized"));
+	}
+	crate::seen::user(&addr, caller).await;
+
+	// Check rate limit.
+	if let Some(secs) = crate::limit::throttle(&addr, caller, crate::limit::Cost::Cheap) {
+		return Ok(resp::too_many_requests("Too many requests", secs));
+	}
+
+	let mut from_poll = events.subscribe();
+	let (mut sender, body) = Body::channel();
+
+	tokio::task::spawn(async move {
+		let allowed = |e: &Event| caller.rpc(&e.method());
+
+		macro_rules! send_or_break {
+			($bytes:expr) => {
+				// The client disconnected.
+				if sender.send_data($bytes).await.is_err() {
+					break;
+				}
+			}
+		}
+
+		'outer: loop {
+			// Send the current state, on connect and after falling behind.
+			for event in current(collection).await.iter().filter(|e| allowed(e)) {
+				if let Some(bytes) = sse(event) {
+					if sender.send_data(bytes).await.is_err() {
+						break 'outer;
+					}
+				}
+			}
+
+			loop {
+				tokio::select! {
+					event = from_poll.recv() => match event {
+						Ok(event) => {
+							if !allowed(&event) {
+								continue;
+							}
+							if let Some(bytes) = sse(&event) {
+								send_or_break!(bytes);
+							}
+						},
+						Err(RecvError::Lagged(n)) => {
+							trace!("Event - client lagged by {n} events, re-sending current state");
+							continue 'outer;
+						},
+						Err(RecvError::Closed) => break 'outer,
+					},
+
+					_ = tokio::time::sleep(KEEP_ALIVE) => {
+						send_or_break!(Bytes::from_static(b": keep-alive\n\n"));
+					},
+				}
+			}
+
+			break;
+		}
+
+		debug!("Event - client disconnected");
+	});
+
+	Ok(resp::rest_live(body, MIME_EVENT_STREAM))
+}
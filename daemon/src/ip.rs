@@ -0,0 +1,380 @@
+//---------------------------------------------------------------------------------------------------- Use
+use anyhow::anyhow;
+use serde::{Serialize,Serializer,Deserialize,Deserializer};
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+use std::str::FromStr;
+use http::header::{HeaderMap,FORWARDED};
+use strum::{Display,EnumString,IntoStaticStr};
+
+//---------------------------------------------------------------------------------------------------- Ips
+/// One or multiple IP addresses to bind to.
+///
+/// This is so the config can be either the old
+/// `ip = "127.0.0.1"` or `ip = ["127.0.0.1", "::1"]`.
+#[derive(Clone,Debug,PartialEq,Eq,Serialize,Deserialize)]
+#[serde(untagged)]
+pub enum Ips {
+	One(IpAddr),
+	Many(Vec<IpAddr>),
+}
+
+impl Ips {
+	/// Every IP, duplicates removed.
+	pub fn into_vec(self) -> Vec<IpAddr> {
+		let mut vec = match self {
+			Self::One(ip)    => vec![ip],
+			Self::Many(vec)  => vec,
+		};
+		let mut seen = std::collections::HashSet::new();
+		vec.retain(|ip| seen.insert(*ip));
+		vec
+	}
+}
+
+impl From<Vec<IpAddr>> for Ips {
+	fn from(mut vec: Vec<IpAddr>) -> Self {
+		if vec.len() == 1 {
+			Self::One(vec.remove(0))
+		} else {
+			Self::Many(vec)
+		}
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- IpNet
+/// An IPv4 or IPv6 CIDR range, e.g: `192.168.0.0/16`, `fd00::/8`.
+///
+/// An IP without a prefix length (`127.0.0.1`) is only that IP.
+#[derive(Copy,Clone,Debug,Hash,PartialEq,Eq,PartialOrd,Ord)]
+pub struct IpNet {
+	addr:   IpAddr,
+	prefix: u8,
+}
+
+impl IpNet {
+	/// If `ip` is within this range.
+	///
+	/// IPv4-mapped IPv6 addresses (`::ffff:127.0.0.1`)
+	/// are treated as their IPv4 address.
+	pub fn contains(&self, ip: &IpAddr) -> bool {
+		match (self.addr, canonical(*ip)) {
+			(IpAddr::V4(net), IpAddr::V4(ip)) => {
+				let shift = u32::from(32 - self.prefix);
+				u32::from(net).checked_shr(shift).unwrap_or(0) == u32::from(ip).checked_shr(shift).unwrap_or(0)
+			},
+			(IpAddr::V6(net), IpAddr::V6(ip)) => {
+				let shift = u32::from(128 - self.prefix);
+				u128::from(net).checked_shr(shift).unwrap_or(0) == u128::from(ip).checked_shr(shift).unwrap_or(0)
+			},
+			_ => false,
+		}
+	}
+
+	/// If this is `0.0.0.0` or `::`, which
+	/// in `exclusive_ips` means every IP.
+	pub fn is_unspecified(&self) -> bool {
+		self.addr.is_unspecified()
+	}
+
+	const fn max_prefix(addr: &IpAddr) -> u8 {
+		match addr {
+			IpAddr::V4(_) => 32,
+			IpAddr::V6(_) => 128,
+		}
+	}
+}
+
+impl From<IpAddr> for IpNet {
+	fn from(addr: IpAddr) -> Self {
+		Self { addr, prefix: Self::max_prefix(&addr) }
+	}
+}
+
+impl FromStr for IpNet {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (addr, prefix) = match s.trim().split_once('/') {
+			Some((a, p)) => (a, Some(p)),
+			None         => (s.trim(), None),
+		};
+
+		let Ok(addr) = addr.parse::<IpAddr>() else {
+			return Err(anyhow!("invalid IP address: {addr}"));
+		};
+		let max = Self::max_prefix(&addr);
+
+		let prefix = match prefix {
+			None => max,
+			Some(p) => match p.parse::<u8>() {
+				Ok(p) if p <= max => p,
+				_ => return Err(anyhow!("invalid prefix length: {p}, must be 0..={max}")),
+			},
+		};
+
+		Ok(Self { addr, prefix })
+	}
+}
+
+impl std::fmt::Display for IpNet {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if self.prefix == Self::max_prefix(&self.addr) {
+			write!(f, "{}", self.addr)
+		} else {
+			write!(f, "{}/{}", self.addr, self.prefix)
+		}
+	}
+}
+
+impl Serialize for IpNet {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for IpNet {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = String::deserialize(deserializer)?;
+		Self::from_str(&s).map_err(serde::de::Error::custom)
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- ProxyHeader
+/// The header trusted proxies put the client IP in.
+///
+/// Only this header is read, the other one is ignored, as a proxy
+/// setting one usually passes the other through from the client untouched.
+#[derive(Copy,Clone,Debug,Default,PartialEq,Eq,Serialize,Deserialize,Display,EnumString,IntoStaticStr)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ProxyHeader {
+	/// `X-Forwarded-For: 192.0.2.60, 2001:db8::1`
+	#[default]
+	XForwardedFor,
+	/// RFC 7239: `Forwarded: for=192.0.2.60;proto=http, for="[2001:db8::1]:4711"`
+	Forwarded,
+}
+
+//---------------------------------------------------------------------------------------------------- Free functions
+/// Turn IPv4-mapped IPv6 addresses (`::ffff:127.0.0.1`) into IPv4.
+///
+/// Dual-stack listeners see IPv4 clients as these.
+pub fn canonical(ip: IpAddr) -> IpAddr {
+	match ip {
+		IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+			Some(v4) => IpAddr::V4(v4),
+			None     => ip,
+		},
+		ip => ip,
+	}
+}
+
+/// The real client IP of a connection from `peer`.
+///
+/// If `peer` is a trusted proxy, the chain in the `header` is walked
+/// from right to left, skipping trusted proxies, until the first
+/// untrusted IP, which is the client.
+///
+/// Headers from untrusted peers are ignored, as anyone can set them.
+///
+/// Returns `None` if the chain hits a hop that isn't an IP (`unknown`, `_hidden`)
+/// before an untrusted IP, as the client can't be known, and the
+/// trusted proxy in front of it shouldn't be mistaken for the client.
+pub fn client(peer: IpAddr, headers: &HeaderMap, trusted: &BTreeSet<IpNet>, header: ProxyHeader) -> Option<IpAddr> {
+	let peer = canonical(peer);
+	if !trusted.iter().any(|net| net.contains(&peer)) {
+		return Some(peer);
+	}
+
+	let mut client = peer;
+	for hop in forwarded_chain(headers, header).into_iter().rev() {
+		client = canonical(hop?);
+		if !trusted.iter().any(|net| net.contains(&client)) {
+			break;
+		}
+	}
+
+	Some(client)
+}
+
+// Every hop in `header`, left to right.
+// Hops that aren't IPs (`unknown`, `_hidden`) are `None`.
+fn forwarded_chain(headers: &HeaderMap, header: ProxyHeader) -> Vec<Option<IpAddr>> {
+	let values = |name: &str| headers
+		.get_all(name)
+		.iter()
+		.filter_map(|v| v.to_str().ok())
+		.flat_map(|v| v.split(','))
+		.collect::<Vec<&str>>();
+
+	match header {
+		ProxyHeader::XForwardedFor => {
+			values("x-forwarded-for")
+				.into_iter()
+				.map(parse_node)
+				.collect()
+		},
+
+		ProxyHeader::Forwarded => {
+			values(FORWARDED.as_str())
+				.into_iter()
+				.map(|element| {
+					element
+						.split(';')
+						.filter_map(|pair| pair.trim().split_once('='))
+						.find(|(k, _)| k.trim().eq_ignore_ascii_case("for"))
+						.and_then(|(_, v)| parse_node(v))
+				})
+				.collect()
+		},
+	}
+}
+
+// Parse a single forwarded node, with optional quotes, brackets and port.
+fn parse_node(node: &str) -> Option<IpAddr> {
+	let node = node.trim().trim_matches('"');
+
+	if let Ok(ip) = node.parse() {
+		return Some(ip);
+	}
+
+	// `[2001:db8::1]:4711`
+	if let Some(rest) = node.strip_prefix('[') {
+		return rest.split_once(']')?.0.parse().ok();
+	}
+
+	// `192.0.2.60:4711`
+	node.split_once(':')?.0.parse().ok()
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn net(s: &str) -> IpNet {
+		s.parse().unwrap()
+	}
+
+	fn ip(s: &str) -> IpAddr {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn parse() {
+		assert_eq!(net("127.0.0.1").to_string(), "127.0.0.1");
+		assert_eq!(net("127.0.0.1/32").to_string(), "127.0.0.1");
+		assert_eq!(net("192.168.0.0/16").to_string(), "192.168.0.0/16");
+		assert_eq!(net("fd00::/8").to_string(), "fd00::/8");
+		assert!("192.168.0.0/33".parse::<IpNet>().is_err());
+		assert!("fd00::/129".parse::<IpNet>().is_err());
+		assert!("hello/8".parse::<IpNet>().is_err());
+	}
+
+	#[test]
+	fn contains() {
+		assert!(net("192.168.0.0/16").contains(&ip("192.168.2.10")));
+		assert!(!net("192.168.0.0/16").contains(&ip("192.169.0.1")));
+		assert!(net("127.0.0.1").contains(&ip("127.0.0.1")));
+		assert!(!net("127.0.0.1").contains(&ip("127.0.0.2")));
+		assert!(net("0.0.0.0/0").contains(&ip("1.2.3.4")));
+		assert!(!net("0.0.0.0/0").contains(&ip("::1")));
+
+		assert!(net("fd00::/8").contains(&ip("fd12:3456::1")));
+		assert!(!net("fd00::/8").contains(&ip("fe80::1")));
+		assert!(net("::/0").contains(&ip("::1")));
+
+		// IPv4-mapped.
+		assert!(net("127.0.0.0/8").contains(&ip("::ffff:127.0.0.1")));
+	}
+
+	#[test]
+	fn ips() {
+		#[derive(Deserialize)]
+		struct T { ip: Ips }
+
+		let t: T = toml_edit::de::from_str(r#"ip = "127.0.0.1""#).unwrap();
+		assert_eq!(t.ip.into_vec(), [ip("127.0.0.1")]);
+
+		let t: T = toml_edit::de::from_str(r#"ip = ["127.0.0.1", "::1", "127.0.0.1"]"#).unwrap();
+		assert_eq!(t.ip.into_vec(), [ip("127.0.0.1"), ip("::1")]);
+	}
+
+	#[test]
+	fn client() {
+		use http::header::HeaderValue;
+
+		let trusted = BTreeSet::from([net("127.0.0.1"), net("10.0.0.0/8")]);
+		let headers = |values: &[(&'static str, &'static str)]| {
+			let mut h = HeaderMap::new();
+			for (name, value) in values {
+				h.append(*name, HeaderValue::from_static(value));
+			}
+			h
+		};
+		let xff = |peer, h: &HeaderMap| super::client(ip(peer), h, &trusted, ProxyHeader::XForwardedFor);
+		let fwd = |peer, h: &HeaderMap| super::client(ip(peer), h, &trusted, ProxyHeader::Forwarded);
+
+		// Untrusted peer, header ignored.
+		let h = headers(&[("x-forwarded-for", "1.1.1.1")]);
+		assert_eq!(xff("2.2.2.2", &h), Some(ip("2.2.2.2")));
+
+		// Trusted peer, trusted hops skipped.
+		let h = headers(&[("x-forwarded-for", "6.6.6.6, 1.1.1.1, 10.0.0.5")]);
+		assert_eq!(xff("127.0.0.1", &h), Some(ip("1.1.1.1")));
+
+		// No header.
+		assert_eq!(xff("::ffff:127.0.0.1", &HeaderMap::new()), Some(ip("127.0.0.1")));
+
+		// `Forwarded`, with quotes, brackets and ports.
+		let h = headers(&[("forwarded", r#"for=1.1.1.1:80;proto=https, for="[2001:db8::1]:4711""#)]);
+		assert_eq!(fwd("127.0.0.1", &h), Some(ip("2001:db8::1")));
+
+		// Obfuscated, the trusted proxy isn't the client.
+		let h = headers(&[("forwarded", "for=1.1.1.1, for=_hidden")]);
+		assert_eq!(fwd("127.0.0.1", &h), None);
+		let h = headers(&[("x-forwarded-for", "unknown")]);
+		assert_eq!(xff("127.0.0.1", &h), None);
+
+		// Obfuscated, but after the client.
+		let h = headers(&[("x-forwarded-for", "unknown, 1.1.1.1")]);
+		assert_eq!(xff("127.0.0.1", &h), Some(ip("1.1.1.1")));
+	}
+
+	#[test]
+	fn client_spoofed() {
+		use http::header::HeaderValue;
+
+		// An `X-Forwarded-For` only proxy, passing
+		// through the client's own `Forwarded` header.
+		let trusted = BTreeSet::from([net("127.0.0.1")]);
+		let mut h = HeaderMap::new();
+		h.insert("forwarded", HeaderValue::from_static("for=10.10.10.10"));
+		h.insert("x-forwarded-for", HeaderValue::from_static("1.1.1.1"));
+
+		let client = super::client(ip("127.0.0.1"), &h, &trusted, ProxyHeader::XForwardedFor);
+		assert_eq!(client, Some(ip("1.1.1.1")));
+
+		// `for=unknown` doesn't turn into the proxy either.
+		h.insert("forwarded", HeaderValue::from_static("for=unknown"));
+		let client = super::client(ip("127.0.0.1"), &h, &trusted, ProxyHeader::XForwardedFor);
+		assert_eq!(client, Some(ip("1.1.1.1")));
+	}
+
+	#[test]
+	fn proxy_header() {
+		assert_eq!("x-forwarded-for".parse::<ProxyHeader>().unwrap(), ProxyHeader::XForwardedFor);
+		assert_eq!("forwarded".parse::<ProxyHeader>().unwrap(), ProxyHeader::Forwarded);
+		assert_eq!(ProxyHeader::XForwardedFor.to_string(), "x-forwarded-for");
+		assert_eq!(serde_json::to_string(&ProxyHeader::Forwarded).unwrap(), r#""forwarded""#);
+	}
+
+	#[test]
+	fn serde() {
+		let json = r#"["10.0.0.0/8","::1"]"#;
+		let nets: Vec<IpNet> = serde_json::from_str(json).unwrap();
+		assert_eq!(nets, [net("10.0.0.0/8"), net("::1")]);
+		assert_eq!(serde_json::to_string(&nets).unwrap(), json);
+	}
+}
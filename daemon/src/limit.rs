@@ -0,0 +1,450 @@
+//---------------------------------------------------------------------------------------------------- Use
+use log::{info,trace};
+use std::collections::BTreeMap;
+use std::net::{IpAddr,Ipv6Addr,SocketAddr};
+use std::sync::Mutex;
+use std::time::{Duration,Instant};
+use benri::lock;
+use http::{
+	header::AUTHORIZATION,
+	request::Parts,
+};
+use rpc::resource::Resource;
+use crate::config::config;
+use crate::user::Caller;
+
+//---------------------------------------------------------------------------------------------------- Constants
+// Past this many clients, idle ones get removed,
+// at most once every `PRUNE_EVERY` so a full map
+// doesn't get walked on every request.
+const PRUNE_LEN: usize = 1024;
+const PRUNE_EVERY: Duration = Duration::from_secs(10);
+
+// New clients past this many are throttled
+// until enough of the others go idle.
+const MAX_LEN: usize = 65536;
+
+// An empty bucket is full again after a minute.
+const REFILL: Duration = Duration::from_secs(60);
+
+//---------------------------------------------------------------------------------------------------- Statics
+// Rate limit and failed authorization state of all clients.
+static CLIENTS: Mutex<Clients> = Mutex::new(Clients { map: BTreeMap::new(), pruned: None });
+
+//---------------------------------------------------------------------------------------------------- Clients
+struct Clients {
+	map: BTreeMap<Client, State>,
+	// When idle clients were last removed.
+	pruned: Option<Instant>,
+}
+
+impl Clients {
+	// Remove idle clients, if there are enough
+	// of them and it has been `PRUNE_EVERY`.
+	fn prune(&mut self, now: Instant) {
+		if self.map.len() <= PRUNE_LEN || self.pruned.is_some_and(|t| now.saturating_duration_since(t) < PRUNE_EVERY) {
+			return;
+		}
+
+		let len    = self.map.len();
+		let forget = forget();
+		self.map.retain(|_, s| !s.idle(now, forget));
+		self.pruned = Some(now);
+		trace!("Limit - pruned [{}] idle clients", len - self.map.len());
+	}
+
+	// The state of this client, seen at `now`.
+	//
+	// `None` if it's a new client and there are already `MAX_LEN`.
+	fn state(&mut self, client: Client, now: Instant) -> Option<&mut State> {
+		self.prune(now);
+
+		if self.map.len() >= MAX_LEN && !self.map.contains_key(&client) {
+			return None;
+		}
+
+		let state = self.map.entry(client).or_insert_with(|| State::new(now));
+		if state.idle(now, forget()) {
+			*state = State::new(now);
+		}
+		state.seen = now;
+		Some(state)
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Cost
+/// How expensive a request is, each has its own rate limit.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+pub enum Cost {
+	/// `rate_limit_cheap`, e.g: `state_*`, `toggle`, `/art`.
+	Cheap,
+	/// `rate_limit_expensive`, e.g: `collection_new`, `collection_full`, `/artist` ZIPs.
+	Expensive,
+}
+
+impl Cost {
+	/// The cost of a JSON-RPC method.
+	pub const fn rpc(method: &rpc::Method) -> Self {
+		use rpc::Method::*;
+		match method {
+			CollectionNew          |
+			CollectionFull         |
+			CollectionFullArtists  |
+			CollectionFullAlbums   |
+			CollectionFullSongs    |
+			CollectionEntries      |
+			CollectionHealth       |
+			CollectionResourceSize |
+			DaemonRemoveCache      |
+			DaemonSave             |
+			PlaylistFull           |
+			PlaylistImport         => Self::Expensive,
+			_ => Self::Cheap,
+		}
+	}
+
+	/// The cost of a REST resource.
+	pub const fn rest(resource: &Resource) -> Self {
+		match resource {
+			// ZIPs and transcoding.
+			Resource::Collection |
+			Resource::Playlist   |
+			Resource::Artist     |
+			Resource::Album      |
+			Resource::Transcode  => Self::Expensive,
+			Resource::Song       |
+			Resource::Art        |
+			Resource::Stream     => Self::Cheap,
+		}
+	}
+
+	// Requests per minute, `None` if unlimited.
+	fn limit(self) -> Option<u32> {
+		match self {
+			Self::Cheap     => config().rate_limit_cheap,
+			Self::Expensive => config().rate_limit_expensive,
+		}
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Client
+// Authenticated clients are limited per user (or token), everyone else per IP.
+//
+// Tokens are keyed by ID, not label, labels are free-form
+// and could be shared with a username or another token.
+//
+// IPv6 clients are keyed by their /64, a single host
+// usually gets a whole one and could hop between its IPs.
+#[derive(Copy,Clone,Debug,PartialEq,Eq,PartialOrd,Ord)]
+enum Client {
+	Ip(IpAddr),
+	User(&'static str),
+	Token(&'static str),
+}
+
+impl Client {
+	// The client for an IP.
+	fn ip(ip: IpAddr) -> Self {
+		match crate::ip::canonical(ip) {
+			IpAddr::V6(v6) => Self::Ip(IpAddr::V6(Ipv6Addr::from(u128::from(v6) & !u128::from(u64::MAX)))),
+			ip => Self::Ip(ip),
+		}
+	}
+
+	// The client for an authenticated caller.
+	fn from_caller(caller: Caller) -> Option<Self> {
+		match caller {
+			Caller::Token(t) => Some(Self::Token(&t.id)),
+			_ => caller.name().map(Self::User),
+		}
+	}
+}
+
+impl std::fmt::Display for Client {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Ip(IpAddr::V6(ip)) => write!(f, "{ip}/64"),
+			Self::Ip(ip)             => write!(f, "{ip}"),
+			Self::User(name)         => write!(f, "{name}"),
+			Self::Token(id)          => write!(f, "token:{id}"),
+		}
+	}
+}
+
+#[derive(Copy,Clone,Debug)]
+struct State {
+	cheap:     Option<Bucket>,
+	expensive: Option<Bucket>,
+	// Failed authorizations in a row, IPs only.
+	failures:  u32,
+	locked_until: Option<Instant>,
+	// The last request.
+	seen:      Instant,
+}
+
+impl State {
+	fn new(now: Instant) -> Self {
+		Self {
+			cheap:        None,
+			expensive:    None,
+			failures:     0,
+			locked_until: None,
+			seen:         now,
+		}
+	}
+
+	fn bucket(&mut self, cost: Cost) -> &mut Option<Bucket> {
+		match cost {
+			Cost::Cheap     => &mut self.cheap,
+			Cost::Expensive => &mut self.expensive,
+		}
+	}
+
+	fn locked(&self, now: Instant) -> bool {
+		self.locked_until.is_some_and(|t| t > now)
+	}
+
+	fn throttled(&self, now: Instant) -> bool {
+		[(self.cheap, Cost::Cheap), (self.expensive, Cost::Expensive)]
+			.into_iter()
+			.any(|(b, cost)| match (b, cost.limit()) {
+				(Some(b), Some(limit)) => b.tokens(limit, now) < 1.0,
+				_ => false,
+			})
+	}
+
+	// Nothing worth remembering after `forget()` without a request.
+	fn idle(&self, now: Instant, forget: Duration) -> bool {
+		!self.locked(now) && now.saturating_duration_since(self.seen) >= forget
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Bucket
+// A token bucket that holds up to `limit` tokens,
+// and refills `limit` tokens per minute.
+#[derive(Copy,Clone,Debug,PartialEq)]
+struct Bucket {
+	tokens: f64,
+	last:   Instant,
+}
+
+impl Bucket {
+	// A full bucket.
+	fn new(limit: u32, now: Instant) -> Self {
+		Self { tokens: f64::from(limit), last: now }
+	}
+
+	// The amount of tokens at `now`.
+	fn tokens(&self, limit: u32, now: Instant) -> f64 {
+		let limit = f64::from(limit);
+		let secs  = now.saturating_duration_since(self.last).as_secs_f64();
+		(self.tokens + secs * limit / 60.0).min(limit)
+	}
+
+	// Take a token, else return the seconds until there is one.
+	fn take(&mut self, limit: u32, now: Instant) -> Result<(), u64> {
+		self.tokens = self.tokens(limit, now);
+		self.last   = now;
+
+		if self.tokens >= 1.0 {
+			self.tokens -= 1.0;
+			Ok(())
+		} else {
+			Err(((1.0 - self.tokens) * 60.0 / f64::from(limit)).ceil() as u64)
+		}
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Free functions
+// How long a client is remembered without a request.
+//
+// Buckets are full again after `REFILL`, and failed
+// authorizations are forgotten after `lockout_time`.
+fn forget() -> Duration {
+	REFILL.max(Duration::from_secs(config().lockout_time))
+}
+
+/// If this IP is locked out from too many failed
+/// authorizations, returns the seconds left.
+pub fn locked(addr: &SocketAddr) -> Option<u64> {
+	let now = Instant::now();
+	let clients = lock!(CLIENTS);
+	let until = clients.map.get(&Client::ip(addr.ip()))?.locked_until?;
+
+	if until > now {
+		Some(until.duration_since(now).as_secs().max(1))
+	} else {
+		None
+	}
+}
+
+/// A failed authorization from this IP.
+///
+/// Only counted if an `authorization` header was actually sent,
+/// clients that didn't try to authenticate don't get locked out.
+pub fn fail(addr: &SocketAddr, parts: &Parts) {
+	let Some(attempts) = config().lockout_attempts else {
+		return;
+	};
+	if !parts.headers.contains_key(AUTHORIZATION) {
+		return;
+	}
+
+	let ip = Client::ip(addr.ip());
+	let mut clients = lock!(CLIENTS);
+	// Too many clients, this request is rejected anyway.
+	let Some(state) = clients.state(ip, Instant::now()) else {
+		return;
+	};
+
+	state.failures += 1;
+	trace!("Limit - [{ip}] failed authorization [{}/{attempts}]", state.failures);
+
+	if state.failures >= attempts {
+		info!("Limit - [{ip}] locked out for [{}] seconds", config().lockout_time);
+		state.failures = 0;
+		state.locked_until = Some(Instant::now() + Duration::from_secs(config().lockout_time));
+	}
+}
+
+/// Take a request from this caller's rate limit.
+///
+/// If throttled, returns the seconds until the next request is allowed.
+///
+/// This also resets the failed authorizations of an authenticated caller's IP.
+pub fn throttle(addr: &SocketAddr, caller: Caller, cost: Cost) -> Option<u64> {
+	let limit = cost.limit();
+	let user  = Client::from_caller(caller);
+
+	if limit.is_none() && user.is_none() {
+		return None;
+	}
+
+	let now = Instant::now();
+	let ip  = Client::ip(addr.ip());
+	let mut clients = lock!(CLIENTS);
+
+	if user.is_some() {
+		if let Some(state) = clients.map.get_mut(&ip) {
+			state.failures = 0;
+		}
+	}
+
+	let limit = limit?;
+	let client = user.unwrap_or(ip);
+
+	let Some(state) = clients.state(client, now) else {
+		info!("Limit - [{MAX_LEN}] clients reached, throttling [{client}]");
+		return Some(PRUNE_EVERY.as_secs());
+	};
+
+	let bucket = state.bucket(cost).get_or_insert_with(|| Bucket::new(limit, now));
+
+	match bucket.take(limit, now) {
+		Ok(())  => None,
+		Err(secs) => {
+			trace!("Limit - [{client}] throttled for [{secs}] seconds");
+			Some(secs)
+		},
+	}
+}
+
+/// All currently throttled clients, IPs, usernames or `token:<ID>`.
+pub fn throttled() -> Vec<String> {
+	let now = Instant::now();
+	lock!(CLIENTS)
+		.map
+		.iter()
+		.filter(|(_, s)| s.throttled(now))
+		.map(|(c, _)| c.to_string())
+		.collect()
+}
+
+/// All currently locked out IPs, IPv6 as their /64 network.
+pub fn locked_ips() -> Vec<IpAddr> {
+	let now = Instant::now();
+	lock!(CLIENTS)
+		.map
+		.iter()
+		.filter_map(|(c, s)| match c {
+			Client::Ip(ip) if s.locked(now) => Some(*ip),
+			_ => None,
+		})
+		.collect()
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bucket() {
+		let now = Instant::now();
+		let mut b = Bucket::new(2, now);
+
+		assert_eq!(b.take(2, now), Ok(()));
+		assert_eq!(b.take(2, now), Ok(()));
+		// 2 per minute, 1 token every 30 seconds.
+		assert_eq!(b.take(2, now), Err(30));
+
+		let later = now + Duration::from_secs(15);
+		assert_eq!(b.take(2, later), Err(15));
+
+		let later = now + Duration::from_secs(30);
+		assert_eq!(b.take(2, later), Ok(()));
+
+		// Never more than the limit.
+		let later = now + Duration::from_secs(3600);
+		assert_eq!(b.tokens(2, later), 2.0);
+	}
+
+	#[test]
+	fn cost() {
+		assert_eq!(Cost::rpc(&rpc::Method::StateAudio), Cost::Cheap);
+		assert_eq!(Cost::rpc(&rpc::Method::CollectionNew), Cost::Expensive);
+		assert_eq!(Cost::rest(&Resource::Art), Cost::Cheap);
+		assert_eq!(Cost::rest(&Resource::Artist), Cost::Expensive);
+	}
+
+	#[test]
+	fn client() {
+		// A token labeled the same as a user doesn't share its buckets.
+		assert_ne!(Client::Token("alice"), Client::User("alice"));
+		assert_eq!(Client::Token("0123").to_string(), "token:0123");
+
+		assert_eq!(Client::from_caller(Caller::Master("alice")), Some(Client::User("alice")));
+		assert_eq!(Client::from_caller(Caller::Unrestricted), None);
+		assert_eq!(Client::from_caller(Caller::Anonymous), None);
+	}
+
+	#[test]
+	fn client_ip() {
+		let ip = |s: &str| Client::ip(s.parse().unwrap());
+
+		assert_eq!(ip("192.168.2.10"), Client::Ip("192.168.2.10".parse().unwrap()));
+		// IPv4-mapped IPv6 is the IPv4 client.
+		assert_eq!(ip("::ffff:192.168.2.10"), ip("192.168.2.10"));
+		// The whole /64 is one client.
+		assert_eq!(ip("2001:db8::1"), ip("2001:db8::ffff:ffff:ffff:ffff"));
+		assert_ne!(ip("2001:db8::1"), ip("2001:db8:0:1::1"));
+		assert_eq!(ip("2001:db8::1").to_string(), "2001:db8::/64");
+	}
+
+	#[test]
+	fn idle() {
+		let now    = Instant::now();
+		let forget = Duration::from_secs(60);
+		let mut s  = State::new(now);
+
+		// Failures don't keep a client around forever.
+		s.failures = 1;
+		assert!(!s.idle(now, forget));
+		assert!(s.idle(now + forget, forget));
+
+		// Locked out clients stay until the lockout ends.
+		s.locked_until = Some(now + forget * 2);
+		assert!(!s.idle(now + forget, forget));
+		assert!(s.idle(now + forget * 2, forget));
+	}
+}
@@ -1,17 +1,26 @@
+mod broadcast;
 mod cert;
 mod cli;
 mod config;
 mod constants;
 mod docs;
+mod event;
+mod ip;
+mod limit;
 mod macros;
 mod ptr;
+mod range;
 mod seen;
 mod statics;
 mod shutdown;
+mod token;
+mod transcode;
 mod rest;
 mod resp;
 mod rpc;
 mod router;
+mod user;
+mod watch;
 mod zip;
 
 fn main() {
@@ -48,6 +57,26 @@ fn main() {
 		std::process::exit(0);
 	}
 
+	// Select the audio output, this must be done before `Kernel` spawns `Audio`.
+	shukusai::audio::select_output(&CONFIG.output);
+	if let Some(path) = &CONFIG.output_wav_path {
+		shukusai::audio::set_wav_path(path.clone());
+	}
+
+	// Set the folder-structure fallback, this must be done before `Kernel` creates a `Collection`.
+	// SAFETY: unwrap is okay, the templates were checked in `build_and_set()`.
+	shukusai::template::set_path_templates(
+		CONFIG.collection_path_templates
+			.iter()
+			.map(|t| shukusai::template::PathTemplate::new(t).unwrap())
+			.collect()
+	);
+
+	// Start the broadcast encoder, this must be done before `Kernel` spawns `Audio`.
+	if CONFIG.broadcast {
+		crate::broadcast::init(CONFIG.broadcast_format, CONFIG.broadcast_bitrate);
+	}
+
 	// Setup `Kernel` <-> `Frontend` channels.
 	let (to_kernel, from_kernel) = match shukusai::kernel::Kernel::spawn(!disable_watch, !disable_media_controls) {
 		Ok((t, f)) => (t, f),
@@ -66,6 +95,11 @@ fn main() {
 	}
 	// Set the default `previous` audio threshold.
 	benri::atomic_store!(shukusai::audio::PREVIOUS_THRESHOLD, CONFIG.previous_threshold);
+	// Set the default crossfade.
+	let crossfade = shukusai::audio::Crossfade::new(CONFIG.crossfade, CONFIG.crossfade_curve);
+	benri::send!(TO_KERNEL, shukusai::kernel::FrontendToKernel::Crossfade(crossfade));
+	// Set the default loudness normalization.
+	benri::send!(TO_KERNEL, shukusai::kernel::FrontendToKernel::ReplayGain(CONFIG.replay_gain));
 
 	// Create documentation.
 	if CONFIG.docs {
@@ -91,6 +125,12 @@ fn main() {
 		log::info!("festivald ... Skipping cache clean");
 	}
 
+	// Load bearer tokens.
+	match crate::token::init() {
+		Ok(n)  => log::info!("festivald ... Tokens: {n}"),
+		Err(e) => crate::exit!("Could not load tokens: {e}"),
+	}
+
 	// Start HTTP router.
 	crate::router::init(CONFIG, TO_KERNEL, FROM_KERNEL);
 }
@@ -0,0 +1,306 @@
+// HTTP `Range` requests (RFC 7233) and conditional `GET` (RFC 7232).
+//
+// This only parses headers, the actual
+// file serving is done in `rest.rs`.
+
+//---------------------------------------------------------------------------------------------------- Use
+use hyper::header::{
+	HeaderMap,
+	HeaderValue,
+	ACCEPT_RANGES,
+	ETAG,
+	IF_MODIFIED_SINCE,
+	IF_NONE_MATCH,
+	IF_RANGE,
+	LAST_MODIFIED,
+	RANGE,
+};
+use std::ops::Range;
+use std::time::{Duration,SystemTime,UNIX_EPOCH};
+
+//---------------------------------------------------------------------------------------------------- Constants
+// More ranges than this in a single request
+// is ignored and the whole file is sent instead.
+//
+// Real clients never need this many, and a request
+// with thousands of tiny ranges is a cheap way to
+// make us do a lot of work.
+const MAX_RANGES: usize = 16;
+
+//---------------------------------------------------------------------------------------------------- Validator
+// The `ETag` and `Last-Modified` of a file.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct Validator {
+	pub etag:          String,
+	pub last_modified: SystemTime,
+}
+
+impl Validator {
+	// Create from the file's length and modification time.
+	//
+	// Returns `None` if the OS doesn't give us a modification time.
+	pub fn new(md: &std::fs::Metadata) -> Option<Self> {
+		let modified = md.modified().ok()?;
+		let since    = modified.duration_since(UNIX_EPOCH).ok()?;
+
+		Some(Self {
+			etag:          format!(r#""{:x}-{:x}""#, md.len(), since.as_nanos()),
+			// HTTP dates only have second precision.
+			last_modified: UNIX_EPOCH + Duration::from_secs(since.as_secs()),
+		})
+	}
+
+	// Add `ETag`, `Last-Modified` and `Accept-Ranges` to a response.
+	pub fn insert(&self, headers: &mut HeaderMap) {
+		headers.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+		if let Ok(etag) = HeaderValue::from_str(&self.etag) {
+			headers.insert(ETAG, etag);
+		}
+
+		if let Ok(date) = HeaderValue::from_str(&httpdate::fmt_http_date(self.last_modified)) {
+			headers.insert(LAST_MODIFIED, date);
+		}
+	}
+
+	// If the client already has this exact file, i.e, we should respond with `304`.
+	//
+	// `If-None-Match` takes precedence over `If-Modified-Since`.
+	pub fn not_modified(&self, headers: &HeaderMap) -> bool {
+		if let Some(value) = headers.get(IF_NONE_MATCH) {
+			let Ok(value) = value.to_str() else {
+				return false;
+			};
+
+			// Weak comparison, `W/` is ignored.
+			return value
+				.split(',')
+				.map(str::trim)
+				.any(|etag| etag == "*" || etag.trim_start_matches("W/") == self.etag);
+		}
+
+		if let Some(value) = headers.get(IF_MODIFIED_SINCE) {
+			if let Some(since) = value.to_str().ok().and_then(|s| httpdate::parse_http_date(s).ok()) {
+				return self.last_modified <= since;
+			}
+		}
+
+		false
+	}
+
+	// If `If-Range` (if any) still matches this file.
+	//
+	// This uses strong comparison, so weak `ETag`'s never match.
+	fn if_range(&self, headers: &HeaderMap) -> bool {
+		let Some(value) = headers.get(IF_RANGE) else {
+			return true;
+		};
+
+		let Ok(value) = value.to_str() else {
+			return false;
+		};
+
+		if value.starts_with('"') {
+			value == self.etag
+		} else if value.starts_with("W/") {
+			false
+		} else {
+			httpdate::parse_http_date(value).is_ok_and(|date| date == self.last_modified)
+		}
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Ranges
+// What part of a file to respond with.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub enum Ranges {
+	// The whole file (`200`).
+	Full,
+	// These byte ranges (`206`), each is non-empty and within the file.
+	Partial(Vec<Range<u64>>),
+	// None of the requested ranges are within the file (`416`).
+	Unsatisfiable,
+}
+
+impl Ranges {
+	// Get the requested ranges of a file `len` bytes long.
+	//
+	// Invalid or unknown `Range` headers are ignored (the
+	// whole file is sent), as the RFC says they should be.
+	pub fn new(headers: &HeaderMap, validator: &Validator, len: u64) -> Self {
+		let Some(value) = headers.get(RANGE) else {
+			return Self::Full;
+		};
+
+		if !validator.if_range(headers) {
+			return Self::Full;
+		}
+
+		match value.to_str() {
+			Ok(s)  => Self::parse(s, len),
+			Err(_) => Self::Full,
+		}
+	}
+
+	// Parse a `bytes=...` range set.
+	fn parse(s: &str, len: u64) -> Self {
+		let Some(set) = s.trim().strip_prefix("bytes=") else {
+			return Self::Full;
+		};
+
+		let mut parsed = 0;
+		let mut ranges = vec![];
+
+		for spec in set.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+			parsed += 1;
+			if parsed > MAX_RANGES {
+				return Self::Full;
+			}
+
+			let Some((start, end)) = spec.split_once('-') else {
+				return Self::Full;
+			};
+			let (start, end) = (start.trim(), end.trim());
+
+			let range = if start.is_empty() {
+				// `-500`, the last 500 bytes.
+				let Ok(suffix) = end.parse::<u64>() else {
+					return Self::Full;
+				};
+				len.saturating_sub(suffix)..len
+			} else {
+				let Ok(start) = start.parse::<u64>() else {
+					return Self::Full;
+				};
+
+				// `500-`, everything after the first 500 bytes.
+				let end = if end.is_empty() {
+					len
+				} else {
+					match end.parse::<u64>() {
+						// The end is inclusive.
+						Ok(end) if end >= start => end.saturating_add(1).min(len),
+						_ => return Self::Full,
+					}
+				};
+
+				start..end
+			};
+
+			// Skip unsatisfiable ranges, e.g, starting after the end of the file.
+			if range.start < range.end {
+				ranges.push(range);
+			}
+		}
+
+		if parsed == 0 {
+			Self::Full
+		} else if ranges.is_empty() {
+			Self::Unsatisfiable
+		} else {
+			Self::Partial(ranges)
+		}
+	}
+}
+
+// Format a `Content-Range` value, `range` is exclusive.
+pub fn content_range(range: &Range<u64>, len: u64) -> String {
+	format!("bytes {}-{}/{len}", range.start, range.end - 1)
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const LEN: u64 = 1000;
+
+	fn validator() -> Validator {
+		Validator {
+			etag:          r#""3e8-1""#.to_string(),
+			last_modified: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+		}
+	}
+
+	fn headers(pairs: &[(hyper::header::HeaderName, &str)]) -> HeaderMap {
+		let mut h = HeaderMap::new();
+		for (k, v) in pairs {
+			h.insert(k, HeaderValue::from_str(v).unwrap());
+		}
+		h
+	}
+
+	#[test]
+	fn parse() {
+		assert_eq!(Ranges::parse("bytes=0-499", LEN),         Ranges::Partial(vec![0..500]));
+		assert_eq!(Ranges::parse("bytes=500-", LEN),          Ranges::Partial(vec![500..1000]));
+		assert_eq!(Ranges::parse("bytes=-100", LEN),          Ranges::Partial(vec![900..1000]));
+		assert_eq!(Ranges::parse("bytes=-5000", LEN),         Ranges::Partial(vec![0..1000]));
+		assert_eq!(Ranges::parse("bytes=900-5000", LEN),      Ranges::Partial(vec![900..1000]));
+		assert_eq!(Ranges::parse("bytes=0-0, -1", LEN),       Ranges::Partial(vec![0..1, 999..1000]));
+		assert_eq!(Ranges::parse("bytes=0-9,2000-,20-29", LEN), Ranges::Partial(vec![0..10, 20..30]));
+	}
+
+	#[test]
+	fn unsatisfiable() {
+		assert_eq!(Ranges::parse("bytes=1000-", LEN),       Ranges::Unsatisfiable);
+		assert_eq!(Ranges::parse("bytes=2000-3000", LEN),   Ranges::Unsatisfiable);
+		assert_eq!(Ranges::parse("bytes=-0", LEN),          Ranges::Unsatisfiable);
+		assert_eq!(Ranges::parse("bytes=0-", 0),            Ranges::Unsatisfiable);
+	}
+
+	#[test]
+	fn invalid() {
+		assert_eq!(Ranges::parse("items=0-10", LEN), Ranges::Full);
+		assert_eq!(Ranges::parse("bytes=", LEN),     Ranges::Full);
+		assert_eq!(Ranges::parse("bytes=10-5", LEN), Ranges::Full);
+		assert_eq!(Ranges::parse("bytes=a-b", LEN),  Ranges::Full);
+		assert_eq!(Ranges::parse("bytes=10", LEN),   Ranges::Full);
+
+		let many = format!("bytes={}", ["0-1"; MAX_RANGES + 1].join(","));
+		assert_eq!(Ranges::parse(&many, LEN), Ranges::Full);
+	}
+
+	#[test]
+	fn if_range() {
+		let v = validator();
+
+		let h = headers(&[(RANGE, "bytes=0-9"), (IF_RANGE, r#""3e8-1""#)]);
+		assert_eq!(Ranges::new(&h, &v, LEN), Ranges::Partial(vec![0..10]));
+
+		let h = headers(&[(RANGE, "bytes=0-9"), (IF_RANGE, r#""old""#)]);
+		assert_eq!(Ranges::new(&h, &v, LEN), Ranges::Full);
+
+		let h = headers(&[(RANGE, "bytes=0-9"), (IF_RANGE, r#"W/"3e8-1""#)]);
+		assert_eq!(Ranges::new(&h, &v, LEN), Ranges::Full);
+
+		let date = httpdate::fmt_http_date(v.last_modified);
+		let h = headers(&[(RANGE, "bytes=0-9"), (IF_RANGE, &date)]);
+		assert_eq!(Ranges::new(&h, &v, LEN), Ranges::Partial(vec![0..10]));
+	}
+
+	#[test]
+	fn not_modified() {
+		let v = validator();
+
+		assert!(!v.not_modified(&HeaderMap::new()));
+		assert!(v.not_modified(&headers(&[(IF_NONE_MATCH, r#""3e8-1""#)])));
+		assert!(v.not_modified(&headers(&[(IF_NONE_MATCH, r#""a", W/"3e8-1""#)])));
+		assert!(v.not_modified(&headers(&[(IF_NONE_MATCH, "*")])));
+		assert!(!v.not_modified(&headers(&[(IF_NONE_MATCH, r#""a""#)])));
+
+		let later   = httpdate::fmt_http_date(v.last_modified + Duration::from_secs(1));
+		let earlier = httpdate::fmt_http_date(v.last_modified - Duration::from_secs(1));
+		assert!(v.not_modified(&headers(&[(IF_MODIFIED_SINCE, &later)])));
+		assert!(!v.not_modified(&headers(&[(IF_MODIFIED_SINCE, &earlier)])));
+
+		// `If-None-Match` wins.
+		assert!(!v.not_modified(&headers(&[(IF_NONE_MATCH, r#""a""#), (IF_MODIFIED_SINCE, &later)])));
+	}
+
+	#[test]
+	fn content_range() {
+		assert_eq!(super::content_range(&(0..500), LEN),     "bytes 0-499/1000");
+		assert_eq!(super::content_range(&(999..1000), LEN),  "bytes 999-999/1000");
+	}
+}
@@ -9,7 +9,11 @@ use hyper::{
 		CONTENT_LENGTH,
 		CONTENT_TYPE,
 		CONTENT_DISPOSITION,
+		CACHE_CONTROL,
+		CONTENT_RANGE,
 		WWW_AUTHENTICATE,
+		RETRY_AFTER,
+		LOCATION,
 	},
 };
 use http::{
@@ -117,6 +121,68 @@ pub fn rest_zip(body: hyper::body::Body, name: &str, len: Option<u64>) -> Respon
 	}
 }
 
+// Partial streaming body (206) of a file (inline/attachment).
+//
+// `content_type` is the file's MIME for a single range,
+// or `multipart/byteranges` for multiple ranges, in which
+// case `content_range` is `None`.
+pub fn rest_partial(
+	body:          hyper::body::Body,
+	name:          &str,
+	content_type:  &str,
+	inline:        bool,
+	content_range: Option<String>,
+	len:           u64,
+) -> Response<Body> {
+	let content_disposition = match inline {
+		true  => format!(r#"{INLINE}; filename="{name}""#,),
+		false => format!(r#"{ATTACHMENT}; filename="{name}""#),
+	};
+
+	let mut b = Builder::new()
+		.status(StatusCode::PARTIAL_CONTENT)
+		.header(SERVER, FESTIVALD_SERVER)
+		.header(CONTENT_TYPE, content_type)
+		.header(CONTENT_LENGTH, len)
+		.header(CONTENT_DISPOSITION, content_disposition);
+
+	let mut b = if let Some(content_range) = content_range {
+		b.header(CONTENT_RANGE, content_range)
+	} else {
+		b
+	};
+
+	match b.body(body) {
+		Ok(r)  => r,
+		Err(e) => server_err("Internal server error"),
+	}
+}
+
+// The client's cached copy is still valid (304).
+pub fn not_modified() -> Response<Body> {
+	// SAFETY: This `.unwraps()` are safe. The content is static.
+	Builder::new()
+		.status(StatusCode::NOT_MODIFIED)
+		.header(SERVER, FESTIVALD_SERVER)
+		.body(Body::empty())
+		.unwrap()
+}
+
+// Never-ending live body (e.g, the `/stream` broadcast).
+pub fn rest_live(body: hyper::body::Body, mime: &str) -> Response<Body> {
+	match Builder::new()
+		.status(StatusCode::OK)
+		.header(SERVER, FESTIVALD_SERVER)
+		.header(CONTENT_TYPE, mime)
+		.header(CACHE_CONTROL, "no-cache, no-store")
+		.header(CONTENT_DISPOSITION, INLINE)
+		.body(body)
+	{
+		Ok(r)  => r,
+		Err(e) => server_err("Internal server error"),
+	}
+}
+
 pub fn rest_ok_msg(msg: &'static str) -> Response<Body> {
 	// SAFETY: This `.unwraps()` are safe. The content is static.
 	Builder::new()
@@ -142,6 +208,18 @@ pub fn not_found(msg: &'static str) -> Response<Body> {
 		.unwrap()
 }
 
+// Invalid request parameters (400)
+pub fn bad_request(msg: &'static str) -> Response<Body> {
+	// SAFETY: This `.unwraps()` are safe. The content is static.
+	Builder::new()
+		.status(StatusCode::BAD_REQUEST)
+		.header(SERVER, FESTIVALD_SERVER)
+		.header(CONTENT_TYPE, TEXT_PLAIN_UTF_8.essence_str())
+		.header(CONTENT_LENGTH, msg.len())
+		.body(Body::from(msg))
+		.unwrap()
+}
+
 // Unauthorized request (401)
 pub fn unauthorized(msg: &'static str) -> Response<Body> {
 	// SAFETY: This `.unwraps()` are safe. The content is static.
@@ -167,6 +245,31 @@ pub fn forbidden(msg: &'static str) -> Response<Body> {
 		.unwrap()
 }
 
+// Permanent redirect (308)
+pub fn permanent_redirect(location: &str) -> Response<Body> {
+	// SAFETY: This `.unwraps()` are safe. `location` is the validated `base_path`.
+	Builder::new()
+		.status(StatusCode::PERMANENT_REDIRECT)
+		.header(SERVER, FESTIVALD_SERVER)
+		.header(LOCATION, location)
+		.header(CONTENT_LENGTH, 0)
+		.body(Body::empty())
+		.unwrap()
+}
+
+// Too many requests (429)
+pub fn too_many_requests(msg: &'static str, retry_after: u64) -> Response<Body> {
+	// SAFETY: This `.unwraps()` are safe. The content is static.
+	Builder::new()
+		.status(StatusCode::TOO_MANY_REQUESTS)
+		.header(SERVER, FESTIVALD_SERVER)
+		.header(CONTENT_TYPE, TEXT_PLAIN_UTF_8.essence_str())
+		.header(CONTENT_LENGTH, msg.len())
+		.header(RETRY_AFTER, retry_after)
+		.body(Body::from(msg))
+		.unwrap()
+}
+
 // Method now allowed (405)
 pub fn method_not_allowed(msg: &'static str) -> Response<Body> {
 	// SAFETY: This `.unwraps()` are safe. The content is static.
@@ -205,6 +308,20 @@ pub fn server_err_dyn(msg: String) -> Response<Body> {
 	}
 }
 
+// None of the requested ranges are within the file (416).
+pub fn range_not_satisfiable(len: u64) -> Response<Body> {
+	const MSG: &str = "Range not satisfiable";
+	// SAFETY: This `.unwraps()` are safe. The content is static.
+	Builder::new()
+		.status(StatusCode::RANGE_NOT_SATISFIABLE)
+		.header(SERVER, FESTIVALD_SERVER)
+		.header(CONTENT_TYPE, TEXT_PLAIN_UTF_8.essence_str())
+		.header(CONTENT_LENGTH, MSG.len())
+		.header(CONTENT_RANGE, format!("bytes */{len}"))
+		.body(Body::from(MSG))
+		.unwrap()
+}
+
 // We're in the middle of a `Collection` reset.
 pub fn resetting_rest() -> Response<Body> {
 	const MSG: &str = "Currently resetting the Collection";
@@ -339,6 +456,41 @@ pub fn result_cache<'a>(string: &str, id: Option<json_rpc::Id<'a>>) -> Response<
 	}
 }
 
+// A JSON-RPC batch, `responses` are each (already serialized) response, in order.
+pub fn batch(responses: &[hyper::body::Bytes]) -> Response<Body> {
+	let mut r = Vec::with_capacity(responses.iter().map(|b| b.len() + 2).sum::<usize>() + 2);
+
+	r.push(b'[');
+	for (i, response) in responses.iter().enumerate() {
+		if i != 0 {
+			r.extend_from_slice(b",\n");
+		}
+		r.extend_from_slice(response);
+	}
+	r.push(b']');
+
+	match Builder::new()
+		.status(StatusCode::OK)
+		.header(SERVER, FESTIVALD_SERVER)
+		.header(CONTENT_TYPE, APPLICATION_JSON.essence_str())
+		.header(CONTENT_LENGTH, r.len())
+		.body(Body::from(r))
+	{
+		Ok(r)  => r,
+		Err(_) => internal_error(None),
+	}
+}
+
+// A JSON-RPC notification (or a batch of only notifications), there is no response body.
+pub fn no_content() -> Response<Body> {
+	// SAFETY: This `.unwraps()` are safe. The content is static.
+	Builder::new()
+		.status(StatusCode::NO_CONTENT)
+		.header(SERVER, FESTIVALD_SERVER)
+		.body(Body::empty())
+		.unwrap()
+}
+
 //---------------------------------------------------------------------------------------------------- JSON-RPC specific error response
 pub fn parse_error<'a>(id: Option<json_rpc::Id<'a>>) -> Response<Body> {
 	// SAFETY: These `.unwraps()` are safe. The content is static.
@@ -2,14 +2,15 @@
 use anyhow::anyhow;
 use log::{error,info,warn,debug,trace};
 use std::sync::Arc;
-use std::net::SocketAddrV4;
+use std::net::SocketAddr;
 use hyper::{
 	Request,
 	Response,
-	body::Body,
+	body::{Body,Bytes},
 };
 use mime::TEXT_PLAIN_UTF_8;
 use hyper::header::{
+	HeaderMap,
 	CONTENT_LENGTH,
 	CONTENT_TYPE,
 	CONTENT_DISPOSITION,
@@ -31,20 +32,21 @@ use crate::{
 	ptr::CollectionPtr,
 };
 use rpc::resource::Resource;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt,AsyncSeekExt};
 use std::{
 	path::{Path,PathBuf},
-	io::Write,
+	io::{SeekFrom,Write},
+	ops::Range,
 };
-use crate::config::AUTH;
+use crate::range::{Ranges,Validator};
 use std::str::FromStr;
 use benri::debug_panic;
 use std::collections::VecDeque;
-use shukusai::state::PLAYLISTS;
+use shukusai::state::{PLAYLISTS,Playlists,PlaylistFormat};
 use std::collections::btree_set::BTreeSet;
 
 //---------------------------------------------------------------------------------------------------- Const
-pub const REST_ENDPOINTS: [&'static str; 7] = [
+pub const REST_ENDPOINTS: [&'static str; 9] = [
 	"key",
 	"map",
 	"art",
@@ -52,6 +54,8 @@ pub const REST_ENDPOINTS: [&'static str; 7] = [
 	"rand",
 	"playlist",
 	"collection",
+	"stream",
+	"transcode",
 ];
 
 pub const ERR_END: &str = "Unknown endpoint";
@@ -60,21 +64,27 @@ pub const ERR_END: &str = "Unknown endpoint";
 // Check auth.
 async fn rest_auth_ok(
 	parts:    &Parts,
-	addr:     &SocketAddrV4,
+	addr:     &SocketAddr,
 	resource: Resource,
 ) -> Option<Response<Body>> {
-	if !config().no_auth_rest.as_ref().is_some_and(|h| h.contains(&resource)) {
-		if let Some(hash) = AUTH.get() {
-			if !crate::router::auth_ok(parts, hash).await {
-				if crate::seen::seen(&addr).await {
-					crate::router::sleep_on_fail().await;
-				}
-				return Some(resp::unauthorized("Unauthorized"));
-			}
+	let caller = crate::user::caller(parts).await;
+
+	if !caller.rest(&resource) {
+		if !caller.anonymous() {
+			return Some(resp::forbidden("Forbidden"));
+		}
+		crate::limit::fail(addr, parts);
+		if crate::seen::seen(&addr).await {
+			crate::router::sleep_on_fail().await;
 		}
+		return Some(resp::unauthorized("Unauthorized"));
 	}
 
-	None
+	crate::seen::user(addr, caller).await;
+
+	// Check rate limit.
+	crate::limit::throttle(addr, caller, crate::limit::Cost::rest(&resource))
+		.map(|secs| resp::too_many_requests("Too many requests", secs))
 }
 
 //---------------------------------------------------------------------------------------------------- `/` filename check
@@ -89,7 +99,7 @@ fn slash(s: String) -> String {
 //---------------------------------------------------------------------------------------------------- REST Handler
 pub async fn handle(
 	parts:  Parts,
-	addr:       SocketAddrV4,
+	addr:       SocketAddr,
 	collection: &'static CollectionPtr,
 ) -> Result<Response<Body>, anyhow::Error> {
 	// If we're in the middle of a `Collection` reset, respond with "busy".
@@ -146,8 +156,8 @@ pub async fn handle(
 		match resource {
 			Resource::Artist => key_artist(key, collection.arc()).await,
 			Resource::Album  => key_album(key, collection.arc()).await,
-			Resource::Song   => key_song(key, collection.arc()).await,
-			Resource::Art    => key_art(key, collection.arc()).await,
+			Resource::Song   => key_song(key, &parts.headers, collection.arc()).await,
+			Resource::Art    => key_art(key, &parts.headers, collection.arc()).await,
 			_ => {
 				debug_panic!("parsed resource {resource:?}, but reached unreachable");
 				Ok(resp::server_err("Unknown resource"))
@@ -188,7 +198,7 @@ pub async fn handle(
 				if let Some(resp) = rest_auth_ok(&parts, &addr, Resource::Song).await {
 					return Ok(resp);
 				}
-				map_song(artist.as_ref(), a.as_ref(), s.as_ref(), collection.arc()).await
+				map_song(artist.as_ref(), a.as_ref(), s.as_ref(), &parts.headers, collection.arc()).await
 			},
 
 			// Artist
@@ -222,7 +232,7 @@ pub async fn handle(
 		}
 
 		if let Some(album) = album {
-			art_album(artist.as_ref(), album.as_ref(), collection.arc()).await
+			art_album(artist.as_ref(), album.as_ref(), &parts.headers, collection.arc()).await
 		} else {
 			art_artist(artist.as_ref(), collection.arc()).await
 		}
@@ -250,8 +260,8 @@ pub async fn handle(
 		match resource {
 			Resource::Artist => current_artist(collection.arc()).await,
 			Resource::Album  => current_album(collection.arc()).await,
-			Resource::Song   => current_song(collection.arc()).await,
-			Resource::Art    => current_art(collection.arc()).await,
+			Resource::Song   => current_song(&parts.headers, collection.arc()).await,
+			Resource::Art    => current_art(&parts.headers, collection.arc()).await,
 			_ => {
 				debug_panic!("parsed resource {resource:?}, but reached unreachable");
 				Ok(resp::server_err("Unknown resource"))
@@ -281,8 +291,8 @@ pub async fn handle(
 		match resource {
 			Resource::Artist => rand_artist(collection.arc()).await,
 			Resource::Album  => rand_album(collection.arc()).await,
-			Resource::Song   => rand_song(collection.arc()).await,
-			Resource::Art    => rand_art(collection.arc()).await,
+			Resource::Song   => rand_song(&parts.headers, collection.arc()).await,
+			Resource::Art    => rand_art(&parts.headers, collection.arc()).await,
 			_ => {
 				debug_panic!("parsed resource {resource:?}, but reached unreachable");
 				Ok(resp::server_err("Unknown resource"))
@@ -301,13 +311,25 @@ pub async fn handle(
 			None => return Ok(resp::not_found("Missing playlist name")),
 		};
 
-		// Return error if more than 2 endpoints.
+		// Optional playlist file format.
+		let format = match split.next() {
+			Some(s) if !s.is_empty() => match PlaylistFormat::from_str(s) {
+				Ok(f)  => Some(f),
+				Err(_) => return Ok(resp::not_found("Unknown playlist format")),
+			},
+			_ => None,
+		};
+
+		// Return error if more than 3 endpoints.
 		match split.next() {
 			Some(s) if !s.is_empty() => return Ok(resp::not_found(ERR_END)),
 			_ => (),
 		}
 
-		playlist_fn(playlist_name, collection.arc()).await
+		match format {
+			Some(f) => playlist_format(playlist_name, f, collection.arc()).await,
+			None    => playlist_fn(playlist_name, collection.arc()).await,
+		}
 	//-------------------------------------------------- `/collection` endpoint.
 	} else if ep1 == "collection" {
 		// Auth.
@@ -321,6 +343,47 @@ pub async fn handle(
 		}
 
 		collection_fn(collection.arc()).await
+	//-------------------------------------------------- `/stream` endpoint.
+	} else if ep1 == "stream" {
+		// Auth.
+		if let Some(resp) = rest_auth_ok(&parts, &addr, Resource::Stream).await {
+			return Ok(resp);
+		}
+
+		match split.next() {
+			Some(s) if !s.is_empty() => return Ok(resp::not_found(ERR_END)),
+			_ => (),
+		}
+
+		crate::broadcast::stream().await
+	//-------------------------------------------------- `/transcode` endpoint.
+	} else if ep1 == "transcode" {
+		// Auth.
+		if let Some(resp) = rest_auth_ok(&parts, &addr, Resource::Transcode).await {
+			return Ok(resp);
+		}
+
+		let Some(ep2) = split.next() else {
+			return Ok(resp::not_found("Missing endpoint: [key]"));
+		};
+
+		// Return error if more than 2 endpoints.
+		match split.next() {
+			Some(s) if !s.is_empty() => return Ok(resp::not_found(ERR_END)),
+			_ => (),
+		}
+
+		// Parse `usize` key.
+		let Ok(key) = ep2.parse::<usize>() else {
+			return Ok(resp::not_found("Key parse failure"));
+		};
+
+		let transcode = match crate::transcode::Transcode::from_query(parts.uri.query()) {
+			Ok(t)  => t,
+			Err(e) => return Ok(resp::bad_request(e)),
+		};
+
+		crate::transcode::transcode(key, transcode, &parts.headers, collection.arc()).await
 	//-------------------------------------------------- unknown endpoint.
 	} else {
 		Ok(resp::not_found(ERR_END))
@@ -348,6 +411,45 @@ fn mmap_file(path: &Path) -> Result<memmap2::Mmap, Response<Body>> {
 	}
 }
 
+// A `Song`'s bytes to write into a ZIP.
+//
+// `Song`'s split from a CUE sheet share their file with the
+// rest of the disc, so only their range gets transcoded in.
+enum SongFile {
+	Mmap(memmap2::Mmap),
+	Ranged,
+}
+
+impl SongFile {
+	fn new(song: &Song) -> Result<Self, Response<Body>> {
+		if crate::transcode::ranged(song) {
+			Ok(Self::Ranged)
+		} else {
+			mmap_file(&song.path).map(Self::Mmap)
+		}
+	}
+
+	// The extension of the file inside the ZIP.
+	fn extension<'a>(&self, song: &'a Song) -> &'a str {
+		match self {
+			Self::Mmap(_) => &song.extension,
+			Self::Ranged  => crate::transcode::Format::default().extension(),
+		}
+	}
+
+	// Write into the currently started ZIP file.
+	fn write(&self, zip: &mut zip::ZipWriter<std::fs::File>, song: &Song) -> Result<(), anyhow::Error> {
+		match self {
+			Self::Mmap(mmap) => Ok(zip.write_all(mmap)?),
+			Self::Ranged => crate::transcode::encode_song(
+				song,
+				crate::transcode::Transcode::default(),
+				|bytes| Ok(zip.write_all(&bytes)?),
+			),
+		}
+	}
+}
+
 // This is the old (safe) method.
 //
 // Regular `async` read into `Vec<u8>`
@@ -439,17 +541,18 @@ async fn impl_album_inner(
 //			Ok(b)  => b,
 //			Err(r) => return Some(r),
 //		};
-		let mmap = match mmap_file(&song.path) {
-			Ok(m)  => m,
+		let song_file = match SongFile::new(song) {
+			Ok(f)  => f,
 			Err(r) => return Some(r),
 		};
+		let extension = song_file.extension(song);
 
 		// Keep adding to PATH if we've seen this file.
 		let mut attempt = 1_usize;
 		let song_title = slash(song.title.to_string());
-		let mut file_path: Arc<str> = format!("{folder}/{}.{}", song_title, song.extension).into();
+		let mut file_path: Arc<str> = format!("{folder}/{}.{}", song_title, extension).into();
 		while !seen.insert(Arc::clone(&file_path)) {
-			file_path = format!("{folder}/{} ({attempt}).{}", song_title, song.extension).into();
+			file_path = format!("{folder}/{} ({attempt}).{}", song_title, extension).into();
 			attempt += 1;
 		};
 
@@ -459,7 +562,7 @@ async fn impl_album_inner(
 				return Some(resp::server_err_dyn(format!("Song file error: {} - {} - {}", artist.name, album.title, song.title)));
 			}
 
-			if zip.write(&*mmap).is_err() {
+			if song_file.write(zip, song).is_err() {
 				let (artist, album, song) = collection.walk(song_key);
 				return Some(resp::server_err_dyn(format!("Zip file error: {} - {} - {}", artist.name, album.title, song.title)));
 			}
@@ -620,9 +723,16 @@ async fn impl_album(album: &Album, collection: &Arc<Collection>) -> Result<Respo
 	Ok(resp::rest_zip(body, &zip_name, len))
 }
 
-async fn impl_song(song: &Song, collection: &Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
+async fn impl_song(song: &Song, headers: &HeaderMap, collection: &Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	trace!("REST - impl_song(): {}", song.title);
 
+	// `Song`'s split from a CUE sheet share their file with
+	// the rest of the disc, so serve a transcode of their range.
+	if crate::transcode::ranged(song) {
+		let transcode = crate::transcode::Transcode::default();
+		return crate::transcode::transcode(song.key.inner(), transcode, headers, Arc::clone(collection)).await;
+	}
+
 	// Format the file name.
 	let (artist, album, _) = collection.walk(song.key);
 	let name = format!(
@@ -640,14 +750,10 @@ async fn impl_song(song: &Song, collection: &Arc<Collection>) -> Result<Response
 		return Ok(resp::server_err_dyn(format!("Song file error: {name}")));
 	};
 
-	let len    = file_len(&file).await;
-	let stream = FramedRead::new(file, BytesCodec::new());
-	let body   = Body::wrap_stream(stream);
-
-	Ok(resp::rest_stream(body, &name, &song.mime, len))
+	impl_file(headers, file, &name, &song.mime, false).await
 }
 
-async fn impl_art(album: &Album, collection: &Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
+async fn impl_art(album: &Album, headers: &HeaderMap, collection: &Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	// If art exists...
 	let Art::Known { path, mime, len, extension } = &album.art  else {
 		let artist = &collection.artists[album.artist];
@@ -669,11 +775,145 @@ async fn impl_art(album: &Album, collection: &Arc<Collection>) -> Result<Respons
 		return Ok(resp::server_err_dyn(format!("Album art error: {name}")));
 	};
 
-	let len    = file_len(&file).await;
-	let stream = FramedRead::new(file, BytesCodec::new());
-	let body   = Body::wrap_stream(stream);
+	impl_file(headers, file, &name, mime, true).await
+}
+
+// Serve a single file (`Song`/art), respecting the `Range`, `If-Range`,
+// `If-None-Match` and `If-Modified-Since` request headers.
+//
+// `art` files are shown inline unless `direct_download` is enabled.
+pub async fn impl_file(
+	headers:  &HeaderMap,
+	mut file: tokio::fs::File,
+	name:     &str,
+	mime:     &str,
+	art:      bool,
+) -> Result<Response<Body>, anyhow::Error> {
+	let md        = file.metadata().await.ok();
+	let len       = md.as_ref().map(|md| md.len());
+	let validator = md.as_ref().and_then(Validator::new);
+
+	// Ranges and caching need the length and modification time,
+	// if the OS doesn't give us those, just send the whole file.
+	let (Some(len), Some(validator)) = (len, validator) else {
+		let stream = FramedRead::new(file, BytesCodec::new());
+		let body   = Body::wrap_stream(stream);
+		return Ok(match art {
+			true  => resp::rest_stream_maybe_inline(body, name, mime, len),
+			false => resp::rest_stream(body, name, mime, len),
+		});
+	};
+
+	if validator.not_modified(headers) {
+		let mut r = resp::not_modified();
+		validator.insert(r.headers_mut());
+		return Ok(r);
+	}
+
+	let inline = art && !config().direct_download;
+
+	let mut r = match Ranges::new(headers, &validator, len) {
+		Ranges::Full => {
+			let stream = FramedRead::new(file, BytesCodec::new());
+			let body   = Body::wrap_stream(stream);
+			match art {
+				true  => resp::rest_stream_maybe_inline(body, name, mime, Some(len)),
+				false => resp::rest_stream(body, name, mime, Some(len)),
+			}
+		},
+
+		Ranges::Unsatisfiable => return Ok(resp::range_not_satisfiable(len)),
+
+		Ranges::Partial(ranges) if ranges.len() == 1 => {
+			let range = &ranges[0];
+			let range_len = range.end - range.start;
+
+			file.seek(SeekFrom::Start(range.start)).await?;
+			let stream = FramedRead::new(file.take(range_len), BytesCodec::new());
+			let body   = Body::wrap_stream(stream);
+
+			let content_range = crate::range::content_range(range, len);
+			resp::rest_partial(body, name, mime, inline, Some(content_range), range_len)
+		},
+
+		Ranges::Partial(ranges) => {
+			// Random, so that it (almost certainly) doesn't appear in the file.
+			let boundary = format!("festivald_{:016x}", rand::random::<u64>());
+
+			// The headers before each part, and the final boundary.
+			let parts: Vec<Bytes> = ranges
+				.iter()
+				.map(|r| {
+					let content_range = crate::range::content_range(r, len);
+					Bytes::from(format!("\r\n--{boundary}\r\nContent-Type: {mime}\r\nContent-Range: {content_range}\r\n\r\n"))
+				})
+				.collect();
+			let end = Bytes::from(format!("\r\n--{boundary}--\r\n"));
+
+			let body_len =
+				parts.iter().map(|p| p.len() as u64).sum::<u64>() +
+				ranges.iter().map(|r| r.end - r.start).sum::<u64>() +
+				end.len() as u64;
+
+			let (sender, body) = Body::channel();
+			tokio::spawn(send_ranges(file, ranges, parts, end, sender));
+
+			let content_type = format!("multipart/byteranges; boundary={boundary}");
+			resp::rest_partial(body, name, &content_type, inline, None, body_len)
+		},
+	};
+
+	validator.insert(r.headers_mut());
+	Ok(r)
+}
+
+// Write each range of `file` with its part headers into a `multipart/byteranges` body.
+async fn send_ranges(
+	mut file:   tokio::fs::File,
+	ranges:     Vec<Range<u64>>,
+	parts:      Vec<Bytes>,
+	end:        Bytes,
+	mut sender: hyper::body::Sender,
+) {
+	// Read size of each chunk sent.
+	const CHUNK: usize = 64 * 1024;
+
+	for (range, part) in ranges.into_iter().zip(parts) {
+		if sender.send_data(part).await.is_err() {
+			return;
+		}
+
+		if file.seek(SeekFrom::Start(range.start)).await.is_err() {
+			sender.abort();
+			return;
+		}
+
+		let mut take = (&mut file).take(range.end - range.start);
+		loop {
+			let mut buf = vec![0; CHUNK];
+			match take.read(&mut buf).await {
+				Ok(0) => break,
+				Ok(n) => {
+					buf.truncate(n);
+					if sender.send_data(Bytes::from(buf)).await.is_err() {
+						return;
+					}
+				},
+				Err(_) => {
+					sender.abort();
+					return;
+				},
+			}
+		}
 
-	Ok(resp::rest_stream_maybe_inline(body, &name, mime, len))
+		// The file got shorter, our `Content-Length` is now wrong.
+		if take.limit() != 0 {
+			sender.abort();
+			return;
+		}
+	}
+
+	let _ = sender.send_data(end).await;
 }
 
 async fn impl_playlist(
@@ -726,8 +966,8 @@ async fn impl_playlist(
 //			Ok(b)  => b,
 //			Err(r) => return Ok(r),
 //		};
-		let mmap = match mmap_file(&song.path) {
-			Ok(m)  => m,
+		let song_file = match SongFile::new(song) {
+			Ok(f)  => f,
 			Err(r) => return Ok(r),
 		};
 
@@ -735,14 +975,14 @@ async fn impl_playlist(
 		let artist_name = slash(artist.name.to_string());
 		let album_title = slash(album.title.to_string());
 		let song_title  = slash(song.title.to_string());
-		let file_path = format!("{index}{s}{artist_name}{s}{album_title}{s}{song_title}{s}.{}", song.extension);
+		let file_path = format!("{index}{s}{artist_name}{s}{album_title}{s}{song_title}{s}.{}", song_file.extension(song));
 
 		let r = tokio::task::block_in_place(|| {
 			if zip.start_file(file_path, options).is_err() {
 				return Some(resp::server_err_dyn(format!("Song file error: {} - {} - {}", artist.name, album.title, song.title)));
 			}
 
-			if zip.write(&*mmap).is_err() {
+			if song_file.write(&mut zip, song).is_err() {
 				return Some(resp::server_err_dyn(format!("Zip file error: {} - {} - {}", artist.name, album.title, song.title)));
 			}
 
@@ -795,22 +1035,22 @@ pub async fn key_album(key: usize, collection: Arc<Collection>) -> Result<Respon
 	}
 }
 
-pub async fn key_song(key: usize, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
+pub async fn key_song(key: usize, headers: &HeaderMap, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	let key = SongKey::from(key);
 
 	if let Some(song) = collection.songs.get(key) {
-		impl_song(song, &collection).await
+		impl_song(song, headers, &collection).await
 	} else {
 		Ok(resp::not_found("Song key is invalid"))
 	}
 }
 
-pub async fn key_art(key: usize, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
+pub async fn key_art(key: usize, headers: &HeaderMap, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	let key = AlbumKey::from(key);
 
 	// If key exists...
 	if let Some(album) = collection.albums.get(key) {
-		impl_art(album, &collection).await
+		impl_art(album, headers, &collection).await
 	} else {
 		Ok(resp::not_found("Album key is invalid"))
 	}
@@ -833,9 +1073,9 @@ pub async fn map_album(artist: &str, album: &str, collection: Arc<Collection>) -
 	}
 }
 
-pub async fn map_song(artist: &str, album: &str, song: &str, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
+pub async fn map_song(artist: &str, album: &str, song: &str, headers: &HeaderMap, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	if let Some((song, _)) = collection.song(artist, album, song) {
-		impl_song(song, &collection).await
+		impl_song(song, headers, &collection).await
 	} else {
 		Ok(resp::not_found("Artist/Album/Song not found"))
 	}
@@ -877,23 +1117,23 @@ pub async fn current_album(collection: Arc<Collection>) -> Result<Response<Body>
 	}
 }
 
-pub async fn current_song(collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
+pub async fn current_song(headers: &HeaderMap, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	let song = impl_current_song().await;
 
 	if let Some(key) = song {
 		let song = &collection.songs[key];
-		impl_song(song, &collection).await
+		impl_song(song, headers, &collection).await
 	} else {
 		Ok(resp::not_found("No current song"))
 	}
 }
 
-pub async fn current_art(collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
+pub async fn current_art(headers: &HeaderMap, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	let song = impl_current_song().await;
 
 	if let Some(key) = song {
 		let (album, key) = collection.album_from_song(key);
-		impl_art(album, &collection).await
+		impl_art(album, headers, &collection).await
 	} else {
 		Ok(resp::not_found("No current song"))
 	}
@@ -916,20 +1156,20 @@ pub async fn rand_album(collection: Arc<Collection>) -> Result<Response<Body>, a
 	impl_album(&collection.albums[key], &collection).await
 }
 
-pub async fn rand_song(collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
+pub async fn rand_song(headers: &HeaderMap, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	let Some(key) = collection.rand_song(None) else {
 		return Ok(resp::not_found("No songs"));
 	};
 
-	impl_song(&collection.songs[key], &collection).await
+	impl_song(&collection.songs[key], headers, &collection).await
 }
 
-pub async fn rand_art(collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
+pub async fn rand_art(headers: &HeaderMap, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	let Some(key) = collection.rand_album(None) else {
 		return Ok(resp::not_found("No art"));
 	};
 
-	impl_art(&collection.albums[key], &collection).await
+	impl_art(&collection.albums[key], headers, &collection).await
 }
 
 //---------------------------------------------------------------------------------------------------- `/art`
@@ -1023,10 +1263,10 @@ pub async fn art_artist(artist: &str, collection: Arc<Collection>) -> Result<Res
 	Ok(resp::rest_zip(body, &zip_name, len))
 }
 
-pub async fn art_album(artist: &str, album: &str, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
+pub async fn art_album(artist: &str, album: &str, headers: &HeaderMap, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	// If album exists...
 	if let Some((album, key)) = collection.album(artist, album) {
-		impl_art(album, &collection).await
+		impl_art(album, headers, &collection).await
 	} else {
 		Ok(resp::not_found("Album was not found"))
 	}
@@ -1040,6 +1280,23 @@ pub async fn playlist_fn(
 	impl_playlist(playlist_name, &collection).await
 }
 
+pub async fn playlist_format(
+	playlist_name: &str,
+	format: PlaylistFormat,
+	collection: Arc<Collection>,
+) -> Result<Response<Body>, anyhow::Error> {
+	trace!("REST - playlist_format(): {playlist_name}, {format}");
+
+	let Some(playlist) = PLAYLISTS.read().get(playlist_name).map(|v| v.clone()) else {
+		return Ok(resp::server_err("Playlist was not found"));
+	};
+
+	let data = Playlists::export(playlist_name, &playlist, format, &collection);
+	let name = format!("Playlist{}{}.{}", config().filename_separator, slash(playlist_name.to_string()), format.extension());
+
+	Ok(resp::rest_ok(data.into_bytes(), &name, format.mime()))
+}
+
 //---------------------------------------------------------------------------------------------------- `/collection`
 pub async fn collection_fn(collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	// Zip name.
@@ -1099,9 +1356,48 @@ pub async fn collection_fn(collection: Arc<Collection>) -> Result<Response<Body>
 }
 
 //---------------------------------------------------------------------------------------------------- TESTS
-//#[cfg(test)]
-//mod tests {
-//	#[test]
-//		fn __TEST__() {
-//	}
-//}
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SONG: &str = "../assets/audio/song_4.flac";
+
+	// Write `song` into a new ZIP and read it back.
+	fn zip_song(song: &Song, path: &Path) -> (String, Vec<u8>) {
+		let song_file = SongFile::new(song).unwrap();
+		let name      = format!("song.{}", song_file.extension(song));
+
+		let file = std::fs::File::options().read(true).write(true).create(true).truncate(true).open(path).unwrap();
+		let mut zip = zip::ZipWriter::new(file);
+		zip.start_file(&name, zip::write::FileOptions::default()).unwrap();
+		song_file.write(&mut zip, song).unwrap();
+		let file = zip.finish().unwrap();
+
+		let mut zip   = zip::ZipArchive::new(file).unwrap();
+		let mut entry = zip.by_index(0).unwrap();
+		let mut bytes = vec![];
+		std::io::Read::read_to_end(&mut entry, &mut bytes).unwrap();
+
+		(entry.name().to_string(), bytes)
+	}
+
+	#[test]
+	fn song_file() {
+		let path = std::env::temp_dir().join("festivald_test_song_file.zip");
+
+		// The whole file is written as-is.
+		let song = Song { path: PathBuf::from(SONG), extension: "flac".into(), ..Default::default() };
+		let (name, bytes) = zip_song(&song, &path);
+		assert_eq!(name, "song.flac");
+		assert_eq!(bytes, std::fs::read(SONG).unwrap());
+
+		// A CUE `Song` only gets its range, transcoded.
+		let song = Song { start: 500, end: Some(1500), ..song };
+		let (name, bytes) = zip_song(&song, &path);
+		assert_eq!(name, "song.opus");
+		assert!(bytes.starts_with(b"OggS"));
+		assert!(bytes.len() < std::fs::metadata(SONG).unwrap().len() as usize);
+
+		std::fs::remove_file(&path).unwrap();
+	}
+}
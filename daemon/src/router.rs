@@ -7,10 +7,6 @@ use log::{error,info,warn,debug,trace};
 use disk::{Bincode2,Json};
 use std::sync::Arc;
 use std::path::PathBuf;
-use std::net::{
-	Ipv4Addr,
-	SocketAddrV4,
-};
 use hyper::{
 	body::Body,
 	server::conn::Http,
@@ -23,7 +19,6 @@ use hyper::{
 };
 use http::{
 	header::{
-		AUTHORIZATION,
 		CONTENT_TYPE,
 		CONTENT_LENGTH,
 		WWW_AUTHENTICATE,
@@ -46,7 +41,7 @@ use shukusai::{
 	constants::DASH,
 };
 use crate::{
-	config::{AUTH,config,Config,ConfigBuilder},
+	config::{config,Config,ConfigBuilder},
 	statics::{
 		ConnectionToken,
 		RESETTING,
@@ -84,12 +79,26 @@ pub async fn init(
 	TO_KERNEL:   &'static Sender<FrontendToKernel>,
 	FROM_KERNEL: &'static Receiver<KernelToFrontend>,
 ) {
-	// Bind to address.
-	let addr     = SocketAddrV4::new(CONFIG.ip, CONFIG.port);
-	let listener = match TcpListener::bind(addr).await {
-		Ok(l)  => l,
-		Err(e) => crate::exit!("could not bind to [{addr}]: {e}"),
-	};
+	// Bind to all addresses.
+	//
+	// On most systems `::` also accepts IPv4 connections, which would
+	// conflict with any IPv4 address on the same port, e.g: `["0.0.0.0", "::"]`.
+	// If there are IPv4 addresses, IPv6 listeners only accept IPv6.
+	let v6only = CONFIG.ip.iter().any(|ip| ip.is_ipv4());
+	let mut listeners = Vec::with_capacity(CONFIG.ip.len());
+	for ip in CONFIG.ip.iter() {
+		let addr = SocketAddr::new(*ip, CONFIG.port);
+		match bind(addr, v6only) {
+			Ok(l)  => listeners.push(l),
+			Err(e) => crate::exit!("could not bind to [{addr}]: {e}"),
+		}
+	}
+
+	// Create `listener` <-> `router` channel.
+	//
+	// Each listener gets its own task that accepts
+	// TCP streams and forwards them to the router loop.
+	let (to_router_tcp, mut from_listener_tcp) = tokio::sync::mpsc::channel::<(TcpStream, SocketAddr)>(64);
 
 	// Create `task` <-> `router` back channel for `sys_*`.
 	let (to_router_sys, mut from_task_sys) = tokio::sync::mpsc::channel::<()>(1);
@@ -100,25 +109,42 @@ pub async fn init(
 
 	// These last forever.
 	let (
-		LISTENER,
+		LISTENERS,
+		FROM_LISTENER_TCP,
 		TO_ROUTER_SYS,
 		FROM_TASK_SYS,
 		TO_ROUTER_COLLECTION,
 		FROM_TASK_COLLECTION,
 	): (
-		&'static TcpListener,
+		&'static [TcpListener],
+		&'static mut tokio::sync::mpsc::Receiver::<(TcpStream, SocketAddr)>,
 		&'static tokio::sync::mpsc::Sender::<()>,
 		&'static mut tokio::sync::mpsc::Receiver::<()>,
 		&'static tokio::sync::mpsc::Sender::<Arc<Collection>>,
 		&'static mut tokio::sync::mpsc::Receiver::<Arc<Collection>>,
 	) = (
-		Box::leak(Box::new(listener)),
+		Box::leak(listeners.into_boxed_slice()),
+		Box::leak(Box::new(from_listener_tcp)),
 		Box::leak(Box::new(to_router_sys)),
 		Box::leak(Box::new(from_task_sys)),
 		Box::leak(Box::new(to_router_collection)),
 		Box::leak(Box::new(from_task_collection)),
 	);
 
+	// Start accepting TCP streams.
+	for listener in LISTENERS.iter() {
+		let to_router_tcp = to_router_tcp.clone();
+		tokio::task::spawn(async move {
+			loop {
+				match listener.accept().await {
+					Ok(s)  => if to_router_tcp.send(s).await.is_err() { return; },
+					Err(e) => error!("Router - TCP stream error: {e}"),
+				}
+			}
+		});
+	}
+	drop(to_router_tcp);
+
 	// Wait until `Kernel` has given us `Arc<Collection>`.
 	let mut collection = loop {
 		match recv!(FROM_KERNEL) {
@@ -137,6 +163,14 @@ pub async fn init(
 	let ptr = CollectionPtr(ptr);
 	let mut COLLECTION_PTR: &'static CollectionPtr = Box::leak(Box::new(ptr));
 
+	// Start turning state changes into `/event`'s.
+	crate::event::init();
+
+	// Start watching `collection_paths` for changes.
+	if CONFIG.collection_watch {
+		crate::watch::init(COLLECTION_PTR, TO_KERNEL, FROM_KERNEL, TO_ROUTER_COLLECTION);
+	}
+
 	// Instead of branching everytime for HTTP/HTTPS or
 	// using dynamic dispatch or an enum and matching it,
 	// we'll just "implement" the main loop "twice".
@@ -154,11 +188,11 @@ pub async fn init(
 				tokio::select! {
 					biased; // Top-to-bottom.
 
-					// Accept TCP stream, and get peer's IP.
-					l = LISTENER.accept() => {
-						match l {
-							Ok((s, a)) => { atomic_add!(TOTAL_CONNECTIONS, 1); break (s, a) },
-							Err(e)     => error!("Router - TCP stream error: {e}"),
+					// Accept TCP stream (from any listener), and get peer's IP.
+					l = FROM_LISTENER_TCP.recv() => {
+						if let Some((s, a)) = l {
+							atomic_add!(TOTAL_CONNECTIONS, 1);
+							break (s, a);
 						}
 					},
 
@@ -180,6 +214,8 @@ pub async fn init(
 							// Ok, we're safe, atomically update the pointer back. //
 							let ptr = std::ptr::addr_of_mut!(collection);          //
 							COLLECTION_PTR.0.store(ptr, Ordering::SeqCst); // <------
+
+							crate::event::collection(&collection);
 						} else {
 							debug_panic!("Router - New Collection message but it was None");
 						}
@@ -228,27 +264,34 @@ pub async fn init(
 		}}
 	}
 
-	// Prints `protocol`, `ip`, and `port` in color.
+	// Prints `protocol`, `ip`, and `port` in color, for every listener.
 	macro_rules! listening {
 		() => {{
 			let protocol = if CONFIG.tls { "https" } else { "http" };
-
-			let port = match LISTENER.local_addr() {
-				Ok(a) => if let SocketAddr::V4(a) = a {
-					a.port()
-				} else {
-					addr.port()
-				}
-				_ => addr.port(),
-			};
+			let base     = CONFIG.base_path.as_deref().unwrap_or("");
 
 			const PURPLE: &str = "\x1b[1;95m";
 			const YELLOW: &str = "\x1b[1;93m";
 			const BLUE:   &str = "\x1b[1;94m";
 			const WHITE:  &str = "\x1b[1;97m";
 			const OFF:    &str = "\x1b[0m";
-			let listening = format!("| festivald listening on {PURPLE}{protocol}{OFF}://{YELLOW}{}{OFF}:{BLUE}{port}{OFF} |", addr.ip());
-			println!("{WHITE}{0}{OFF}\n{listening}\n{WHITE}{0}{OFF}", "=".repeat(listening.len() - 33));
+
+			for (listener, ip) in LISTENERS.iter().zip(CONFIG.ip.iter()) {
+				let addr = match listener.local_addr() {
+					Ok(a)  => a,
+					Err(_) => SocketAddr::new(*ip, CONFIG.port),
+				};
+
+				// IPv6 addresses need brackets in URLs.
+				let ip = match addr {
+					SocketAddr::V4(a) => a.ip().to_string(),
+					SocketAddr::V6(a) => format!("[{}]", a.ip()),
+				};
+				let port = addr.port();
+
+				let listening = format!("| festivald listening on {PURPLE}{protocol}{OFF}://{YELLOW}{ip}{OFF}:{BLUE}{port}{OFF}{base} |");
+				println!("{WHITE}{0}{OFF}\n{listening}\n{WHITE}{0}{OFF}", "=".repeat(listening.len() - 33));
+			}
 		}}
 	}
 
@@ -312,6 +355,30 @@ pub async fn init(
 	}
 }
 
+//---------------------------------------------------------------------------------------------------- Bind
+// Create a TCP listener on `addr`.
+//
+// This is `TcpListener::bind()`, but `IPV6_V6ONLY` is set on IPv6 sockets if `v6only`.
+fn bind(addr: SocketAddr, v6only: bool) -> std::io::Result<TcpListener> {
+	use socket2::{Domain,Protocol,Socket,Type};
+
+	let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+
+	if addr.is_ipv6() {
+		socket.set_only_v6(v6only)?;
+	}
+
+	// Same as `tokio`, so restarts don't fail on `TIME_WAIT` sockets.
+	#[cfg(unix)]
+	socket.set_reuse_address(true)?;
+
+	socket.set_nonblocking(true)?;
+	socket.bind(&addr.into())?;
+	socket.listen(1024)?;
+
+	TcpListener::from_std(socket.into())
+}
+
 //---------------------------------------------------------------------------------------------------- Handle HTTP
 // Handle HTTP requests.
 async fn http(
@@ -378,18 +445,24 @@ async fn route(
 ) -> Result<Response<Body>, anyhow::Error> {
 	atomic_add!(TOTAL_REQUESTS, 1);
 
-	//-------------------------------------------------- Only accept IPv4
-	let addr = match addr {
-		std::net::SocketAddr::V4(addr) => {
-			debug!("Router - New connection: [{}]", addr.ip());
-			addr
-		},
-		std::net::SocketAddr::V6(addr) => {
-			warn!("Router - Skipping IPv6 connection: [{}]", addr.ip());
-			sleep_on_fail().await;
-			return Ok(resp::server_err("IPv4 not supported"));
+	//-------------------------------------------------- Normalize IP
+	// Dual-stack listeners see IPv4 clients as `::ffff:a.b.c.d`,
+	// turn those back into IPv4 so `seen` and `exclusive_ips` match.
+	//
+	// Behind a trusted reverse proxy, the real client
+	// IP is in the `trusted_proxy_header` header.
+	let ip = match &config().trusted_proxies {
+		Some(trusted) => match crate::ip::client(addr.ip(), req.headers(), trusted, config().trusted_proxy_header) {
+			Some(ip) => ip,
+			None => {
+				info!("Router - Unknown client behind proxy [{}], skipping", addr.ip());
+				return Ok(resp::bad_request("Unknown client IP in forwarded header"));
+			},
 		},
+		None => crate::ip::canonical(addr.ip()),
 	};
+	let addr = SocketAddr::new(ip, addr.port());
+	debug!("Router - New connection: [{}]", addr.ip());
 
 	crate::seen::add(&addr).await;
 
@@ -398,19 +471,58 @@ async fn route(
 	//-------------------------------------------------- Exclusive IP list
 	let ip = addr.ip();
 	if let Some(ips) = &config().exclusive_ips {
-		if !ips.contains(ip) {
+		if !ips.iter().any(|net| net.contains(&ip)) {
 			info!("Router - IP not in exclusive list, skipping [{ip}]");
 			sleep_on_fail().await;
 			return Ok(resp::forbidden("IP not in exclusive list"));
 		}
 	}
 
+	//-------------------------------------------------- Lockout
+	if let Some(secs) = crate::limit::locked(&addr) {
+		info!("Router - IP locked out, skipping [{ip}]");
+		return Ok(resp::too_many_requests("Too many failed authorizations", secs));
+	}
+
 	//-------------------------------------------------- Authorization
 	let (mut parts, body) = req.into_parts();
 
+	//-------------------------------------------------- Base path
+	// Strip the `base_path` prefix, the
+	// rest of the routing sees it as `/`.
+	if let Some(base) = &config().base_path {
+		let path = parts.uri.path();
+		let Some(rest) = path.strip_prefix(base.as_str()) else {
+			return Ok(resp::not_found(crate::rest::ERR_END));
+		};
+
+		// `/festival` -> `/festival/`, so the
+		// documentation's relative links work.
+		if rest.is_empty() && parts.method == hyper::Method::GET {
+			return Ok(resp::permanent_redirect(&format!("{base}/")));
+		} else if !rest.is_empty() && !rest.starts_with('/') {
+			// `/festivalx`
+			return Ok(resp::not_found(crate::rest::ERR_END));
+		}
+
+		let rest = if rest.is_empty() { "/" } else { rest };
+		let uri = match parts.uri.query() {
+			Some(q) => format!("{rest}?{q}"),
+			None    => rest.to_string(),
+		};
+
+		match uri.parse() {
+			Ok(uri) => parts.uri = uri,
+			Err(_)  => return Ok(resp::bad_request("Invalid URI")),
+		}
+	}
+
 	//-------------------------------------------------- JSON-RPC
 	if parts.method == hyper::Method::POST {
 		crate::rpc::handle(parts, body, addr, COLLECTION_PTR, TO_KERNEL, FROM_KERNEL, TO_ROUTER_S, TO_ROUTER_C).await
+	//-------------------------------------------------- Events
+	} else if parts.method == hyper::Method::GET && parts.uri.path() == "/event" {
+		crate::event::handle(parts, addr, COLLECTION_PTR).await
 	//-------------------------------------------------- REST
 	} else if crate::rest::REST_ENDPOINTS.contains({
 		let mut uri = parts.uri.path().split("/");
@@ -446,16 +558,19 @@ async fn route(
 		{
 			Ok(r) => {
 				// Check auth.
-				if !config().no_auth_docs {
-					if let Some(hash) = AUTH.get() {
-						if !auth_ok(&req.into_parts().0, hash).await {
-							if crate::seen::seen(&addr).await {
-								sleep_on_fail().await;
-							}
-							return Ok(resp::unauthorized("Unauthorized"));
-						}
+				let parts  = req.into_parts().0;
+				let caller = crate::user::caller(&parts).await;
+				if !caller.docs() {
+					if !caller.anonymous() {
+						return Ok(resp::forbidden("Forbidden"));
+					}
+					crate::limit::fail(&addr, &parts);
+					if crate::seen::seen(&addr).await {
+						sleep_on_fail().await;
 					}
+					return Ok(resp::unauthorized("Unauthorized"));
 				}
+				crate::seen::user(&addr, caller).await;
 
 				Ok(r)
 			},
@@ -467,28 +582,6 @@ async fn route(
 	}
 }
 
-//---------------------------------------------------------------------------------------------------- Auth
-// Verify authentication, ask for it, or ignore
-// if none is set in our config.
-pub async fn auth_ok(parts: &Parts, hash: &rpc::hash::Hash) -> bool {
-	match parts.headers.get(AUTHORIZATION) {
-		// AUTH header exists.
-		Some(s) => {
-			// Attempt to turn into UTF-8 string.
-			let string = match String::from_utf8(s.as_bytes().into()) {
-				Ok(s)  => s,
-				Err(e) => return false,
-			};
-
-			// Check if the hash matches our existing one.
-			hash.same(string)
-		},
-
-		// AUTH header doesn't exist, reject this request.
-		None => false,
-	}
-}
-
 //---------------------------------------------------------------------------------------------------- Sleep
 // Sleep for a random while.
 // Used for timing out requests, preventing timing attacks, etc.
@@ -2,13 +2,16 @@
 use anyhow::anyhow;
 use log::{error,info,warn,debug,trace};
 use std::sync::Arc;
-use std::net::SocketAddrV4;
+use std::net::SocketAddr;
 use hyper::{
 	Request,
 	Response,
 	body::Body,
 };
-use http::request::Parts;
+use http::{
+	StatusCode,
+	request::Parts,
+};
 use serde_json::value::{
 	RawValue,Value,
 };
@@ -20,6 +23,12 @@ use shukusai::{
 	state::{
 		AUDIO_STATE,
 		PLAYLISTS,
+		SMART_PLAYLISTS,
+		STATS,
+		SongStats,
+		SmartPlaylist,
+		Playlists,
+		PlaylistFormat,
 	},
 	collection::{
 		Collection,
@@ -45,7 +54,7 @@ use shukusai::{
 use crate::{
 	resp,
 	constants::FESTIVALD_VERSION,
-	config::{AUTH,Config,config},
+	config::{Config,config},
 	statics::{
 		RESETTING,
 		TOTAL_CONNECTIONS,
@@ -59,7 +68,7 @@ use benri::{
 	atomic_load,
 	atomic_store,
 	debug_panic,
-	lock,send,recv,
+	lock,send,
 	secs_f64,now,
 };
 use crossbeam::channel::{
@@ -100,7 +109,11 @@ impl_err! {
 	ERR_INDEX,          15, "Bad index, greater or equal to queue length",
 	ERR_OFFSET,         16, "Bad offset, greater or equal to amount of songs",
 	ERR_PLAYLIST,       17, "Playlist doesn't exist",
-	ERR_INDEX_PLAYLIST, 18, "Bad index, greater or equal to playlist length"
+	ERR_INDEX_PLAYLIST, 18, "Bad index, greater or equal to playlist length",
+	ERR_GENRE,          19, "Genre doesn't exist",
+	ERR_PLAYLIST_FORMAT,20, "Playlist file format could not be detected",
+	ERR_PERMISSION,     21, "Forbidden, not allowed to use this method",
+	ERR_RATE_LIMIT,     22, "Too many requests, try again later"
 }
 
 //---------------------------------------------------------------------------------------------------- Response "Cache"
@@ -125,6 +138,9 @@ pub static CACHE_PLAYLIST_FULL:            tokio::sync::RwLock<String> = tokio::
 // That string is `14` bytes.
 const END_POP: u8 = 14;
 
+// The most requests a single batch can have.
+const BATCH_MAX: usize = 256;
+
 // This sets all of the below.
 pub async fn cache_set_all(collection: &Arc<Collection>) {
 	cache_set_collection_full(collection).await;
@@ -361,7 +377,7 @@ macro_rules! lac {
 pub async fn handle(
 	parts:       Parts,
 	body:        Body,
-	addr:        SocketAddrV4,
+	addr:        SocketAddr,
 	collection:  &'static CollectionPtr,
 	TO_KERNEL:   &'static Sender<FrontendToKernel>,
 	FROM_KERNEL: &'static Receiver<KernelToFrontend>,
@@ -372,15 +388,100 @@ pub async fn handle(
 	let body = hyper::body::to_bytes(body).await?;
 
 	// Check if JSON.
-	if serde_json::from_slice::<&RawValue>(&body).is_err() {
+	let Ok(json) = serde_json::from_slice::<&RawValue>(&body) else {
 		return Ok(resp::parse_error(None));
 	};
 
-	// Parse request.
-	let Ok(request) = serde_json::from_slice::<json_rpc::Request<&RawValue, &RawValue>>(&body) else {
+	// Check who this is once, not for every request in a batch.
+	let caller = crate::user::caller(&parts).await;
+
+	// Single request.
+	if !json.get().starts_with('[') {
+		return handle_single(&parts, caller, json, addr, collection, TO_KERNEL, FROM_KERNEL, TO_ROUTER_S, TO_ROUTER_C).await;
+	}
+
+	// Batch request, each one is handled in order.
+	let Ok(batch) = serde_json::from_str::<Vec<&RawValue>>(json.get()) else {
 		return Ok(resp::parse_error(None));
 	};
 
+	if batch.is_empty() || batch.len() > BATCH_MAX {
+		return Ok(resp::invalid_request(None));
+	}
+
+	debug!("RPC - Batch: {}", batch.len());
+
+	let mut responses = Vec::with_capacity(batch.len());
+	for request in batch {
+		let response = handle_single(&parts, caller, request, addr, collection, TO_KERNEL, FROM_KERNEL, TO_ROUTER_S, TO_ROUTER_C).await?;
+
+		// Notifications don't get a response.
+		if response.status() != StatusCode::NO_CONTENT {
+			responses.push(hyper::body::to_bytes(response.into_body()).await?);
+		}
+	}
+
+	// A batch of only notifications gets nothing back.
+	if responses.is_empty() {
+		Ok(resp::no_content())
+	} else {
+		Ok(resp::batch(&responses))
+	}
+}
+
+// A single request, either on its own or as part of a batch.
+async fn handle_single(
+	parts:       &Parts,
+	caller:      crate::user::Caller,
+	json:        &RawValue,
+	addr:        SocketAddr,
+	collection:  &'static CollectionPtr,
+	TO_KERNEL:   &'static Sender<FrontendToKernel>,
+	FROM_KERNEL: &'static Receiver<KernelToFrontend>,
+	TO_ROUTER_S: &'static tokio::sync::mpsc::Sender::<()>,
+	TO_ROUTER_C: &'static tokio::sync::mpsc::Sender::<Arc<Collection>>,
+) -> Result<Response<Body>, anyhow::Error> {
+	// Parse request.
+	let Ok(request) = serde_json::from_str::<json_rpc::Request<&RawValue, &RawValue>>(json.get()) else {
+		return Ok(resp::invalid_request(None));
+	};
+
+	// Requests without an `id` are notifications, they
+	// are still executed but the client gets no response.
+	if request.id.is_none() {
+		let method = request.method.get().to_string();
+		match call(parts, caller, request, addr, collection, TO_KERNEL, FROM_KERNEL, TO_ROUTER_S, TO_ROUTER_C).await {
+			Ok(_)  => trace!("RPC - Notification: {method}"),
+			Err(e) => warn!("RPC - Notification: {method} error: {e}"),
+		}
+		return Ok(resp::no_content());
+	}
+
+	// An error here must not drop the rest of a batch,
+	// the requests before it have already been executed.
+	let id = request.id.clone();
+	let method = request.method.get().to_string();
+	match call(parts, caller, request, addr, collection, TO_KERNEL, FROM_KERNEL, TO_ROUTER_S, TO_ROUTER_C).await {
+		Ok(r)  => Ok(r),
+		Err(e) => {
+			warn!("RPC - {method} error: {e}");
+			Ok(resp::internal_error(id))
+		},
+	}
+}
+
+// Check auth and call the method.
+async fn call(
+	parts:       &Parts,
+	caller:      crate::user::Caller,
+	request:     json_rpc::Request<&RawValue, &RawValue>,
+	addr:        SocketAddr,
+	collection:  &'static CollectionPtr,
+	TO_KERNEL:   &'static Sender<FrontendToKernel>,
+	FROM_KERNEL: &'static Receiver<KernelToFrontend>,
+	TO_ROUTER_S: &'static tokio::sync::mpsc::Sender::<()>,
+	TO_ROUTER_C: &'static tokio::sync::mpsc::Sender::<Arc<Collection>>,
+) -> Result<Response<Body>, anyhow::Error> {
 	// If we're in the middle of a `Collection` reset, respond with "busy".
 	if crate::statics::resetting() {
 		return Ok(resp::resetting(ERR_RESETTING.0, ERR_RESETTING.1, request.id));
@@ -392,15 +493,21 @@ pub async fn handle(
 	};
 
 	// Check auth.
-	if !config().no_auth_rpc.as_ref().is_some_and(|h| h.contains(&method)) {
-		if let Some(hash) = AUTH.get() {
-			if !crate::router::auth_ok(&parts, hash).await {
-				if crate::seen::seen(&addr).await {
-					crate::router::sleep_on_fail().await;
-				}
-				return Ok(resp::unauth_rpc(ERR_AUTH.0, ERR_AUTH.1, request.id));
-			}
+	if !caller.rpc(&method) {
+		if !caller.anonymous() {
+			return Ok(resp::error(ERR_PERMISSION.0, ERR_PERMISSION.1, request.id));
+		}
+		crate::limit::fail(&addr, parts);
+		if crate::seen::seen(&addr).await {
+			crate::router::sleep_on_fail().await;
 		}
+		return Ok(resp::unauth_rpc(ERR_AUTH.0, ERR_AUTH.1, request.id));
+	}
+	crate::seen::user(&addr, caller).await;
+
+	// Check rate limit.
+	if crate::limit::throttle(&addr, caller, crate::limit::Cost::rpc(&method)).is_some() {
+		return Ok(resp::error(ERR_RATE_LIMIT.0, ERR_RATE_LIMIT.1, request.id));
 	}
 
 	use rpc::Method::*;
@@ -433,15 +540,18 @@ pub async fn handle(
 		CollectionResourceSize => lac!(method, request, collection_resource_size, collection.arc()).await,
 
 		//-------------------------------------------------- Daemon
+		DaemonBroadcast   => lac!(method, request, daemon_broadcast).await,
 		DaemonConfig      => lac!(method, request, daemon_config).await,
 		DaemonMethods     => lac!(method, request, daemon_methods).await,
 		DaemonNoAuthRpc   => lac!(method, request, daemon_no_auth_rpc).await,
 		DaemonNoAuthRest  => lac!(method, request, daemon_no_auth_rest).await,
+		DaemonPermissions => lac!(method, request, daemon_permissions, caller).await,
 		DaemonRemoveCache => lac!(method, request, daemon_remove_cache).await,
 		DaemonSave        => lac!(method, request, daemon_save, TO_KERNEL).await,
 		DaemonSeenIps     => lac!(method, request, daemon_seen_ips).await,
 		DaemonShutdown    => lac!(method, request, daemon_shutdown, TO_ROUTER_S).await,
 		DaemonState       => lac!(method, request, daemon_state).await,
+		DaemonUser        => lac!(method, request, daemon_user, caller).await,
 
 		//-------------------------------------------------- State
 		StateAudio      => lac!(method, request, state_audio, collection.arc()).await,
@@ -481,6 +591,15 @@ pub async fn handle(
 		MapAlbumSongs    => ppacor!(method, request, map_album_songs, rpc::param::MapAlbumSongs, collection.arc()).await,
 		MapAlbumEntries  => ppacor!(method, request, map_album_entries, rpc::param::MapAlbumEntries, collection.arc()).await,
 
+		//-------------------------------------------------- Genre
+		GenreList        => lac!(method, request, genre_list, collection.arc()).await,
+		GenreAlbums      => ppacor!(method, request, genre_albums, rpc::param::GenreAlbums, collection.arc()).await,
+
+		//-------------------------------------------------- Stats
+		StatsSong           => ppacor!(method, request, stats_song, rpc::param::StatsSong, collection.arc()).await,
+		StatsMostPlayed     => ppacor!(method, request, stats_most_played, rpc::param::StatsMostPlayed, collection.arc()).await,
+		StatsRecentlyPlayed => ppacor!(method, request, stats_recently_played, rpc::param::StatsRecentlyPlayed, collection.arc()).await,
+
 		//-------------------------------------------------- Current
 		CurrentArtist => lac!(method, request, current_artist, collection.arc()).await,
 		CurrentAlbum  => lac!(method, request, current_album, collection.arc()).await,
@@ -516,6 +635,7 @@ pub async fn handle(
 		Volume             => ppacor!(method, request, volume, rpc::param::Volume, TO_KERNEL).await,
 		VolumeUp           => ppacor!(method, request, volume_up, rpc::param::VolumeUp, TO_KERNEL).await,
 		VolumeDown         => ppacor!(method, request, volume_down, rpc::param::VolumeDown, TO_KERNEL).await,
+		Crossfade          => ppacor!(method, request, crossfade, rpc::param::Crossfade, TO_KERNEL).await,
 
 		//-------------------------------------------------- Queue
 		QueueAddKeyArtist  => ppacor!(method, request, queue_add_key_artist, rpc::param::QueueAddKeyArtist, collection.arc(), TO_KERNEL).await,
@@ -529,6 +649,7 @@ pub async fn handle(
 		QueueAddRandSong   => ppacor!(method, request, queue_add_rand_song, rpc::param::QueueAddRandSong, collection.arc(), TO_KERNEL).await,
 		QueueAddRandEntry  => ppacor!(method, request, queue_add_rand_entry, rpc::param::QueueAddRandEntry, collection.arc(), TO_KERNEL).await,
 		QueueAddPlaylist   => ppacor!(method, request, queue_add_playlist, rpc::param::QueueAddPlaylist, collection.arc(), TO_KERNEL).await,
+		QueueAddGenre      => ppacor!(method, request, queue_add_genre, rpc::param::QueueAddGenre, collection.arc(), TO_KERNEL).await,
 		QueueSetIndex      => ppacor!(method, request, queue_set_index, rpc::param::QueueSetIndex, TO_KERNEL).await,
 		QueueRemoveRange   => ppacor!(method, request, queue_remove_range, rpc::param::QueueRemoveRange, TO_KERNEL).await,
 
@@ -544,9 +665,19 @@ pub async fn handle(
 		PlaylistAddMapArtist => ppacor!(method, request, playlist_add_map_artist, rpc::param::PlaylistAddMapArtist, collection.arc()).await,
 		PlaylistAddMapAlbum  => ppacor!(method, request, playlist_add_map_album, rpc::param::PlaylistAddMapAlbum, collection.arc()).await,
 		PlaylistAddMapSong   => ppacor!(method, request, playlist_add_map_song, rpc::param::PlaylistAddMapSong, collection.arc()).await,
+		PlaylistAddGenre     => ppacor!(method, request, playlist_add_genre, rpc::param::PlaylistAddGenre, collection.arc()).await,
 		PlaylistSingle       => ppacor!(method, request, playlist_single, rpc::param::PlaylistSingle, collection.arc()).await,
 		PlaylistBrief        => lac!(method, request, playlist_brief).await,
 		PlaylistFull         => lac!(method, request, playlist_full).await,
+		PlaylistSmartNew     => ppacor!(method, request, playlist_smart_new, rpc::param::PlaylistSmartNew, collection.arc()).await,
+		PlaylistSmartList    => lac!(method, request, playlist_smart_list).await,
+		PlaylistImport       => ppacor!(method, request, playlist_import, rpc::param::PlaylistImport, collection.arc()).await,
+		PlaylistExport       => ppacor!(method, request, playlist_export, rpc::param::PlaylistExport, collection.arc()).await,
+
+		//-------------------------------------------------- Token
+		TokenNew    => ppacor!(method, request, token_new, rpc::param::TokenNew, caller).await,
+		TokenList   => lac!(method, request, token_list).await,
+		TokenRevoke => ppacor!(method, request, token_revoke, rpc::param::TokenRevoke, caller).await,
 	}
 }
 
@@ -555,7 +686,7 @@ pub async fn handle(
 // more important that `Audio` has quick access to
 // `AUDIO_STATE`, so this function is for accessing
 // it in a more low priority way.
-async fn audio_state_low_priority_lock() -> std::sync::RwLockReadGuard<'static, shukusai::state::AudioState> {
+pub async fn audio_state_low_priority_lock() -> std::sync::RwLockReadGuard<'static, shukusai::state::AudioState> {
 	loop {
 		if let Ok(lock) = AUDIO_STATE.try_read() {
 			return lock;
@@ -585,34 +716,6 @@ async fn collection_new<'a>(
 			return Ok(resp::resetting(ERR_RESETTING.0, ERR_RESETTING.1, id));
 		}
 
-		// `RESETTING` should be `true` from this point onward.
-		debug_assert_eq!(atomic_load!(RESETTING), true);
-
-		// Wait until we are the last `task`
-		// with this current `Arc<Collection>`
-		//
-		// Due to some `hyper` closure move stuff,
-		// a `task` will have 2 `Arc<Collection>`.
-		// Something about `service_fn` maybe being
-		// called multiple times, so you can't "move"
-		// things into it (even though it's 1 connection
-		// per service, so it should only called once? idk).
-		//
-		// Regardless, wait until we're close enough.
-		// `CCD` doesn't deconstruct for `festivald` anyway.
-		//
-		// `Kernel` + `Audio` + `Search` + `task` + `task` == 5
-		loop {
-			let sc = Arc::strong_count(&collection);
-
-			if sc > 5 {
-				debug!("RPC - collection_new(): strong count == {sc}, waiting...");
-				tokio::time::sleep(Duration::from_millis(10)).await;
-			} else {
-				break;
-			}
-		}
-
 		// Priority goes to parameter PATHs, then fallback to `collection_paths`,
 		// else send empty `Vec`, `shukusai` will handle it and use default the Music directory.
 		let paths = match params.paths {
@@ -624,21 +727,7 @@ async fn collection_new<'a>(
 			debug!("RPC - Collection Reset Path: {}", p.display());
 		}
 
-		send!(TO_KERNEL, FrontendToKernel::NewCollection(paths));
-
-		// Wait until `Kernel` has given us `Arc<Collection>`.
-		let collection = loop {
-			match recv!(FROM_KERNEL) {
-				KernelToFrontend::NewCollection(c) => break c,
-				_ => (),
-			}
-		};
-
-		// Set RPC cache.
-		cache_set_all(&collection).await;
-
-		// We're done resetting.
-		atomic_store!(RESETTING, false);
+		let collection = collection_reset(FrontendToKernel::NewCollection(paths), collection, TO_KERNEL, FROM_KERNEL).await;
 
 		// Respond to user.
 		let r = rpc::resp::CollectionNew {
@@ -659,6 +748,77 @@ async fn collection_new<'a>(
 	}).await
 }
 
+// The reset part of `collection_new`, also used by `watch.rs` for updates.
+//
+// Sends `msg` (`NewCollection` or `UpdateCollection`) to `Kernel`, waits
+// for the new `Arc<Collection>`, sets the RPC cache and `RESETTING` back to
+// `false`. `RESETTING` must already be set to `true` by the caller.
+//
+// `collection` is the caller's copy of the current `Arc<Collection>`.
+//
+// The returned `Collection` still has to be sent to `Router`.
+pub async fn collection_reset(
+	msg:         FrontendToKernel,
+	collection:  Arc<Collection>,
+	TO_KERNEL:   &'static Sender<FrontendToKernel>,
+	FROM_KERNEL: &'static Receiver<KernelToFrontend>,
+) -> Arc<Collection> {
+	// `RESETTING` should be `true` from this point onward.
+	debug_assert_eq!(atomic_load!(RESETTING), true);
+
+	// Wait until we are the last `task`
+	// with this current `Arc<Collection>`
+	//
+	// Due to some `hyper` closure move stuff,
+	// a `task` will have 2 `Arc<Collection>`.
+	// Something about `service_fn` maybe being
+	// called multiple times, so you can't "move"
+	// things into it (even though it's 1 connection
+	// per service, so it should only called once? idk).
+	//
+	// Regardless, wait until we're close enough.
+	// `CCD` doesn't deconstruct for `festivald` anyway.
+	//
+	// `Kernel` + `Audio` + `Search` + `task` + `task` == 5
+	//
+	// `watch.rs` only holds 1, so it waits for every `task`.
+	loop {
+		let sc = Arc::strong_count(&collection);
+
+		if sc > 5 {
+			debug!("RPC - collection_reset(): strong count == {sc}, waiting...");
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		} else {
+			break;
+		}
+	}
+	drop(collection);
+
+	// Make sure no `search` is waiting on `Kernel` as well.
+	let kernel_lock = crate::statics::KERNEL_LOCK.lock().await;
+
+	send!(TO_KERNEL, msg);
+
+	// Wait until `Kernel` has given us `Arc<Collection>`.
+	let collection = loop {
+		match FROM_KERNEL.try_recv() {
+			Ok(KernelToFrontend::NewCollection(c)) => break c,
+			Ok(_) => trace!("RPC - collection_reset(): ignoring non-NewCollection message"),
+			Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+		}
+	};
+
+	drop(kernel_lock);
+
+	// Set RPC cache.
+	cache_set_all(&collection).await;
+
+	// We're done resetting.
+	atomic_store!(RESETTING, false);
+
+	collection
+}
+
 async fn collection_brief<'a>(id: Option<Id<'a>>, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	let resp = rpc::resp::CollectionBrief {
 		empty: collection.empty,
@@ -722,22 +882,29 @@ async fn collection_perf<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyho
 
 async fn collection_health<'a>(id: Option<Id<'a>>, collection: Arc<Collection>) -> Result<Response<Body>, anyhow::Error> {
 	let mut vec = vec![];
+	let mut path_metadata = vec![];
+
+	fn entry<'b>(collection: &'b Collection, song: &'b shukusai::collection::Song) -> shukusai::collection::EntryJson<'b> {
+		let album  = &collection.albums[song.album];
+		let artist = &collection.artists[album.artist];
+
+		shukusai::collection::EntryJson {
+			artist: Cow::Borrowed(&artist.name),
+			album: Cow::Borrowed(&album.title),
+			song: Cow::Borrowed(&song.title),
+			key_artist: ArtistKey::from(album.artist),
+			key_album: AlbumKey::from(song.album),
+			key_song: SongKey::from(song.key),
+			path: Cow::Borrowed(song.path.as_path()),
+		}
+	}
 
 	for song in collection.songs.iter() {
 		if !song.path.exists() {
-			let album  = &collection.albums[song.album];
-			let artist = &collection.artists[album.artist];
-
-			let json = shukusai::collection::EntryJson {
-				artist: Cow::Borrowed(&artist.name),
-				album: Cow::Borrowed(&album.title),
-				song: Cow::Borrowed(&song.title),
-				key_artist: ArtistKey::from(album.artist),
-				key_album: AlbumKey::from(song.album),
-				key_song: SongKey::from(song.key),
-				path: Cow::Borrowed(song.path.as_path()),
-			};
-			vec.push(json);
+			vec.push(entry(&collection, song));
+		}
+		if song.path_metadata {
+			path_metadata.push(entry(&collection, song));
 		}
 	}
 
@@ -746,6 +913,8 @@ async fn collection_health<'a>(id: Option<Id<'a>>, collection: Arc<Collection>)
 		"song_len": collection.songs.len(),
 		"missing_len": vec.len(),
 		"missing": vec,
+		"path_metadata_len": path_metadata.len(),
+		"path_metadata": path_metadata,
 	});
 
 	Ok(resp::result(resp, id))
@@ -915,16 +1084,37 @@ async fn state_volume<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyhow::
 }
 
 //---------------------------------------------------------------------------------------------------- Daemon
+async fn daemon_broadcast<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyhow::Error> {
+	let resp = rpc::resp::DaemonBroadcast {
+		enabled:         config().broadcast,
+		format:          Cow::Borrowed(config().broadcast_format.into()),
+		bitrate:         config().broadcast_bitrate,
+		listeners:       crate::broadcast::listeners(),
+		total_listeners: atomic_load!(crate::broadcast::TOTAL_LISTENERS),
+	};
+
+	Ok(resp::result(resp, id))
+}
+
 async fn daemon_config<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyhow::Error> {
 	let c = config();
 
 	let resp = serde_json::json!({
-		"ip":                  c.ip,
+		"ip":                  Cow::Borrowed(&c.ip),
 		"port":                c.port,
 		"max_connections":     c.max_connections,
 		"exclusive_ips":       c.exclusive_ips.as_ref().map(|h| Cow::Borrowed(h)),
+		"trusted_proxies":     c.trusted_proxies.as_ref().map(|h| Cow::Borrowed(h)),
+		"trusted_proxy_header": c.trusted_proxy_header,
+		"base_path":           c.base_path.as_deref(),
 		"sleep_on_fail":       c.sleep_on_fail.clone(),
+		"rate_limit_cheap":    c.rate_limit_cheap,
+		"rate_limit_expensive": c.rate_limit_expensive,
+		"lockout_attempts":    c.lockout_attempts,
+		"lockout_time":        c.lockout_time,
 		"collection_paths":    Cow::Borrowed(&c.collection_paths),
+		"collection_watch":    c.collection_watch,
+		"collection_path_templates": Cow::Borrowed(&c.collection_path_templates),
 		"tls":                 c.tls,
 		"certificate":         c.certificate.as_ref().map(|p| Cow::Borrowed(p.as_path())),
 		"key":                 c.key.as_ref().map(|p| Cow::Borrowed(p.as_path())),
@@ -936,10 +1126,19 @@ async fn daemon_config<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyhow:
 		"watch":               c.watch,
 		"cache_clean":         c.cache_clean,
 		"cache_time":          c.cache_time,
+		"transcode_cache_size": c.transcode_cache_size,
 		"restore_audio_state": c.restore_audio_state,
 		"previous_threshold":  c.previous_threshold,
+		"crossfade":           c.crossfade,
+		"crossfade_curve":     c.crossfade_curve,
+		"replay_gain":         c.replay_gain,
+		"broadcast":           c.broadcast,
+		"broadcast_format":    c.broadcast_format,
+		"broadcast_bitrate":   c.broadcast_bitrate,
+		"output":              Cow::Borrowed(&c.output),
+		"output_wav_path":     c.output_wav_path.as_ref().map(|p| Cow::Borrowed(p.as_path())),
 		"media_controls":      c.media_controls,
-		"authorization":       AUTH.get().is_some(),
+		"authorization":       crate::user::enabled(),
 		"confirm_no_tls_auth": c.confirm_no_tls_auth,
 		"no_auth_rpc":         c.no_auth_rpc.as_ref().map(|h| Cow::Borrowed(h)),
 		"no_auth_rest":        c.no_auth_rest.as_ref().map(|h| Cow::Borrowed(h)),
@@ -967,6 +1166,16 @@ async fn daemon_save<'a>(
 			return Ok(resp::error(ERR_FS.0, ERR_FS.1, id));
 		}
 
+		// Save `SmartPlaylists`.
+		if SMART_PLAYLISTS.read().save_atomic().is_err() {
+			return Ok(resp::error(ERR_FS.0, ERR_FS.1, id));
+		}
+
+		// Save `Stats`.
+		if STATS.read().save_atomic().is_err() {
+			return Ok(resp::error(ERR_FS.0, ERR_FS.1, id));
+		}
+
 		Ok(resp::result_ok(id))
 	}).await
 }
@@ -1036,7 +1245,9 @@ async fn daemon_state<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyhow::
 		rest:                config().rest,
 		docs:                config().docs,
 		direct_download:     config().direct_download,
-		authorization:       AUTH.get().is_some(),
+		authorization:       crate::user::enabled(),
+		throttled:           crate::limit::throttled(),
+		locked:              crate::limit::locked_ips(),
 		version:             Cow::Borrowed(FESTIVALD_VERSION),
 		commit:              Cow::Borrowed(COMMIT),
 		os:                  Cow::Borrowed(OS_ARCH),
@@ -1047,13 +1258,22 @@ async fn daemon_state<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyhow::
 
 async fn daemon_seen_ips<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyhow::Error> {
 	let seen = crate::seen::SEEN_IPS.read().await.clone();
+	let mut seen_users = crate::seen::SEEN_USERS.read().await.clone();
 
 	let mut vec = Vec::with_capacity(seen.len());
 
 	for (ip, count) in seen.into_iter() {
+		let users = seen_users
+			.remove(&ip)
+			.unwrap_or_default()
+			.into_iter()
+			.map(|(name, count)| (name.to_string(), count))
+			.collect();
+
 		let inner = rpc::resp::DaemonSeenIpsInner {
 			ip,
 			count,
+			users,
 		};
 		vec.push(inner);
 	}
@@ -1108,6 +1328,40 @@ async fn daemon_no_auth_rest<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, a
 	Ok(resp::result(resp, id))
 }
 
+async fn daemon_permissions<'a>(id: Option<Id<'a>>, caller: crate::user::Caller) -> Result<Response<Body>, anyhow::Error> {
+	use strum::IntoEnumIterator;
+
+	let rpc: BTreeSet<Cow<'static, str>> = rpc::Method::iter()
+		.filter(|m| caller.rpc(m))
+		.map(|m| Cow::Borrowed(m.into()))
+		.collect();
+
+	let rest: BTreeSet<Cow<'static, str>> = rpc::resource::Resource::iter()
+		.filter(|r| caller.rest(r))
+		.map(|r| Cow::Borrowed(r.into()))
+		.collect();
+
+	let resp = rpc::resp::DaemonPermissions {
+		all:  caller.all(),
+		rpc:  Cow::Owned(rpc),
+		rest: Cow::Owned(rest),
+		docs: caller.docs(),
+	};
+
+	Ok(resp::result(resp, id))
+}
+
+async fn daemon_user<'a>(id: Option<Id<'a>>, caller: crate::user::Caller) -> Result<Response<Body>, anyhow::Error> {
+	let resp = rpc::resp::DaemonUser {
+		authenticated: caller.name().is_some(),
+		user:          caller.name().map(Cow::Borrowed),
+		role:          caller.role_name().map(Cow::Borrowed),
+		token:         caller.token().map(|t| Cow::Borrowed(t.id.as_str())),
+	};
+
+	Ok(resp::result(resp, id))
+}
+
 //---------------------------------------------------------------------------------------------------- Key (exact key)
 async fn key_artist<'a>(
 	params:     rpc::param::KeyArtist,
@@ -1179,7 +1433,13 @@ async fn key_artist_songs<'a>(
 ) -> Result<Response<Body>, anyhow::Error> {
 	let key = ArtistKey::from(params.key);
 	if collection.artists.get(key).is_some() {
-		let r = collection.artist_songs(key);
+		let r = if params.appearances {
+			let mut r = collection.artist_songs(key).into_vec();
+			r.extend_from_slice(&collection.artist_appearances(key));
+			r.into_boxed_slice()
+		} else {
+			collection.artist_songs(key)
+		};
 		Ok(resp::result(serde_json::json!({ "len": r.len(), "songs": r }), id))
 	} else {
 		Ok(resp::error(ERR_KEY_ARTIST.0, ERR_KEY_ARTIST.1, id))
@@ -1460,6 +1720,88 @@ async fn map_album_entries<'a>(
 	}
 }
 
+//---------------------------------------------------------------------------------------------------- Genre
+async fn genre_list<'a>(
+	id:         Option<Id<'a>>,
+	collection: Arc<Collection>,
+) -> Result<Response<Body>, anyhow::Error> {
+	let r = &collection.genres;
+	Ok(resp::result(serde_json::json!({ "len": r.len(), "genres": r }), id))
+}
+
+async fn genre_albums<'a>(
+	params:     rpc::param::GenreAlbums<'a>,
+	id:         Option<Id<'a>>,
+	collection: Arc<Collection>,
+) -> Result<Response<Body>, anyhow::Error> {
+	if let Some(r) = collection.genre_albums(&params.genre) {
+		Ok(resp::result(serde_json::json!({ "len": r.len(), "albums": r }), id))
+	} else {
+		Ok(resp::error(ERR_GENRE.0, ERR_GENRE.1, id))
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Stats
+async fn stats_song<'a>(
+	params:     rpc::param::StatsSong,
+	id:         Option<Id<'a>>,
+	collection: Arc<Collection>,
+) -> Result<Response<Body>, anyhow::Error> {
+	let key = SongKey::from(params.key);
+	if collection.songs.get(key).is_none() {
+		return Ok(resp::error(ERR_KEY_SONG.0, ERR_KEY_SONG.1, id));
+	}
+
+	// Songs that were never played have no entry.
+	let s = STATS.read().song(key, &collection).copied().unwrap_or_default();
+
+	let resp = rpc::resp::StatsSong {
+		play_count:   s.play_count,
+		skip_count:   s.skip_count,
+		first_played: s.first_played,
+		last_played:  s.last_played,
+		listened:     s.listened,
+	};
+
+	Ok(resp::result(resp, id))
+}
+
+async fn stats_most_played<'a>(
+	params:     rpc::param::StatsMostPlayed,
+	id:         Option<Id<'a>>,
+	collection: Arc<Collection>,
+) -> Result<Response<Body>, anyhow::Error> {
+	let vec = STATS.read().most_played(&collection);
+	Ok(resp::result(stats_songs(vec, params.len), id))
+}
+
+async fn stats_recently_played<'a>(
+	params:     rpc::param::StatsRecentlyPlayed,
+	id:         Option<Id<'a>>,
+	collection: Arc<Collection>,
+) -> Result<Response<Body>, anyhow::Error> {
+	let vec = STATS.read().recently_played(&collection);
+	Ok(resp::result(stats_songs(vec, params.len), id))
+}
+
+// Shared response of `stats_most_played` & `stats_recently_played`.
+fn stats_songs(vec: Vec<(SongKey, SongStats)>, len: Option<usize>) -> serde_json::Value {
+	let songs: Vec<rpc::resp::StatsSongInner> = vec
+		.into_iter()
+		.take(len.unwrap_or(usize::MAX))
+		.map(|(key, s)| rpc::resp::StatsSongInner {
+			key,
+			play_count:   s.play_count,
+			skip_count:   s.skip_count,
+			first_played: s.first_played,
+			last_played:  s.last_played,
+			listened:     s.listened,
+		})
+		.collect();
+
+	serde_json::json!({ "len": songs.len(), "songs": songs })
+}
+
 //---------------------------------------------------------------------------------------------------- Current (audio state)
 async fn current_artist<'a>(
 	id:         Option<Id<'a>>,
@@ -1846,6 +2188,16 @@ async fn volume_down<'a>(
 	Ok(resp::result(resp, id))
 }
 
+async fn crossfade<'a>(
+	params:    rpc::param::Crossfade,
+	id:        Option<Id<'a>>,
+	TO_KERNEL: &Sender<FrontendToKernel>,
+) -> Result<Response<Body>, anyhow::Error> {
+	let crossfade = shukusai::audio::Crossfade::new(params.seconds, params.curve);
+	send!(TO_KERNEL, FrontendToKernel::Crossfade(crossfade));
+	Ok(resp::result_ok(id))
+}
+
 //---------------------------------------------------------------------------------------------------- Queue
 macro_rules! get_append {
 	($params:expr, $id:expr) => {
@@ -2088,6 +2440,26 @@ async fn queue_add_playlist<'a>(
 	}
 }
 
+async fn queue_add_genre<'a>(
+	params:     rpc::param::QueueAddGenre<'a>,
+	id:         Option<Id<'a>>,
+	collection: Arc<Collection>,
+	TO_KERNEL:  &Sender<FrontendToKernel>
+) -> Result<Response<Body>, anyhow::Error> {
+	if let Some(genre) = collection.genre(&params.genre) {
+		let append = get_append!(params, id);
+		let offset = get_offset!(params.offset, genre.songs.len(), id);
+
+		let genre = Arc::clone(&genre.name);
+
+		send!(TO_KERNEL, FrontendToKernel::QueueAddGenre((genre, append, params.clear, params.play, offset)));
+
+		Ok(resp::result_ok(id))
+	} else {
+		Ok(resp::error(ERR_GENRE.0, ERR_GENRE.1, id))
+	}
+}
+
 async fn queue_set_index<'a>(
 	params:    rpc::param::QueueSetIndex,
 	id:        Option<Id<'a>>,
@@ -2124,6 +2496,9 @@ async fn playlist_new<'a>(
 	id:          Option<Id<'a>>,
 	collection:  Arc<Collection>,
 ) -> Result<Response<Body>, anyhow::Error> {
+	// This is now a normal playlist.
+	SMART_PLAYLISTS.write().remove(&*params.playlist);
+
 	match PLAYLISTS.write().playlist_new(&params.playlist) {
 		Some(v) => Ok(resp::result(serde_json::json!({ "len": v.len(), "entries": v }), id)),
 		None    => Ok(resp::result(rpc::resp::PlaylistNew { len: None, entries: None }, id)),
@@ -2135,6 +2510,8 @@ async fn playlist_remove<'a>(
 	id:          Option<Id<'a>>,
 	collection:  Arc<Collection>,
 ) -> Result<Response<Body>, anyhow::Error> {
+	SMART_PLAYLISTS.write().remove(&*params.playlist);
+
 	match PLAYLISTS.write().playlist_remove(params.playlist.into()) {
 		Some(v) => Ok(resp::result(serde_json::json!({ "len": v.len(), "entries": v }), id)),
 		None    => Ok(resp::error(ERR_PLAYLIST.0, ERR_PLAYLIST.1, id)),
@@ -2146,7 +2523,14 @@ async fn playlist_clone<'a>(
 	id:          Option<Id<'a>>,
 	collection:  Arc<Collection>,
 ) -> Result<Response<Body>, anyhow::Error> {
-	match PLAYLISTS.write().playlist_clone(params.from.into(), &params.to) {
+	let result = PLAYLISTS.write().playlist_clone(params.from.into(), &params.to);
+
+	// The clone is a normal playlist.
+	if result.is_ok() {
+		SMART_PLAYLISTS.write().remove(&*params.to);
+	}
+
+	match result {
 		Ok(Some(v)) => Ok(resp::result(serde_json::json!({ "len": v.len(), "entries": v }), id)),
 		Ok(None)    => Ok(resp::result(rpc::resp::PlaylistClone { len: None, entries: None }, id)),
 		Err(_)      => Ok(resp::error(ERR_PLAYLIST.0, ERR_PLAYLIST.1, id)),
@@ -2310,6 +2694,24 @@ async fn playlist_add_map_song<'a>(
 	Ok(resp::result(rpc::resp::PlaylistAddMapSong { existed, old_len, new_len }, id))
 }
 
+async fn playlist_add_genre<'a>(
+	params:      rpc::param::PlaylistAddGenre<'a>,
+	id:          Option<Id<'a>>,
+	collection:  Arc<Collection>,
+) -> Result<Response<Body>, anyhow::Error> {
+	let Some(genre) = collection.genre(&params.genre) else {
+		return Ok(resp::error(ERR_GENRE.0, ERR_GENRE.1, id));
+	};
+
+	let playlist: Arc<str> = params.playlist.into();
+	let mut p = PLAYLISTS.write();
+
+	let append = get_append_playlist!(params, id, p, playlist);
+
+	let (existed, old_len, new_len) = p.playlist_add_genre(playlist, genre, append, &collection);
+	Ok(resp::result(rpc::resp::PlaylistAddGenre { existed, old_len, new_len }, id))
+}
+
 async fn playlist_single<'a>(
 	params:      rpc::param::PlaylistSingle<'a>,
 	id:          Option<Id<'a>>,
@@ -2353,6 +2755,154 @@ async fn playlist_brief<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyhow
 	Ok(resp::result(resp, id))
 }
 
+async fn playlist_smart_new<'a>(
+	params:      rpc::param::PlaylistSmartNew<'a>,
+	id:          Option<Id<'a>>,
+	collection:  Arc<Collection>,
+) -> Result<Response<Body>, anyhow::Error> {
+	let playlist: Arc<str> = params.playlist.into();
+
+	let smart = SmartPlaylist {
+		rules: params.rules,
+		limit: params.limit,
+		sort:  params.sort.unwrap_or_default(),
+	};
+
+	// Lock order: `SMART_PLAYLISTS` -> `STATS` -> `PLAYLISTS`, same as `CCD`.
+	let mut s = SMART_PLAYLISTS.write();
+	let entries = smart.evaluate(&collection, &STATS.read(), benri::unix!());
+	let len = entries.len();
+	s.insert(Arc::clone(&playlist), smart);
+	let existed = PLAYLISTS.write().insert(playlist, entries).is_some();
+	drop(s);
+
+	Ok(resp::result(rpc::resp::PlaylistSmartNew { existed, len }, id))
+}
+
+async fn playlist_smart_list<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyhow::Error> {
+	let playlists = SMART_PLAYLISTS.read().clone();
+	Ok(resp::result(rpc::resp::PlaylistSmartList { len: playlists.len(), playlists }, id))
+}
+
+async fn playlist_import<'a>(
+	params:      rpc::param::PlaylistImport<'a>,
+	id:          Option<Id<'a>>,
+	collection:  Arc<Collection>,
+) -> Result<Response<Body>, anyhow::Error> {
+	// The file's contents are sent by the client, `festivald` never reads
+	// arbitrary files on its host. This also means relative `PATH`'s in
+	// the file have nothing to be relative to, so they are only matched
+	// by their metadata.
+	let Some(format) = params.format.or_else(|| PlaylistFormat::detect(&params.data)) else {
+		return Ok(resp::error(ERR_PLAYLIST_FORMAT.0, ERR_PLAYLIST_FORMAT.1, id));
+	};
+
+	let entries     = Playlists::import(&params.data, format, None, &collection);
+	let len         = entries.len();
+	let valid_len   = Playlists::valid_len(&entries);
+	let invalid_len = len - valid_len;
+
+	// This is now a normal playlist.
+	SMART_PLAYLISTS.write().remove(&*params.playlist);
+	let existed = PLAYLISTS.write().insert(params.playlist.into(), entries).is_some();
+
+	Ok(resp::result(rpc::resp::PlaylistImport { existed, len, valid_len, invalid_len }, id))
+}
+
+async fn playlist_export<'a>(
+	params:      rpc::param::PlaylistExport<'a>,
+	id:          Option<Id<'a>>,
+	collection:  Arc<Collection>,
+) -> Result<Response<Body>, anyhow::Error> {
+	let Some(playlist) = PLAYLISTS.read().get(&*params.playlist).map(|v| v.clone()) else {
+		return Ok(resp::error(ERR_PLAYLIST.0, ERR_PLAYLIST.1, id));
+	};
+
+	// Only `XSPF` can hold invalid entries.
+	let len = match params.format {
+		PlaylistFormat::Xspf => playlist.len(),
+		_ => Playlists::valid_len(&playlist),
+	};
+	let data = Playlists::export(&params.playlist, &playlist, params.format, &collection);
+
+	Ok(resp::result(rpc::resp::PlaylistExport { format: params.format, len, data: Cow::Owned(data) }, id))
+}
+
+//---------------------------------------------------------------------------------------------------- Token
+async fn token_new<'a>(
+	params: rpc::param::TokenNew<'a>,
+	id:     Option<Id<'a>>,
+	caller: crate::user::Caller,
+) -> Result<Response<Body>, anyhow::Error> {
+	// Tokens can't create tokens, even if `token_new` is one of their
+	// methods, else a short-lived token could create a permanent one.
+	if caller.token().is_some() {
+		return Ok(resp::error(ERR_PERMISSION.0, ERR_PERMISSION.1, id));
+	}
+
+	// A token can't be given more than its creator has.
+	if !params.methods.iter().all(|m| caller.rpc(m)) {
+		return Ok(resp::error(ERR_PERMISSION.0, ERR_PERMISSION.1, id));
+	}
+
+	let methods = params.methods.into_iter().collect();
+	let (token, secret) = match crate::token::new(params.label.into_owned(), methods, params.expire) {
+		Ok(t)  => t,
+		Err(e) => {
+			warn!("Token - {e}");
+			return Ok(resp::error(ERR_FS.0, ERR_FS.1, id));
+		},
+	};
+
+	let resp = rpc::resp::TokenNew {
+		id:      Cow::Borrowed(&token.id),
+		label:   Cow::Borrowed(&token.label),
+		token:   Cow::Owned(secret),
+		created: token.created,
+		expires: token.expires,
+		methods: token.methods.clone(),
+	};
+
+	Ok(resp::result(resp, id))
+}
+
+async fn token_list<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyhow::Error> {
+	let now = benri::unix!();
+
+	let resp: Vec<rpc::resp::TokenListInner> = crate::token::list()
+		.into_iter()
+		.map(|t| rpc::resp::TokenListInner {
+			id:      Cow::Borrowed(&t.id),
+			label:   Cow::Borrowed(&t.label),
+			created: t.created,
+			expires: t.expires,
+			expired: t.expired(now),
+			methods: t.methods.clone(),
+		})
+		.collect();
+
+	Ok(resp::result(rpc::resp::TokenList(resp), id))
+}
+
+async fn token_revoke<'a>(
+	params: rpc::param::TokenRevoke<'a>,
+	id:     Option<Id<'a>>,
+	caller: crate::user::Caller,
+) -> Result<Response<Body>, anyhow::Error> {
+	// Tokens can't revoke tokens, including other ones.
+	if caller.token().is_some() {
+		return Ok(resp::error(ERR_PERMISSION.0, ERR_PERMISSION.1, id));
+	}
+
+	match crate::token::revoke(&params.id) {
+		Ok(existed) => Ok(resp::result(rpc::resp::TokenRevoke { existed }, id)),
+		Err(e) => {
+			warn!("Token - {e}");
+			Ok(resp::error(ERR_FS.0, ERR_FS.1, id))
+		},
+	}
+}
+
 async fn playlist_full<'a>(id: Option<Id<'a>>) -> Result<Response<Body>, anyhow::Error> {
 	let playlists = PLAYLISTS.read().clone();
 
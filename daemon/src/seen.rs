@@ -1,37 +1,61 @@
 //---------------------------------------------------------------------------------------------------- Use
 use anyhow::anyhow;
 use log::{error,info,warn,debug,trace};
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 use tokio::sync::{
 	RwLock,
 };
 use std::net::{
-	Ipv4Addr,
-	SocketAddrV4,
+	IpAddr,
+	SocketAddr,
 };
 
 //----------------------------------------------------------------------------------------------------
 // Global map of seen IPs and their connection count.
-pub static SEEN_IPS: RwLock<BTreeMap<Ipv4Addr, u64>> = RwLock::const_new(BTreeMap::new());
+pub static SEEN_IPS: RwLock<BTreeMap<IpAddr, u64>> = RwLock::const_new(BTreeMap::new());
 
-pub async fn add(addr: &std::net::SocketAddrV4) {
-	SEEN_IPS.write().await.entry(*addr.ip()).and_modify(|c| { *c += 1 }).or_insert(1);
+pub async fn add(addr: &SocketAddr) {
+	SEEN_IPS.write().await.entry(addr.ip()).and_modify(|c| { *c += 1 }).or_insert(1);
 }
 
-pub async fn seen(addr: &std::net::SocketAddrV4) -> bool {
-	match SEEN_IPS.read().await.get(addr.ip()) {
+pub async fn seen(addr: &SocketAddr) -> bool {
+	match SEEN_IPS.read().await.get(&addr.ip()) {
 		Some(k) => k > &1,
 		None    => false,
 	}
 }
 
-pub async fn count(addr: &std::net::SocketAddrV4) -> u64 {
-	match SEEN_IPS.read().await.get(addr.ip()) {
+pub async fn count(addr: &SocketAddr) -> u64 {
+	match SEEN_IPS.read().await.get(&addr.ip()) {
 		Some(k) => *k,
 		None    => 0,
 	}
 }
 
+//----------------------------------------------------------------------------------------------------
+// Global map of seen IPs, the users that authenticated
+// from them and how many requests each user made.
+//
+// Tokens are keyed by ID (`token:<ID>`) like `limit.rs`,
+// labels are free-form and could be shared with a username.
+pub static SEEN_USERS: RwLock<BTreeMap<IpAddr, BTreeMap<Cow<'static, str>, u64>>> = RwLock::const_new(BTreeMap::new());
+
+pub async fn user(addr: &SocketAddr, caller: crate::user::Caller) {
+	let name = match (caller.token(), caller.name()) {
+		(Some(t), _)    => Cow::Owned(format!("token:{}", t.id)),
+		(None, Some(n)) => Cow::Borrowed(n),
+		(None, None)    => return,
+	};
+
+	SEEN_USERS.write().await
+		.entry(addr.ip())
+		.or_default()
+		.entry(name)
+		.and_modify(|c| { *c += 1 })
+		.or_insert(1);
+}
+
 //---------------------------------------------------------------------------------------------------- TESTS
 //#[cfg(test)]
 //mod tests {
@@ -0,0 +1,246 @@
+//---------------------------------------------------------------------------------------------------- Use
+use anyhow::anyhow;
+use serde::{Serialize,Deserialize};
+use std::collections::{BTreeMap,BTreeSet};
+use std::sync::RwLock;
+use benri::{lockr,lockw};
+use rand::Rng;
+use disk::Json;
+use const_format::formatcp;
+use shukusai::constants::{
+	FESTIVAL,FRONTEND_SUB_DIR,STATE_SUB_DIR,
+};
+
+//---------------------------------------------------------------------------------------------------- Constants
+// Length of the random bytes in a token's ID and secret.
+const ID_LEN:     usize = 8;
+const SECRET_LEN: usize = 32;
+
+//---------------------------------------------------------------------------------------------------- Statics
+// All the current tokens, keyed by ID.
+//
+// Tokens are leaked so that a `Caller` can hold onto one without
+// cloning, they are small and only created by an authorized caller.
+static TOKENS: RwLock<BTreeMap<String, &'static Token>> = RwLock::new(BTreeMap::new());
+
+//---------------------------------------------------------------------------------------------------- Tokens
+disk::json!(Tokens, disk::Dir::Data, FESTIVAL, formatcp!("{FRONTEND_SUB_DIR}/{STATE_SUB_DIR}"), "tokens");
+/// The tokens, as saved on disk, keyed by ID.
+#[derive(Clone,Debug,Default,PartialEq,Eq,Serialize,Deserialize)]
+#[serde(transparent)]
+pub struct Tokens(BTreeMap<String, TokenJson>);
+
+/// A single token, as saved on disk.
+///
+/// The secret is never saved, only its hash.
+#[derive(Clone,Debug,PartialEq,Eq,Serialize,Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenJson {
+	label:   String,
+	hash:    String,
+	created: u64,
+	expires: Option<u64>,
+	methods: BTreeSet<rpc::Method>,
+}
+
+//---------------------------------------------------------------------------------------------------- Token
+/// A bearer token, after its hash was decoded.
+#[derive(Debug)]
+pub struct Token {
+	/// The public part of the token.
+	pub id: String,
+	/// A human readable label, e.g: `kitchen-kiosk`.
+	pub label: String,
+	hash: rpc::hash::Hash,
+	/// UNIX timestamp of when this token was created.
+	pub created: u64,
+	/// UNIX timestamp of when this token expires, [`None`] for never.
+	pub expires: Option<u64>,
+	/// The JSON-RPC methods this token is allowed to use.
+	pub methods: BTreeSet<rpc::Method>,
+}
+
+impl Token {
+	/// If this token has expired at the UNIX timestamp `now`.
+	pub fn expired(&self, now: u64) -> bool {
+		self.expires.is_some_and(|e| now >= e)
+	}
+
+	fn to_json(&self) -> TokenJson {
+		TokenJson {
+			label:   self.label.clone(),
+			hash:    self.hash.encode(),
+			created: self.created,
+			expires: self.expires,
+			methods: self.methods.clone(),
+		}
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Free functions
+/// Load the tokens from disk.
+///
+/// Tokens that cannot be decoded are skipped.
+pub fn init() -> Result<usize, anyhow::Error> {
+	if Tokens::exists().is_err() {
+		return Ok(0);
+	}
+
+	let tokens = Tokens::from_file()?;
+	let mut map = lockw!(TOKENS);
+
+	for (id, json) in tokens.0 {
+		let Some(hash) = rpc::hash::Hash::decode(&json.hash) else {
+			log::warn!("Token - Skipping token with invalid hash: {id}");
+			continue;
+		};
+
+		let token = Token {
+			id: id.clone(),
+			label:   json.label,
+			hash,
+			created: json.created,
+			expires: json.expires,
+			methods: json.methods,
+		};
+
+		map.insert(id, Box::leak(Box::new(token)));
+	}
+
+	Ok(map.len())
+}
+
+// Save all tokens to disk.
+fn save(map: &BTreeMap<String, &'static Token>) -> Result<(), anyhow::Error> {
+	let tokens = Tokens(map.iter().map(|(id, t)| (id.clone(), t.to_json())).collect());
+	tokens.save_atomic()?;
+	Ok(())
+}
+
+// `N` random bytes as a hex string.
+fn rand_hex<const N: usize>() -> String {
+	use std::fmt::Write;
+	let bytes: [u8; N] = rand::thread_rng().gen();
+	bytes.iter().fold(String::with_capacity(N * 2), |mut s, b| {
+		let _ = write!(s, "{b:02x}");
+		s
+	})
+}
+
+/// Create a new token, and save it to disk.
+///
+/// `expire` is in seconds from now.
+///
+/// Returns the token and the full `ID.SECRET` string,
+/// this is the only time the secret is available.
+pub fn new(
+	label:   String,
+	methods: BTreeSet<rpc::Method>,
+	expire:  Option<u64>,
+) -> Result<(&'static Token, String), anyhow::Error> {
+	let mut map = lockw!(TOKENS);
+
+	// Practically never happens, but don't overwrite an existing ID.
+	let mut id = rand_hex::<ID_LEN>();
+	while map.contains_key(&id) {
+		id = rand_hex::<ID_LEN>();
+	}
+
+	let secret  = rand_hex::<SECRET_LEN>();
+	let created = benri::unix!();
+
+	let token = Token {
+		id: id.clone(),
+		label,
+		hash: rpc::hash::Hash::new(secret.clone()),
+		created,
+		expires: expire.map(|e| created.saturating_add(e)),
+		methods,
+	};
+
+	let token: &'static Token = Box::leak(Box::new(token));
+	map.insert(id.clone(), token);
+
+	if let Err(e) = save(&map) {
+		map.remove(&id);
+		return Err(anyhow!("could not save tokens: {e}"));
+	}
+
+	Ok((token, format!("{id}.{secret}")))
+}
+
+/// Revoke a token, and save the rest to disk.
+///
+/// Returns `true` if the token existed.
+pub fn revoke(id: &str) -> Result<bool, anyhow::Error> {
+	let mut map = lockw!(TOKENS);
+
+	let Some(token) = map.remove(id) else {
+		return Ok(false);
+	};
+
+	if let Err(e) = save(&map) {
+		map.insert(id.to_string(), token);
+		return Err(anyhow!("could not save tokens: {e}"));
+	}
+
+	Ok(true)
+}
+
+/// All tokens, including expired ones.
+pub fn list() -> Vec<&'static Token> {
+	lockr!(TOKENS).values().copied().collect()
+}
+
+/// Find the token matching an `ID.SECRET` string.
+///
+/// Expired tokens never match.
+pub fn get(bearer: &str) -> Option<&'static Token> {
+	let (id, secret) = bearer.trim().split_once('.')?;
+	let token = *lockr!(TOKENS).get(id)?;
+
+	if token.expired(benri::unix!()) || !token.hash.same(secret.to_string()) {
+		return None;
+	}
+
+	Some(token)
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rand_hex_len() {
+		let id = rand_hex::<ID_LEN>();
+		assert_eq!(id.len(), ID_LEN * 2);
+		assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+		assert_ne!(rand_hex::<SECRET_LEN>(), rand_hex::<SECRET_LEN>());
+	}
+
+	#[test]
+	fn expired() {
+		let token = |expires| Token {
+			id:      String::new(),
+			label:   String::new(),
+			hash:    rpc::hash::Hash::new(String::new()),
+			created: 100,
+			expires,
+			methods: BTreeSet::new(),
+		};
+
+		assert!(!token(None).expired(u64::MAX));
+		assert!(!token(Some(200)).expired(199));
+		assert!(token(Some(200)).expired(200));
+	}
+
+	#[test]
+	fn json() {
+		let json = r#"{"0123456789abcdef":{"label":"kiosk","hash":"00$11","created":1,"expires":null,"methods":["toggle","next"]}}"#;
+		let tokens: Tokens = serde_json::from_str(json).unwrap();
+		let t = &tokens.0["0123456789abcdef"];
+		assert_eq!(t.label, "kiosk");
+		assert_eq!(t.methods, BTreeSet::from([rpc::Method::Toggle, rpc::Method::Next]));
+	}
+}
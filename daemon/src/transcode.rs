@@ -0,0 +1,956 @@
+// On-the-fly transcoding for the `/transcode` REST endpoint.
+//
+// The `Song` is decoded with Symphonia and encoded into Opus/MP3/AAC
+// in a blocking thread. The encoded bytes are streamed to the client
+// as they come out of the encoder _and_ written into the transcode
+// cache, so the next request for the same `Song`/format/bitrate is
+// served straight from disk (with `Range` support).
+
+//---------------------------------------------------------------------------------------------------- Use
+use anyhow::{anyhow,bail};
+use serde::{Serialize,Deserialize};
+use log::{error,info,warn,debug,trace};
+use hyper::{
+	Response,
+	body::{Body,Bytes},
+	header::HeaderMap,
+};
+use std::sync::Arc;
+use std::io::Write;
+use std::path::{Path,PathBuf};
+use std::str::FromStr;
+use std::time::SystemTime;
+use strum::{
+	AsRefStr,
+	Display,
+	EnumString,
+	EnumVariantNames,
+	IntoStaticStr,
+};
+use symphonia::core::{
+	audio::SampleBuffer,
+	codecs::DecoderOptions,
+	errors::Error as SymphoniaError,
+	formats::{FormatOptions,SeekMode,SeekTo},
+	io::MediaSourceStream,
+	meta::MetadataOptions,
+	probe::Hint,
+	units::Time,
+};
+use rubato::Resampler as _;
+use shukusai::collection::{Collection,Song,SongKey};
+use crate::{
+	resp,
+	config::{config,BROADCAST_BITRATES},
+	constants::FESTIVALD_NAME_VER,
+	zip::TranscodeCache,
+};
+
+//---------------------------------------------------------------------------------------------------- Constants
+/// The default `bitrate` (kbps) if the client didn't specify one.
+pub const TRANSCODE_BITRATE_DEFAULT: u16 = 128;
+
+/// The lowest `bitrate` (kbps) Opus/AAC can be encoded at.
+pub const TRANSCODE_BITRATE_MIN: u16 = 32;
+
+/// The highest `bitrate` (kbps) Opus/AAC can be encoded at.
+pub const TRANSCODE_BITRATE_MAX: u16 = 320;
+
+// How many encoded chunks can be queued for the
+// client before the encoder waits for it to catch up.
+const CHUNK_QUEUE: usize = 16;
+
+// Opus always encodes at 48kHz, anything else is resampled.
+const OPUS_RATE: u32 = 48_000;
+
+// 20ms Opus frames.
+const OPUS_FRAME: usize = 960;
+
+// The max size of a single Opus packet.
+const OPUS_PACKET: usize = 4000;
+
+// The amount of frames the resampler takes in at a time.
+const RESAMPLE_CHUNK: usize = 1024;
+
+// AAC-LC frames are 1024 samples (per channel).
+#[cfg(feature = "aac")]
+const AAC_FRAME: usize = 1024;
+
+const ERR_CACHE: &str = "Transcode cache error";
+
+//---------------------------------------------------------------------------------------------------- Format
+/// The formats `/transcode` and `/stream` can encode into.
+#[derive(Copy,Clone,Debug,Default,PartialEq,Eq,Hash,Serialize,Deserialize,AsRefStr,Display,EnumString,EnumVariantNames,IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+	#[default]
+	/// Opus in an Ogg container.
+	Opus,
+	/// MP3.
+	Mp3,
+	/// AAC-LC in ADTS.
+	Aac,
+}
+
+impl Format {
+	/// If `festivald` was built with this format's encoder.
+	///
+	/// MP3 needs the `mp3` feature, AAC needs the `aac` feature.
+	pub const fn available(self) -> bool {
+		match self {
+			Self::Opus => true,
+			Self::Mp3  => cfg!(feature = "mp3"),
+			Self::Aac  => cfg!(feature = "aac"),
+		}
+	}
+
+	/// The MIME type of this format.
+	pub const fn mime(self) -> &'static str {
+		match self {
+			Self::Opus => "audio/ogg",
+			Self::Mp3  => "audio/mpeg",
+			Self::Aac  => "audio/aac",
+		}
+	}
+
+	/// The file extension of this format.
+	pub const fn extension(self) -> &'static str {
+		match self {
+			Self::Opus => "opus",
+			Self::Mp3  => "mp3",
+			Self::Aac  => "aac",
+		}
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Transcode
+/// What a `/transcode` request asked for.
+#[derive(Copy,Clone,Debug,PartialEq,Eq,Hash)]
+pub struct Transcode {
+	pub format:  Format,
+	pub bitrate: u16,
+}
+
+impl Default for Transcode {
+	fn default() -> Self {
+		Self {
+			format:  Format::default(),
+			bitrate: TRANSCODE_BITRATE_DEFAULT,
+		}
+	}
+}
+
+impl Transcode {
+	/// Parse the URI query, e.g: `format=opus&bitrate=96`.
+	///
+	/// Missing parameters use the default.
+	/// Unknown parameters and invalid values are an error.
+	pub fn from_query(query: Option<&str>) -> Result<Self, &'static str> {
+		let mut this = Self::default();
+
+		for pair in query.unwrap_or_default().split('&').filter(|s| !s.is_empty()) {
+			match pair.split_once('=') {
+				Some(("format", v)) => {
+					let Ok(format) = Format::from_str(&v.to_ascii_lowercase()) else {
+						return Err("Unknown format, must be one of: [opus, mp3, aac]");
+					};
+					if !format.available() {
+						return Err("Format is not available, festivald was built without its encoder");
+					}
+					this.format = format;
+				},
+				Some(("bitrate", v)) => {
+					let Ok(bitrate) = v.parse::<u16>() else {
+						return Err("Bitrate parse failure");
+					};
+					this.bitrate = bitrate;
+				},
+				_ => return Err("Unknown query parameter, must be one of: [format, bitrate]"),
+			}
+		}
+
+		match this.format {
+			Format::Mp3 if !BROADCAST_BITRATES.contains(&this.bitrate) => {
+				Err("Invalid MP3 bitrate, must be one of: [64, 96, 112, 128, 160, 192, 224, 256, 320]")
+			},
+			Format::Opus | Format::Aac if !(TRANSCODE_BITRATE_MIN..=TRANSCODE_BITRATE_MAX).contains(&this.bitrate) => {
+				Err("Invalid bitrate, must be in-between [32-320]")
+			},
+			_ => Ok(this),
+		}
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- `/transcode`
+pub async fn transcode(
+	key:        usize,
+	transcode:  Transcode,
+	headers:    &HeaderMap,
+	collection: Arc<Collection>,
+) -> Result<Response<Body>, anyhow::Error> {
+	let Some(song) = collection.songs.get(SongKey::from(key)) else {
+		return Ok(resp::not_found("Song key is invalid"));
+	};
+
+	// Format the file name.
+	let (artist, album, _) = collection.walk(song.key);
+	let name = format!(
+		"{}{}{}{}{}.{}",
+		artist.name,
+		config().filename_separator,
+		album.title,
+		config().filename_separator,
+		song.title,
+		transcode.format.extension(),
+	);
+
+	// The `Collection` timestamp is included so that
+	// a new `Collection` re-using the same key doesn't
+	// get served the old `Song`.
+	let cache_name = format!(
+		"{}_{key}_{}.{}",
+		collection.timestamp,
+		transcode.bitrate,
+		transcode.format.extension(),
+	);
+
+	let Ok(cache) = TranscodeCache::new(&cache_name) else {
+		return Ok(resp::server_err(ERR_CACHE));
+	};
+
+	// If the file exists already, serve it.
+	if cache.exists() {
+		if let Ok(file) = tokio::fs::File::open(&cache.real).await {
+			trace!("REST - TranscodeCache hit: {cache_name}");
+			return crate::rest::impl_file(headers, file, &name, transcode.format.mime(), false).await;
+		}
+	}
+
+	// Else, start encoding.
+	let Ok(tmp) = std::fs::File::create(&cache.tmp) else {
+		return Ok(resp::server_err(ERR_CACHE));
+	};
+
+	// `Err` means the encoder failed and the body should be aborted.
+	let (to_body, mut from_encoder) = tokio::sync::mpsc::channel::<Result<Bytes, ()>>(CHUNK_QUEUE);
+	let (mut sender, body) = Body::channel();
+
+	let path      = song.path.clone();
+	let extension = Arc::clone(&song.extension);
+	let range     = (song.start, song.end);
+
+	tokio::task::spawn_blocking(move || {
+		let mut tmp = tmp;
+
+		if let Some(dir) = cache.real.parent() {
+			evict(dir, config().transcode_cache_size.saturating_mul(1_000_000));
+		}
+
+		let result = encode(&path, &extension, range, transcode, |bytes| {
+			tmp.write_all(&bytes)?;
+			to_body.blocking_send(Ok(Bytes::from(bytes))).map_err(|_| anyhow!("client disconnected"))
+		});
+
+		match result.and_then(|_| Ok(tmp.flush()?)) {
+			Ok(_) => {
+				drop(tmp);
+				match cache.tmp_to_real() {
+					Ok(_)  => debug!("Transcode - cached: {}", cache.real.display()),
+					Err(e) => warn!("Transcode - cache rename error: {e}"),
+				}
+			},
+			Err(e) => {
+				debug!("Transcode - {}: {e}", path.display());
+				let _ = to_body.blocking_send(Err(()));
+			},
+		}
+	});
+
+	// Forward the encoded chunks into the body.
+	tokio::task::spawn(async move {
+		while let Some(chunk) = from_encoder.recv().await {
+			match chunk {
+				Ok(bytes) => if sender.send_data(bytes).await.is_err() {
+					return;
+				},
+				Err(()) => {
+					sender.abort();
+					return;
+				},
+			}
+		}
+	});
+
+	Ok(resp::rest_stream(body, &name, transcode.format.mime(), None))
+}
+
+//---------------------------------------------------------------------------------------------------- Cache
+// Remove the least recently modified transcodes
+// until the cache directory is under `max` bytes.
+//
+// `0` means unlimited.
+fn evict(dir: &Path, max: u64) {
+	if max == 0 {
+		return;
+	}
+
+	let Ok(entries) = std::fs::read_dir(dir) else {
+		return;
+	};
+
+	let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+		.filter_map(Result::ok)
+		.filter_map(|e| {
+			let md = e.metadata().ok()?;
+			if !md.is_file() {
+				return None;
+			}
+			Some((md.modified().ok()?, md.len(), e.path()))
+		})
+		.collect();
+
+	let mut total: u64 = files.iter().map(|f| f.1).sum();
+	if total <= max {
+		return;
+	}
+
+	files.sort_by_key(|f| f.0);
+
+	for (_, len, path) in files {
+		if total <= max {
+			break;
+		}
+
+		match std::fs::remove_file(&path) {
+			Ok(_)  => {
+				debug!("Transcode - evicted: {}", path.display());
+				total = total.saturating_sub(len);
+			},
+			Err(e) => warn!("Transcode - eviction error: {}: {e}", path.display()),
+		}
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Encode
+/// If this `Song` is only a range of its file, i.e. it was split from a CUE sheet.
+pub const fn ranged(song: &Song) -> bool {
+	song.start != 0 || song.end.is_some()
+}
+
+/// Decode a `Song` and encode it, `out` gets called with every encoded chunk.
+///
+/// For `Song`'s split from a CUE sheet, only its part of the file is encoded.
+pub fn encode_song(
+	song:      &Song,
+	transcode: Transcode,
+	out:       impl FnMut(Vec<u8>) -> Result<(), anyhow::Error>,
+) -> Result<(), anyhow::Error> {
+	encode(&song.path, &song.extension, (song.start, song.end), transcode, out)
+}
+
+// Decode the file at `path` and encode it, `out` gets called with every encoded chunk.
+//
+// Only `range` (milliseconds) of the file is encoded, `(0, None)` is the whole file.
+//
+// Like `broadcast.rs`, anything above stereo only keeps the front left/right.
+fn encode(
+	path:      &Path,
+	extension: &str,
+	range:     (u64, Option<u64>),
+	transcode: Transcode,
+	mut out:   impl FnMut(Vec<u8>) -> Result<(), anyhow::Error>,
+) -> Result<(), anyhow::Error> {
+	let file = std::fs::File::open(path)?;
+	let mss  = MediaSourceStream::new(Box::new(file), Default::default());
+
+	let mut hint = Hint::new();
+	hint.with_extension(extension);
+
+	let probed = symphonia::default::get_probe().format(
+		&hint,
+		mss,
+		&FormatOptions::default(),
+		&MetadataOptions::default(),
+	)?;
+	let mut reader = probed.format;
+
+	let Some(track) = reader.default_track() else {
+		bail!("no audio track");
+	};
+	let track_id = track.id;
+	let mut decoder = symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+	// The range as timestamps.
+	let (start, end) = match (range, track.codec_params.time_base) {
+		((0, None), _) => (0, None),
+		((start, end), Some(tb)) => (
+			tb.calc_timestamp(ms_to_time(start)),
+			end.map(|end| tb.calc_timestamp(ms_to_time(end))),
+		),
+		(_, None) => bail!("no time base to find the song range with"),
+	};
+
+	if start != 0 {
+		reader.seek(SeekMode::Accurate, SeekTo::TimeStamp { ts: start, track_id })?;
+		decoder.reset();
+	}
+
+	// Created with the first decoded `SignalSpec`.
+	let mut encoder: Option<(Box<dyn Encode>, u32, usize)> = None;
+
+	// Re-used buffers.
+	let mut buf:    Option<SampleBuffer<f32>> = None;
+	let mut stereo: Vec<f32> = vec![];
+
+	loop {
+		let packet = match reader.next_packet() {
+			Ok(p) => p,
+			// End of file.
+			Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+			Err(e) => return Err(e.into()),
+		};
+
+		if packet.track_id() != track_id {
+			continue;
+		}
+
+		// Outside of the range.
+		if end.is_some_and(|end| packet.ts >= end) {
+			break;
+		}
+		if packet.ts + packet.dur <= start {
+			continue;
+		}
+
+		let decoded = match decoder.decode(&packet) {
+			Ok(d) => d,
+			// Skip corrupt packets.
+			Err(SymphoniaError::DecodeError(e)) => {
+				trace!("Transcode - decode error: {e}");
+				continue;
+			},
+			Err(e) => return Err(e.into()),
+		};
+
+		if decoded.frames() == 0 {
+			continue;
+		}
+
+		let spec     = *decoded.spec();
+		let channels = spec.channels.count().max(1);
+
+		if encoder.is_none() {
+			encoder = Some((new_encoder(transcode, spec.rate, channels.min(2))?, spec.rate, channels));
+		}
+		let Some((e, rate, c)) = encoder.as_mut() else {
+			continue;
+		};
+
+		if *rate != spec.rate || *c != channels {
+			bail!("format changed mid-stream: {}Hz, {channels} channel(s)", spec.rate);
+		}
+
+		let b = match buf.take() {
+			Some(b) if b.capacity() >= decoded.capacity() * channels => b,
+			_ => SampleBuffer::<f32>::new(decoded.capacity() as u64, spec),
+		};
+		// Packets at the edges of the range can straddle it.
+		let frames = decoded.frames();
+		let (front, back) = trim(packet.ts, packet.dur, frames, start, end);
+
+		let b = buf.insert(b);
+		b.copy_interleaved_ref(decoded);
+		let samples = &b.samples()[front * channels..(frames - back).max(front) * channels];
+
+		let samples: &[f32] = if channels <= 2 {
+			samples
+		} else {
+			stereo.clear();
+			for frame in samples.chunks_exact(channels) {
+				stereo.extend_from_slice(&frame[..2]);
+			}
+			&stereo
+		};
+
+		let bytes = e.encode(samples)?;
+		if !bytes.is_empty() {
+			out(bytes)?;
+		}
+	}
+
+	let Some((mut e, _, _)) = encoder else {
+		bail!("no audio decoded");
+	};
+
+	let bytes = e.finish()?;
+	if !bytes.is_empty() {
+		out(bytes)?;
+	}
+
+	Ok(())
+}
+
+// How many frames to cut from the `(front, back)` of a decoded
+// packet at `ts` lasting `dur` with `frames`, so only `start..end` is left.
+fn trim(ts: u64, dur: u64, frames: usize, start: u64, end: Option<u64>) -> (usize, usize) {
+	let dur = u128::from(dur.max(1));
+	let to_frames = |t: u64| (u128::from(t) * frames as u128 / dur).min(frames as u128) as usize;
+
+	let front = to_frames(start.saturating_sub(ts));
+	let back  = end.map_or(0, |end| to_frames((ts + dur as u64).saturating_sub(end)));
+
+	(front, back)
+}
+
+fn ms_to_time(ms: u64) -> Time {
+	Time::new(ms / 1000, (ms % 1000) as f64 / 1000.0)
+}
+
+/// An audio encoder, input is interleaved `f32` with 1 or 2 channels.
+///
+/// This is also used by the `/stream` broadcast.
+pub trait Encode {
+	/// Encode samples, returns whatever is ready.
+	///
+	/// Any headers the format starts with are returned by the first call.
+	fn encode(&mut self, pcm: &[f32]) -> Result<Vec<u8>, anyhow::Error>;
+	/// This is the end of the stream, returns everything left.
+	fn finish(&mut self) -> Result<Vec<u8>, anyhow::Error>;
+}
+
+/// Create an encoder for this format/bitrate.
+pub fn new_encoder(transcode: Transcode, rate: u32, channels: usize) -> Result<Box<dyn Encode>, anyhow::Error> {
+	debug!("Transcode - new {} encoder: {}kbps, {rate}Hz, {channels} channel(s)", transcode.format, transcode.bitrate);
+
+	Ok(match transcode.format {
+		Format::Opus => Box::new(Opus::new(transcode.bitrate, rate, channels)?),
+		#[cfg(feature = "mp3")]
+		Format::Mp3  => Box::new(Mp3::new(transcode.bitrate, rate, channels)?),
+		#[cfg(feature = "aac")]
+		Format::Aac  => Box::new(Aac::new(transcode.bitrate, rate, channels)?),
+		// `Transcode::from_query()` already rejects these.
+		#[allow(unreachable_patterns)]
+		format => bail!("{format} is not available"),
+	})
+}
+
+//---------------------------------------------------------------------------------------------------- MP3
+// LAME resamples rates MP3 doesn't support (e.g, 96kHz) by itself.
+#[cfg(feature = "mp3")]
+struct Mp3 {
+	encoder:  mp3lame_encoder::Encoder,
+	channels: usize,
+}
+
+#[cfg(feature = "mp3")]
+impl Mp3 {
+	fn new(bitrate: u16, rate: u32, channels: usize) -> Result<Self, anyhow::Error> {
+		use mp3lame_encoder::{Birtate,Builder,Quality};
+
+		let bitrate = match bitrate {
+			64  => Birtate::Kbps64,
+			96  => Birtate::Kbps96,
+			112 => Birtate::Kbps112,
+			128 => Birtate::Kbps128,
+			160 => Birtate::Kbps160,
+			224 => Birtate::Kbps224,
+			256 => Birtate::Kbps256,
+			320 => Birtate::Kbps320,
+			_   => Birtate::Kbps192,
+		};
+
+		let Some(mut b) = Builder::new() else {
+			bail!("could not allocate encoder");
+		};
+
+		b.set_num_channels(channels as u8).map_err(|e| anyhow!("{e:?}"))?;
+		b.set_sample_rate(rate).map_err(|e| anyhow!("{e:?}"))?;
+		b.set_brate(bitrate).map_err(|e| anyhow!("{e:?}"))?;
+		b.set_quality(Quality::Good).map_err(|e| anyhow!("{e:?}"))?;
+
+		Ok(Self {
+			encoder: b.build().map_err(|e| anyhow!("{e:?}"))?,
+			channels,
+		})
+	}
+}
+
+#[cfg(feature = "mp3")]
+impl Encode for Mp3 {
+	fn encode(&mut self, pcm: &[f32]) -> Result<Vec<u8>, anyhow::Error> {
+		let mut out = Vec::with_capacity(mp3lame_encoder::max_required_buffer_size(pcm.len() / self.channels));
+
+		let n = if self.channels == 1 {
+			self.encoder.encode(mp3lame_encoder::MonoPcm(pcm), out.spare_capacity_mut())
+		} else {
+			self.encoder.encode(mp3lame_encoder::InterleavedPcm(pcm), out.spare_capacity_mut())
+		}.map_err(|e| anyhow!("{e:?}"))?;
+
+		// SAFETY: the encoder wrote `n` bytes.
+		unsafe { out.set_len(n); }
+		Ok(out)
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, anyhow::Error> {
+		let mut out = Vec::with_capacity(7200); // LAME's worst case for a flush.
+		let n = self.encoder.flush::<mp3lame_encoder::Flush>(out.spare_capacity_mut()).map_err(|e| anyhow!("{e:?}"))?;
+
+		// SAFETY: the encoder wrote `n` bytes.
+		unsafe { out.set_len(n); }
+		Ok(out)
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Opus
+// Opus in Ogg, as described in RFC 7845.
+struct Opus {
+	encoder:   opus::Encoder,
+	resampler: Option<Resampler>,
+	ogg:       ogg::PacketWriter<'static, Vec<u8>>,
+	serial:    u32,
+	channels:  usize,
+	// Samples the decoder should skip at the start.
+	pre_skip:  u64,
+	// Real (non-padding) samples per channel at 48kHz so far.
+	samples:   u64,
+	// Samples per channel encoded so far, including padding.
+	encoded:   u64,
+	// 48kHz interleaved samples waiting for a full frame.
+	pcm:       Vec<f32>,
+	// Re-used buffers.
+	resampled: Vec<f32>,
+	packet:    Vec<u8>,
+	// The last packet, it's held onto until we
+	// know if it's the end of the stream or not.
+	last:      Option<(Vec<u8>, u64)>,
+}
+
+impl Opus {
+	fn new(bitrate: u16, rate: u32, channels: usize) -> Result<Self, anyhow::Error> {
+		let mode = if channels == 1 { opus::Channels::Mono } else { opus::Channels::Stereo };
+		let mut encoder = opus::Encoder::new(OPUS_RATE, mode, opus::Application::Audio)?;
+		encoder.set_bitrate(opus::Bitrate::Bits(i32::from(bitrate) * 1000))?;
+		let pre_skip = encoder.get_lookahead()? as u64;
+
+		let resampler = if rate == OPUS_RATE {
+			None
+		} else {
+			Some(Resampler::new(rate, OPUS_RATE, channels)?)
+		};
+
+		let mut this = Self {
+			encoder,
+			resampler,
+			ogg:       ogg::PacketWriter::new(vec![]),
+			serial:    rand::random(),
+			channels,
+			pre_skip,
+			samples:   0,
+			encoded:   0,
+			pcm:       vec![],
+			resampled: vec![],
+			packet:    vec![0; OPUS_PACKET],
+			last:      None,
+		};
+
+		// Identification header.
+		let mut head = Vec::with_capacity(19);
+		head.extend_from_slice(b"OpusHead");
+		head.push(1); // Version
+		head.push(channels as u8);
+		head.extend_from_slice(&(pre_skip as u16).to_le_bytes());
+		head.extend_from_slice(&rate.to_le_bytes()); // Original sample rate
+		head.extend_from_slice(&0_i16.to_le_bytes()); // Output gain
+		head.push(0); // Channel mapping family
+		this.ogg.write_packet(head, this.serial, ogg::PacketWriteEndInfo::EndPage, 0)?;
+
+		// Comment header.
+		let mut tags = Vec::with_capacity(16 + FESTIVALD_NAME_VER.len());
+		tags.extend_from_slice(b"OpusTags");
+		tags.extend_from_slice(&(FESTIVALD_NAME_VER.len() as u32).to_le_bytes());
+		tags.extend_from_slice(FESTIVALD_NAME_VER.as_bytes());
+		tags.extend_from_slice(&0_u32.to_le_bytes()); // No comments
+		this.ogg.write_packet(tags, this.serial, ogg::PacketWriteEndInfo::EndPage, 0)?;
+
+		Ok(this)
+	}
+
+	// Encode every full frame in `self.pcm`.
+	fn encode_frames(&mut self) -> Result<(), anyhow::Error> {
+		let frame_len = OPUS_FRAME * self.channels;
+
+		while self.pcm.len() >= frame_len {
+			let n = self.encoder.encode_float(&self.pcm[..frame_len], &mut self.packet)?;
+			self.pcm.drain(..frame_len);
+			self.encoded += OPUS_FRAME as u64;
+
+			// The granule position is the sample count
+			// (including `pre_skip`) at the end of the packet.
+			if let Some((packet, granule)) = self.last.replace((self.packet[..n].to_vec(), self.encoded)) {
+				self.ogg.write_packet(packet, self.serial, ogg::PacketWriteEndInfo::NormalPacket, granule)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	// Take whatever Ogg pages are ready.
+	fn take(&mut self) -> Vec<u8> {
+		std::mem::take(self.ogg.inner_mut())
+	}
+}
+
+impl Encode for Opus {
+	fn encode(&mut self, pcm: &[f32]) -> Result<Vec<u8>, anyhow::Error> {
+		match self.resampler.as_mut() {
+			Some(r) => {
+				self.resampled.clear();
+				r.process(pcm, &mut self.resampled)?;
+				self.pcm.extend_from_slice(&self.resampled);
+				self.samples += (self.resampled.len() / self.channels) as u64;
+			},
+			None => {
+				self.pcm.extend_from_slice(pcm);
+				self.samples += (pcm.len() / self.channels) as u64;
+			},
+		}
+
+		self.encode_frames()?;
+		Ok(self.take())
+	}
+
+	fn finish(&mut self) -> Result<Vec<u8>, anyhow::Error> {
+		if let Some(r) = self.resampler.as_mut() {
+			self.resampled.clear();
+			r.flush(&mut self.resampled)?;
+			self.pcm.extend_from_slice(&self.resampled);
+			self.samples += (self.resampled.len() / self.channels) as u64;
+		}
+
+		// Pad with silence until the `pre_skip`
+		// and all the real samples are encoded.
+		let end = self.pre_skip + self.samples;
+		while self.encoded + (self.pcm.len() / self.channels) as u64 < end || self.pcm.len() % (OPUS_FRAME * self.channels) != 0 {
+			self.pcm.push(0.0);
+		}
+		self.encode_frames()?;
+
+		// The last page's granule position trims the padding.
+		if let Some((packet, _)) = self.last.take() {
+			self.ogg.write_packet(packet, self.serial, ogg::PacketWriteEndInfo::EndStream, end)?;
+		}
+
+		Ok(self.take())
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Resampler
+// Resamples interleaved samples, Opus only takes 48kHz.
+struct Resampler {
+	inner:    rubato::FftFixedIn<f32>,
+	channels: usize,
+	// Planar input waiting for a full chunk.
+	input:    Vec<Vec<f32>>,
+}
+
+impl Resampler {
+	fn new(from: u32, to: u32, channels: usize) -> Result<Self, anyhow::Error> {
+		Ok(Self {
+			inner: rubato::FftFixedIn::new(from as usize, to as usize, RESAMPLE_CHUNK, 2, channels)?,
+			channels,
+			input: vec![vec![]; channels],
+		})
+	}
+
+	// Resample `pcm`, appending to `out`.
+	fn process(&mut self, pcm: &[f32], out: &mut Vec<f32>) -> Result<(), anyhow::Error> {
+		for frame in pcm.chunks_exact(self.channels) {
+			for (c, s) in self.input.iter_mut().zip(frame) {
+				c.push(*s);
+			}
+		}
+
+		while self.input[0].len() >= self.inner.input_frames_next() {
+			let n = self.inner.input_frames_next();
+			let resampled = self.inner.process(&self.input.iter().map(|c| &c[..n]).collect::<Vec<&[f32]>>(), None)?;
+
+			for c in self.input.iter_mut() {
+				c.drain(..n);
+			}
+
+			for i in 0..resampled[0].len() {
+				for c in resampled.iter() {
+					out.push(c[i]);
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	// Resample whatever is left, padded with silence.
+	fn flush(&mut self, out: &mut Vec<f32>) -> Result<(), anyhow::Error> {
+		if self.input[0].is_empty() {
+			return Ok(());
+		}
+
+		let n = self.inner.input_frames_next();
+		for c in self.input.iter_mut() {
+			c.resize(n, 0.0);
+		}
+
+		self.process(&[], out)
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- AAC
+// AAC-LC in ADTS, encoded by `fdk-aac`.
+#[cfg(feature = "aac")]
+struct Aac {
+	encoder: fdk_aac::enc::Encoder,
+	channels: usize,
+	// Re-used buffers.
+	pcm:     Vec<i16>,
+	out:     Vec<u8>,
+}
+
+#[cfg(feature = "aac")]
+impl Aac {
+	fn new(bitrate: u16, rate: u32, channels: usize) -> Result<Self, anyhow::Error> {
+		use fdk_aac::enc::{AudioObjectType,BitRate,ChannelMode,EncoderParams,Transport};
+
+		let params = EncoderParams {
+			bit_rate:          BitRate::Cbr(u32::from(bitrate) * 1000),
+			sample_rate:       rate,
+			transport:         Transport::Adts,
+			channels:          if channels == 1 { ChannelMode::Mono } else { ChannelMode::Stereo },
+			audio_object_type: AudioObjectType::Mpeg4LowComplexity,
+		};
+
+		Ok(Self {
+			encoder: fdk_aac::enc::Encoder::new(params).map_err(|e| anyhow!("{e:?}"))?,
+			channels,
+			pcm:     vec![],
+			out:     vec![0; 8192],
+		})
+	}
+
+	// Encode everything in `self.pcm`.
+	fn encode_pcm(&mut self) -> Result<Vec<u8>, anyhow::Error> {
+		let mut bytes = vec![];
+
+		while !self.pcm.is_empty() {
+			let info = self.encoder.encode(&self.pcm, &mut self.out).map_err(|e| anyhow!("{e:?}"))?;
+			bytes.extend_from_slice(&self.out[..info.output_size]);
+			self.pcm.drain(..info.input_consumed);
+
+			if info.input_consumed == 0 && info.output_size == 0 {
+				break;
+			}
+		}
+
+		Ok(bytes)
+	}
+}
+
+#[cfg(feature = "aac")]
+impl Encode for Aac {
+	fn encode(&mut self, pcm: &[f32]) -> Result<Vec<u8>, anyhow::Error> {
+		self.pcm.extend(pcm.iter().map(|s| (s.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16));
+		self.encode_pcm()
+	}
+
+	// `fdk-aac` doesn't expose a flush, so push
+	// a couple frames of silence through instead
+	// to get the encoder's delayed samples out.
+	fn finish(&mut self) -> Result<Vec<u8>, anyhow::Error> {
+		self.pcm.resize(self.pcm.len() + AAC_FRAME * 2 * self.channels, 0);
+		self.encode_pcm()
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn query() {
+		assert_eq!(Transcode::from_query(None), Ok(Transcode::default()));
+		assert_eq!(Transcode::from_query(Some("")), Ok(Transcode::default()));
+		if Format::Mp3.available() {
+			assert_eq!(
+				Transcode::from_query(Some("format=mp3&bitrate=320")),
+				Ok(Transcode { format: Format::Mp3, bitrate: 320 }),
+			);
+		} else {
+			assert!(Transcode::from_query(Some("format=mp3&bitrate=320")).is_err());
+		}
+		assert_eq!(
+			Transcode::from_query(Some("bitrate=96&format=OPUS")),
+			Ok(Transcode { format: Format::Opus, bitrate: 96 }),
+		);
+		if Format::Aac.available() {
+			assert_eq!(
+				Transcode::from_query(Some("format=aac")),
+				Ok(Transcode { format: Format::Aac, bitrate: TRANSCODE_BITRATE_DEFAULT }),
+			);
+		} else {
+			assert!(Transcode::from_query(Some("format=aac")).is_err());
+		}
+	}
+
+	#[test]
+	fn query_invalid() {
+		assert!(Transcode::from_query(Some("format=flac")).is_err());
+		assert!(Transcode::from_query(Some("bitrate=abc")).is_err());
+		assert!(Transcode::from_query(Some("bitrate=16")).is_err());
+		assert!(Transcode::from_query(Some("bitrate=1000")).is_err());
+		assert!(Transcode::from_query(Some("format=mp3&bitrate=100")).is_err());
+		assert!(Transcode::from_query(Some("volume=100")).is_err());
+		assert!(Transcode::from_query(Some("format")).is_err());
+	}
+
+	#[test]
+	fn encode_range() {
+		let path = Path::new("../assets/audio/song_4.flac");
+		let len  = |range| {
+			let mut len = 0;
+			encode(path, "flac", range, Transcode::default(), |bytes| { len += bytes.len(); Ok(()) }).unwrap();
+			len
+		};
+
+		let full  = len((0, None));
+		let start = len((500, None));
+		let range = len((500, Some(1500)));
+		assert!(full > start);
+		assert!(start > range);
+		assert!(range > 0);
+	}
+
+	#[test]
+	fn evict() {
+		let dir = std::env::temp_dir().join("festivald_test_evict");
+		let _ = std::fs::remove_dir_all(&dir);
+		std::fs::create_dir_all(dir.join("tmp")).unwrap();
+
+		for i in 0..4 {
+			std::fs::write(dir.join(i.to_string()), [0; 100]).unwrap();
+			std::thread::sleep(std::time::Duration::from_millis(10));
+		}
+
+		super::evict(&dir, 250);
+		assert!(!dir.join("0").exists());
+		assert!(!dir.join("1").exists());
+		assert!(dir.join("2").exists());
+		assert!(dir.join("3").exists());
+		assert!(dir.join("tmp").exists());
+
+		// Unlimited.
+		super::evict(&dir, 0);
+		assert!(dir.join("2").exists());
+
+		std::fs::remove_dir_all(&dir).unwrap();
+	}
+}
@@ -0,0 +1,263 @@
+//---------------------------------------------------------------------------------------------------- Use
+use serde::{Serialize,Deserialize};
+use std::collections::{BTreeMap,BTreeSet};
+use std::sync::Mutex;
+use once_cell::sync::{Lazy,OnceCell};
+use tokio::sync::Semaphore;
+use benri::lock;
+use http::{
+	header::AUTHORIZATION,
+	request::Parts,
+};
+use crate::config::{AUTH,AUTH_USER,config};
+
+//---------------------------------------------------------------------------------------------------- Statics
+// SAFETY: This does not get initialized if there's no `[users]` config.
+// This is okay because we will only ever use `.get()`.
+pub static USERS: OnceCell<BTreeMap<String, User>> = OnceCell::new();
+
+// `Argon2` is slow on purpose, so verifications run on blocking
+// threads, with at most this many at once (1 per CPU thread).
+static VERIFY: Lazy<Semaphore> = Lazy::new(|| {
+	Semaphore::new(std::thread::available_parallelism().map_or(1, |n| n.get()))
+});
+
+// Verified against for unknown usernames, so
+// they take as long as a wrong password would.
+static DUMMY: Lazy<rpc::hash::Password> = Lazy::new(|| rpc::hash::Password::new(String::new()));
+
+// Verify `pass` against `password` on a blocking thread.
+async fn verify(password: &'static rpc::hash::Password, pass: String) -> bool {
+	// The semaphore is never closed.
+	let Ok(_permit) = VERIFY.acquire().await else {
+		return false;
+	};
+
+	tokio::task::spawn_blocking(move || password.same(pass)).await.unwrap_or(false)
+}
+
+#[inline]
+/// If any form of authorization is enabled,
+/// either `authorization` or `[users]`.
+pub fn enabled() -> bool {
+	AUTH.get().is_some() || USERS.get().is_some()
+}
+
+//---------------------------------------------------------------------------------------------------- Role
+/// A named set of permissions, from the `[roles]` config.
+#[derive(Clone,Debug,Default,PartialEq,Eq,Serialize,Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Role {
+	/// Allow every method, resource, and the documentation.
+	#[serde(default)]
+	pub all: bool,
+	/// Allowed JSON-RPC methods.
+	#[serde(default)]
+	pub rpc: BTreeSet<rpc::Method>,
+	/// Allowed REST resources.
+	#[serde(default)]
+	pub rest: BTreeSet<rpc::resource::Resource>,
+	/// Allow the documentation.
+	#[serde(default)]
+	pub docs: bool,
+}
+
+//---------------------------------------------------------------------------------------------------- User
+/// A user, as written in the `[users]` config.
+#[derive(Clone,Debug,PartialEq,Eq,Serialize,Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserConfig {
+	/// The output of `festivald --hash-password`.
+	pub password: String,
+	/// The name of a role in `[roles]`.
+	pub role: String,
+}
+
+/// A user, after config validation.
+#[derive(Debug)]
+pub struct User {
+	password: rpc::hash::Password,
+	// A `SHA256` hash of the last password that matched,
+	// so `Argon2` isn't run for every single request.
+	// This only ever lives in memory.
+	verified: Mutex<Option<rpc::hash::Hash>>,
+	/// The name of this user's role.
+	pub role: String,
+}
+
+impl User {
+	pub fn new(password: rpc::hash::Password, role: String) -> Self {
+		Self { password, verified: Mutex::new(None), role }
+	}
+
+	/// If `pass` is this user's password.
+	pub async fn same(&'static self, pass: String) -> bool {
+		if lock!(self.verified).as_ref().is_some_and(|h| h.same(pass.clone())) {
+			return true;
+		}
+
+		if verify(&self.password, pass.clone()).await {
+			*lock!(self.verified) = Some(rpc::hash::Hash::new(pass));
+			true
+		} else {
+			false
+		}
+	}
+
+	/// This user's [`Role`].
+	///
+	/// INVARIANT: the role was checked to exist in `[roles]` on startup.
+	pub fn role(&self) -> &'static Role {
+		&config().roles[&self.role]
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Caller
+/// Who sent a request, and what they are allowed to do.
+#[derive(Copy,Clone,Debug)]
+pub enum Caller {
+	/// Authorization is disabled, everything is allowed.
+	Unrestricted,
+	/// Authenticated with the `authorization` config, everything is allowed.
+	Master(&'static str),
+	/// Authenticated as a user from `[users]`.
+	User(&'static str, &'static User),
+	/// Authenticated with a bearer token, only its methods are allowed.
+	Token(&'static crate::token::Token),
+	/// Not authenticated, only `no_auth_*` is allowed.
+	Anonymous,
+}
+
+impl Caller {
+	/// The username (or token label), if authenticated.
+	pub fn name(&self) -> Option<&'static str> {
+		match self {
+			Self::Master(n) | Self::User(n, _) => Some(*n),
+			Self::Token(t) => Some(&t.label),
+			_ => None,
+		}
+	}
+
+	/// The token, if authenticated with one.
+	pub fn token(&self) -> Option<&'static crate::token::Token> {
+		match self {
+			Self::Token(t) => Some(*t),
+			_ => None,
+		}
+	}
+
+	/// The name of the user's role, if authenticated as a user.
+	pub fn role_name(&self) -> Option<&'static str> {
+		match self {
+			Self::User(_, u) => Some(&u.role),
+			_ => None,
+		}
+	}
+
+	/// If no authentication was provided (or it was wrong).
+	pub fn anonymous(&self) -> bool {
+		matches!(self, Self::Anonymous)
+	}
+
+	/// If everything is allowed.
+	pub fn all(&self) -> bool {
+		match self {
+			Self::Unrestricted | Self::Master(_) => true,
+			Self::User(_, u) => u.role().all,
+			Self::Token(_) | Self::Anonymous => false,
+		}
+	}
+
+	/// If this caller is allowed to use this JSON-RPC method.
+	pub fn rpc(&self, method: &rpc::Method) -> bool {
+		if config().no_auth_rpc.as_ref().is_some_and(|h| h.contains(method)) {
+			return true;
+		}
+
+		match self {
+			Self::User(_, u) => { let r = u.role(); r.all || r.rpc.contains(method) },
+			Self::Token(t)   => t.methods.contains(method),
+			_ => self.all(),
+		}
+	}
+
+	/// If this caller is allowed to use this REST resource.
+	pub fn rest(&self, resource: &rpc::resource::Resource) -> bool {
+		if config().no_auth_rest.as_ref().is_some_and(|h| h.contains(resource)) {
+			return true;
+		}
+
+		match self {
+			Self::User(_, u) => { let r = u.role(); r.all || r.rest.contains(resource) },
+			_ => self.all(),
+		}
+	}
+
+	/// If this caller is allowed to view the documentation.
+	pub fn docs(&self) -> bool {
+		if config().no_auth_docs {
+			return true;
+		}
+
+		match self {
+			Self::User(_, u) => { let r = u.role(); r.all || r.docs },
+			_ => self.all(),
+		}
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Authentication
+/// Figure out who sent this request from the `authorization` HTTP header.
+///
+/// This is either `Basic` (the `authorization` config or `[users]`)
+/// or `Bearer` (a token created with `token_new`).
+///
+/// This should only be called once per HTTP request, `[users]` passwords use `Argon2`.
+pub async fn caller(parts: &Parts) -> Caller {
+	if !enabled() {
+		return Caller::Unrestricted;
+	}
+
+	// Attempt to turn into UTF-8 string.
+	let Some(header) = parts.headers.get(AUTHORIZATION) else {
+		return Caller::Anonymous;
+	};
+	let Ok(string) = std::str::from_utf8(header.as_bytes()) else {
+		return Caller::Anonymous;
+	};
+
+	// Check bearer tokens.
+	if string.get(..7).is_some_and(|s| s.eq_ignore_ascii_case("bearer ")) {
+		return match crate::token::get(&string[7..]) {
+			Some(t) => Caller::Token(t),
+			None    => Caller::Anonymous,
+		};
+	}
+
+	// Check if the hash matches the `authorization` one.
+	if let Some(hash) = AUTH.get() {
+		if hash.same(string.to_string()) {
+			// SAFETY: `AUTH_USER` is set alongside `AUTH`.
+			return Caller::Master(AUTH_USER.get().map(|s| s.as_str()).unwrap_or_default());
+		}
+	}
+
+	// Else, check `[users]`.
+	let Some(users) = USERS.get() else {
+		return Caller::Anonymous;
+	};
+	let Some((name, pass)) = rpc::base64::decode_authorization_basic_header(string) else {
+		return Caller::Anonymous;
+	};
+
+	let Some((name, user)) = users.get_key_value(&name) else {
+		verify(&DUMMY, pass).await;
+		return Caller::Anonymous;
+	};
+
+	if user.same(pass).await {
+		Caller::User(name, user)
+	} else {
+		Caller::Anonymous
+	}
+}
@@ -0,0 +1,123 @@
+// Filesystem watcher for `collection_paths`.
+//
+// `shukusai`'s `CollectionWatch` sends us batches of changed
+// PATHs, these get turned into an incremental `Collection`
+// update, which goes through the same reset path as
+// `collection_new`, except only the changed PATHs are scanned.
+
+//---------------------------------------------------------------------------------------------------- Use
+use log::{error,info,warn,debug};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::path::PathBuf;
+use std::time::Duration;
+use crossbeam::channel::{
+	Sender,
+	Receiver,
+	TryRecvError,
+};
+use shukusai::{
+	CollectionWatch,
+	collection::Collection,
+	kernel::{
+		FrontendToKernel,
+		KernelToFrontend,
+	},
+};
+use benri::{
+	now,
+	secs_f64,
+};
+use crate::{
+	config::config,
+	ptr::CollectionPtr,
+	statics::RESETTING,
+};
+
+//---------------------------------------------------------------------------------------------------- Constants
+// How often the watcher channel is checked.
+const POLL: Duration = Duration::from_millis(250);
+
+//---------------------------------------------------------------------------------------------------- Init
+// Start watching `collection_paths`.
+//
+// This must be called from within the `tokio` runtime.
+pub fn init(
+	COLLECTION_PTR: &'static CollectionPtr,
+	TO_KERNEL:      &'static Sender<FrontendToKernel>,
+	FROM_KERNEL:    &'static Receiver<KernelToFrontend>,
+	TO_ROUTER_C:    &'static tokio::sync::mpsc::Sender::<Arc<Collection>>,
+) {
+	let paths = config().collection_paths.clone();
+
+	if paths.is_empty() {
+		warn!("Watch - [collection_paths] is empty, not watching the Collection");
+		return;
+	}
+
+	let from_watch = match CollectionWatch::spawn(paths) {
+		Ok(r)  => r,
+		Err(e) => {
+			error!("Watch - could not watch [collection_paths], the Collection will not be updated automatically: {e}");
+			return;
+		},
+	};
+
+	tokio::task::spawn(async move {
+		loop {
+			match from_watch.try_recv() {
+				Ok(paths) => update(paths, COLLECTION_PTR, TO_KERNEL, FROM_KERNEL, TO_ROUTER_C).await,
+				Err(TryRecvError::Empty) => tokio::time::sleep(POLL).await,
+				Err(TryRecvError::Disconnected) => {
+					error!("Watch - watcher disconnected, the Collection will not be updated automatically");
+					break;
+				},
+			}
+		}
+	});
+}
+
+//---------------------------------------------------------------------------------------------------- Update
+// Same as `collection_new`, but with `FrontendToKernel::UpdateCollection`.
+//
+// Instead of erroring if a reset is already in
+// progress, this waits until it is done.
+async fn update(
+	paths:          Vec<PathBuf>,
+	COLLECTION_PTR: &'static CollectionPtr,
+	TO_KERNEL:      &'static Sender<FrontendToKernel>,
+	FROM_KERNEL:    &'static Receiver<KernelToFrontend>,
+	TO_ROUTER_C:    &'static tokio::sync::mpsc::Sender::<Arc<Collection>>,
+) {
+	// Compare and set `RESETTING`.
+	while RESETTING.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+		debug!("Watch - Collection is being reset, waiting...");
+		tokio::time::sleep(Duration::from_millis(500)).await;
+	}
+
+	let now = now!();
+
+	for p in paths.iter() {
+		debug!("Watch - Changed PATH: {}", p.display());
+	}
+	info!("Watch - updating Collection, {} changed PATHs", paths.len());
+
+	let collection = crate::rpc::collection_reset(
+		FrontendToKernel::UpdateCollection(paths),
+		COLLECTION_PTR.arc(),
+		TO_KERNEL,
+		FROM_KERNEL,
+	).await;
+
+	info!(
+		"Watch - Collection updated in {}s, {} artists, {} albums, {} songs",
+		secs_f64!(now),
+		collection.count_artist,
+		collection.count_album,
+		collection.count_song,
+	);
+
+	// Send to `Router`.
+	// SAFETY: should never panic since the `Receiver` lives forever.
+	TO_ROUTER_C.send(collection).await.unwrap();
+}
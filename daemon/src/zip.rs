@@ -16,21 +16,25 @@ use rand::{
 
 //---------------------------------------------------------------------------------------------------- TmpZip
 pub fn clean_cache() -> Result<(), anyhow::Error> {
-	let dir = ArtistZip::sub_dir_parent_path()?;
-
-	if !dir.exists() {
-		return Ok(());
+	// The `zip/` directory, and the `transcode/` directory.
+	let zip = ArtistZip::sub_dir_parent_path()?;
+	let transcode = match TranscodeCache::absolute_path()?.parent() {
+		Some(p) => p.to_path_buf(),
+		None    => return Err(anyhow!("transcode cache has no parent directory")),
+	};
+
+	for dir in [zip, transcode] {
+		if dir.exists() {
+			std::fs::remove_dir_all(&dir)?;
+		}
 	}
 
-	match std::fs::remove_dir_all(&dir) {
-		Ok(_)  => Ok(()),
-		Err(e) => Err(e.into()),
-	}
+	Ok(())
 }
 
-macro_rules! impl_zip {
+macro_rules! impl_cache {
 	($type:ident, $sub_dir:literal) => {
-		disk::empty!($type, disk::Dir::Cache, FESTIVAL, formatcp!("{FRONTEND_SUB_DIR}/zip/{}", $sub_dir), "tmp");
+		disk::empty!($type, disk::Dir::Cache, FESTIVAL, formatcp!("{FRONTEND_SUB_DIR}/{}", $sub_dir), "tmp");
 		#[derive(Debug)]
 		pub struct $type {
 			pub real: PathBuf,
@@ -64,7 +68,7 @@ macro_rules! impl_zip {
 				let real = std::mem::take(&mut self.real);
 				let tmp  = std::mem::take(&mut self.tmp);
 
-				// Removes the temporary ZIPs.
+				// Removes the temporary files.
 				tokio::task::spawn(async move {
 					if tmp.exists() {
 						match tokio::fs::remove_file(&tmp).await {
@@ -78,7 +82,7 @@ macro_rules! impl_zip {
 						return;
 					}
 
-					// Removes the created cached ZIPs `x` seconds _after_ creation.
+					// Removes the created cached files `x` seconds _after_ creation.
 					tokio::time::sleep(std::time::Duration::from_secs(crate::config::config().cache_time)).await;
 
 					if real.exists() {
@@ -93,11 +97,12 @@ macro_rules! impl_zip {
 	}
 }
 
-impl_zip!(CollectionZip, "collection");
-impl_zip!(PlaylistZip,   "playlist");
-impl_zip!(ArtistZip,     "artist");
-impl_zip!(AlbumZip,      "album");
-impl_zip!(ArtZip,        "art");
+impl_cache!(CollectionZip,  "zip/collection");
+impl_cache!(PlaylistZip,    "zip/playlist");
+impl_cache!(ArtistZip,      "zip/artist");
+impl_cache!(AlbumZip,       "zip/album");
+impl_cache!(ArtZip,         "zip/art");
+impl_cache!(TranscodeCache, "transcode");
 
 //---------------------------------------------------------------------------------------------------- TESTS
 //#[cfg(test)]
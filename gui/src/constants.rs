@@ -152,7 +152,7 @@ pub const GUI: &str = "gui";
 pub const STATE_VERSION: u8 = 1;
 
 /// Current major version of `GUI`'s `Settings`
-pub const SETTINGS_VERSION: u8 = 3;
+pub const SETTINGS_VERSION: u8 = 4;
 
 //---------------------------------------------------------------------------------------------------- Resolution
 // 700.0 works on some `Album`'s in view tabs
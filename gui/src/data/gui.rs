@@ -4,6 +4,7 @@ use crossbeam::channel::{Receiver, Sender};
 use shukusai::{
     collection::{AlbumKey, ArtistKey, Collection, KeyEnum, SongKey},
     kernel::{FrontendToKernel, KernelToFrontend},
+    sort::SongSort,
     state::{AudioState, ResetState},
 };
 use std::path::PathBuf;
@@ -89,6 +90,10 @@ pub struct Gui {
     pub rfd_open: Arc<AtomicBool>,
     /// If a file was selected with RFD.
     pub rfd_new: Arc<Mutex<Option<PathBuf>>>,
+    /// If a playlist file to import was selected with RFD.
+    pub rfd_playlist_import: Arc<Mutex<Option<PathBuf>>>,
+    /// If a playlist (name) and a file to export it to was selected with RFD.
+    pub rfd_playlist_export: Arc<Mutex<Option<(Arc<str>, PathBuf)>>>,
     /// A buffer of the indices of the PATHs the user wants deleted.
     pub deleted_paths: Vec<usize>,
 
@@ -134,6 +139,11 @@ pub struct Gui {
     pub count_song: String,
     /// A cached, formatted version of [`Collection::count_art`]
     pub count_art: String,
+    /// The `Songs` tab order for the play statistic sorts, cached
+    /// with the [`SongSort`] and `STATS.generation()` it was sorted with.
+    ///
+    /// This must be cleared when the [`Collection`] changes.
+    pub song_sort_cache: Option<(SongSort, u64, Box<[SongKey]>)>,
 
     // Exit state.
     /// Are we currently in the process of exiting?
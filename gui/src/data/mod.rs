@@ -14,6 +14,8 @@ mod settings1;
 pub use settings1::*;
 mod settings2;
 pub use settings2::*;
+mod settings3;
+pub use settings3::*;
 
 mod gui;
 pub use gui::*;
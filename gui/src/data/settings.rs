@@ -11,7 +11,7 @@ use crate::constants::{
 use crate::data::{AlbumSizing, SearchSort, WindowTitle};
 use const_format::formatcp;
 use shukusai::{
-    audio::PREVIOUS_THRESHOLD_DEFAULT,
+    audio::{OUTPUT_DEVICE, PREVIOUS_THRESHOLD_DEFAULT},
     constants::{FESTIVAL, HEADER, STATE_SUB_DIR},
     search::SearchKind,
     sort::{AlbumSort, ArtistSort, SongSort},
@@ -87,6 +87,9 @@ pub struct Settings {
     /// What `egui::Context::pixels_per_point` are we set to?
     /// Default is `1.0`, this allows the user to scale manually.
     pub pixels_per_point: f32,
+
+    /// The name of the [`shukusai::audio::Output`] to play to.
+    pub output: String,
 }
 
 impl Settings {
@@ -108,6 +111,7 @@ impl Settings {
             accent_color: ACCENT_COLOR,
             collection_paths: vec![],
             pixels_per_point: PIXELS_PER_POINT_DEFAULT,
+            output: OUTPUT_DEVICE.to_string(),
         }
     }
 }
@@ -128,11 +132,11 @@ mod test {
 
     // Empty.
     const S1: Lazy<Settings> = Lazy::new(|| {
-        Settings::from_path("../assets/festival/gui/state/settings3_new.bin").unwrap()
+        Settings::from_path("../assets/festival/gui/state/settings4_new.bin").unwrap()
     });
     // Filled.
     const S2: Lazy<Settings> = Lazy::new(|| {
-        Settings::from_path("../assets/festival/gui/state/settings3_real.bin").unwrap()
+        Settings::from_path("../assets/festival/gui/state/settings4_real.bin").unwrap()
     });
 
     #[test]
@@ -154,5 +158,6 @@ mod test {
         assert_eq!(S2.accent_color, egui::Color32::from_rgb(97, 101, 119));
         assert_eq!(S2.collection_paths, [PathBuf::from("/home/main/Music")]);
         assert_eq!(S2.pixels_per_point.round(), 2.0);
+        assert_eq!(S2.output, "null");
     }
 }
@@ -12,7 +12,7 @@ use crate::data::{AlbumSizing, ArtistSubTab, SearchSort, Settings, WindowTitle};
 use const_format::formatcp;
 use disk::Bincode2;
 use shukusai::{
-    audio::PREVIOUS_THRESHOLD_DEFAULT,
+    audio::{OUTPUT_DEVICE, PREVIOUS_THRESHOLD_DEFAULT},
     constants::{FESTIVAL, HEADER, STATE_SUB_DIR},
     search::SearchKind,
     sort::{AlbumSort, ArtistSort, SongSort},
@@ -172,6 +172,7 @@ impl Into<Settings> for Settings0 {
             // New fields.
             pixels_per_point: PIXELS_PER_POINT_DEFAULT,
             auto_save: AUTO_SAVE_INTERVAL_SECONDS,
+            output: OUTPUT_DEVICE.to_string(),
         }
     }
 }
@@ -11,7 +11,7 @@ use const_format::formatcp;
 use disk::Bincode2;
 use serde::{Deserialize, Serialize};
 use shukusai::{
-    audio::PREVIOUS_THRESHOLD_DEFAULT,
+    audio::{OUTPUT_DEVICE, PREVIOUS_THRESHOLD_DEFAULT},
     constants::{FESTIVAL, HEADER, STATE_SUB_DIR},
     search::SearchKind,
     sort::{AlbumSort, ArtistSort, SongSort},
@@ -153,6 +153,7 @@ impl Into<Settings> for Settings1 {
 
             // New fields.
             auto_save: AUTO_SAVE_INTERVAL_SECONDS,
+            output: OUTPUT_DEVICE.to_string(),
         }
     }
 }
@@ -8,6 +8,7 @@ use strum::{AsRefStr, Display, EnumCount, EnumIter, EnumString, EnumVariantNames
 pub const VIEW: &str = "View";
 pub const ALBUMS: &str = "Albums";
 pub const ARTISTS: &str = "Artists";
+pub const GENRES: &str = "Genres";
 pub const SONGS: &str = "Songs";
 pub const QUEUE: &str = "Queue";
 pub const PLAYLISTS: &str = "Playlists";
@@ -58,15 +59,32 @@ pub enum Tab {
     Playlists,
     Search,
     Settings,
+    // This is at the end so the `State` enum
+    // number tags of the other tabs stay the same.
+    Genres,
 }
 
 impl Tab {
+    /// Every [`Tab`], in the order they are shown in the GUI.
+    pub const ALL: [Self; Self::COUNT] = [
+        Self::View,
+        Self::Albums,
+        Self::Artists,
+        Self::Genres,
+        Self::Songs,
+        Self::Queue,
+        Self::Playlists,
+        Self::Search,
+        Self::Settings,
+    ];
+
     /// No [`String`] allocation.
     pub fn human(&self) -> &'static str {
         match self {
             Self::View => VIEW,
             Self::Albums => ALBUMS,
             Self::Artists => ARTISTS,
+            Self::Genres => GENRES,
             Self::Songs => SONGS,
             Self::Queue => QUEUE,
             Self::Playlists => PLAYLISTS,
@@ -83,7 +101,8 @@ impl Tab {
         match self {
             Self::View => Self::Albums,
             Self::Albums => Self::Artists,
-            Self::Artists => Self::Songs,
+            Self::Artists => Self::Genres,
+            Self::Genres => Self::Songs,
             Self::Songs => Self::Queue,
             Self::Queue => Self::Playlists,
             Self::Playlists => Self::Search,
@@ -101,7 +120,8 @@ impl Tab {
             Self::View => Self::Settings,
             Self::Albums => Self::View,
             Self::Artists => Self::Albums,
-            Self::Songs => Self::Artists,
+            Self::Genres => Self::Artists,
+            Self::Songs => Self::Genres,
             Self::Queue => Self::Songs,
             Self::Playlists => Self::Queue,
             Self::Search => Self::Playlists,
@@ -132,4 +152,16 @@ mod tests {
             assert!(set3.insert(i.previous()));
         }
     }
+
+    #[test]
+    // Asserts `ALL` has every variant in `.next()` order.
+    fn all() {
+        let set: std::collections::HashSet<Tab> = Tab::ALL.into_iter().collect();
+        assert_eq!(set.len(), Tab::COUNT);
+
+        for w in Tab::ALL.windows(2) {
+            assert_eq!(w[0].next(), w[1]);
+        }
+        assert_eq!(Tab::ALL[Tab::COUNT - 1].next(), Tab::ALL[0]);
+    }
 }
@@ -24,6 +24,56 @@ pub fn spawn_rfd_thread(rfd_open: Arc<AtomicBool>, rfd_new: Arc<Mutex<Option<Pat
     });
 }
 
+pub fn spawn_rfd_playlist_import_thread(
+    rfd_open: Arc<AtomicBool>,
+    rfd_playlist_import: Arc<Mutex<Option<PathBuf>>>,
+) {
+    std::thread::spawn(move || {
+        atomic_store!(rfd_open, true);
+
+        match rfd::FileDialog::new()
+            .set_title("Import a playlist")
+            .add_filter("Playlist", &["m3u8", "m3u", "xspf", "pls"])
+            .pick_file()
+        {
+            Some(path) => {
+                info!("RFD - Selected playlist PATH: {}", path.display());
+                lock!(rfd_playlist_import).replace(path);
+            }
+            None => info!("RFD - No playlist PATH selected"),
+        }
+
+        atomic_store!(rfd_open, false);
+    });
+}
+
+pub fn spawn_rfd_playlist_export_thread(
+    rfd_open: Arc<AtomicBool>,
+    rfd_playlist_export: Arc<Mutex<Option<(Arc<str>, PathBuf)>>>,
+    playlist: Arc<str>,
+) {
+    std::thread::spawn(move || {
+        atomic_store!(rfd_open, true);
+
+        match rfd::FileDialog::new()
+            .set_title("Export playlist")
+            .set_file_name(&format!("{playlist}.m3u8"))
+            .add_filter("M3U8", &["m3u8"])
+            .add_filter("XSPF", &["xspf"])
+            .add_filter("PLS", &["pls"])
+            .save_file()
+        {
+            Some(path) => {
+                info!("RFD - Selected playlist export PATH: {}", path.display());
+                lock!(rfd_playlist_export).replace((playlist, path));
+            }
+            None => info!("RFD - No playlist export PATH selected"),
+        }
+
+        atomic_store!(rfd_open, false);
+    });
+}
+
 //---------------------------------------------------------------------------------------------------- TESTS
 //#[cfg(test)]
 //mod tests {
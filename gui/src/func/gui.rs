@@ -14,8 +14,9 @@ use shukusai::{
     collection::{Collection, Keychain},
     constants::PLAYLIST_VERSION,
     kernel::FrontendToKernel,
-    state::{AUDIO_STATE, PLAYLISTS},
+    state::{PlaylistFormat, Playlists, AUDIO_STATE, PLAYLISTS},
 };
+use std::path::PathBuf;
 use std::sync::Arc;
 
 //---------------------------------------------------------------------------------------------------- GUI `Drop` impl
@@ -131,6 +132,12 @@ impl Gui {
 
     /// Reset [`Settings`] to the original.
     pub fn reset_settings(&mut self) {
+        if self.settings.output != self.og_settings.output {
+            send!(
+                self.to_kernel,
+                FrontendToKernel::Output(self.og_settings.output.clone())
+            );
+        }
         self.settings = self.og_settings.clone();
     }
 
@@ -213,6 +220,104 @@ impl Gui {
         }
     }
 
+    /// Open a file picker for a playlist file to import (spawns RFD thread).
+    pub fn pick_playlist_import(&self) {
+        if atomic_load!(self.rfd_open) {
+            warn!("GUI - Playlist import requested, but RFD is already open");
+        } else {
+            crate::func::spawn_rfd_playlist_import_thread(
+                Arc::clone(&self.rfd_open),
+                Arc::clone(&self.rfd_playlist_import),
+            );
+        }
+    }
+
+    /// Open a file picker for where to export a playlist to (spawns RFD thread).
+    pub fn pick_playlist_export(&self, playlist: Arc<str>) {
+        if atomic_load!(self.rfd_open) {
+            warn!("GUI - Playlist export requested, but RFD is already open");
+        } else {
+            crate::func::spawn_rfd_playlist_export_thread(
+                Arc::clone(&self.rfd_open),
+                Arc::clone(&self.rfd_playlist_export),
+                playlist,
+            );
+        }
+    }
+
+    /// Import a playlist file as a new playlist named after the file.
+    ///
+    /// This must not be called while holding the [`PLAYLISTS`] lock.
+    pub fn import_playlist(&mut self, path: PathBuf) {
+        let s = match std::fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("GUI - Playlist import failed: {} ... {e}", path.display());
+                crate::toast_err!(self, format!("Playlist import failed: {e}"));
+                return;
+            }
+        };
+
+        let Some(format) = PlaylistFormat::from_path(&path).or_else(|| PlaylistFormat::detect(&s))
+        else {
+            crate::toast_err!(self, "Playlist import failed: unknown playlist format");
+            return;
+        };
+
+        let entries = Playlists::import(&s, format, path.parent(), &self.collection);
+        let valid = Playlists::valid_len(&entries);
+        let len = entries.len();
+
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Playlist".to_string());
+
+        // Prevent overwriting existing playlists.
+        let mut playlists = PLAYLISTS.write();
+        let mut name = stem.clone();
+        let mut import = String::new();
+        while playlists.contains_key(name.as_str()) {
+            import += " (Import)";
+            name = format!("{stem}{import}");
+        }
+        playlists.insert(name.as_str().into(), entries);
+        drop(playlists);
+
+        info!("GUI - Imported playlist [{name}] ... {valid}/{len} valid entries");
+        crate::toast_ok!(
+            self,
+            format!("Imported [{name}], {valid}/{len} songs were found in the Collection")
+        );
+    }
+
+    /// Export a playlist into a file, the format is chosen by its extension.
+    ///
+    /// This must not be called while holding the [`PLAYLISTS`] lock.
+    pub fn export_playlist(&mut self, playlist: &str, path: PathBuf) {
+        let format = PlaylistFormat::from_path(&path).unwrap_or_default();
+
+        let Some(entries) = PLAYLISTS.read().get(playlist).map(|v| v.clone()) else {
+            crate::toast_err!(self, "Playlist export failed: playlist does not exist");
+            return;
+        };
+
+        let data = Playlists::export(playlist, &entries, format, &self.collection);
+        match std::fs::write(&path, data) {
+            Ok(_) => {
+                info!(
+                    "GUI - Exported playlist [{playlist}] ... {}",
+                    path.display()
+                );
+                crate::toast_ok!(self, format!("Exported [{playlist}] to {}", path.display()));
+            }
+            Err(e) => {
+                warn!("GUI - Playlist export failed: {} ... {e}", path.display());
+                crate::toast_err!(self, format!("Playlist export failed: {e}"));
+            }
+        }
+    }
+
     /// Perform all the necessary steps to reset
     /// the [`Collection`] and enter the proper state.
     pub fn reset_collection(&mut self) {
@@ -228,6 +333,7 @@ impl Gui {
 
         // Drop our real `Collection`.
         self.collection = Collection::dummy();
+        self.song_sort_cache = None;
 
         // Send signal to `Kernel`.
         if self.settings.collection_paths.is_empty() {
@@ -259,6 +365,7 @@ impl Gui {
     /// This should be called after we received a new [`Collection`].
     pub fn cache_collection(&mut self) {
         self.format_count_assign();
+        self.song_sort_cache = None;
     }
 
     /// Increments the [`Album`] art size.
@@ -3,7 +3,7 @@ use crate::constants::{
     APP_HEIGHT_DEFAULT, APP_HEIGHT_MIN, APP_WIDTH_DEFAULT, APP_WIDTH_MIN, FONT_ARRAY,
     RUNTIME_WIDTH, SETTINGS_VERSION, SPACING, STATE_VERSION, VISUALS,
 };
-use crate::data::{DebugInfo, Settings, Settings0, Settings1, Settings2, State, State0};
+use crate::data::{DebugInfo, Settings, Settings0, Settings1, Settings2, Settings3, State, State0};
 use benri::{atomic_store, now, send};
 use crossbeam::channel::{Receiver, Sender};
 use disk::{Bincode2, Json};
@@ -156,6 +156,7 @@ impl crate::data::Gui {
         // Read `Settings` from disk.
         let settings = Settings::from_versions(&[
             (SETTINGS_VERSION, Settings::from_file),
+            (3, Settings3::disk_into),
             (2, Settings2::disk_into),
             (1, Settings1::disk_into),
             (0, Settings0::disk_into),
@@ -182,6 +183,9 @@ impl crate::data::Gui {
             shukusai::audio::PREVIOUS_THRESHOLD,
             settings.previous_threshold
         );
+        if settings.output != shukusai::audio::selected_output() {
+            send!(to_kernel, FrontendToKernel::Output(settings.output.clone()));
+        }
 
         // Send `CachePath` signal to `Kernel`.
         if settings.collection_paths.is_empty() {
@@ -282,6 +286,8 @@ impl crate::data::Gui {
             // `rfd`.
             rfd_open: Arc::new(AtomicBool::new(false)),
             rfd_new: Arc::new(Mutex::new(None)),
+            rfd_playlist_import: Arc::new(Mutex::new(None)),
+            rfd_playlist_export: Arc::new(Mutex::new(None)),
             deleted_paths: vec![],
 
             // Search state.
@@ -304,6 +310,7 @@ impl crate::data::Gui {
             count_album: "Albums: 0".to_string(),
             count_song: "Songs: 0".to_string(),
             count_art: "Art: 0".to_string(),
+            song_sort_cache: None,
 
             exiting: false,
             exit_instant: now!(),
@@ -115,9 +115,12 @@ pub const PLAYLIST_DELETE: &str = "Delete this playlist";
 pub const PLAYLIST_EDIT: &str = "Edit this playlist's name";
 pub const PLAYLIST_EDIT_SAVE: &str = "Save this playlist with the new name";
 pub const PLAYLIST_COPY: &str = "Create a copy of this playlist";
+pub const PLAYLIST_IMPORT: &str = "Import a M3U/M3U8, XSPF or PLS playlist file";
+pub const PLAYLIST_EXPORT: &str = "Export this playlist as a M3U8, XSPF or PLS playlist file (chosen by the file extension)";
 pub const PLAYLIST_COUNT: &str = "Total amount of playlists";
 pub const PLAYLIST_TOTAL_SONG: &str = "Total playlist song count";
 pub const PLAYLIST_TOTAL_RUNTIME: &str = "Total playlist runtime";
+pub const PLAYLIST_SMART: &str = "This is a smart playlist, its songs are re-computed from these rules on every new Collection";
 pub const PLAYLIST_ENTRY_DELETE: &str = "Delete this entry";
 pub const PLAYLIST_ENTRY_UP: &str = "Move this entry up";
 pub const PLAYLIST_ENTRY_DOWN: &str = "Move this entry down";
@@ -159,6 +162,11 @@ This setting is disabled if set to [0]."#;
 pub const RESTORE_STATE: &str =
     "Restore playback state from the last session when opening Festival";
 pub const WINDOW_TITLE: &str = "Set Festival's window title when changing songs";
+pub const OUTPUT: &str = r#"Which audio output to play to.
+
+[pulseaudio]/[cpal] is the audio device.
+[null] plays into nothing, in real-time.
+[wav] writes everything played into a WAV file, in real-time."#;
 pub const ACCENT_COLOR: &str = formatcp!(
     "Which accent color to use (default: [{}, {}, {}])",
     ACCENT_COLOR_RGB[0],
@@ -240,7 +248,7 @@ Settings v{SETTINGS_VERSION}
 // This is inaccurate because `char` != `u8` but meh.
 pub const SEARCH_MAX: &str =
     formatcp!("Search character limit has been reached ({SEARCH_MAX_LEN})");
-pub const SEARCH_BAR:              &str = "Search for albums, artists, and songs.\nYou can start typing from anywhere in Festival to start searching.";
+pub const SEARCH_BAR:              &str = "Search for albums, artists, and songs.\nYou can start typing from anywhere in Festival to start searching.\n\nFilters: artist:  album:  song:  genre:  year:  runtime:\nExample: artist:radiohead year:>2000 genre:rock runtime:<5m \"exact phrase\"";
 pub const SEARCH_HELP: &str = "🔍 Search for albums, artists, and songs.";
 pub const SEARCH_EMPTY_COLLECTION: &str = "The Collection is empty. There is nothing to search.";
 pub const SEARCH_SORT_SONG: &str = "Search by song title";
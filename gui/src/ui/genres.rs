@@ -0,0 +1,93 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::{
+    constants::MEDIUM_GRAY,
+    text::{ARTIST_TOTAL_ALBUM, ARTIST_TOTAL_SONG},
+};
+use egui::{Label, RichText, ScrollArea, TextStyle};
+use readable::Unsigned;
+
+//---------------------------------------------------------------------------------------------------- Genres
+impl crate::data::Gui {
+    #[inline(always)]
+    pub fn show_tab_genres(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        width: f32,
+        height: f32,
+    ) {
+        self.set_visuals(ui);
+
+        // Sizing.
+        let width = ui.available_width();
+        let height = ui.available_height();
+
+        ScrollArea::vertical()
+            .id_source("Genre")
+            .max_width(width)
+            .max_height(height)
+            .auto_shrink([false; 2])
+            .show_viewport(ui, |ui, _| {
+                // For each `Genre`...
+                for genre in self.collection.genres.iter() {
+                    // `Genre` name.
+                    let label_name = Label::new(
+                        RichText::new(&*genre.name).text_style(TextStyle::Name("30".into())),
+                    );
+
+                    // `Genre` album count.
+                    let label_album = Label::new(
+                        RichText::new(Unsigned::from(genre.albums.len()).as_str())
+                            .color(MEDIUM_GRAY)
+                            .text_style(TextStyle::Name("25".into())),
+                    );
+
+                    // `Genre` song count.
+                    let label_song = Label::new(
+                        RichText::new(Unsigned::from(genre.songs.len()).as_str())
+                            .color(MEDIUM_GRAY)
+                            .text_style(TextStyle::Name("25".into())),
+                    );
+
+                    ui.horizontal(|ui| {
+                        ui.add(label_name);
+                        ui.add_space(20.0);
+                        ui.add(label_album).on_hover_text(ARTIST_TOTAL_ALBUM);
+                        ui.add_space(20.0);
+                        ui.add(label_song).on_hover_text(ARTIST_TOTAL_SONG);
+                    });
+
+                    ui.add_space(10.0);
+
+                    // Its `Album`'s.
+                    ScrollArea::horizontal()
+                        .id_source(&genre.name)
+                        .max_width(f32::INFINITY)
+                        .max_height(120.0)
+                        .auto_shrink([false; 2])
+                        .show_viewport(ui, |ui, _| {
+                            ui.horizontal(|ui| {
+                                crate::no_rounding!(ui);
+
+                                for key in genre.albums.iter() {
+                                    let album = &self.collection.albums[key];
+
+                                    crate::album_button!(
+                                        self,
+                                        album,
+                                        *key,
+                                        ui,
+                                        ctx,
+                                        120.0,
+                                        &*album.title
+                                    );
+                                }
+                            });
+                        });
+
+                    ui.add_space(10.0);
+                    ui.separator();
+                }
+            });
+    }
+}
@@ -470,6 +470,12 @@ macro_rules! song_button {
             $crate::copy_song!($self, $ui.ctx(), $song);
         }
 
+        // `Song`'s in compilations show their track artist as well.
+        let name: std::borrow::Cow<'_, str> = match &$song.track_artist {
+            Some(artist) => format!("{} - {artist}", $song.title).into(),
+            None => (&*$song.title).into(),
+        };
+
         // FIXME:
         // This is a little too eager to chop and doesn't
         // scale as the width gets longer, aka, we have enough
@@ -486,7 +492,7 @@ macro_rules! song_button {
         // HACK HACK:
         // Queue tab has wider song buttons, so
         // we must vary the width for that.
-        let ascii = $song.title.is_ascii();
+        let ascii = name.is_ascii();
         let head_len = match $self.state.tab == crate::data::Tab::Queue {
             true => {
                 if ascii {
@@ -505,9 +511,9 @@ macro_rules! song_button {
         };
         let head_len = (width / head_len) as usize;
 
-        let head = readable::HeadTail::head_dot(&&*$song.title, head_len);
+        let head = readable::HeadTail::head_dot(&&*name, head_len);
         // If we chopped the title, show the full title on hover.
-        let chopped = &*$song.title == head;
+        let chopped = &*name == head;
 
         let resp = $ui.allocate_ui_at_rect(rect, |ui| {
             ui.horizontal_centered(|ui| match (chopped, $song.track) {
@@ -524,7 +530,7 @@ macro_rules! song_button {
                         $song.runtime.as_str(),
                         head
                     )))
-                    .on_hover_text(&*$song.title),
+                    .on_hover_text(&*name),
                 (true, None) => ui.add(egui::Label::new(format!(
                     "{: >3}{: >8}    {}",
                     "???",
@@ -538,7 +544,7 @@ macro_rules! song_button {
                         $song.runtime.as_str(),
                         head
                     )))
-                    .on_hover_text(&*$song.title),
+                    .on_hover_text(&*name),
             });
         });
     };
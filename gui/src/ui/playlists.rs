@@ -5,9 +5,9 @@ use crate::{
     text::{
         PLAYLIST_COPY, PLAYLIST_COUNT, PLAYLIST_CREATE, PLAYLIST_DELETE, PLAYLIST_EDIT,
         PLAYLIST_EDIT_SAVE, PLAYLIST_EMPTY, PLAYLIST_ENTRY_DELETE, PLAYLIST_ENTRY_DOWN,
-        PLAYLIST_ENTRY_UP, PLAYLIST_EXISTS, PLAYLIST_INVALID, PLAYLIST_TEXT, PLAYLIST_TEXT_EMPTY,
-        PLAYLIST_TOTAL_RUNTIME, PLAYLIST_TOTAL_SONG, SELECT_PLAYLIST, UI_DOWN, UI_MINUS, UI_PLUS,
-        UI_UP,
+        PLAYLIST_ENTRY_UP, PLAYLIST_EXISTS, PLAYLIST_EXPORT, PLAYLIST_IMPORT, PLAYLIST_INVALID,
+        PLAYLIST_SMART, PLAYLIST_TEXT, PLAYLIST_TEXT_EMPTY, PLAYLIST_TOTAL_RUNTIME,
+        PLAYLIST_TOTAL_SONG, SELECT_PLAYLIST, UI_DOWN, UI_MINUS, UI_PLUS, UI_UP,
     },
 };
 use egui::{Button, Label, RichText, ScrollArea, SelectableLabel, Sense, TextEdit, TextStyle};
@@ -75,6 +75,13 @@ impl crate::data::Gui {
         let width = ui.available_width();
         let height = ui.available_height();
 
+        //-------------------------------------------------- Smart playlist definitions.
+        // This must be locked before `PLAYLISTS`, and must
+        // not be written to while `PLAYLISTS` is locked.
+        let smart = shukusai::state::SMART_PLAYLISTS.read().clone();
+        let mut smart_remove: Option<Arc<str>> = None;
+        let mut smart_rename: Option<(Arc<str>, Arc<str>)> = None;
+
         //-------------------------------------------------- Acquire playlist lock.
         let mut playlists = shukusai::state::PLAYLISTS.write();
 
@@ -122,6 +129,16 @@ impl crate::data::Gui {
                                     });
                                 }
 
+                                // Import button.
+                                let button = Button::new(RichText::new("📂").size(SIZE - 5.0));
+                                if ui
+                                    .add_sized([SIZE2, SIZE2], button)
+                                    .on_hover_text(PLAYLIST_IMPORT)
+                                    .clicked()
+                                {
+                                    self.pick_playlist_import();
+                                }
+
                                 // Playlist count
                                 let text = Label::new(
                                     RichText::new(format!("[{}]", playlists.len()))
@@ -276,6 +293,15 @@ impl crate::data::Gui {
                                         self.playlist_clone = Some(Arc::clone(playlist_name));
                                     }
 
+                                    let button = Button::new(RichText::new("💾").size(SIZE - 5.0));
+                                    if ui
+                                        .add_sized([SIZE2, SIZE2], button)
+                                        .on_hover_text(PLAYLIST_EXPORT)
+                                        .clicked()
+                                    {
+                                        self.pick_playlist_export(Arc::clone(playlist_name));
+                                    }
+
                                     ui.add_space(15.0);
 
                                     if playlist_name_is_being_edited {
@@ -293,6 +319,26 @@ impl crate::data::Gui {
                                             && ui.input(|i| i.key_pressed(egui::Key::Enter));
                                     } else {
                                         crate::playlist_label!(self, playlist_name, ui, label_name);
+                                        if let Some(smart) = smart.get(playlist_name) {
+                                            let rules: Vec<String> =
+                                                smart.rules.iter().map(|r| r.to_string()).collect();
+                                            let limit = match smart.limit {
+                                                Some(l) => Unsigned::from(l).to_string(),
+                                                None => "None".to_string(),
+                                            };
+                                            let hover = format!(
+                                                "{PLAYLIST_SMART}\n\nRules: {}\nLimit: {limit}\nSort: {}",
+                                                rules.join(", "),
+                                                smart.sort.human(),
+                                            );
+                                            ui.add_space(20.0);
+                                            ui.add(Label::new(
+                                                RichText::new("Smart")
+                                                    .color(YELLOW)
+                                                    .text_style(TextStyle::Name("25".into())),
+                                            ))
+                                            .on_hover_text(hover);
+                                        }
                                         ui.add_space(20.0);
                                         ui.add(label_count).on_hover_text(PLAYLIST_TOTAL_SONG);
                                         ui.add_space(20.0);
@@ -334,6 +380,7 @@ impl crate::data::Gui {
                             None => (),
                             Some(p) => {
                                 playlists.remove(&p);
+                                smart_remove = Some(p);
                             }
                         }
 
@@ -342,6 +389,7 @@ impl crate::data::Gui {
                             (Some(from), Some(to)) => {
                                 if let Some(value) = playlists.remove(&**from) {
                                     playlists.insert(Arc::clone(to), value);
+                                    smart_rename = Some((Arc::clone(from), Arc::clone(to)));
                                 }
 
                                 self.playlist_from = None;
@@ -671,6 +719,25 @@ impl crate::data::Gui {
                 }
             }
         } // end of match.
+
+        //-------------------------------------------------- Update smart playlist definitions.
+        drop(playlists);
+        if smart_remove.is_some() || smart_rename.is_some() {
+            let mut smart = shukusai::state::SMART_PLAYLISTS.write();
+            if let Some(p) = smart_remove {
+                smart.remove(&p);
+            }
+            if let Some((from, to)) = smart_rename {
+                match smart.remove(&from) {
+                    Some(s) => {
+                        smart.insert(to, s);
+                    }
+                    None => {
+                        smart.remove(&to);
+                    }
+                }
+            }
+        }
     }
 }
 
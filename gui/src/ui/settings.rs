@@ -13,13 +13,14 @@ use crate::constants::{
 };
 use crate::data::{AlbumSizing, WindowTitle};
 use crate::text::*;
-use benri::{atomic_store, flip};
+use benri::{atomic_store, flip, send};
 use egui::containers::scroll_area::ScrollBarVisibility;
 use egui::{
     Button, ComboBox, Label, RichText, ScrollArea, SelectableLabel, Sense, Slider, TextStyle,
 };
 use shukusai::{
     constants::COPYRIGHT,
+    kernel::FrontendToKernel,
     search::SearchKind,
     sort::{AlbumSort, ArtistSort, SongSort},
 };
@@ -315,6 +316,38 @@ impl crate::data::Gui {
                 ui.separator();
                 ui.add_space(40.0);
 
+                //-------------------------------------------------- Output.
+                // Heading.
+                let label = Label::new(
+                    RichText::new("Output")
+                        .color(BONE)
+                        .text_style(TextStyle::Heading),
+                );
+                ui.add_sized([width, text], label).on_hover_text(OUTPUT);
+
+                ui.add_space(10.0);
+
+                // ComboBox.
+                ui.add_space(10.0);
+                let old_output = self.settings.output.clone();
+                ComboBox::from_id_source("settings_output")
+                    .selected_text(RichText::new(&self.settings.output).color(BONE))
+                    .show_ui(ui, |ui| {
+                        for i in shukusai::audio::outputs() {
+                            ui.selectable_value(&mut self.settings.output, i.to_string(), i);
+                        }
+                    });
+                if old_output != self.settings.output {
+                    send!(
+                        self.to_kernel,
+                        FrontendToKernel::Output(self.settings.output.clone())
+                    );
+                }
+
+                ui.add_space(40.0);
+                ui.separator();
+                ui.add_space(40.0);
+
                 //-------------------------------------------------- Album Art Size.
                 // Heading.
                 let label = Label::new(
@@ -6,7 +6,9 @@ use egui::{ComboBox, Label, RichText, ScrollArea, Sense};
 use egui_extras::{Column, TableBuilder};
 use log::warn;
 use readable::Unsigned;
+use shukusai::collection::SongKey;
 use shukusai::sort::SongSort;
+use shukusai::state::STATS;
 
 //---------------------------------------------------------------------------------------------------- Songs
 impl crate::data::Gui {
@@ -24,7 +26,7 @@ impl crate::data::Gui {
         let width = ui.available_width();
         let height = ui.available_height();
         // c == Column sizing
-        let c_width = (width / 10.0) - 10.0; // Account for separators, let `Path` peek a little.
+        let c_width = (width / 12.0) - 10.0; // Account for separators, let `Path` peek a little.
         let c_title = c_width * 2.5;
         let c_album = c_width * 2.5;
         let c_artist = c_width;
@@ -32,6 +34,8 @@ impl crate::data::Gui {
         let c_runtime = c_width;
         let c_track = c_width;
         let c_disc = c_width;
+        let c_plays = c_width;
+        let c_last_played = c_width;
 
         // `.show_rows()` is slightly faster than
         // `.show_viewport()` but we need to know
@@ -80,6 +84,8 @@ impl crate::data::Gui {
                     .column(Column::initial(c_runtime).resizable(true).clip(true))
                     .column(Column::initial(c_track).resizable(true).clip(true))
                     .column(Column::initial(c_disc).resizable(true).clip(true))
+                    .column(Column::initial(c_plays).resizable(true).clip(true))
+                    .column(Column::initial(c_last_played).resizable(true).clip(true))
                     .column(Column::remainder().clip(true))
                     .auto_shrink([false; 2])
                     .max_scroll_height(height)
@@ -220,15 +226,87 @@ impl crate::data::Gui {
                         header.col(|ui| {
                             ui.strong("Disc");
                         });
+
+                        // Plays.
+                        header.col(|ui| {
+                            const SORT: [SongSort; 2] = [PlayCount, PlayCountRev];
+
+                            ComboBox::from_id_source("__song_sort_plays")
+                                .selected_text(RichText::new("Plays").color(WHITE))
+                                .width(ui.available_width() - 5.0)
+                                .show_ui(ui, |ui| {
+                                    for i in SORT {
+                                        if ui
+                                            .selectable_label(
+                                                self.settings.song_sort == i,
+                                                i.human(),
+                                            )
+                                            .clicked()
+                                        {
+                                            self.settings.song_sort = i;
+                                        }
+                                    }
+                                });
+                        });
+
+                        // Last played.
+                        header.col(|ui| {
+                            const SORT: [SongSort; 2] = [LastPlayed, LastPlayedRev];
+
+                            ComboBox::from_id_source("__song_sort_last_played")
+                                .selected_text(RichText::new("Last played").color(WHITE))
+                                .width(ui.available_width() - 5.0)
+                                .show_ui(ui, |ui| {
+                                    for i in SORT {
+                                        if ui
+                                            .selectable_label(
+                                                self.settings.song_sort == i,
+                                                i.human(),
+                                            )
+                                            .clicked()
+                                        {
+                                            self.settings.song_sort = i;
+                                        }
+                                    }
+                                });
+                        });
+
                         header.col(|ui| {
                             ui.strong("Path");
                         });
                     })
                     .body(|mut body| {
                         // Song iterator.
-                        for key in self.collection.song_iter(self.settings.song_sort) {
+                        //
+                        // The play statistic sorts aren't in the `Collection`,
+                        // so they go through `Stats`, and are only re-sorted
+                        // when the sort or `Stats` change.
+                        let stats = STATS.read();
+                        let sort = self.settings.song_sort;
+                        let keys: &[SongKey] = match sort {
+                            SongSort::PlayCount
+                            | SongSort::PlayCountRev
+                            | SongSort::LastPlayed
+                            | SongSort::LastPlayedRev => {
+                                let generation = STATS.generation();
+                                if !matches!(
+                                    &self.song_sort_cache,
+                                    Some((s, g, _)) if *s == sort && *g == generation
+                                ) {
+                                    self.song_sort_cache = None;
+                                }
+                                let (_, _, keys) = self.song_sort_cache.get_or_insert_with(|| {
+                                    let keys = stats.sort_songs(sort, &self.collection);
+                                    (sort, generation, keys.into_owned().into())
+                                });
+                                keys
+                            }
+                            _ => self.collection.song_iter(sort).as_slice(),
+                        };
+                        for key in keys.iter() {
                             body.row(ROW_HEIGHT, |mut row| {
                                 let (artist, album, song) = self.collection.walk(key);
+                                let stats = stats.song(*key, &self.collection).copied();
 
                                 row.col(|ui| {
                                     crate::song_label!(
@@ -285,6 +363,25 @@ impl crate::data::Gui {
                                     }),
                                 };
 
+                                match stats {
+                                    Some(s) => {
+                                        row.col(|ui| {
+                                            ui.label(Unsigned::from(s.play_count).as_str());
+                                        });
+                                        row.col(|ui| {
+                                            ui.label(date(s.last_played));
+                                        });
+                                    }
+                                    None => {
+                                        row.col(|ui| {
+                                            ui.label("0");
+                                        });
+                                        row.col(|ui| {
+                                            ui.label("Never");
+                                        });
+                                    }
+                                }
+
                                 row.col(|ui| {
                                     ui.add_space(5.0);
 
@@ -311,6 +408,29 @@ impl crate::data::Gui {
     }
 }
 
+//---------------------------------------------------------------------------------------------------- Date
+// UNIX timestamp -> `YYYY-MM-DD` (UTC), `Never` if `0`.
+//
+// This is Howard Hinnant's `civil_from_days()`:
+// <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn date(unix: u64) -> String {
+    if unix == 0 {
+        return "Never".to_string();
+    }
+
+    let z = (unix / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
 //---------------------------------------------------------------------------------------------------- TESTS
 //#[cfg(test)]
 //mod tests {
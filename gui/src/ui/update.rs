@@ -27,7 +27,6 @@ use shukusai::{
 };
 use std::sync::Arc;
 use std::time::Instant;
-use strum::*;
 
 //---------------------------------------------------------------------------------------------------- `GUI`'s eframe impl.
 impl eframe::App for Gui {
@@ -510,6 +509,14 @@ impl Gui {
             }
         }
 
+        // Check if `RFD` thread selected a playlist to import/export.
+        if let Some(p) = lock!(self.rfd_playlist_import).take() {
+            self.import_playlist(p);
+        }
+        if let Some((playlist, p)) = lock!(self.rfd_playlist_export).take() {
+            self.export_playlist(&playlist, p);
+        }
+
         // Show full-screen debug screen if `true`.
         if self.debug_screen {
             self.show_debug_screen(ctx, width, height);
@@ -758,7 +765,7 @@ impl Gui {
             ui.vertical_centered_justified(|ui| {
                 // Display `SelectableLabel` for each `Tab`.
                 ui.add_space(2.5);
-                for tab in Tab::iter() {
+                for tab in Tab::ALL {
                     if ui
                         .add_sized(
                             [tab_width, tab_height],
@@ -864,6 +871,7 @@ impl Gui {
                 Tab::View => self.show_tab_view(ui, ctx, width, height),
                 Tab::Albums => self.show_tab_albums(ui, ctx, width, height),
                 Tab::Artists => self.show_tab_artists(ui, ctx, width, height),
+                Tab::Genres => self.show_tab_genres(ui, ctx, width, height),
                 Tab::Songs => self.show_tab_songs(ui, ctx, width, height),
                 Tab::Queue => self.show_tab_queue(ui, ctx, width, height),
                 Tab::Playlists => self.show_tab_playlists(ui, ctx, width, height),
@@ -21,6 +21,24 @@ pub fn encode_with_authorization_basic_header(mut input: String) -> String {
 	basic
 }
 
+//---------------------------------------------------------------------------------------------------- Base64 Decode
+/// 1. Takes in an `authorization` HTTP header value
+/// 2. Strips the `Basic ` prefix
+/// 3. Decodes the `base64` and splits it into `(username, password)`
+pub fn decode_authorization_basic_header(input: &str) -> Option<(String, String)> {
+	let encoded = input.strip_prefix("Basic ")?;
+
+	let bytes = STANDARD.decode(encoded.trim()).ok()?;
+	let mut decoded = String::from_utf8(bytes).ok()?;
+
+	let split = decoded
+		.split_once(':')
+		.map(|(user, pass)| (user.to_string(), pass.to_string()));
+	decoded.zeroize();
+
+	split
+}
+
 //---------------------------------------------------------------------------------------------------- TESTS
 #[cfg(test)]
 mod tests {
@@ -35,4 +53,16 @@ mod tests {
 
 		assert_eq!(encoded, EXPECTED);
 	}
+
+	#[test]
+	fn decode() {
+		const INPUT: &str = "Basic bXlfdXNlcm5hbWU6bXlfcGFzc3dvcmQ=";
+
+		let (user, pass) = decode_authorization_basic_header(INPUT).unwrap();
+		assert_eq!(user, "my_username");
+		assert_eq!(pass, "my_password");
+
+		assert!(decode_authorization_basic_header("bXlfdXNlcm5hbWU6bXlfcGFzc3dvcmQ=").is_none());
+		assert!(decode_authorization_basic_header("Basic !!!").is_none());
+	}
 }
@@ -0,0 +1,165 @@
+//---------------------------------------------------------------------------------------------------- Use
+use serde::{Serialize,Deserialize};
+use shukusai::collection::SongKey;
+use strum::{
+	AsRefStr,
+	Display,
+	EnumVariantNames,
+	IntoStaticStr,
+};
+use crate::{
+	impl_struct,
+	Method,
+};
+
+//---------------------------------------------------------------------------------------------------- Event data
+impl_struct! {
+	Song,
+	song_key:  Option<SongKey>,
+	queue_idx: Option<usize>,
+	runtime:   u32
+}
+impl_struct! {
+	Elapsed,
+	elapsed: u32,
+	runtime: u32
+}
+impl_struct! {
+	Playing,
+	playing: bool
+}
+impl_struct! {
+	Volume,
+	volume: u8
+}
+impl_struct! {
+	Repeat,
+	repeat: shukusai::audio::Repeat
+}
+impl_struct! {
+	Queue,
+	len:  usize,
+	keys: Vec<SongKey>
+}
+impl_struct! {
+	Playlists,
+	len:       usize,
+	playlists: Vec<String>
+}
+impl_struct! {
+	Reset,
+	resetting: bool,
+	percent:   f64,
+	phase:     shukusai::state::Phase,
+	specific:  String
+}
+impl_struct! {
+	Collection,
+	empty:        bool,
+	timestamp:    u64,
+	count_artist: u64,
+	count_album:  u64,
+	count_song:   u64,
+	count_art:    u64
+}
+
+//---------------------------------------------------------------------------------------------------- Event
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+#[derive(AsRefStr,Display,EnumVariantNames,IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case", tag = "event", content = "data")]
+/// Enum of all the events pushed by `festivald`'s `/event` endpoint
+///
+/// Each event is sent as a Server-Sent Event, where the `event` field is the
+/// `lower_snake_case` variant name (e.g, `song`), and the `data` field is the
+/// JSON of the inner struct, see [`Event::data`].
+pub enum Event {
+	/// The current `Song` changed
+	Song(Song),
+	/// The elapsed time of the current `Song` changed (once a second when playing, or after a seek)
+	Elapsed(Elapsed),
+	/// Audio was played or paused
+	Playing(Playing),
+	/// The volume changed
+	Volume(Volume),
+	/// The repeat mode changed
+	Repeat(Repeat),
+	/// The queue was modified
+	Queue(Queue),
+	/// A `Playlist` was created, removed or modified
+	Playlists(Playlists),
+	/// Progress of a `Collection` reset
+	Reset(Reset),
+	/// A new `Collection` is now being used
+	Collection(Collection),
+}
+
+impl Event {
+	/// Every [`Method`] returned by [`Event::method`].
+	pub const METHODS: [Method; 8] = [
+		Method::StateAudio,
+		Method::StateRuntime,
+		Method::StatePlaying,
+		Method::StateVolume,
+		Method::StateRepeat,
+		Method::StateQueueKey,
+		Method::PlaylistBrief,
+		Method::CollectionBrief,
+	];
+
+	/// The JSON-RPC [`Method`] that returns the same kind of data.
+	///
+	/// Clients that are not authorized only receive the
+	/// events whose method is in `festivald`'s `no_auth_rpc`.
+	pub const fn method(&self) -> Method {
+		match self {
+			Self::Song(_)       => Method::StateAudio,
+			Self::Elapsed(_)    => Method::StateRuntime,
+			Self::Playing(_)    => Method::StatePlaying,
+			Self::Volume(_)     => Method::StateVolume,
+			Self::Repeat(_)     => Method::StateRepeat,
+			Self::Queue(_)      => Method::StateQueueKey,
+			Self::Playlists(_)  => Method::PlaylistBrief,
+			Self::Reset(_) | Self::Collection(_) => Method::CollectionBrief,
+		}
+	}
+
+	/// The JSON of the inner struct.
+	pub fn data(&self) -> Result<String, serde_json::Error> {
+		match self {
+			Self::Song(x)       => serde_json::to_string(x),
+			Self::Elapsed(x)    => serde_json::to_string(x),
+			Self::Playing(x)    => serde_json::to_string(x),
+			Self::Volume(x)     => serde_json::to_string(x),
+			Self::Repeat(x)     => serde_json::to_string(x),
+			Self::Queue(x)      => serde_json::to_string(x),
+			Self::Playlists(x)  => serde_json::to_string(x),
+			Self::Reset(x)      => serde_json::to_string(x),
+			Self::Collection(x) => serde_json::to_string(x),
+		}
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn name() {
+		let e = Event::Volume(Volume { volume: 25 });
+		assert_eq!(e.as_ref(), "volume");
+		assert_eq!(e.data().unwrap(), r#"{"volume":25}"#);
+		assert_eq!(
+			serde_json::to_string(&e).unwrap(),
+			r#"{"event":"volume","data":{"volume":25}}"#,
+		);
+	}
+
+	#[test]
+	fn method() {
+		assert_eq!(Event::Playing(Playing { playing: true }).method(), Method::StatePlaying);
+		assert_eq!(Event::Queue(Queue { len: 0, keys: vec![] }).method(), Method::StateQueueKey);
+		assert!(Event::METHODS.contains(&Event::Playlists(Playlists { len: 0, playlists: vec![] }).method()));
+	}
+}
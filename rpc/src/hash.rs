@@ -1,5 +1,10 @@
 //---------------------------------------------------------------------------------------------------- Use
 use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use argon2::{
+	Argon2,PasswordHash,PasswordHasher,PasswordVerifier,
+	password_hash::{SaltString,rand_core::OsRng},
+};
 use rand::Rng;
 use zeroize::{ZeroizeOnDrop,Zeroize};
 use std::pin::Pin;
@@ -46,8 +51,92 @@ impl Hash {
 	}
 
 	/// Compare `self` with a hash of another `String`.
+	///
+	/// This comparison is constant-time.
 	pub fn same(&self, new_input: String) -> bool {
-		&self.hash == &Hash::hash(new_input, &self.salt)
+		self.hash == Hash::hash(new_input, &self.salt)
+	}
+
+	/// Encode into a `SALT$HASH` hex string, for storing on disk.
+	pub fn encode(&self) -> String {
+		format!("{}${}", hex::encode(&*self.salt.0), hex::encode(&*self.hash.0))
+	}
+
+	/// Decode a string created by [`Hash::encode`].
+	pub fn decode(s: &str) -> Option<Self> {
+		let (salt, hash) = s.trim().split_once('$')?;
+
+		Some(Self {
+			hash: PinBox::from_hex(hash)?,
+			salt: PinBox::from_hex(salt)?,
+		})
+	}
+}
+
+//---------------------------------------------------------------------------------------------------- Password
+/// A password hashed with `Argon2id`, for the `[users]` config.
+///
+/// Unlike [`Hash`], this is meant to be stored on disk,
+/// so it uses a slow key derivation function instead of `SHA256`.
+///
+/// The string is in the `PHC` format, e.g: `$argon2id$v=19$m=19456,t=2,p=1$SALT$HASH`.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Zeroize,ZeroizeOnDrop)]
+pub struct Password(String);
+
+#[cfg(not(debug_assertions))]
+impl std::fmt::Debug for Password {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+		write!(f, "crate::hash::Password")
+	}
+}
+
+impl Password {
+	/// Hash a password with a random salt.
+	pub fn new(mut input: String) -> Self {
+		let salt = SaltString::generate(&mut OsRng);
+
+		// SAFETY: this only fails on invalid parameters/salt,
+		// the defaults and a generated salt are always valid.
+		let phc = Argon2::default()
+			.hash_password(input.as_bytes(), &salt)
+			.expect("Argon2 default hashing failed")
+			.to_string();
+
+		input.zeroize();
+		Self(phc)
+	}
+
+	/// Compare `self` with another password.
+	///
+	/// This comparison is constant-time.
+	pub fn same(&self, mut input: String) -> bool {
+		// INVARIANT: validated in `decode()` or created by `new()`.
+		let Ok(hash) = PasswordHash::new(&self.0) else {
+			return false;
+		};
+
+		let same = Argon2::default().verify_password(input.as_bytes(), &hash).is_ok();
+		input.zeroize();
+		same
+	}
+
+	/// Encode into a `PHC` string, for storing on disk.
+	pub fn encode(&self) -> String {
+		self.0.clone()
+	}
+
+	/// Decode a string created by [`Password::encode`].
+	///
+	/// Only `Argon2` hashes with valid parameters are accepted.
+	pub fn decode(s: &str) -> Option<Self> {
+		let s = s.trim();
+		let hash = PasswordHash::new(s).ok()?;
+		argon2::Algorithm::new(hash.algorithm.as_str()).ok()?;
+		argon2::Params::try_from(&hash).ok()?;
+		hash.hash?;
+
+		Some(Self(s.to_string()))
 	}
 }
 
@@ -60,9 +149,17 @@ pub fn sha256(input: &str) -> String {
 }
 
 #[cfg_attr(debug_assertions, derive(Debug))]
-#[derive(Zeroize,ZeroizeOnDrop,PartialEq)]
+#[derive(Zeroize,ZeroizeOnDrop)]
 pub(super) struct PinBox(Pin<Box<[u8; LEN]>>);
 
+// Constant-time, so comparing hashes doesn't leak
+// how many leading bytes matched through timing.
+impl PartialEq for PinBox {
+	fn eq(&self, other: &Self) -> bool {
+		self.0[..].ct_eq(&other.0[..]).into()
+	}
+}
+
 impl PinBox {
 	fn zero() -> Self {
 		Self(Box::pin([0; LEN]))
@@ -71,6 +168,12 @@ impl PinBox {
 	fn rand() -> Self {
 		Self(Box::pin(rand::thread_rng().gen()))
 	}
+
+	fn from_hex(s: &str) -> Option<Self> {
+		let mut bytes = [0; LEN];
+		hex::decode_to_slice(s, &mut bytes).ok()?;
+		Some(Self(Box::pin(bytes)))
+	}
 }
 
 //---------------------------------------------------------------------------------------------------- TESTS
@@ -114,4 +217,47 @@ mod tests {
 			panic!(r#"!h2().same("h2".into())"#)
 		}
 	}
+
+	#[test]
+	fn encode_decode() {
+		let h = Hash::new("password".into());
+		let s = h.encode();
+		assert_eq!(s.len(), LEN * 4 + 1);
+
+		let d = Hash::decode(&s).unwrap();
+		assert_eq!(h, d);
+		assert!(d.same("password".into()));
+
+		assert!(Hash::decode("").is_none());
+		assert!(Hash::decode("00$11").is_none());
+		assert!(Hash::decode(&s.replace('$', "")).is_none());
+	}
+
+	#[test]
+	fn password() {
+		let p = Password::new("password".into());
+		assert!(p.same("password".into()));
+		assert!(!p.same("passwore".into()));
+		assert!(!p.same(String::new()));
+
+		// Random salt.
+		assert_ne!(p.encode(), Password::new("password".into()).encode());
+	}
+
+	#[test]
+	fn password_encode_decode() {
+		let p = Password::new("password".into());
+		let s = p.encode();
+		assert!(s.starts_with("$argon2id$"));
+
+		let d = Password::decode(&s).unwrap();
+		assert_eq!(s, d.encode());
+		assert!(d.same("password".into()));
+
+		// Old `SALT$HASH`, not a `PHC` string, or not `Argon2`.
+		assert!(Password::decode(&Hash::new("password".into()).encode()).is_none());
+		assert!(Password::decode("").is_none());
+		assert!(Password::decode("$argon2id$v=19$m=19456,t=2,p=1").is_none());
+		assert!(Password::decode("$pbkdf2-sha256$i=1000$c2FsdHNhbHQ$aGFzaGhhc2hoYXNoaGFzaA").is_none());
+	}
 }
@@ -13,6 +13,9 @@ pub mod resp;
 /// REST resources
 pub mod resource;
 
+/// Events pushed by the `/event` endpoint
+pub mod event;
+
 /// Base64 operations
 pub mod base64;
 /// Hashing operations
@@ -52,15 +52,18 @@ pub enum Method {
 	CollectionHealth,
 	CollectionResourceSize,
 
+	DaemonBroadcast,
 	DaemonConfig,
 	DaemonMethods,
 	DaemonNoAuthRpc,
 	DaemonNoAuthRest,
+	DaemonPermissions,
 	DaemonRemoveCache,
 	DaemonSave,
 	DaemonSeenIps,
 	DaemonShutdown,
 	DaemonState,
+	DaemonUser,
 
 	// State retrieval.
 	StateAudio,
@@ -100,6 +103,15 @@ pub enum Method {
 	MapAlbumSongs,
 	MapAlbumEntries,
 
+	// Genre
+	GenreList,
+	GenreAlbums,
+
+	// Stats
+	StatsSong,
+	StatsMostPlayed,
+	StatsRecentlyPlayed,
+
 	// Current (audio state)
 	CurrentArtist,
 	CurrentAlbum,
@@ -135,6 +147,7 @@ pub enum Method {
 	Volume,
 	VolumeUp,
 	VolumeDown,
+	Crossfade,
 
 	QueueAddKeyArtist,
 	QueueAddKeyAlbum,
@@ -147,6 +160,7 @@ pub enum Method {
 	QueueAddRandSong,
 	QueueAddRandEntry,
 	QueueAddPlaylist,
+	QueueAddGenre,
 	QueueSetIndex,
 	QueueRemoveRange,
 
@@ -162,9 +176,19 @@ pub enum Method {
 	PlaylistAddMapArtist,
 	PlaylistAddMapAlbum,
 	PlaylistAddMapSong,
+	PlaylistAddGenre,
 	PlaylistSingle,
 	PlaylistBrief,
 	PlaylistFull,
+	PlaylistSmartNew,
+	PlaylistSmartList,
+	PlaylistImport,
+	PlaylistExport,
+
+	// Tokens.
+	TokenNew,
+	TokenList,
+	TokenRevoke,
  }
 
 impl Method {
@@ -197,15 +221,18 @@ pub enum Rpc {
 	CollectionHealth(crate::param::CollectionHealth),
 	CollectionResourceSize(crate::param::CollectionResourceSize),
 
+	DaemonBroadcast(crate::param::DaemonBroadcast),
 	DaemonConfig(crate::param::DaemonConfig),
 	DaemonMethods(crate::param::DaemonMethods),
 	DaemonNoAuthRpc(crate::param::DaemonNoAuthRpc),
 	DaemonNoAuthRest(crate::param::DaemonNoAuthRest),
+	DaemonPermissions(crate::param::DaemonPermissions),
 	DaemonRemoveCache(crate::param::DaemonRemoveCache),
 	DaemonSave(crate::param::DaemonSave),
 	DaemonSeenIps(crate::param::DaemonSeenIps),
 	DaemonShutdown(crate::param::DaemonShutdown),
 	DaemonState(crate::param::DaemonState),
+	DaemonUser(crate::param::DaemonUser),
 
 	StateAudio(crate::param::StateAudio),
 	StateQueueKey(crate::param::StateQueueKey),
@@ -242,6 +269,13 @@ pub enum Rpc {
 	MapAlbumSongs(crate::param::MapAlbumSongsOwned),
 	MapAlbumEntries(crate::param::MapAlbumEntriesOwned),
 
+	GenreList(crate::param::GenreList),
+	GenreAlbums(crate::param::GenreAlbumsOwned),
+
+	StatsSong(crate::param::StatsSong),
+	StatsMostPlayed(crate::param::StatsMostPlayed),
+	StatsRecentlyPlayed(crate::param::StatsRecentlyPlayed),
+
 	CurrentArtist(crate::param::CurrentArtist),
 	CurrentAlbum(crate::param::CurrentAlbum),
 	CurrentSong(crate::param::CurrentSong),
@@ -273,6 +307,7 @@ pub enum Rpc {
 	Volume(crate::param::Volume),
 	VolumeUp(crate::param::VolumeUp),
 	VolumeDown(crate::param::VolumeDown),
+	Crossfade(crate::param::Crossfade),
 
 	QueueAddKeyArtist(crate::param::QueueAddKeyArtist),
 	QueueAddKeyAlbum(crate::param::QueueAddKeyAlbum),
@@ -285,6 +320,7 @@ pub enum Rpc {
 	QueueAddRandSong(crate::param::QueueAddRandSong),
 	QueueAddRandEntry(crate::param::QueueAddRandEntry),
 	QueueAddPlaylist(crate::param::QueueAddPlaylistOwned),
+	QueueAddGenre(crate::param::QueueAddGenreOwned),
 	QueueSetIndex(crate::param::QueueSetIndex),
 	QueueRemoveRange(crate::param::QueueRemoveRange),
 
@@ -299,9 +335,17 @@ pub enum Rpc {
 	PlaylistAddMapArtist(crate::param::PlaylistAddMapArtistOwned),
 	PlaylistAddMapAlbum(crate::param::PlaylistAddMapAlbumOwned),
 	PlaylistAddMapSong(crate::param::PlaylistAddMapSongOwned),
+	PlaylistAddGenre(crate::param::PlaylistAddGenreOwned),
 	PlaylistSingle(crate::param::PlaylistSingleOwned),
 	PlaylistBrief(crate::param::PlaylistBrief),
 	PlaylistFull(crate::param::PlaylistFull),
+	PlaylistSmartNew(crate::param::PlaylistSmartNewOwned),
+	PlaylistSmartList(crate::param::PlaylistSmartList),
+	PlaylistImport(crate::param::PlaylistImportOwned),
+	PlaylistExport(crate::param::PlaylistExportOwned),
+	TokenNew(crate::param::TokenNewOwned),
+	TokenList(crate::param::TokenList),
+	TokenRevoke(crate::param::TokenRevokeOwned),
 }
 
 //---------------------------------------------------------------------------------------------------- TESTS
@@ -10,6 +10,8 @@ use shukusai::{
 	collection::{SongKey,AlbumKey,ArtistKey},
 	audio::Append2,
 	search::SearchKind,
+	sort::SongSort,
+	state::{Rule,PlaylistFormat},
 };
 
 use crate::{
@@ -90,6 +92,11 @@ impl_rpc! {
 }
 
 //---------------------------------------------------------------------------------------------------- Daemon
+impl_rpc! {
+	"Retrieve the state of the live HTTP audio broadcast",
+	"daemon/daemon_broadcast",
+	DaemonBroadcast => Method::DaemonBroadcast
+}
 impl_rpc! {
 	"Retrieve the active configuration of `festivald`",
 	"daemon/daemon_config",
@@ -110,6 +117,11 @@ impl_rpc! {
 	"daemon/daemon_no_auth_rest",
 	DaemonNoAuthRest => Method::DaemonNoAuthRest
 }
+impl_rpc! {
+	"Retrieve the JSON-RPC methods and REST resources the caller is allowed to use",
+	"daemon/daemon_permissions",
+	DaemonPermissions => Method::DaemonPermissions
+}
 impl_rpc! {
 	"Remove `festivald` cache from disk",
 	"daemon/daemon_remove_cache",
@@ -135,6 +147,11 @@ impl_rpc! {
 	"daemon/daemon_state",
 	DaemonState => Method::DaemonState
 }
+impl_rpc! {
+	"Retrieve the user the caller is authenticated as, and their role",
+	"daemon/daemon_user",
+	DaemonUser => Method::DaemonUser
+}
 
 //---------------------------------------------------------------------------------------------------- State
 impl_rpc! {
@@ -219,7 +236,10 @@ impl_rpc_param! {
 	"key/key_artist_songs",
 	KeyArtistSongs => Method::KeyArtistSongs,
 	"Artist key (unsigned integer)",
-	key: usize
+	key: usize,
+	"Should Songs by other Artists that this Artist appears on be included?",
+	#[serde(default)]
+	appearances: bool
 }
 impl_rpc_param! {
 	"Input an Artist key, retrieve all their Songs in Entry form",
@@ -374,6 +394,43 @@ impl_rpc_param! {
 	album: String
 }
 
+//---------------------------------------------------------------------------------------------------- Genre
+impl_rpc! {
+	"Retrieve all genres",
+	"genre/genre_list",
+	GenreList => Method::GenreList
+}
+impl_struct_lt!(GenreAlbums, #[serde(borrow)] genre: Cow<'a, str>);
+impl_rpc_param! {
+	"Input a genre name (case-insensitive), retrieve all its Albums",
+	"genre/genre_albums",
+	GenreAlbumsOwned => Method::GenreAlbums,
+	"Genre name",
+	genre: String
+}
+
+//---------------------------------------------------------------------------------------------------- Stats
+impl_rpc_param! {
+	"Input a Song key, retrieve its play statistics",
+	"stats/stats_song",
+	StatsSong => Method::StatsSong,
+	"Song key (unsigned integer)",
+	key: usize
+}
+impl_rpc_param! {
+	"Retrieve the most played Songs",
+	"stats/stats_most_played",
+	StatsMostPlayed => Method::StatsMostPlayed,
+	"How many Songs should be returned? (all if omitted)",
+	len: Option<usize>
+}
+impl_rpc_param! {
+	"Retrieve the most recently played Songs",
+	"stats/stats_recently_played",
+	StatsRecentlyPlayed => Method::StatsRecentlyPlayed,
+	"How many Songs should be returned? (all if omitted)",
+	len: Option<usize>
+}
 
 //---------------------------------------------------------------------------------------------------- Current
 impl_rpc! {
@@ -425,7 +482,7 @@ impl_rpc_param! {
 	"Input a string, retrieve arrays of Artist's, Album's, and Song's, sorted by how similar their names/titles are to the input",
 	"search/search",
 	SearchOwned => Method::Search,
-	"The string to match against, to use as input",
+	"The string to match against, to use as input, may contain filters like `artist:radiohead year:>2000`",
 	input: String,
 	"Type of search",
 	#[arg(value_name = "all|sim60|sim70|sim80|top25|top5|top1")]
@@ -436,7 +493,7 @@ impl_rpc_param! {
 	"Input a string, retrieve an array of Artist's, sorted by how similar their names are to the input",
 	"search/search_artist",
 	SearchArtistOwned => Method::SearchArtist,
-	"The string to match against, to use as input",
+	"The string to match against, to use as input, may contain filters like `artist:radiohead year:>2000`",
 	input: String,
 	"Type of search",
 	#[arg(value_name = "all|sim60|sim70|sim80|top25|top5|top1")]
@@ -447,7 +504,7 @@ impl_rpc_param! {
 	"Input a string, retrieve an array of Album's, sorted by how similar their titles are to the input",
 	"search/search_album",
 	SearchAlbumOwned => Method::SearchAlbum,
-	"The string to match against, to use as input",
+	"The string to match against, to use as input, may contain filters like `artist:radiohead year:>2000`",
 	input: String,
 	"Type of search",
 	#[arg(value_name = "all|sim60|sim70|sim80|top25|top5|top1")]
@@ -458,7 +515,7 @@ impl_rpc_param! {
 	"Input a string, retrieve an array of Song's, sorted by how similar their titles are to the input",
 	"search/search_song",
 	SearchSongOwned => Method::SearchSong,
-	"The string to match against, to use as input",
+	"The string to match against, to use as input, may contain filters like `artist:radiohead year:>2000`",
 	input: String,
 	"Type of search",
 	#[arg(value_name = "all|sim60|sim70|sim80|top25|top5|top1")]
@@ -469,7 +526,7 @@ impl_rpc_param! {
 	"Input a string, retrieve an array of Song's (in Entry form), sorted by how similar their titles are to the input",
 	"search/search_entry",
 	SearchEntryOwned => Method::SearchEntry,
-	"The string to match against, to use as input",
+	"The string to match against, to use as input, may contain filters like `artist:radiohead year:>2000`",
 	input: String,
 	"Type of search",
 	#[arg(value_name = "all|sim60|sim70|sim80|top25|top5|top1")]
@@ -547,6 +604,17 @@ impl_rpc_param! {
 	#[arg(value_parser = clap::value_parser!(u8).range(0..=100))]
 	down: u8
 }
+impl_rpc_param! {
+	"Set the crossfade between Song's",
+	"playback/crossfade",
+	Crossfade => Method::Crossfade,
+	"How many seconds to crossfade for. 0 turns crossfading off, must be in-between 0..30.",
+	#[arg(value_parser = clap::value_parser!(u8).range(0..=30))]
+	seconds: u8,
+	"The volume curve used while crossfading",
+	#[arg(value_name = "linear|equal_power")]
+	curve: shukusai::audio::CrossfadeCurve
+}
 impl_rpc_param! {
 	"Clear the queue",
 	"playback/clear",
@@ -772,6 +840,25 @@ impl_rpc_param! {
 	"Should we start at an offset within the playlist?",
 	offset: Option<usize>
 }
+impl_struct_lt!(QueueAddGenre, #[serde(borrow)] genre: Cow<'a, str>, append: Append2, clear: bool, play: bool, index: Option<usize>, offset: Option<usize>);
+impl_rpc_param! {
+	"Add all the Songs of a genre to the queue",
+	"queue/queue_add_genre",
+	QueueAddGenreOwned => Method::QueueAddGenre,
+	"The name of the genre (case-insensitive)",
+	genre: String,
+	"In which way should we add to the queue?",
+	#[arg(value_name = "front|back|index")]
+	append: Append2,
+	"Should the queue be cleared before adding?",
+	clear: bool,
+	"Should we start playing?",
+	play: bool,
+	"If the `index` append option was picked, this will be index used",
+	index: Option<usize>,
+	"Should we start at an offset within the genre?",
+	offset: Option<usize>
+}
 impl_rpc_param! {
 	"Set the current Song to a queue index",
 	"queue/queue_set_index",
@@ -934,6 +1021,21 @@ impl_rpc_param! {
 	"If the `index` append option was picked, this will be index used",
 	index: Option<usize>
 }
+impl_struct_lt!(PlaylistAddGenre, #[serde(borrow)] playlist: Cow<'a, str>, genre: Cow<'a, str>, append: Append2, index: Option<usize>);
+impl_rpc_param! {
+	"Add all the songs of a genre to a playlist",
+	"playlist/playlist_add_genre",
+	PlaylistAddGenreOwned => Method::PlaylistAddGenre,
+	"The name of the playlist",
+	playlist: String,
+	"The name of the genre (case-insensitive)",
+	genre: String,
+	"In which way should we add to the queue?",
+	#[arg(value_name = "front|back|index")]
+	append: Append2,
+	"If the `index` append option was picked, this will be index used",
+	index: Option<usize>
+}
 impl_struct_lt!(PlaylistSingle, #[serde(borrow)] playlist: Cow<'a, str>);
 impl_rpc_param! {
 	"Retrieve a single playlist",
@@ -952,6 +1054,76 @@ impl_rpc! {
 	"playlist/playlist_full",
 	PlaylistFull => Method::PlaylistFull
 }
+impl_struct_lt!(PlaylistSmartNew, #[serde(borrow)] playlist: Cow<'a, str>, rules: Vec<Rule>, limit: Option<usize>, sort: Option<SongSort>);
+impl_rpc_param! {
+	"Create a smart playlist, its Songs are computed from rules",
+	"playlist/playlist_smart_new",
+	PlaylistSmartNewOwned => Method::PlaylistSmartNew,
+	"The name of the smart playlist",
+	playlist: String,
+	"All of these rules must match for a Song to be included, e.g: `genre:rock`, `release:2000..2010`",
+	#[arg(value_name = "FIELD:VALUE")]
+	rules: Vec<Rule>,
+	"The maximum amount of Songs",
+	limit: Option<usize>,
+	"The order of the Songs (`lexi` if omitted)",
+	sort: Option<SongSort>
+}
+impl_rpc! {
+	"Retrieve the rules of all smart playlists",
+	"playlist/playlist_smart_list",
+	PlaylistSmartList => Method::PlaylistSmartList
+}
+impl_struct_lt!(PlaylistImport, #[serde(borrow)] playlist: Cow<'a, str>, #[serde(borrow)] data: Cow<'a, str>, format: Option<PlaylistFormat>);
+impl_rpc_param! {
+	"Import the contents of a M3U/M3U8, XSPF or PLS playlist file (and replace the playlist if it exists)",
+	"playlist/playlist_import",
+	PlaylistImportOwned => Method::PlaylistImport,
+	"The name of the playlist to import into",
+	playlist: String,
+	"The contents of the playlist file",
+	data: String,
+	"The format of the file (`m3u8`, `xspf`, `pls`), detected if omitted",
+	format: Option<PlaylistFormat>
+}
+impl_struct_lt!(PlaylistExport, #[serde(borrow)] playlist: Cow<'a, str>, format: PlaylistFormat);
+impl_rpc_param! {
+	"Export a playlist as a M3U8, XSPF or PLS playlist file",
+	"playlist/playlist_export",
+	PlaylistExportOwned => Method::PlaylistExport,
+	"The name of the playlist",
+	playlist: String,
+	"The format of the file (`m3u8`, `xspf`, `pls`)",
+	format: PlaylistFormat
+}
+
+//---------------------------------------------------------------------------------------------------- Token
+impl_struct_lt!(TokenNew, #[serde(borrow)] label: Cow<'a, str>, methods: Vec<Method>, expire: Option<u64>);
+impl_rpc_param! {
+	"Create a bearer token that can only use some methods",
+	"token/token_new",
+	TokenNewOwned => Method::TokenNew,
+	"A label for the token, e.g: `kitchen-kiosk`",
+	label: String,
+	"The methods the token is allowed to use, e.g: `toggle`",
+	#[arg(value_name = "METHOD")]
+	methods: Vec<Method>,
+	"Seconds until the token expires (never if omitted)",
+	expire: Option<u64>
+}
+impl_rpc! {
+	"Retrieve all bearer tokens (without their secrets)",
+	"token/token_list",
+	TokenList => Method::TokenList
+}
+impl_struct_lt!(TokenRevoke, #[serde(borrow)] id: Cow<'a, str>);
+impl_rpc_param! {
+	"Revoke a bearer token",
+	"token/token_revoke",
+	TokenRevokeOwned => Method::TokenRevoke,
+	"The ID of the token",
+	id: String
+}
 
 //---------------------------------------------------------------------------------------------------- TESTS
 #[cfg(test)]
@@ -1011,8 +1183,10 @@ mod tests {
 
 	#[test]
 	fn key_artist_songs() {
-		t(KeyArtistSongs { key: usize::MAX }, r#"{"key":18446744073709551615}"#);
-		t(KeyArtistSongs { key: 0 }, r#"{"key":0}"#);
+		t(KeyArtistSongs { key: usize::MAX, appearances: true }, r#"{"key":18446744073709551615,"appearances":true}"#);
+		t(KeyArtistSongs { key: 0, appearances: false }, r#"{"key":0,"appearances":false}"#);
+		let d: KeyArtistSongs = serde_json::from_str(r#"{"key":0}"#).unwrap();
+		assert_eq!(d, KeyArtistSongs { key: 0, appearances: false });
 	}
 
 	#[test]
@@ -1115,6 +1289,30 @@ mod tests {
 		t(MapAlbumEntriesOwned { artist: "hello".into(), album: "hello2".into() }, r#"{"artist":"hello","album":"hello2"}"#);
 	}
 
+	//---------------------------------------------------------------------------------------------------- Genre
+	#[test]
+	fn genre_albums() {
+		t(GenreAlbumsOwned { genre: "hello".into() }, r#"{"genre":"hello"}"#);
+	}
+
+	//---------------------------------------------------------------------------------------------------- Stats
+	#[test]
+	fn stats_song() {
+		t(StatsSong { key: 0 }, r#"{"key":0}"#);
+	}
+
+	#[test]
+	fn stats_most_played() {
+		t(StatsMostPlayed { len: None }, r#"{"len":null}"#);
+		t(StatsMostPlayed { len: Some(10) }, r#"{"len":10}"#);
+	}
+
+	#[test]
+	fn stats_recently_played() {
+		t(StatsRecentlyPlayed { len: None }, r#"{"len":null}"#);
+		t(StatsRecentlyPlayed { len: Some(10) }, r#"{"len":10}"#);
+	}
+
 	//---------------------------------------------------------------------------------------------------- Search
 	#[test]
 	fn search() {
@@ -1169,6 +1367,12 @@ mod tests {
 		t(Volume { volume: 0 },       r#"{"volume":0}"#);
 	}
 
+	#[test]
+	fn crossfade() {
+		t(Crossfade { seconds: 0,  curve: shukusai::audio::CrossfadeCurve::Linear },     r#"{"seconds":0,"curve":"linear"}"#);
+		t(Crossfade { seconds: 30, curve: shukusai::audio::CrossfadeCurve::EqualPower }, r#"{"seconds":30,"curve":"equal_power"}"#);
+	}
+
 	#[test]
 	fn volume_up() {
 		t(VolumeUp { up: u8::MAX }, r#"{"up":255}"#);
@@ -1446,6 +1650,16 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn queue_add_genre() {
+		t(QueueAddGenreOwned { genre: "hello".into(), append: shukusai::audio::Append2::Back, clear: false, play: false, index: None, offset: None },
+			r#"{"genre":"hello","append":"back","clear":false,"play":false,"index":null,"offset":null}"#
+		);
+		t(QueueAddGenreOwned { genre: "hello".into(), append: shukusai::audio::Append2::Index, clear: true, play: true, index: Some(1), offset: Some(1) },
+			r#"{"genre":"hello","append":"index","clear":true,"play":true,"index":1,"offset":1}"#
+		);
+	}
+
 	#[test]
 	fn queue_set_index() {
 		t(QueueSetIndex { index: usize::MAX }, r#"{"index":18446744073709551615}"#);
@@ -1464,6 +1678,49 @@ mod tests {
 		t(PlaylistNewOwned { playlist: "hello".into() }, r#"{"playlist":"hello"}"#);
 	}
 
+	#[test]
+	fn playlist_smart_new() {
+		t(PlaylistSmartNewOwned { playlist: "hello".into(), rules: vec![], limit: None, sort: None },
+			r#"{"playlist":"hello","rules":[],"limit":null,"sort":null}"#
+		);
+		t(PlaylistSmartNewOwned {
+			playlist: "hello".into(),
+			rules: vec![Rule::Genre("rock".into()), Rule::Release { from: Some(2000), to: None }],
+			limit: Some(10),
+			sort: Some(SongSort::PlayCount),
+		},
+			r#"{"playlist":"hello","rules":[{"genre":"rock"},{"release":{"from":2000,"to":null}}],"limit":10,"sort":"play_count"}"#
+		);
+	}
+
+	#[test]
+	fn playlist_import() {
+		t(PlaylistImportOwned { playlist: "hello".into(), data: "/hello.flac\n".into(), format: None },
+			r#"{"playlist":"hello","data":"/hello.flac\n","format":null}"#
+		);
+		t(PlaylistImportOwned { playlist: "hello".into(), data: "".into(), format: Some(PlaylistFormat::Xspf) },
+			r#"{"playlist":"hello","data":"","format":"xspf"}"#
+		);
+	}
+
+	#[test]
+	fn playlist_export() {
+		t(PlaylistExportOwned { playlist: "hello".into(), format: PlaylistFormat::Pls }, r#"{"playlist":"hello","format":"pls"}"#);
+	}
+
+	#[test]
+	fn token_new() {
+		t(TokenNewOwned { label: "kiosk".into(), methods: vec![Method::Toggle, Method::Volume], expire: Some(3600) },
+			r#"{"label":"kiosk","methods":["toggle","volume"],"expire":3600}"#
+		);
+		t(TokenNewOwned { label: "kiosk".into(), methods: vec![], expire: None }, r#"{"label":"kiosk","methods":[],"expire":null}"#);
+	}
+
+	#[test]
+	fn token_revoke() {
+		t(TokenRevokeOwned { id: "0123456789abcdef".into() }, r#"{"id":"0123456789abcdef"}"#);
+	}
+
 	#[test]
 	fn playlist_remove() {
 		t(PlaylistRemoveOwned { playlist: "hello".into() }, r#"{"playlist":"hello"}"#);
@@ -1562,6 +1819,16 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn playlist_add_genre() {
+		t(PlaylistAddGenreOwned { playlist: "hello".into(), genre: "hello".into(), append: shukusai::audio::Append2::Back, index: None },
+			r#"{"playlist":"hello","genre":"hello","append":"back","index":null}"#
+		);
+		t(PlaylistAddGenreOwned { playlist: "hello".into(), genre: "hello".into(), append: shukusai::audio::Append2::Index, index: Some(0) },
+			r#"{"playlist":"hello","genre":"hello","append":"index","index":0}"#
+		);
+	}
+
 	#[test]
 	fn playlist_single() {
 		t(PlaylistSingleOwned { playlist: "hello".into() }, r#"{"playlist":"hello"}"#);
@@ -26,6 +26,8 @@ pub enum Resource {
 	Album,
 	Song,
 	Art,
+	Stream,
+	Transcode,
 }
 
 impl Resource {
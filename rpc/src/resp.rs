@@ -11,6 +11,7 @@ use shukusai::{
 			SongJson,
 			AlbumJson,
 			ArtistJson,
+			GenreJson,
 		},
 		SongKey,
 		AlbumKey,
@@ -29,12 +30,13 @@ use crate::{
 	impl_struct_anon_lt,
 };
 use std::{
-	net::Ipv4Addr,
+	net::IpAddr,
 	path::{Path,PathBuf},
 	borrow::Cow,
 	collections::{
 		VecDeque,
 		HashSet,
+		BTreeMap,
 		BTreeSet,
 	},
 };
@@ -121,7 +123,10 @@ impl_struct_lt! {
 	song_len: usize,
 	missing_len: usize,
 	#[serde(borrow)]
-	missing: Cow<'a, [shukusai::collection::EntryJson<'a>]>
+	missing: Cow<'a, [shukusai::collection::EntryJson<'a>]>,
+	path_metadata_len: usize,
+	#[serde(borrow)]
+	path_metadata: Cow<'a, [shukusai::collection::EntryJson<'a>]>
 }
 impl_struct! {
 	CollectionResourceSize,
@@ -132,12 +137,23 @@ impl_struct! {
 //---------------------------------------------------------------------------------------------------- Daemon
 impl_struct_lt! {
 	DaemonConfig,
-	ip:                  std::net::Ipv4Addr,
+	#[serde(borrow)]
+	ip:                  Cow<'a, [IpAddr]>,
 	port:                u16,
 	max_connections:     Option<u64>,
 	#[serde(borrow)]
-	exclusive_ips:       Option<Cow<'a, BTreeSet<Ipv4Addr>>>,
+	exclusive_ips:       Option<Cow<'a, BTreeSet<Cow<'a, str>>>>,
+	#[serde(borrow)]
+	trusted_proxies:     Option<Cow<'a, BTreeSet<Cow<'a, str>>>>,
+	#[serde(borrow)]
+	trusted_proxy_header: Cow<'a, str>,
+	#[serde(borrow)]
+	base_path:           Option<Cow<'a, str>>,
 	sleep_on_fail:       Option<u64>,
+	rate_limit_cheap:    Option<u32>,
+	rate_limit_expensive: Option<u32>,
+	lockout_attempts:    Option<u32>,
+	lockout_time:        u64,
 	#[serde(borrow)]
 	collection_paths:    Cow<'a, [PathBuf]>,
 	tls:                 bool,
@@ -164,6 +180,15 @@ impl_struct_lt! {
 	no_auth_rest:        Option<Cow<'a, BTreeSet<Cow<'a, str>>>>,
 	no_auth_docs:        bool
 }
+impl_struct_lt! {
+	DaemonBroadcast,
+	enabled:         bool,
+	#[serde(borrow)]
+	format:          Cow<'a, str>,
+	bitrate:         u16,
+	listeners:       u64,
+	total_listeners: u64
+}
 impl_struct_lt! {
 	DaemonMethods,
 	len: usize,
@@ -182,6 +207,15 @@ impl_struct_lt! {
 	#[serde(borrow)]
 	rest: Cow<'a, BTreeSet<Cow<'a, str>>>
 }
+impl_struct_lt! {
+	DaemonPermissions,
+	all: bool,
+	#[serde(borrow)]
+	rpc: Cow<'a, BTreeSet<Cow<'a, str>>>,
+	#[serde(borrow)]
+	rest: Cow<'a, BTreeSet<Cow<'a, str>>>,
+	docs: bool
+}
 impl_struct_lt! {
 	DaemonRemoveCacheInner,
 	#[serde(borrow)]
@@ -191,8 +225,9 @@ impl_struct_lt! {
 impl_struct_anon_lt!(DaemonRemoveCache, Cow<'a, [DaemonRemoveCacheInner<'a>]>);
 impl_struct! {
 	DaemonSeenIpsInner,
-	ip: std::net::Ipv4Addr,
-	count: u64
+	ip: IpAddr,
+	count: u64,
+	users: BTreeMap<String, u64>
 }
 impl_struct_anon_lt!(DaemonSeenIps, Cow<'a, [DaemonSeenIpsInner]>);
 impl_struct_lt! {
@@ -216,6 +251,8 @@ impl_struct_lt! {
 	docs:                bool,
 	direct_download:     bool,
 	authorization:       bool,
+	throttled:           Vec<String>,
+	locked:              Vec<IpAddr>,
 	#[serde(borrow)]
 	version: Cow<'a, str>,
 	#[serde(borrow)]
@@ -223,6 +260,16 @@ impl_struct_lt! {
 	#[serde(borrow)]
 	os: Cow<'a, str>
 }
+impl_struct_lt! {
+	DaemonUser,
+	authenticated: bool,
+	#[serde(borrow)]
+	user: Option<Cow<'a, str>>,
+	#[serde(borrow)]
+	role: Option<Cow<'a, str>>,
+	#[serde(borrow)]
+	token: Option<Cow<'a, str>>
+}
 
 //---------------------------------------------------------------------------------------------------- State
 impl_struct_lt! {
@@ -416,6 +463,49 @@ impl_struct_lt! {
 	entries: Cow<'a, [shukusai::collection::EntryJson<'a>]>
 }
 
+//---------------------------------------------------------------------------------------------------- Genre
+impl_struct_lt! {
+	GenreList,
+	len: usize,
+	#[serde(borrow)]
+	genres: Cow<'a, [GenreJson<'a>]>
+}
+impl_struct_lt! {
+	GenreAlbums,
+	len: usize,
+	#[serde(borrow)]
+	albums: Cow<'a, [AlbumJson<'a>]>
+}
+
+//---------------------------------------------------------------------------------------------------- Stats
+impl_struct! {
+	StatsSong,
+	play_count:   u64,
+	skip_count:   u64,
+	first_played: u64,
+	last_played:  u64,
+	listened:     u64
+}
+impl_struct! {
+	StatsSongInner,
+	key:          SongKey,
+	play_count:   u64,
+	skip_count:   u64,
+	first_played: u64,
+	last_played:  u64,
+	listened:     u64
+}
+impl_struct_lt! {
+	StatsMostPlayed,
+	len: usize,
+	songs: Cow<'a, [StatsSongInner]>
+}
+impl_struct_lt! {
+	StatsRecentlyPlayed,
+	len: usize,
+	songs: Cow<'a, [StatsSongInner]>
+}
+
 //---------------------------------------------------------------------------------------------------- Current
 impl_struct_lt! {
 	CurrentArtist,
@@ -539,6 +629,7 @@ impl_struct! {
 //impl_struct_anon!(QueueAddMapArtist, ());
 //impl_struct_anon!(QueueAddMapAlbum, ());
 //impl_struct_anon!(QueueAddMapSong, ());
+//impl_struct_anon!(QueueAddGenre, ());
 impl_struct_lt!(QueueAddRandArtist, #[serde(borrow)] artist: Cow<'a, ArtistJson<'a>>);
 impl_struct_lt!(QueueAddRandAlbum, #[serde(borrow)] album: Cow<'a, AlbumJson<'a>>);
 impl_struct_lt!(QueueAddRandSong, #[serde(borrow)] song: Cow<'a, SongJson<'a>>);
@@ -558,6 +649,7 @@ impl_struct!(PlaylistAddKeySong, existed: bool, old_len: usize, new_len: usize);
 impl_struct!(PlaylistAddMapArtist, existed: bool, old_len: usize, new_len: usize);
 impl_struct!(PlaylistAddMapAlbum, existed: bool, old_len: usize, new_len: usize);
 impl_struct!(PlaylistAddMapSong, existed: bool, old_len: usize, new_len: usize);
+impl_struct!(PlaylistAddGenre, existed: bool, old_len: usize, new_len: usize);
 impl_struct_lt! {
 	PlaylistSingle,
 	#[serde(borrow)]
@@ -584,6 +676,37 @@ impl_struct_lt! {
 	#[serde(borrow)]
 	playlists: Cow<'a, PlaylistsJson<'a>>
 }
+impl_struct!(PlaylistSmartNew, existed: bool, len: usize);
+impl_struct!(PlaylistSmartList, len: usize, playlists: shukusai::state::SmartPlaylists);
+impl_struct!(PlaylistImport, existed: bool, len: usize, valid_len: usize, invalid_len: usize);
+impl_struct_lt!(PlaylistExport, format: shukusai::state::PlaylistFormat, len: usize, #[serde(borrow)] data: Cow<'a, str>);
+
+//---------------------------------------------------------------------------------------------------- Token
+impl_struct_lt! {
+	TokenNew,
+	#[serde(borrow)]
+	id: Cow<'a, str>,
+	#[serde(borrow)]
+	label: Cow<'a, str>,
+	#[serde(borrow)]
+	token: Cow<'a, str>,
+	created: u64,
+	expires: Option<u64>,
+	methods: BTreeSet<crate::method::Method>
+}
+impl_struct_lt! {
+	TokenListInner,
+	#[serde(borrow)]
+	id: Cow<'a, str>,
+	#[serde(borrow)]
+	label: Cow<'a, str>,
+	created: u64,
+	expires: Option<u64>,
+	expired: bool,
+	methods: BTreeSet<crate::method::Method>
+}
+impl_struct_anon_lt!(TokenList, Vec<TokenListInner<'a>>);
+impl_struct!(TokenRevoke, existed: bool);
 
 //---------------------------------------------------------------------------------------------------- TESTS
 #[cfg(test)]
@@ -1,11 +1,18 @@
 //---------------------------------------------------------------------------------------------------- Use
 use crate::{
     audio::{
-        output::{AudioOutput, Output},
-        Append, AudioToKernel, KernelToAudio, Repeat, Seek, Volume,
+        backend::{self, OUTPUT_DEVICE, OUTPUT_NULL},
+        null::NullOutput,
+        output::{self, AudioOutput, AudioOutputError, Output, OutputOpen},
+        Append, AudioToKernel, Broadcast, Crossfade, Fade, KernelToAudio, Repeat, ReplayGain, Seek,
+        Volume,
+    },
+    collection::MapKey,
+    collection::{AlbumKey, ArtistKey, Collection, Song, SongKey},
+    state::{
+        AudioState, SongEnd, AUDIO_STATE, MEDIA_CONTROLS_RAISE, MEDIA_CONTROLS_SHOULD_EXIT, STATS,
+        VOLUME,
     },
-    collection::{AlbumKey, ArtistKey, Collection, SongKey},
-    state::{AudioState, AUDIO_STATE, MEDIA_CONTROLS_RAISE, MEDIA_CONTROLS_SHOULD_EXIT, VOLUME},
 };
 use anyhow::anyhow;
 use benri::{debug_panic, flip, log::*, sleep, sync::*};
@@ -18,9 +25,9 @@ use std::fs::File;
 use std::sync::atomic::AtomicU32;
 use std::time::Duration;
 use symphonia::core::{
-    audio::{AsAudioBufferRef, AudioBuffer, Signal},
+    audio::{AsAudioBufferRef, AudioBuffer, AudioBufferRef, Signal, SignalSpec},
     codecs::{Decoder, DecoderOptions},
-    formats::{FormatOptions, FormatReader},
+    formats::{FormatOptions, FormatReader, Packet},
     io::MediaSourceStream,
     meta::MetadataOptions,
     probe::Hint,
@@ -55,6 +62,14 @@ const RECV_TIMEOUT: Duration = Duration::from_millis(4);
 // they either are there or we break and continue with audio.
 const MSG_PROCESS_LIMIT: u8 = 6;
 
+// How many seconds before the end of the current
+// song should we open the next song in the queue?
+//
+// Opening a song (especially on slow disks or
+// network mounts) can take a while, this gives
+// us a head start so there's no gap at EOF.
+const NEXT_SECONDS: u64 = 5;
+
 /// When receiving a `Previous` signal, there is runtime
 /// threshold for the song to reach until we reset the
 /// current instead of actually going to the previous song.
@@ -71,26 +86,53 @@ pub const PREVIOUS_THRESHOLD_DEFAULT: u32 = 3;
 
 //---------------------------------------------------------------------------------------------------- Audio Init
 pub(crate) struct Audio {
-    // A handle to the audio output device.
-    output: AudioOutput,
-
-    // SOMEDAY:
-    // We should probably have a `next` AudioReader lined up and ready to go.
-    // There is currently no audible gap between `set()`'s but that may not
-    // be the case on a _really_ slow HDD.
-    //
-    // The audio sample buffer we hold may be enough to mask this, though.
-    //
-    // Also, straying away from the current `cursor + queue` design and
-    // having a `next` thing ready makes things more complicated, although,
-    // when the time comes to rewrite all of `Audio` and `AudioState` and
-    // not tangle them together so much, this should be considered.
+    // A handle to the audio output (device, file, etc).
+    output: Box<dyn Output>,
+    // The function that opens `output`, this is used to re-open
+    // it when the `SignalSpec` of the audio changes.
+    open: OutputOpen,
+    // Where played audio is also sent, if `BROADCAST` is set.
+    broadcast: Option<Broadcast>,
 
     // The current song.
     current: Option<AudioReader>,
+    // The next song, opened ahead of time.
+    //
+    // This gets opened when the current song is
+    // nearing its end, so that when it hits EOF
+    // we can swap it in without waiting on the disk
+    // and without flushing the leftover samples,
+    // i.e, gapless playback.
+    //
+    // It is only used if it still matches what
+    // `AudioState::peek_next()` returns at EOF,
+    // so any queue/repeat changes in-between
+    // will simply cause it to be thrown away.
+    next: Option<AudioReader>,
+    // The current crossfade settings.
+    crossfade: Crossfade,
+    // An in-progress crossfade from `current` into `next`.
+    //
+    // While this exists, `next` is being decoded
+    // and mixed into `current`'s samples, so `next`
+    // can no longer be used as a fresh reader.
+    fade: Option<Fade>,
+    // The current loudness normalization mode.
+    replay_gain: ReplayGain,
+    // The gain factor of the current song, as set in `output`.
+    //
+    // This is kept so that a re-opened `output` can be re-set.
+    gain: f32,
     // The existence of this field means we should
     // be seeking in the next loop iteration.
     seek: Option<symphonia::core::units::Time>,
+    // The song being listened to (for `STATS`)
+    // and how many seconds it has been played for.
+    //
+    // The start is only recorded after the first
+    // second actually played, so a restored or
+    // paused song doesn't count as being played.
+    listening: Option<(SongKey, u64)>,
 
     // A local copy of `AUDIO_STATE`.
     // This exists so we don't have to lock
@@ -119,6 +161,8 @@ pub(crate) struct Audio {
 // metadata needed to be held while
 // playing back a song.
 pub(crate) struct AudioReader {
+    // The song's key.
+    key: SongKey,
     // The current song.
     reader: Box<dyn FormatReader>,
     // The current song's decoder.
@@ -127,6 +171,82 @@ pub(crate) struct AudioReader {
     timebase: TimeBase,
     // Elapsed `Time`
     time: Time,
+    // A packet that was read ahead of time.
+    //
+    // A pre-opened `next` reader reads its first
+    // packet immediately so that the disk is touched
+    // before we actually need it, this gets used
+    // before calling `next_packet()` again.
+    packet: Option<Packet>,
+    // The range of the song within the file, as timestamps.
+    //
+    // This is only not the whole file for
+    // `Song`'s that were split from a CUE sheet.
+    start: u64,
+    end: Option<u64>,
+}
+
+impl AudioReader {
+    // Read the next packet within the song's range.
+    //
+    // Packets that end before `start` are skipped,
+    // and reaching `end` is the same as hitting EOF.
+    fn next_packet(
+        reader: &mut Box<dyn FormatReader>,
+        start: u64,
+        end: Option<u64>,
+    ) -> Result<Packet, symphonia::core::errors::Error> {
+        loop {
+            let packet = reader.next_packet()?;
+
+            if end.is_some_and(|end| packet.ts >= end) {
+                return Err(symphonia::core::errors::Error::IoError(
+                    std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "end of song"),
+                ));
+            }
+
+            if packet.ts + packet.dur > start {
+                return Ok(packet);
+            }
+        }
+    }
+
+    // How many frames to cut from the `(front, back)` of a decoded
+    // `packet` with `frames`, so only frames in `start..end` are played.
+    //
+    // Packets at the edges of a CUE song's range usually straddle
+    // `start`/`end`, the rest of the frames belong to the neighbouring songs.
+    //
+    // `None` if the whole packet is within the range.
+    fn trim(
+        packet: &Packet,
+        frames: usize,
+        start: u64,
+        end: Option<u64>,
+    ) -> Option<(usize, usize)> {
+        let dur = u128::from(packet.dur.max(1));
+        let to_frames =
+            |ts: u64| (u128::from(ts) * frames as u128 / dur).min(frames as u128) as usize;
+
+        let front = to_frames(start.saturating_sub(packet.ts));
+        let back = end.map_or(0, |end| {
+            to_frames((packet.ts + packet.dur).saturating_sub(end))
+        });
+
+        if front == 0 && back == 0 {
+            None
+        } else {
+            Some((front, back))
+        }
+    }
+
+    // Copy `decoded` with `(front, back)` frames cut off, see `trim()`.
+    fn trimmed(decoded: &AudioBufferRef<'_>, (front, back): (usize, usize)) -> AudioBuffer<f32> {
+        let mut buf = AudioBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+        decoded.convert(&mut buf);
+        buf.trim(front, back);
+        buf
+    }
 }
 
 impl Audio {
@@ -148,14 +268,33 @@ impl Audio {
         let weak = Arc::downgrade(&collection);
         drop(collection);
 
+        // The output the `Frontend` selected.
+        let (name, mut open) = match backend::find(backend::selected_output()) {
+            Some(o) => o,
+            None => {
+                debug_panic!("selected output is not registered");
+                (OUTPUT_DEVICE, AudioOutput::try_open as OutputOpen)
+            }
+        };
+
         // Loop until we can connect to an audio device.
         let mut tries = 0_usize;
         let output = loop {
-            match AudioOutput::dummy() {
+            match output::dummy(open) {
                 Ok(o) => {
-                    debug!("Audio Init [1/3] ... dummy output device");
+                    debug!("Audio Init [1/3] ... dummy output: {name}");
                     break o;
                 }
+                // There's no need to wait on a device if we
+                // can broadcast the audio instead, discard
+                // the local audio in real-time.
+                Err(e) if crate::audio::BROADCAST.get().is_some() => {
+                    warn!("Audio Init [1/3] ... output error: {e:?} ... broadcasting with the {OUTPUT_NULL} output");
+                    backend::select_output(OUTPUT_NULL);
+                    open = NullOutput::try_open;
+                    // INVARIANT: the `null` output can't fail.
+                    break output::dummy(open).unwrap();
+                }
                 Err(e) => {
                     if tries == 5 {
                         warn!("Audio Init [1/3] ... output device error: {e:?} ... will continue to retry every {RETRY_SECONDS} seconds, but will only log when we succeed");
@@ -167,6 +306,7 @@ impl Audio {
             }
             sleep!(RETRY_SECONDS);
         };
+        let broadcast = Broadcast::new();
 
         // Media Controls.
         let (to_audio, from_mc) = crossbeam::channel::unbounded::<souvlaki::MediaControlEvent>();
@@ -195,8 +335,16 @@ impl Audio {
         // Init data.
         let audio = Self {
             output,
+            open,
+            broadcast,
             current: None,
+            next: None,
+            crossfade: Crossfade::off(),
+            fade: None,
+            replay_gain: ReplayGain::new(),
+            gain: 1.0,
             seek: None,
+            listening: None,
             state,
             media_controls,
             from_mc,
@@ -295,22 +443,42 @@ impl Audio {
             }
 
             //------ Audio decoding & demuxing.
+            // Set if we should open the next song after this iteration.
+            let mut open_next = false;
+
             if let Some(audio_reader) = &mut self.current {
                 let AudioReader {
+                    key,
                     reader,
                     decoder,
                     timebase,
                     time,
+                    packet,
+                    start,
+                    end,
                 } = audio_reader;
 
                 //------ Audio seeking.
                 if let Some(seek) = self.seek.take() {
+                    // Any packet read ahead of time is now stale.
+                    *packet = None;
+
+                    // Cancel any crossfade, `next` was
+                    // partially decoded so it must be re-opened.
+                    if self.fade.take().is_some() {
+                        self.next = None;
+                    }
+
+                    // `seek` is relative to the start of the song,
+                    // which is not the start of the file for CUE songs.
+                    let in_file = timebase.calc_time(timebase.calc_timestamp(seek) + *start);
+
                     // Seeking a little bit before the requested
                     // prevents some stuttering.
                     if let Err(e) = reader.seek(
                         symphonia::core::formats::SeekMode::Coarse,
                         symphonia::core::formats::SeekTo::Time {
-                            time: seek,
+                            time: in_file,
                             track_id: None,
                         },
                     ) {
@@ -329,14 +497,18 @@ impl Audio {
                 }
 
                 // Decode and play the packets belonging to the selected track.
-                // Get the next packet from the format reader.
-                let packet = match reader.next_packet() {
+                // Get the next packet from the format reader
+                // (or the one that was read ahead of time).
+                let packet = match packet
+                    .take()
+                    .map_or_else(|| AudioReader::next_packet(reader, *start, *end), Ok)
+                {
                     Ok(packet) => packet,
                     // We're done playing audio.
                     // This "end of stream" error is currently the only way
                     // a FormatReader can indicate the media is complete.
                     Err(symphonia::core::errors::Error::IoError(_err)) => {
-                        self.skip(1, &mut AUDIO_STATE.write());
+                        self.eof(&mut AUDIO_STATE.write());
                         #[cfg(feature = "gui")]
                         gui_request_update();
                         continue;
@@ -350,6 +522,14 @@ impl Audio {
                 // Decode the packet into audio samples.
                 match decoder.decode(&packet) {
                     Ok(decoded) => {
+                        // Cut off the frames outside of a CUE song's range.
+                        let trimmed = AudioReader::trim(&packet, decoded.frames(), *start, *end)
+                            .map(|range| AudioReader::trimmed(&decoded, range));
+                        let decoded = match &trimmed {
+                            Some(buf) => buf.as_audio_buffer_ref(),
+                            None => decoded,
+                        };
+
                         // Get the audio buffer specification. This is a description of the decoded
                         // audio buffer's sample format and sample rate.
                         let spec = *decoded.spec();
@@ -359,13 +539,14 @@ impl Audio {
                         // decoder, but the length is not.
                         let duration = decoded.capacity() as u64;
 
-                        if spec != self.output.spec || duration != self.output.duration {
+                        if spec != self.output.spec() || duration != self.output.duration() {
                             // If the spec/duration is different, we must re-open a
                             // matching audio output device or audio will get weird.
-                            match AudioOutput::try_open(spec, duration) {
+                            match (self.open)(spec, duration) {
                                 Ok(o) => {
                                     self.output.flush();
                                     self.output = o;
+                                    self.output.set_gain(self.gain);
                                 }
 
                                 // And if we couldn't, pause playback.
@@ -392,7 +573,33 @@ impl Audio {
                         // we'll have to wait - around 0.05-0.08~ seconds per buffer.
                         //
                         // Resampling + Volume is applied within `write()`.
-                        if let Err(e) = self.output.write(decoded) {
+                        //
+                        // If we're crossfading, the `next` song's
+                        // samples get mixed in before writing.
+                        let fading = match (&mut self.fade, &mut self.next) {
+                            (Some(fade), Some(next)) => {
+                                Self::fade_fill(fade, next, decoded.frames())
+                            }
+                            _ => false,
+                        };
+                        let result = if fading {
+                            // SAFETY: `fading` is only `true` if `fade` exists.
+                            let fade = self.fade.as_mut().unwrap();
+                            Self::write(
+                                &mut self.output,
+                                &mut self.broadcast,
+                                self.gain,
+                                fade.mix(decoded).as_audio_buffer_ref(),
+                            )
+                        } else {
+                            // The fade failed, throw away the partially decoded `next`.
+                            if self.fade.take().is_some() {
+                                self.next = None;
+                            }
+                            Self::write(&mut self.output, &mut self.broadcast, self.gain, decoded)
+                        };
+
+                        if let Err(e) = result {
                             // Pause playback on write error.
                             self.state.playing = false;
                             AUDIO_STATE.write().playing = false;
@@ -401,12 +608,32 @@ impl Audio {
                         }
 
                         // Set runtime timestamp.
-                        let new_time = timebase.calc_time(packet.ts);
+                        let new_time = timebase.calc_time(packet.ts.saturating_sub(*start));
                         if time.seconds != new_time.seconds {
                             *time = new_time;
 
                             // Set state.
                             AUDIO_STATE.write().elapsed = Runtime::from(time.seconds);
+                            Self::listen(&mut self.listening, &self.collection);
+
+                            // If we're nearing the end, line up the next song.
+                            let runtime = u64::from(self.collection.songs[*key].runtime.inner());
+                            let crossfade = u64::from(self.crossfade.seconds());
+                            if self.next.is_none() {
+                                open_next = time.seconds + NEXT_SECONDS + crossfade >= runtime;
+                            } else if self.fade.is_none()
+                                && !self.crossfade.is_off()
+                                && time.seconds + crossfade >= runtime
+                            {
+                                self.fade = Self::fade_start(
+                                    *key,
+                                    spec,
+                                    self.crossfade,
+                                    self.replay_gain,
+                                    self.next.as_ref(),
+                                    &self.collection,
+                                );
+                            }
 
                             // Wake up the GUI thread.
                             #[cfg(feature = "gui")]
@@ -427,7 +654,7 @@ impl Audio {
                     }
                     // We're done playing audio.
                     Err(symphonia::core::errors::Error::IoError(_err)) => {
-                        self.skip(1, &mut AUDIO_STATE.write());
+                        self.eof(&mut AUDIO_STATE.write());
                         #[cfg(feature = "gui")]
                         gui_request_update();
                         continue;
@@ -436,6 +663,11 @@ impl Audio {
                 }
             }
 
+            //------ Open the next song ahead of time.
+            if open_next {
+                self.open_next();
+            }
+
             //------ End of `loop {}`.
         }
     }
@@ -455,6 +687,9 @@ impl Audio {
             // Audio settings.
             Repeat(r) => self.repeat(r),
             Volume(v) => self.volume(v),
+            Crossfade(c) => self.crossfade(c),
+            ReplayGain(r) => self.replay_gain(r),
+            Output(name) => self.output(&name),
 
             // Queue.
             QueueAddSong((s_key, append, clear, play)) => {
@@ -469,6 +704,9 @@ impl Audio {
             QueueAddPlaylist((p, append, clear, play, offset)) => {
                 self.queue_add_playlist(p, append, clear, play, offset)
             }
+            QueueAddGenre((g, append, clear, play, offset)) => {
+                self.queue_add_genre(g, append, clear, play, offset)
+            }
             Shuffle => self.shuffle(),
             Clear(play) => {
                 self.clear(play, &mut AUDIO_STATE.write());
@@ -488,7 +726,11 @@ impl Audio {
 
             // Collection.
             DropCollection => self.drop_collection(),
-            NewCollection(arc) => self.collection = arc,
+            NewCollection(arc) => {
+                self.next = None;
+                self.fade = None;
+                self.collection = arc;
+            }
         }
     }
 
@@ -607,6 +849,24 @@ impl Audio {
 
     // Convert a `SongKey` to a playable object.
     fn to_reader(&self, key: SongKey) -> Option<Box<dyn FormatReader>> {
+        match self.probe(key) {
+            Ok(reader) => Some(reader),
+            Err(e) => {
+                fail!(
+                    "Audio - {e} ... {}",
+                    self.collection.songs[key].path.display()
+                );
+                send!(self.to_kernel, AudioToKernel::PathError((key, e)));
+                None
+            }
+        }
+    }
+
+    // Open and probe the `SongKey`'s file.
+    //
+    // This does not report errors to `Kernel`,
+    // the caller is expected to handle that.
+    fn probe(&self, key: SongKey) -> Result<Box<dyn FormatReader>, anyhow::Error> {
         // Get `Song`.
         let song = &self.collection.songs[key];
         let path = &song.path;
@@ -625,35 +885,43 @@ impl Audio {
         // Open file.
         let file = match File::open(path) {
             Ok(f) => f,
-            Err(e) => {
-                fail!("Audio - PATH error: {e} ... {}", path.display());
-                send!(self.to_kernel, AudioToKernel::PathError((key, anyhow!(e))));
-                return None;
-            }
+            Err(e) => return Err(anyhow!("PATH error: {e}")),
         };
 
         // Attempt probe.
         let mss = MediaSourceStream::new(Box::new(file), Default::default());
         match symphonia::default::get_probe().format(&hint, mss, &format_opts, &metadata_opts) {
-            Ok(o) => Some(o.format),
-            Err(e) => {
-                fail!("Audio - Probe error: {e} ... {}", path.display());
-                send!(self.to_kernel, AudioToKernel::PathError((key, anyhow!(e))));
-                None
-            }
+            Ok(o) => Ok(o.format),
+            Err(e) => Err(anyhow!("Probe error: {e}")),
         }
     }
 
     // 1. Takes in a playable song from the above function
     // 2. Creates a decoder for the reader
-    // 3. Sets our current state with it
+    // 3. Returns an `AudioReader` ready to be played
     //
-    // This does nothing on error.
-    fn set_current(
-        &mut self,
-        reader: Box<dyn FormatReader>,
+    // If the song was split from a CUE sheet, this
+    // seeks to the start of it within the file.
+    fn new_reader(
+        song: &Song,
+        mut reader: Box<dyn FormatReader>,
         timebase: TimeBase,
-    ) -> Result<(), anyhow::Error> {
+    ) -> Result<AudioReader, anyhow::Error> {
+        let start = timebase.calc_timestamp(ms_to_time(song.start));
+        let end = song.end.map(|ms| timebase.calc_timestamp(ms_to_time(ms)));
+
+        if song.start != 0 {
+            if let Err(e) = reader.seek(
+                symphonia::core::formats::SeekMode::Accurate,
+                symphonia::core::formats::SeekTo::Time {
+                    time: ms_to_time(song.start),
+                    track_id: None,
+                },
+            ) {
+                return Err(anyhow!("Seek error: {e}"));
+            }
+        }
+
         // Select the first track with a known codec.
         let track = match reader
             .tracks()
@@ -672,43 +940,299 @@ impl Audio {
                 Err(e) => return Err(anyhow!(e)),
             };
 
-        self.current = Some(AudioReader {
+        Ok(AudioReader {
+            key: song.key,
             reader,
             decoder,
             timebase,
             time: Time::new(0, 0.0),
-        });
+            packet: None,
+            start,
+            end,
+        })
+    }
 
+    // Sets the song state after a new `AudioReader` is set.
+    fn set_state(&mut self, key: SongKey, state: &mut std::sync::RwLockWriteGuard<'_, AudioState>) {
+        self.stats_end(SongEnd::Skipped);
+        self.listening = Some((key, 0));
+        state.song = Some(key);
+        state.elapsed = Runtime::zero();
+        state.runtime = self.collection.songs[key].runtime;
+        self.gain = Self::song_gain(self.replay_gain, key, &self.collection);
+        self.output.set_gain(self.gain);
+        #[cfg(feature = "gui")]
+        gui_request_update();
+        self.set_media_controls_metadata(key);
+    }
+
+    // Another second of the current song was played.
+    //
+    // This doesn't take `&mut self` since `current`
+    // is usually borrowed while playing.
+    fn listen(listening: &mut Option<(SongKey, u64)>, collection: &Arc<Collection>) {
+        if let Some((key, seconds)) = listening {
+            if *seconds == 0 {
+                STATS
+                    .write()
+                    .start(MapKey::from_song_key(*key, collection), benri::unix!());
+            }
+            *seconds += 1;
+        }
+    }
+
+    // The current song ended, record it in `STATS`
+    // if it was actually played for at least a second.
+    fn stats_end(&mut self, end: SongEnd) {
+        if let Some((key, seconds)) = self.listening.take() {
+            if seconds != 0 {
+                trace!("Audio - stats_end({key:?}, {seconds}, {end:?})");
+                STATS
+                    .write()
+                    .end(MapKey::from_song_key(key, &self.collection), seconds, end);
+            }
+        }
+    }
+
+    // Write samples to the audio output and
+    // then to the broadcast (if there is one).
+    //
+    // This doesn't take `&mut self` since `current`
+    // is usually borrowed while writing.
+    fn write(
+        output: &mut Box<dyn Output>,
+        broadcast: &mut Option<Broadcast>,
+        gain: f32,
+        buf: symphonia::core::audio::AudioBufferRef<'_>,
+    ) -> Result<(), AudioOutputError> {
+        output.write(buf.clone())?;
+        if let Some(broadcast) = broadcast {
+            broadcast.write(buf, gain);
+        }
         Ok(())
     }
 
-    // Convenience function that combines the above 2 functions.
+    // The gain factor to apply to this song in this mode.
+    fn song_gain(replay_gain: ReplayGain, key: SongKey, collection: &Collection) -> f32 {
+        let song = &collection.songs[key];
+        replay_gain.factor(song, &collection.albums[song.album])
+    }
+
+    // Convenience function that combines the above functions.
     // Does nothing on error.
+    //
+    // If the `next` song is already opened and
+    // it is the same key, it will be used instead.
     fn set(&mut self, key: SongKey, state: &mut std::sync::RwLockWriteGuard<'_, AudioState>) {
-        if let Some(reader) = self.to_reader(key) {
-            // Discard any leftover audio samples.
-            self.output.flush();
+        // Discard any leftover audio samples.
+        self.output.flush();
 
-            if self
-                .set_current(
-                    reader,
-                    TimeBase::new(1, self.collection.songs[key].sample_rate),
-                )
-                .is_ok()
-            {
-                // Set song state.
-                state.song = Some(key);
-                state.elapsed = Runtime::zero();
-                state.runtime = self.collection.songs[key].runtime;
-                #[cfg(feature = "gui")]
-                gui_request_update();
-                self.set_media_controls_metadata(key);
+        // A cancelled crossfade leaves `next` partially decoded.
+        if self.fade.take().is_some() {
+            self.next = None;
+        }
+
+        if let Some(next) = self.next.take() {
+            if next.key == key {
+                trace!("Audio - set({key:?}), using pre-opened reader");
+                self.current = Some(next);
+                self.set_state(key, state);
+                return;
+            }
+        }
+
+        if let Some(reader) = self.to_reader(key) {
+            let timebase = TimeBase::new(1, self.collection.songs[key].sample_rate);
+            if let Ok(reader) = Self::new_reader(&self.collection.songs[key], reader, timebase) {
+                self.current = Some(reader);
+                self.set_state(key, state);
             }
         } else {
             self.clear(false, state);
         }
     }
 
+    // Opens the song that will be played after the current
+    // one and stores it in `self.next`, then reads its first
+    // packet so that it is ready to go when we hit EOF.
+    //
+    // Errors are only logged, `set()` will
+    // report them if they happen again at EOF.
+    fn open_next(&mut self) {
+        let Some((key, queue_idx)) = AUDIO_STATE.read().peek_next() else {
+            return;
+        };
+
+        trace!("Audio - open_next({key:?}, {queue_idx})");
+
+        let timebase = TimeBase::new(1, self.collection.songs[key].sample_rate);
+        let mut reader = match self
+            .probe(key)
+            .and_then(|reader| Self::new_reader(&self.collection.songs[key], reader, timebase))
+        {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Audio - open_next({key:?}) failed: {e}");
+                return;
+            }
+        };
+
+        match AudioReader::next_packet(&mut reader.reader, reader.start, reader.end) {
+            Ok(packet) => reader.packet = Some(packet),
+            Err(e) => {
+                warn!("Audio - open_next({key:?}) failed to read packet: {e}");
+                return;
+            }
+        }
+
+        self.next = Some(reader);
+    }
+
+    // The current song hit EOF.
+    //
+    // If the `next` song was opened ahead of time and it
+    // still matches what should be played next, swap it
+    // in without flushing the leftover audio samples (gapless).
+    //
+    // Else, fallback to a normal `skip()`.
+    //
+    // If we were crossfading into it, the leftover mixed
+    // samples get written and playback continues from
+    // wherever the fade left off in the `next` song.
+    fn eof(&mut self, state: &mut std::sync::RwLockWriteGuard<'_, AudioState>) {
+        self.stats_end(SongEnd::Finished);
+
+        let next = self.next.take();
+        let fade = self.fade.take();
+
+        if let (Some(next), Some((key, queue_idx))) = (next, state.peek_next()) {
+            if next.key == key && !wrap_pause(state, queue_idx) {
+                trace!("Audio - eof(), gapless: {key:?}, queue_idx: {queue_idx}");
+                let elapsed = next.time.seconds;
+
+                // The leftover crossfade samples are scaled relative
+                // to the current song's gain, so they must be written
+                // before `set_state()` switches to the next song's gain.
+                let faded = if let Some(mut fade) = fade {
+                    trace!("Audio - eof(), finishing crossfade at {elapsed}s");
+                    let (output, broadcast, gain) =
+                        (&mut self.output, &mut self.broadcast, self.gain);
+                    fade.drain(output.duration(), |buf| {
+                        if let Err(e) =
+                            Self::write(output, broadcast, gain, buf.as_audio_buffer_ref())
+                        {
+                            warn!("Audio - Couldn't write crossfade samples: {e:?}");
+                        }
+                    });
+                    true
+                } else {
+                    false
+                };
+
+                state.queue_idx = Some(queue_idx);
+                self.current = Some(next);
+                self.set_state(key, state);
+                if faded {
+                    state.elapsed = Runtime::from(elapsed);
+                }
+                return;
+            }
+        }
+
+        self.skip(1, state);
+    }
+
+    // Start crossfading from the current song into `next`.
+    //
+    // Returns `None` if we shouldn't:
+    //   - there is no `next`
+    //   - both songs are in the same album (keep gapless albums gapless)
+    //   - the songs have different sample rates/channels
+    //   - `next` is stale or `Repeat::QueuePause` will pause on it
+    fn fade_start(
+        key: SongKey,
+        spec: SignalSpec,
+        crossfade: Crossfade,
+        replay_gain: ReplayGain,
+        next: Option<&AudioReader>,
+        collection: &Collection,
+    ) -> Option<Fade> {
+        let next = next?;
+
+        if collection.songs[key].album == collection.songs[next.key].album {
+            return None;
+        }
+
+        let params = next.decoder.codec_params();
+        if params.sample_rate != Some(spec.rate) || params.channels != Some(spec.channels) {
+            trace!("Audio - fade_start(), spec mismatch, skipping crossfade");
+            return None;
+        }
+
+        let state = AUDIO_STATE.read();
+        match state.peek_next() {
+            Some((k, queue_idx)) if k == next.key && !wrap_pause(&state, queue_idx) => {
+                trace!("Audio - fade_start({key:?} -> {k:?}), {crossfade:?}");
+                // `output` applies the current song's gain to
+                // the mixed samples, so the next song's samples
+                // are scaled relative to it.
+                let gain = Self::song_gain(replay_gain, next.key, collection)
+                    / Self::song_gain(replay_gain, key, collection);
+                Some(Fade::new(crossfade, spec, gain))
+            }
+            _ => None,
+        }
+    }
+
+    // Decode enough of `next` into the `fade` to cover `frames`.
+    //
+    // Returns `false` if the `next` song couldn't be
+    // decoded (or ended), in which case the fade is cancelled.
+    fn fade_fill(fade: &mut Fade, next: &mut AudioReader, frames: usize) -> bool {
+        while fade.needs(frames) {
+            let packet = match next.packet.take().map_or_else(
+                || AudioReader::next_packet(&mut next.reader, next.start, next.end),
+                Ok,
+            ) {
+                Ok(p) => p,
+                Err(e) => {
+                    warn!(
+                        "Audio - fade_fill({:?}) failed to read packet: {e}",
+                        next.key
+                    );
+                    return false;
+                }
+            };
+
+            match next.decoder.decode(&packet) {
+                Ok(decoded) => {
+                    let trimmed =
+                        AudioReader::trim(&packet, decoded.frames(), next.start, next.end)
+                            .map(|range| AudioReader::trimmed(&decoded, range));
+                    let decoded = match &trimmed {
+                        Some(buf) => buf.as_audio_buffer_ref(),
+                        None => decoded,
+                    };
+
+                    if !fade.push(decoded) {
+                        return false;
+                    }
+                }
+                Err(symphonia::core::errors::Error::DecodeError(_)) => continue,
+                Err(e) => {
+                    warn!("Audio - fade_fill({:?}) failed to decode: {e}", next.key);
+                    return false;
+                }
+            }
+
+            next.time = next
+                .timebase
+                .calc_time(packet.ts.saturating_sub(next.start));
+        }
+
+        true
+    }
+
     // Clears the `Queue`.
     //
     // The `bool` represents if we should
@@ -732,9 +1256,12 @@ impl Audio {
         self.state.playing = keep_playing;
 
         if !keep_playing {
+            self.stats_end(SongEnd::Stopped);
             state.finish();
             self.seek = None;
             self.current = None;
+            self.next = None;
+            self.fade = None;
             if let Some(media_controls) = &mut self.media_controls {
                 if let Err(e) = media_controls.set_playback(souvlaki::MediaPlayback::Stopped) {
                     warn!("Audio - Couldn't update souvlaki playback: {e:#?}");
@@ -829,6 +1356,7 @@ impl Audio {
                         }
                     } else {
                         trace!("Audio - no songs left, calling state.finish()");
+                        self.stats_end(SongEnd::Skipped);
                         state.finish();
                         self.state.finish();
                         self.current = None;
@@ -862,6 +1390,7 @@ impl Audio {
                 state.queue_idx = Some(new_index);
             } else {
                 trace!("Audio - skip({new_index}) > {len}, calling state.finish()");
+                self.stats_end(SongEnd::Skipped);
                 state.finish();
                 self.state.finish();
                 self.current = None;
@@ -991,6 +1520,58 @@ impl Audio {
         AUDIO_STATE.write().repeat = repeat;
     }
 
+    fn crossfade(&mut self, crossfade: Crossfade) {
+        trace!("Audio - {crossfade:?}");
+        self.crossfade = crossfade;
+
+        // Cancel any crossfade in progress.
+        if self.fade.take().is_some() {
+            self.next = None;
+        }
+    }
+
+    fn replay_gain(&mut self, replay_gain: ReplayGain) {
+        trace!("Audio - {replay_gain:?}");
+        self.replay_gain = replay_gain;
+
+        // Cancel any crossfade in progress,
+        // its samples were scaled with the old mode.
+        if self.fade.take().is_some() {
+            self.next = None;
+        }
+
+        // Apply to the current song immediately.
+        self.gain = match &self.current {
+            Some(current) => Self::song_gain(replay_gain, current.key, &self.collection),
+            None => 1.0,
+        };
+        self.output.set_gain(self.gain);
+    }
+
+    fn output(&mut self, name: &str) {
+        trace!("Audio - output({name})");
+
+        let Some((name, open)) = backend::find(name) else {
+            warn!("Audio - unknown output: {name}");
+            return;
+        };
+
+        match open(self.output.spec(), self.output.duration()) {
+            Ok(mut output) => {
+                self.output.flush();
+                output.set_gain(self.gain);
+                if self.state.playing {
+                    let _ = output.play();
+                }
+                self.output = output;
+                self.open = open;
+                backend::select_output(name);
+                debug!("Audio - switched to output: {name}");
+            }
+            Err(e) => send!(self.to_kernel, AudioToKernel::DeviceError(e.into_anyhow())),
+        }
+    }
+
     fn volume(&mut self, volume: Volume) {
         trace!("Audio - {volume:?}");
         atomic_store!(VOLUME, volume.inner());
@@ -1222,6 +1803,65 @@ impl Audio {
         }
     }
 
+    fn queue_add_genre(
+        &mut self,
+        genre: Arc<str>,
+        append: Append,
+        clear: bool,
+        play: bool,
+        offset: usize,
+    ) {
+        trace!("Audio - queue_add_genre({genre}, {append:?}, {clear}, {play}, {offset}");
+
+        // `Genre`'s always have at least 1 `Song`.
+        let keys = match self.collection.genre(&genre) {
+            Some(g) => g.songs.clone(),
+            None => {
+                trace!("Audio - {genre} doesn't exist, skipping");
+                return;
+            }
+        };
+
+        let mut state = AUDIO_STATE.write();
+
+        if clear {
+            self.clear(play, &mut state)
+        }
+
+        // Prevent bad offsets panicking.
+        let offset = if offset >= keys.len() { 0 } else { offset };
+
+        let iter = keys.iter();
+        match append {
+            Append::Back => {
+                iter.for_each(|k| state.queue.push_back(*k));
+                if self.current.is_none() {
+                    state.queue_idx = Some(offset);
+                    self.set(keys[offset], &mut state);
+                }
+            }
+            Append::Front => {
+                iter.rev().for_each(|k| state.queue.push_front(*k));
+                state.queue_idx = Some(offset);
+                self.set(keys[offset], &mut state);
+            }
+            Append::Index(mut i) => {
+                iter.for_each(|k| {
+                    state.queue.insert(i, *k);
+                    i += 1;
+                });
+                if i == 0 {
+                    state.queue_idx = Some(0);
+                    self.set(keys[offset], &mut state);
+                }
+            }
+        }
+
+        if !clear && play {
+            self.inner_play(&mut state);
+        }
+    }
+
     fn queue_set_index(&mut self, index: usize) {
         let mut state = AUDIO_STATE.write();
 
@@ -1379,9 +2019,16 @@ impl Audio {
 
     //-------------------------------------------------- Collection.
     fn drop_collection(&mut self) {
+        // The key won't be valid in the new `Collection`.
+        self.stats_end(SongEnd::Stopped);
+
         // Drop pointer.
         self.collection = Collection::dummy();
 
+        // The keys this holds may not be valid after this.
+        self.next = None;
+        self.fade = None;
+
         // Hang until we get the new one.
         debug!("Audio - Dropped Collection, waiting...");
 
@@ -1398,6 +2045,7 @@ impl Audio {
                     if self.state.song.is_none() {
                         self.current = None;
                     }
+                    self.listening = self.state.song.map(|key| (key, 0));
 
                     return;
                 }
@@ -1410,6 +2058,18 @@ impl Audio {
     }
 }
 
+//---------------------------------------------------------------------------------------------------- Free functions
+// `Repeat::QueuePause` must pause after wrapping around
+// to `queue_idx`, so it can't be played gaplessly.
+fn wrap_pause(state: &AudioState, queue_idx: usize) -> bool {
+    state.repeat == Repeat::QueuePause && state.queue_idx.map_or(false, |i| queue_idx <= i)
+}
+
+// `Song` offsets (milliseconds) -> `Time`.
+fn ms_to_time(ms: u64) -> Time {
+    Time::new(ms / 1000, (ms % 1000) as f64 / 1000.0)
+}
+
 //---------------------------------------------------------------------------------------------------- TESTS
 //#[cfg(test)]
 //mod tests {
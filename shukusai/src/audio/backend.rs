@@ -0,0 +1,118 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::audio::null::NullOutput;
+use crate::audio::output::{AudioOutput, OutputOpen};
+use crate::audio::wav::WavOutput;
+use benri::sync::*;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+//---------------------------------------------------------------------------------------------------- Constants
+#[cfg(target_os = "linux")]
+/// The name of the built-in audio device [`crate::audio::Output`].
+///
+/// This is `pulseaudio` on Linux and `cpal` on Windows/macOS.
+pub const OUTPUT_DEVICE: &str = "pulseaudio";
+#[cfg(not(target_os = "linux"))]
+/// The name of the built-in audio device [`crate::audio::Output`].
+///
+/// This is `pulseaudio` on Linux and `cpal` on Windows/macOS.
+pub const OUTPUT_DEVICE: &str = "cpal";
+
+/// The name of the built-in [`crate::audio::Output`] that discards all audio in real-time.
+///
+/// Useful for headless servers and CI.
+pub const OUTPUT_NULL: &str = "null";
+
+/// The name of the built-in [`crate::audio::Output`] that writes
+/// everything played into a WAV file in real-time.
+///
+/// See [`crate::audio::set_wav_path`].
+pub const OUTPUT_WAV: &str = "wav";
+
+/// The names of all the built-in [`crate::audio::Output`]'s.
+pub const OUTPUTS: [&str; 3] = [OUTPUT_DEVICE, OUTPUT_NULL, OUTPUT_WAV];
+
+//---------------------------------------------------------------------------------------------------- Registry
+// Every registered output, built-ins first.
+static REGISTRY: Lazy<RwLock<Vec<(&'static str, OutputOpen)>>> = Lazy::new(|| {
+    RwLock::new(vec![
+        (OUTPUT_DEVICE, AudioOutput::try_open as OutputOpen),
+        (OUTPUT_NULL, NullOutput::try_open as OutputOpen),
+        (OUTPUT_WAV, WavOutput::try_open as OutputOpen),
+    ])
+});
+
+// The output `Audio` uses.
+static SELECTED: RwLock<&'static str> = RwLock::new(OUTPUT_DEVICE);
+
+/// Register a custom [`crate::audio::Output`] under `name`.
+///
+/// It can then be selected with [`select_output`] or
+/// [`crate::kernel::FrontendToKernel::Output`].
+///
+/// Returns `false` (and does nothing) if `name` is already taken.
+pub fn register_output(name: &'static str, open: OutputOpen) -> bool {
+    let mut registry = lockw!(REGISTRY);
+
+    if registry.iter().any(|(n, _)| *n == name) {
+        return false;
+    }
+
+    registry.push((name, open));
+    true
+}
+
+/// Returns the names of all registered [`crate::audio::Output`]'s, built-ins first.
+pub fn outputs() -> Vec<&'static str> {
+    lockr!(REGISTRY).iter().map(|(n, _)| *n).collect()
+}
+
+/// Select the [`crate::audio::Output`] named `name`.
+///
+/// Call this _before_ [`crate::kernel::Kernel::spawn`] to set the output `Audio`
+/// starts with, use [`crate::kernel::FrontendToKernel::Output`] to switch while running.
+///
+/// Returns `false` (and does nothing) if no output named `name` is registered.
+pub fn select_output(name: &str) -> bool {
+    match find(name) {
+        Some((name, _)) => {
+            *lockw!(SELECTED) = name;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the name of the selected [`crate::audio::Output`].
+pub fn selected_output() -> &'static str {
+    *lockr!(SELECTED)
+}
+
+// Find an output by name.
+pub(crate) fn find(name: &str) -> Option<(&'static str, OutputOpen)> {
+    lockr!(REGISTRY).iter().find(|(n, _)| *n == name).copied()
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Asserts built-ins exist and names can't be registered twice.
+    fn register() {
+        assert_eq!(&outputs()[..3], OUTPUTS);
+        assert!(!register_output(OUTPUT_NULL, NullOutput::try_open));
+        assert!(register_output("test", NullOutput::try_open));
+        assert!(find("test").is_some());
+
+        // The selected output is global, put it back for other tests.
+        let selected = selected_output();
+        assert!(select_output("test"));
+        assert_eq!(selected_output(), "test");
+        assert!(!select_output("not registered"));
+        assert_eq!(selected_output(), "test");
+        assert!(select_output(selected));
+        assert_eq!(selected_output(), selected);
+    }
+}
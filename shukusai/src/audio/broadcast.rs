@@ -0,0 +1,87 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::audio::Volume;
+use crate::state::VOLUME;
+use benri::atomic_load;
+use crossbeam::channel::{Sender, TrySendError};
+use log::{trace, warn};
+use once_cell::sync::OnceCell;
+use symphonia::core::audio::{AudioBufferRef, SampleBuffer, Signal};
+
+//---------------------------------------------------------------------------------------------------- BroadcastPcm
+#[derive(Clone, Debug, PartialEq)]
+/// A chunk of decoded audio sent to [`BROADCAST`].
+///
+/// This is exactly what is being played, i.e, the
+/// volume, `ReplayGain` and crossfade have been applied.
+pub struct BroadcastPcm {
+    /// The sample rate.
+    pub rate: u32,
+    /// The amount of channels.
+    pub channels: u16,
+    /// Interleaved `f32` samples.
+    pub samples: Box<[f32]>,
+}
+
+/// Where `Audio` sends all played audio.
+///
+/// If a `Frontend` wants to broadcast audio somewhere
+/// (e.g, `festivald`'s HTTP stream), it should set
+/// this _before_ calling [`crate::kernel::Kernel::spawn`].
+///
+/// If this is set, `Audio` will fallback to the `null`
+/// [`crate::audio::Output`] if the selected one can't be
+/// opened, so that it continues to play (and send audio here).
+///
+/// If the channel is full, audio is dropped instead of
+/// blocking, so the receiver should keep up.
+pub static BROADCAST: OnceCell<Sender<BroadcastPcm>> = OnceCell::new();
+
+//---------------------------------------------------------------------------------------------------- Broadcast
+// `Audio`'s handle to `BROADCAST`.
+pub(crate) struct Broadcast {
+    to: &'static Sender<BroadcastPcm>,
+    // Re-used interleaving buffer.
+    buf: Option<SampleBuffer<f32>>,
+}
+
+impl Broadcast {
+    // Returns `None` if `BROADCAST` was not set.
+    pub(crate) fn new() -> Option<Self> {
+        BROADCAST.get().map(|to| Self { to, buf: None })
+    }
+
+    // Send these samples to the broadcast.
+    //
+    // `gain` is the `ReplayGain` factor, volume is applied here.
+    pub(crate) fn write(&mut self, decoded: AudioBufferRef<'_>, gain: f32) {
+        let frames = decoded.frames();
+        if frames == 0 {
+            return;
+        }
+
+        let spec = *decoded.spec();
+        let capacity = decoded.capacity() as u64;
+
+        let buf = match self.buf.take() {
+            Some(b) if b.capacity() >= decoded.capacity() * spec.channels.count() => b,
+            _ => SampleBuffer::<f32>::new(capacity, spec),
+        };
+        let buf = self.buf.insert(buf);
+        buf.copy_interleaved_ref(decoded);
+
+        let volume = Volume::new(atomic_load!(VOLUME)).f32() * gain;
+        let pcm = BroadcastPcm {
+            rate: spec.rate,
+            channels: spec.channels.count() as u16,
+            samples: buf.samples().iter().map(|s| s * volume).collect(),
+        };
+
+        match self.to.try_send(pcm) {
+            Ok(_) => (),
+            Err(TrySendError::Full(_)) => {
+                trace!("Audio - broadcast channel full, dropping samples")
+            }
+            Err(TrySendError::Disconnected(_)) => warn!("Audio - broadcast channel disconnected"),
+        }
+    }
+}
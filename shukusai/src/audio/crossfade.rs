@@ -0,0 +1,316 @@
+//---------------------------------------------------------------------------------------------------- Use
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use strum::{AsRefStr, Display, EnumCount, EnumIter, EnumString, EnumVariantNames, IntoStaticStr};
+use symphonia::core::audio::{AudioBuffer, AudioBufferRef, Signal, SignalSpec};
+
+//---------------------------------------------------------------------------------------------------- Constants
+/// [`CrossfadeCurve::Linear`]
+const CURVE_LINEAR: &str = "Fade volume linearly";
+/// [`CrossfadeCurve::EqualPower`]
+const CURVE_EQUAL_POWER: &str = "Fade volume with an equal-power curve";
+
+/// The maximum amount of seconds a [`Crossfade`] can last.
+pub const CROSSFADE_MAX_SECONDS: u8 = 30;
+
+//---------------------------------------------------------------------------------------------------- CrossfadeCurve
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Hash,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    AsRefStr,
+    Display,
+    EnumCount,
+    EnumIter,
+    EnumString,
+    EnumVariantNames,
+    IntoStaticStr,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+/// The volume curve used when crossfading between 2 [`Song`]'s.
+pub enum CrossfadeCurve {
+    /// The outgoing [`Song`] fades out linearly as the incoming [`Song`] fades in.
+    Linear,
+    /// Constant perceived loudness throughout the fade (`cos/sin` curves).
+    EqualPower,
+}
+
+impl CrossfadeCurve {
+    /// Returns the default, [`Self::EqualPower`].
+    pub const fn new() -> Self {
+        Self::EqualPower
+    }
+
+    #[inline]
+    /// Returns formatted, human readable versions.
+    pub const fn human(&self) -> &'static str {
+        match self {
+            Self::Linear => CURVE_LINEAR,
+            Self::EqualPower => CURVE_EQUAL_POWER,
+        }
+    }
+
+    #[inline]
+    /// Returns the `(outgoing, incoming)` gain at `progress`.
+    ///
+    /// `progress` is clamped to `0.0..=1.0`, where `0.0`
+    /// is the start of the fade and `1.0` is the end.
+    pub fn gains(&self, progress: f32) -> (f32, f32) {
+        let p = progress.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => (1.0 - p, p),
+            Self::EqualPower => {
+                let theta = p * std::f32::consts::FRAC_PI_2;
+                (theta.cos(), theta.sin())
+            }
+        }
+    }
+}
+
+impl Default for CrossfadeCurve {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Crossfade
+#[derive(
+    Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd, Serialize, Deserialize, Encode, Decode,
+)]
+/// Crossfade settings.
+///
+/// When enabled, the last `seconds` of the current [`Song`] are
+/// mixed with the first `seconds` of the next [`Song`] in the queue.
+///
+/// Crossfading is skipped for consecutive [`Song`]'s
+/// in the same [`Album`] so that gapless albums stay gapless.
+///
+/// `seconds` is always `<=` [`CROSSFADE_MAX_SECONDS`], `0` means crossfading is off.
+pub struct Crossfade {
+    seconds: u8,
+    curve: CrossfadeCurve,
+}
+
+impl Crossfade {
+    #[inline]
+    /// Create a new [`Crossfade`].
+    ///
+    /// If `seconds` is greater than [`CROSSFADE_MAX_SECONDS`], it will be set to it.
+    pub const fn new(seconds: u8, curve: CrossfadeCurve) -> Self {
+        let seconds = if seconds > CROSSFADE_MAX_SECONDS {
+            CROSSFADE_MAX_SECONDS
+        } else {
+            seconds
+        };
+
+        Self { seconds, curve }
+    }
+
+    #[inline]
+    /// Returns a [`Crossfade`] that is turned off.
+    pub const fn off() -> Self {
+        Self {
+            seconds: 0,
+            curve: CrossfadeCurve::new(),
+        }
+    }
+
+    #[inline]
+    /// Is crossfading turned off?
+    pub const fn is_off(&self) -> bool {
+        self.seconds == 0
+    }
+
+    #[inline]
+    /// How many seconds the crossfade lasts.
+    pub const fn seconds(&self) -> u8 {
+        self.seconds
+    }
+
+    #[inline]
+    /// The volume curve used.
+    pub const fn curve(&self) -> CrossfadeCurve {
+        self.curve
+    }
+}
+
+impl Default for Crossfade {
+    /// Calls [`Crossfade::off`].
+    fn default() -> Self {
+        Self::off()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Fade
+// An in-progress crossfade.
+//
+// `Audio` decodes the incoming song's samples into
+// this as needed, and mixes them into the outgoing
+// song's samples before writing them to the output.
+pub(crate) struct Fade {
+    // The incoming song's planar samples that haven't been mixed yet.
+    fifo: Vec<VecDeque<f32>>,
+    // How many frames have been mixed so far.
+    frames: u64,
+    // How many frames the full fade lasts.
+    total: u64,
+    curve: CrossfadeCurve,
+    // The incoming song's gain relative to the outgoing song's.
+    gain: f32,
+    // The outgoing song's spec, the incoming must match.
+    pub(crate) spec: SignalSpec,
+    // Re-used buffer for the mixed samples.
+    buf: Option<AudioBuffer<f32>>,
+}
+
+impl Fade {
+    pub(crate) fn new(crossfade: Crossfade, spec: SignalSpec, gain: f32) -> Self {
+        Self {
+            fifo: vec![VecDeque::new(); spec.channels.count()],
+            frames: 0,
+            total: u64::from(crossfade.seconds()) * u64::from(spec.rate),
+            curve: crossfade.curve(),
+            gain,
+            spec,
+            buf: None,
+        }
+    }
+
+    #[inline]
+    // Does the FIFO need more incoming samples to cover `frames`?
+    pub(crate) fn needs(&self, frames: usize) -> bool {
+        self.fifo.first().map_or(false, |c| c.len() < frames)
+    }
+
+    #[inline]
+    // Are there any incoming samples left over?
+    pub(crate) fn is_empty(&self) -> bool {
+        self.fifo.first().map_or(true, |c| c.is_empty())
+    }
+
+    // Push the incoming song's decoded samples into the FIFO.
+    //
+    // Returns `false` (and does nothing) if the spec doesn't match.
+    pub(crate) fn push(&mut self, decoded: AudioBufferRef<'_>) -> bool {
+        if *decoded.spec() != self.spec {
+            return false;
+        }
+
+        let mut buf = decoded.make_equivalent::<f32>();
+        decoded.convert(&mut buf);
+
+        let gain = self.gain;
+        for (ch, fifo) in self.fifo.iter_mut().enumerate() {
+            fifo.extend(buf.chan(ch).iter().map(|s| s * gain));
+        }
+
+        true
+    }
+
+    // Mix the outgoing song's samples with the incoming
+    // song's samples in the FIFO, returning the mixed buffer.
+    pub(crate) fn mix(&mut self, decoded: AudioBufferRef<'_>) -> &AudioBuffer<f32> {
+        let capacity = decoded.capacity() as u64;
+
+        let mut buf = match self.buf.take() {
+            Some(b) if b.capacity() as u64 == capacity && *b.spec() == self.spec => b,
+            _ => AudioBuffer::<f32>::new(capacity, self.spec),
+        };
+        decoded.convert(&mut buf);
+
+        self.mix_into(&mut buf);
+        self.buf.insert(buf)
+    }
+
+    // Write the leftover incoming samples (after the outgoing song
+    // hit EOF) into buffers of `capacity`, calling `f` on each one.
+    pub(crate) fn drain(&mut self, capacity: u64, mut f: impl FnMut(&AudioBuffer<f32>)) {
+        let mut buf = AudioBuffer::<f32>::new(capacity, self.spec);
+
+        while !self.is_empty() {
+            let frames = self.fifo[0].len().min(capacity as usize);
+            buf.clear();
+            buf.render_reserved(Some(frames));
+            self.mix_into(&mut buf);
+            f(&buf);
+        }
+    }
+
+    fn mix_into(&mut self, buf: &mut AudioBuffer<f32>) {
+        let frames = buf.frames();
+        let start = self.frames;
+        let total = self.total.max(1) as f32;
+        let curve = self.curve;
+
+        for (ch, fifo) in self.fifo.iter_mut().enumerate() {
+            for (i, sample) in buf.chan_mut(ch).iter_mut().enumerate() {
+                let progress = (start + i as u64) as f32 / total;
+                let (outgoing, incoming) = curve.gains(progress);
+                let next = fifo.pop_front().unwrap_or(0.0);
+                *sample = *sample * outgoing + next * incoming;
+            }
+        }
+
+        self.frames += frames as u64;
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::*;
+
+    #[test]
+    // Asserts each curve starts at the outgoing
+    // song, ends at the incoming song, and is
+    // clamped outside of `0.0..=1.0`.
+    fn gains() {
+        for curve in CrossfadeCurve::iter() {
+            assert_eq!(curve.gains(0.0), (1.0, 0.0));
+            assert_eq!(curve.gains(-1.0), (1.0, 0.0));
+
+            let (o, i) = curve.gains(1.0);
+            assert!(o.abs() < 0.0001);
+            assert!((i - 1.0).abs() < 0.0001);
+            assert_eq!(curve.gains(1.0), curve.gains(2.0));
+        }
+
+        // Equal-power sums to 1.0 in power at the midpoint.
+        let (o, i) = CrossfadeCurve::EqualPower.gains(0.5);
+        assert!((o * o + i * i - 1.0).abs() < 0.0001);
+
+        // Linear sums to 1.0 in amplitude at the midpoint.
+        assert_eq!(CrossfadeCurve::Linear.gains(0.5), (0.5, 0.5));
+    }
+
+    #[test]
+    // Asserts the seconds are clamped.
+    fn new() {
+        let c = Crossfade::new(u8::MAX, CrossfadeCurve::Linear);
+        assert_eq!(c.seconds(), CROSSFADE_MAX_SECONDS);
+        assert!(!c.is_off());
+        assert!(Crossfade::default().is_off());
+        assert!(Crossfade::new(0, CrossfadeCurve::Linear).is_off());
+    }
+
+    #[test]
+    // Asserts each variant gives a different string.
+    fn diff() {
+        let mut set = std::collections::HashSet::new();
+        for i in CrossfadeCurve::iter() {
+            assert!(set.insert(i.human()));
+        }
+    }
+}
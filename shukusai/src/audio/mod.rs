@@ -14,9 +14,23 @@ mod repeat;
 pub use repeat::*;
 mod seek;
 pub use seek::*;
+mod crossfade;
+pub(crate) use crossfade::Fade;
+pub use crossfade::{Crossfade, CrossfadeCurve, CROSSFADE_MAX_SECONDS};
+mod replay_gain;
+pub use replay_gain::ReplayGain;
+mod broadcast;
+pub(crate) use broadcast::Broadcast;
+pub use broadcast::{BroadcastPcm, BROADCAST};
 
 // Symphonia-related.
 pub(super) mod output;
+pub use output::{AudioOutputError, Output, OutputOpen};
+mod backend;
+pub use backend::*;
+mod null;
+mod wav;
+pub use wav::{set_wav_path, wav_path};
 #[cfg(not(target_os = "linux"))]
 pub(super) mod resampler;
 
@@ -1,6 +1,6 @@
 //---------------------------------------------------------------------------------------------------- Use
 use crate::{
-    audio::{Append, Repeat, Seek, Volume},
+    audio::{Append, Crossfade, Repeat, ReplayGain, Seek, Volume},
     collection::{AlbumKey, ArtistKey, Collection, SongKey},
 };
 use std::sync::Arc;
@@ -25,12 +25,16 @@ pub(crate) enum KernelToAudio {
     // Audio settings.
     Repeat(Repeat),
     Volume(Volume),
+    Crossfade(Crossfade),
+    ReplayGain(ReplayGain),
+    Output(String),
 
     // Queue.
     QueueAddSong((SongKey, Append, bool, bool)),
     QueueAddAlbum((AlbumKey, Append, bool, bool, usize)),
     QueueAddArtist((ArtistKey, Append, bool, bool, usize)),
     QueueAddPlaylist((Arc<str>, Append, bool, bool, usize)),
+    QueueAddGenre((Arc<str>, Append, bool, bool, usize)),
     Shuffle,
     Clear(bool),
     Seek((Seek, u64)),
@@ -0,0 +1,117 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::audio::output::{AudioOutputError, Output};
+use std::time::Instant;
+use symphonia::core::audio::{AudioBufferRef, Signal, SignalSpec};
+use symphonia::core::units::Duration;
+
+//---------------------------------------------------------------------------------------------------- Constants
+// How far ahead of real-time `write()` is allowed to get.
+//
+// This acts like the buffer of a real audio device,
+// it's around the same size as the PulseAudio one.
+const AHEAD: std::time::Duration = std::time::Duration::from_millis(50);
+
+//---------------------------------------------------------------------------------------------------- Clock
+// Sleeps so that written audio gets "played" in real-time.
+//
+// This is used by outputs that aren't an actual
+// audio device and so would never block otherwise.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Clock {
+    // When we started, and how much audio was written since then.
+    start: Option<Instant>,
+    written: std::time::Duration,
+}
+
+impl Clock {
+    // Account for `frames` at `rate`, and sleep if we're too far ahead.
+    pub(super) fn tick(&mut self, frames: usize, rate: u32) {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        self.written += std::time::Duration::from_secs_f64(frames as f64 / f64::from(rate));
+
+        if let Some(ahead) = self.written.checked_sub(start.elapsed()) {
+            if ahead > AHEAD {
+                std::thread::sleep(ahead - AHEAD);
+            }
+        }
+    }
+
+    // Start over, e.g, after pausing.
+    pub(super) fn reset(&mut self) {
+        self.start = None;
+        self.written = std::time::Duration::ZERO;
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- NullOutput
+// An `Output` that discards all audio, in real-time.
+//
+// Useful for headless servers (e.g, only broadcasting) and CI.
+pub(crate) struct NullOutput {
+    spec: SignalSpec,
+    duration: Duration,
+    clock: Clock,
+}
+
+impl NullOutput {
+    pub(crate) fn try_open(
+        spec: SignalSpec,
+        duration: Duration,
+    ) -> std::result::Result<Box<dyn Output>, AudioOutputError> {
+        Ok(Box::new(Self {
+            spec,
+            duration,
+            clock: Clock::default(),
+        }))
+    }
+}
+
+impl Output for NullOutput {
+    fn write(&mut self, decoded: AudioBufferRef<'_>) -> std::result::Result<(), AudioOutputError> {
+        self.clock.tick(decoded.frames(), self.spec.rate);
+        Ok(())
+    }
+
+    fn flush(&mut self) {
+        self.clock.reset();
+    }
+
+    fn play(&mut self) -> std::result::Result<(), AudioOutputError> {
+        Ok(())
+    }
+
+    fn pause(&mut self) -> std::result::Result<(), AudioOutputError> {
+        self.clock.reset();
+        Ok(())
+    }
+
+    fn set_gain(&mut self, _: f32) {}
+
+    fn spec(&self) -> SignalSpec {
+        self.spec
+    }
+
+    fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Asserts the clock blocks until (almost) real-time.
+    fn clock() {
+        let mut clock = Clock::default();
+        let now = Instant::now();
+
+        // 3x 100ms.
+        for _ in 0..3 {
+            clock.tick(4_410, 44_100);
+        }
+
+        assert!(now.elapsed() >= std::time::Duration::from_millis(300) - AHEAD);
+    }
+}
@@ -19,49 +19,80 @@ use symphonia::core::audio::*;
 use symphonia::core::units::Duration;
 
 //---------------------------------------------------------------------------------------------------- Audio Output
-// This `Output` trait describes the functions
-// needed to output audio to the device.
-//
-// It's needed because Linux uses `PulseAudio`
-// while Windows/macOS will use the `cpal` backend.
-pub(crate) trait Output: Sized {
+/// An audio output backend.
+///
+/// `Audio` decodes, mixes, and then writes samples into
+/// one of these, e.g: PulseAudio, a WAV file, nothing, etc.
+///
+/// The built-in backends are listed in [`crate::audio::OUTPUTS`], custom
+/// ones can be added with [`crate::audio::register_output()`].
+///
+/// ## Blocking
+/// [`Output::write`] should block for about as long as it takes to play
+/// the samples, `Audio` relies on this to play in real-time.
+///
+/// ## Volume
+/// Implementors must apply the volume (read [`crate::state::VOLUME`])
+/// and the gain set with [`Output::set_gain`] to the samples they receive.
+pub trait Output {
+    /// Write decoded samples.
     fn write(&mut self, decoded: AudioBufferRef<'_>) -> std::result::Result<(), AudioOutputError>;
-    // Discard current audio samples.
+    /// Discard any samples that haven't been played yet.
     fn flush(&mut self);
-    fn try_open(
-        spec: SignalSpec,
-        duration: Duration,
-    ) -> std::result::Result<Self, AudioOutputError>;
+    /// Continue playback.
     fn play(&mut self) -> std::result::Result<(), AudioOutputError>;
+    /// Pause playback.
     fn pause(&mut self) -> std::result::Result<(), AudioOutputError>;
+    /// Set the `ReplayGain` factor applied on top of the volume.
+    fn set_gain(&mut self, gain: f32);
+    /// The [`SignalSpec`] this output was opened with.
+    fn spec(&self) -> SignalSpec;
+    /// The buffer [`Duration`] this output was opened with.
+    fn duration(&self) -> Duration;
+}
 
-    // Open the audio device with dummy values.
-    fn dummy() -> std::result::Result<Self, AudioOutputError> {
-        let spec = SignalSpec {
-            // INVARIANT: Must be non-zero.
-            rate: 44_100,
+/// A function that opens an [`Output`] for a [`SignalSpec`] and buffer [`Duration`].
+///
+/// `Audio` re-opens its output with this whenever
+/// the `SignalSpec` or `Duration` of a song changes.
+pub type OutputOpen =
+    fn(SignalSpec, Duration) -> std::result::Result<Box<dyn Output>, AudioOutputError>;
 
-            // INVARIANT: Must be a valid entry in the below map `match`.
-            channels: Channels::FRONT_LEFT,
-        };
+// Open an output with dummy values.
+pub(crate) fn dummy(open: OutputOpen) -> std::result::Result<Box<dyn Output>, AudioOutputError> {
+    let spec = SignalSpec {
+        // INVARIANT: Must be non-zero.
+        rate: 44_100,
 
-        Self::try_open(spec, 4096)
-    }
+        // INVARIANT: Must be a valid entry in the below map `match`.
+        channels: Channels::FRONT_LEFT,
+    };
+
+    open(spec, 4096)
 }
 
 #[derive(Debug)]
-pub(crate) enum AudioOutputError {
+/// An [`Output`] error.
+pub enum AudioOutputError {
+    /// The output could not be opened.
     OpenStream(anyhow::Error),
+    /// The output could not be played/paused.
     PlayStream(anyhow::Error),
+    /// The output was closed while writing.
     StreamClosed(anyhow::Error),
+    /// The channel layout is not supported.
     Channel(anyhow::Error),
+    /// The [`SignalSpec`] is not supported.
     InvalidSpec(anyhow::Error),
+    /// The device does not support `f32` samples.
     NonF32(anyhow::Error),
+    /// Resampling failed.
     Resampler(anyhow::Error),
 }
 
 impl AudioOutputError {
-    pub(crate) fn into_anyhow(self) -> anyhow::Error {
+    /// Returns the inner error.
+    pub fn into_anyhow(self) -> anyhow::Error {
         use AudioOutputError::*;
         match self {
             OpenStream(a) => a,
@@ -91,23 +122,17 @@ mod output {
         pa: psimple::Simple,
         sample_buf: RawSampleBuffer<f32>,
         audio_buf: AudioBuffer<f32>,
-        pub(crate) spec: SignalSpec,
-        pub(crate) duration: Duration,
+        spec: SignalSpec,
+        duration: Duration,
+        gain: f32,
     }
 
-    impl Output for AudioOutput {
-        fn pause(&mut self) -> std::result::Result<(), AudioOutputError> {
-            Ok(self.flush())
-        }
-
-        fn play(&mut self) -> std::result::Result<(), AudioOutputError> {
-            Ok(())
-        }
-
-        fn try_open(
+    impl AudioOutput {
+        // Open a PulseAudio stream.
+        pub(crate) fn try_open(
             spec: SignalSpec,
             duration: Duration,
-        ) -> std::result::Result<Self, AudioOutputError> {
+        ) -> std::result::Result<Box<dyn Output>, AudioOutputError> {
             // An interleaved buffer is required to send data to PulseAudio. Use a SampleBuffer to
             // move data between Symphonia AudioBuffers and the byte buffers required by PulseAudio.
             let sample_buf = RawSampleBuffer::<f32>::new(duration, spec);
@@ -167,18 +192,29 @@ mod output {
             );
 
             match pa_result {
-                Ok(pa) => Ok(AudioOutput {
+                Ok(pa) => Ok(Box::new(AudioOutput {
                     pa,
                     sample_buf,
                     audio_buf,
                     spec,
                     duration,
-                }),
+                    gain: 1.0,
+                })),
                 Err(err) => Err(AudioOutputError::OpenStream(anyhow!(
                     "stream open error: {err}"
                 ))),
             }
         }
+    }
+
+    impl Output for AudioOutput {
+        fn pause(&mut self) -> std::result::Result<(), AudioOutputError> {
+            Ok(self.flush())
+        }
+
+        fn play(&mut self) -> std::result::Result<(), AudioOutputError> {
+            Ok(())
+        }
 
         fn write(
             &mut self,
@@ -191,7 +227,7 @@ mod output {
 
             // Convert the buffer to `f32` and multiply
             // it by `0.0..1.0` to set volume levels.
-            let volume = Volume::new(atomic_load!(VOLUME)).f32();
+            let volume = Volume::new(atomic_load!(VOLUME)).f32() * self.gain;
             decoded.convert(&mut self.audio_buf);
             self.audio_buf.transform(|f| f * volume);
 
@@ -212,6 +248,18 @@ mod output {
         fn flush(&mut self) {
             _ = self.pa.flush();
         }
+
+        fn set_gain(&mut self, gain: f32) {
+            self.gain = gain;
+        }
+
+        fn spec(&self) -> SignalSpec {
+            self.spec
+        }
+
+        fn duration(&self) -> Duration {
+            self.duration
+        }
     }
 
     /// Maps a set of Symphonia `Channels` to a PulseAudio channel map.
@@ -280,28 +328,17 @@ mod output {
         stream: cpal::Stream,
         resampler: Option<Resampler<f32>>,
         samples: Vec<f32>,
-        pub(crate) spec: SignalSpec,
-        pub(crate) duration: Duration,
+        spec: SignalSpec,
+        duration: Duration,
+        gain: f32,
     }
 
-    impl Output for AudioOutput {
-        fn pause(&mut self) -> std::result::Result<(), AudioOutputError> {
-            self.flush();
-            self.stream
-                .pause()
-                .map_err(|e| AudioOutputError::PlayStream(anyhow!("pause error")))
-        }
-
-        fn play(&mut self) -> std::result::Result<(), AudioOutputError> {
-            self.stream
-                .play()
-                .map_err(|e| AudioOutputError::PlayStream(anyhow!("play error")))
-        }
-
-        fn try_open(
+    impl AudioOutput {
+        // Open a `cpal` stream on the default device.
+        pub(crate) fn try_open(
             spec: SignalSpec,
             duration: Duration,
-        ) -> std::result::Result<Self, AudioOutputError> {
+        ) -> std::result::Result<Box<dyn Output>, AudioOutputError> {
             // Get default host.
             let host = cpal::default_host();
 
@@ -400,7 +437,7 @@ mod output {
 
             let samples = Vec::with_capacity(num_channels * duration as usize);
 
-            Ok(Self {
+            Ok(Box::new(Self {
                 ring_buf,
                 ring_buf_producer,
                 sample_buf,
@@ -409,7 +446,23 @@ mod output {
                 resampler,
                 spec,
                 duration,
-            })
+                gain: 1.0,
+            }))
+        }
+    }
+
+    impl Output for AudioOutput {
+        fn pause(&mut self) -> std::result::Result<(), AudioOutputError> {
+            self.flush();
+            self.stream
+                .pause()
+                .map_err(|e| AudioOutputError::PlayStream(anyhow!("pause error")))
+        }
+
+        fn play(&mut self) -> std::result::Result<(), AudioOutputError> {
+            self.stream
+                .play()
+                .map_err(|e| AudioOutputError::PlayStream(anyhow!("play error")))
         }
 
         fn write(
@@ -444,7 +497,7 @@ mod output {
             self.samples.extend_from_slice(samples);
 
             // Apply volume transformation.
-            let volume = Volume::new(atomic_load!(VOLUME)).f32();
+            let volume = Volume::new(atomic_load!(VOLUME)).f32() * self.gain;
 
             // Taken from: https://docs.rs/symphonia-core/0.5.3/src/symphonia_core/audio.rs.html#680-692
             //
@@ -473,5 +526,17 @@ mod output {
                 std::thread::sleep(std::time::Duration::from_millis(1));
             }
         }
+
+        fn set_gain(&mut self, gain: f32) {
+            self.gain = gain;
+        }
+
+        fn spec(&self) -> SignalSpec {
+            self.spec
+        }
+
+        fn duration(&self) -> Duration {
+            self.duration
+        }
     }
 }
@@ -0,0 +1,134 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::collection::{Album, Gain, Song};
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumCount, EnumIter, EnumString, EnumVariantNames, IntoStaticStr};
+
+//---------------------------------------------------------------------------------------------------- Constants
+/// [`ReplayGain::Off`]
+const REPLAY_GAIN_OFF: &str = "Do not normalize loudness";
+/// [`ReplayGain::Track`]
+const REPLAY_GAIN_TRACK: &str = "Normalize the loudness of each song";
+/// [`ReplayGain::Album`]
+const REPLAY_GAIN_ALBUM: &str = "Normalize the loudness of each album";
+
+//---------------------------------------------------------------------------------------------------- ReplayGain
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Hash,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    AsRefStr,
+    Display,
+    EnumCount,
+    EnumIter,
+    EnumString,
+    EnumVariantNames,
+    IntoStaticStr,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+/// The different loudness normalization modes.
+///
+/// The [`Gain`] used is read from `REPLAYGAIN_*` or `R128_*`
+/// tags, or calculated when creating the [`Collection`].
+///
+/// Gain is never applied in a way that would clip.
+pub enum ReplayGain {
+    /// Play audio as-is.
+    Off,
+    /// Use the [`Song`]'s gain, or the [`Album`]'s if missing.
+    Track,
+    /// Use the [`Album`]'s gain, or the [`Song`]'s if missing.
+    ///
+    /// This keeps the loudness differences between
+    /// [`Song`]'s of the same [`Album`] intact.
+    Album,
+}
+
+impl ReplayGain {
+    /// Returns the default, [`Self::Off`].
+    pub const fn new() -> Self {
+        Self::Off
+    }
+
+    #[inline]
+    /// Returns formatted, human readable versions.
+    pub const fn human(&self) -> &'static str {
+        match self {
+            Self::Off => REPLAY_GAIN_OFF,
+            Self::Track => REPLAY_GAIN_TRACK,
+            Self::Album => REPLAY_GAIN_ALBUM,
+        }
+    }
+
+    #[inline]
+    /// Returns the linear factor samples of this [`Song`] should be multiplied by.
+    ///
+    /// This is `1.0` if [`Self::Off`] or if no [`Gain`] data exists.
+    pub fn factor(&self, song: &Song, album: &Album) -> f32 {
+        let gain: Option<Gain> = match self {
+            Self::Off => None,
+            Self::Track => song.gain.or(album.gain),
+            Self::Album => album.gain.or(song.gain),
+        };
+
+        gain.map_or(1.0, |g| g.factor())
+    }
+}
+
+impl Default for ReplayGain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::*;
+
+    #[test]
+    // Asserts each variant gives a different string.
+    fn diff() {
+        let mut set = std::collections::HashSet::new();
+        for i in ReplayGain::iter() {
+            assert!(set.insert(i.human()));
+        }
+    }
+
+    #[test]
+    // Asserts the correct gain is picked, with fallbacks.
+    fn factor() {
+        let track = Gain {
+            gain: -6.0,
+            peak: Some(1.0),
+        };
+        let album = Gain {
+            gain: -12.0,
+            peak: Some(1.0),
+        };
+
+        let mut song = Song::default();
+        let mut alb = Album::default();
+        assert_eq!(ReplayGain::Track.factor(&song, &alb), 1.0);
+
+        song.gain = Some(track);
+        alb.gain = Some(album);
+        assert_eq!(ReplayGain::Off.factor(&song, &alb), 1.0);
+        assert_eq!(ReplayGain::Track.factor(&song, &alb), track.factor());
+        assert_eq!(ReplayGain::Album.factor(&song, &alb), album.factor());
+
+        alb.gain = None;
+        assert_eq!(ReplayGain::Album.factor(&song, &alb), track.factor());
+    }
+}
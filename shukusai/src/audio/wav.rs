@@ -0,0 +1,309 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::audio::null::Clock;
+use crate::audio::output::{AudioOutputError, Output};
+use crate::audio::Volume;
+use crate::state::VOLUME;
+use anyhow::anyhow;
+use benri::{atomic_load, sync::*};
+use log::{info, warn};
+use std::fs::File;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use symphonia::core::audio::{AudioBufferRef, SampleBuffer, Signal, SignalSpec};
+use symphonia::core::units::Duration;
+
+//---------------------------------------------------------------------------------------------------- Constants
+// Byte offsets of the sizes in the WAV header.
+const OFFSET_RIFF_SIZE: u64 = 4;
+const OFFSET_DATA_SIZE: u64 = 40;
+// Size of the header before the sample data.
+const HEADER_LEN: u32 = 44;
+// The most sample data a single file can hold,
+// the RIFF size (data + header - 8) is a `u32`.
+const MAX_DATA_LEN: u32 = u32::MAX - (HEADER_LEN - 8);
+
+//---------------------------------------------------------------------------------------------------- Statics
+// Where the `wav` output writes to, `None` means the default.
+static WAV_PATH: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+// The WAV file currently being written to.
+//
+// This outlives each `WavOutput`, since `Audio` re-opens
+// its output on every `SignalSpec` change and we want
+// to keep appending to the same file if possible.
+static WAV: Mutex<Option<Wav>> = Mutex::new(None);
+
+/// Set the PATH the `wav` [`Output`] writes to.
+///
+/// This takes effect the next time the output is opened.
+///
+/// If audio with a different sample rate or channel count
+/// gets played, a new file with a numbered suffix is started,
+/// e.g: `festival.wav` -> `festival.1.wav`.
+pub fn set_wav_path(path: PathBuf) {
+    *lockw!(WAV_PATH) = Some(path);
+}
+
+/// The PATH the `wav` [`Output`] writes to.
+///
+/// The default is `festival.wav` in the OS audio directory
+/// (or temporary directory if there isn't one).
+pub fn wav_path() -> PathBuf {
+    match &*lockr!(WAV_PATH) {
+        Some(p) => p.clone(),
+        None => dirs::audio_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("festival.wav"),
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Wav
+// An open WAV file of 32-bit float samples.
+#[derive(Debug)]
+struct Wav {
+    file: File,
+    // The PATH the user set, not the numbered one.
+    path: PathBuf,
+    rate: u32,
+    channels: u16,
+    // Bytes of sample data written so far.
+    len: u32,
+    // How many files were created for this `path`.
+    count: usize,
+}
+
+impl Wav {
+    fn create(path: &Path, count: usize, rate: u32, channels: u16) -> std::io::Result<Self> {
+        let numbered = if count == 0 {
+            path.to_path_buf()
+        } else {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            path.with_file_name(format!("{stem}.{count}.wav"))
+        };
+
+        let mut file = File::create(&numbered)?;
+
+        let block_align = channels * 4;
+        let mut header = Vec::with_capacity(HEADER_LEN as usize);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&(HEADER_LEN - 8).to_le_bytes());
+        header.extend_from_slice(b"WAVE");
+        header.extend_from_slice(b"fmt ");
+        header.extend_from_slice(&16_u32.to_le_bytes());
+        header.extend_from_slice(&3_u16.to_le_bytes()); // IEEE float
+        header.extend_from_slice(&channels.to_le_bytes());
+        header.extend_from_slice(&rate.to_le_bytes());
+        header.extend_from_slice(&(rate * u32::from(block_align)).to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&32_u16.to_le_bytes());
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&0_u32.to_le_bytes());
+        file.write_all(&header)?;
+
+        info!("Audio - WAV output: {}", numbered.display());
+
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+            rate,
+            channels,
+            len: 0,
+            count,
+        })
+    }
+
+    // Append samples, and update the header sizes
+    // so that the file is valid at all times.
+    //
+    // Past ~4GiB the header sizes would overflow, so
+    // the next numbered file is started instead.
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        let len = u32::try_from(bytes.len()).unwrap_or(u32::MAX);
+        if self.len.checked_add(len).map_or(true, |l| l > MAX_DATA_LEN) {
+            *self = Self::create(&self.path, self.count + 1, self.rate, self.channels)?;
+        }
+
+        self.file.write_all(bytes)?;
+        self.len = self.len.saturating_add(len);
+
+        self.file.seek(SeekFrom::Start(OFFSET_RIFF_SIZE))?;
+        self.file
+            .write_all(&(self.len.saturating_add(HEADER_LEN - 8)).to_le_bytes())?;
+        self.file.seek(SeekFrom::Start(OFFSET_DATA_SIZE))?;
+        self.file.write_all(&self.len.to_le_bytes())?;
+        self.file.seek(SeekFrom::End(0))?;
+
+        Ok(())
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- WavOutput
+// An `Output` that writes everything played into a WAV file, in real-time.
+pub(crate) struct WavOutput {
+    spec: SignalSpec,
+    duration: Duration,
+    sample_buf: SampleBuffer<f32>,
+    bytes: Vec<u8>,
+    gain: f32,
+    clock: Clock,
+}
+
+impl WavOutput {
+    pub(crate) fn try_open(
+        spec: SignalSpec,
+        duration: Duration,
+    ) -> std::result::Result<Box<dyn Output>, AudioOutputError> {
+        let path = wav_path();
+        let channels = spec.channels.count() as u16;
+
+        let mut wav = lock!(WAV);
+
+        // Keep appending if the format is the same.
+        let count = match &*wav {
+            Some(w) if w.path == path && w.rate == spec.rate && w.channels == channels => None,
+            Some(w) if w.path == path => Some(w.count + 1),
+            _ => Some(0),
+        };
+
+        if let Some(count) = count {
+            match Wav::create(&path, count, spec.rate, channels) {
+                Ok(w) => *wav = Some(w),
+                Err(e) => {
+                    return Err(AudioOutputError::OpenStream(anyhow!(
+                        "WAV file error: {}: {e}",
+                        path.display()
+                    )))
+                }
+            }
+        }
+
+        Ok(Box::new(Self {
+            spec,
+            duration,
+            sample_buf: SampleBuffer::new(duration, spec),
+            bytes: vec![],
+            gain: 1.0,
+            clock: Clock::default(),
+        }))
+    }
+}
+
+impl Output for WavOutput {
+    fn write(&mut self, decoded: AudioBufferRef<'_>) -> std::result::Result<(), AudioOutputError> {
+        let frames = decoded.frames();
+        if frames == 0 {
+            return Ok(());
+        }
+
+        self.sample_buf.copy_interleaved_ref(decoded);
+
+        let volume = Volume::new(atomic_load!(VOLUME)).f32() * self.gain;
+        self.bytes.clear();
+        for sample in self.sample_buf.samples() {
+            self.bytes.extend_from_slice(&(sample * volume).to_le_bytes());
+        }
+
+        match &mut *lock!(WAV) {
+            Some(wav) => {
+                if let Err(e) = wav.write(&self.bytes) {
+                    warn!("Audio - WAV write error: {e}");
+                    return Err(AudioOutputError::StreamClosed(anyhow!(e)));
+                }
+            }
+            None => return Err(AudioOutputError::StreamClosed(anyhow!("WAV file closed"))),
+        }
+
+        self.clock.tick(frames, self.spec.rate);
+        Ok(())
+    }
+
+    fn flush(&mut self) {
+        self.clock.reset();
+    }
+
+    fn play(&mut self) -> std::result::Result<(), AudioOutputError> {
+        Ok(())
+    }
+
+    fn pause(&mut self) -> std::result::Result<(), AudioOutputError> {
+        self.clock.reset();
+        Ok(())
+    }
+
+    fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+
+    fn spec(&self) -> SignalSpec {
+        self.spec
+    }
+
+    fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Asserts the header is correct and kept up-to-date.
+    fn header() {
+        let path = std::env::temp_dir().join("festival_test_header.wav");
+        let mut wav = Wav::create(&path, 0, 48_000, 2).unwrap();
+        wav.write(&[0; 16]).unwrap();
+        wav.write(&[0; 16]).unwrap();
+        drop(wav);
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        let u16_at = |i: usize| u16::from_le_bytes(bytes[i..i + 2].try_into().unwrap());
+
+        assert_eq!(bytes.len(), 44 + 32);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(u32_at(4), 36 + 32);
+        assert_eq!(&bytes[8..16], b"WAVEfmt ");
+        assert_eq!(u16_at(20), 3);
+        assert_eq!(u16_at(22), 2);
+        assert_eq!(u32_at(24), 48_000);
+        assert_eq!(u32_at(28), 48_000 * 8);
+        assert_eq!(u16_at(32), 8);
+        assert_eq!(u16_at(34), 32);
+        assert_eq!(&bytes[36..40], b"data");
+        assert_eq!(u32_at(40), 32);
+    }
+
+    #[test]
+    // Asserts a full file continues in the next numbered file.
+    fn full() {
+        let path = std::env::temp_dir().join("festival_test_full.wav");
+        let mut wav = Wav::create(&path, 0, 48_000, 2).unwrap();
+        wav.len = MAX_DATA_LEN - 8;
+        wav.write(&[0; 16]).unwrap();
+        assert_eq!(wav.count, 1);
+        assert_eq!(wav.len, 16);
+        drop(wav);
+
+        let numbered = std::env::temp_dir().join("festival_test_full.1.wav");
+        assert_eq!(std::fs::metadata(&numbered).unwrap().len(), 44 + 16);
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&numbered).unwrap();
+    }
+
+    #[test]
+    // Asserts numbered files keep the extension.
+    fn numbered() {
+        let path = std::env::temp_dir().join("festival_test_numbered.wav");
+        let wav = Wav::create(&path, 1, 44_100, 1).unwrap();
+        drop(wav);
+
+        let numbered = std::env::temp_dir().join("festival_test_numbered.1.wav");
+        assert!(numbered.exists());
+        std::fs::remove_file(numbered).unwrap();
+    }
+}
@@ -2,12 +2,14 @@
 use crate::ccd::msg::CcdToKernel;
 use crate::{
     collection::{
-        AlbumKey, Albums, Art, ArtistKey, Artists, Collection, Image, Map, SongKey, Songs,
+        Album, AlbumKey, Albums, Art, Artist, ArtistKey, Artists, Collection, Image, Map, Song,
+        SongKey, Songs,
     },
-    constants::{COLLECTION_VERSION, PLAYLIST_VERSION},
+    constants::{COLLECTION_VERSION, PLAYLIST_VERSION, SEARCH_INDEX_VERSION},
+    search::{SearchIndex, NEW_INDEX},
     state::{Phase, Playlists, PLAYLISTS},
 };
-use benri::{debug_panic, log::*, sync::*, thread::*, time::*};
+use benri::{debug_panic, lock, log::*, sync::*, thread::*, time::*};
 use crossbeam::channel::Sender;
 use disk::{Bincode2, Json, Plain};
 use log::{debug, info, trace, warn};
@@ -168,225 +170,9 @@ impl Ccd {
         let perf_fix = secs_f32!(now);
         trace!("CCD [4/14] ... Fix: {perf_fix}");
 
-        //-------------------------------------------------------------------------------- 5
-        let now = now!();
-        send!(to_kernel, CcdToKernel::UpdatePhase((52.50, Phase::Sort)));
-
-        let sort_artist_lexi = Self::sort_artist_lexi(&vec_artist);
-        let sort_artist_lexi_rev = sort_artist_lexi
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[ArtistKey]>>();
-        let sort_artist_album_count = Self::sort_artist_album_count(&vec_artist);
-        let sort_artist_album_count_rev = sort_artist_album_count
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[ArtistKey]>>();
-        let sort_artist_song_count = Self::sort_artist_song_count(&vec_artist, &vec_album);
-        let sort_artist_song_count_rev = sort_artist_song_count
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[ArtistKey]>>();
-        let sort_artist_runtime = Self::sort_artist_runtime(&vec_artist);
-        let sort_artist_runtime_rev = sort_artist_runtime
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[ArtistKey]>>();
-        let sort_artist_name = Self::sort_artist_name(&vec_artist);
-        let sort_artist_name_rev = sort_artist_name
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[ArtistKey]>>();
-
-        let sort_album_release_artist_lexi =
-            Self::sort_album_release_artist_iter(&sort_artist_lexi, &vec_artist, &vec_album);
-        let sort_album_release_artist_lexi_rev =
-            Self::sort_album_release_artist_iter(&sort_artist_lexi_rev, &vec_artist, &vec_album);
-        let sort_album_release_rev_artist_lexi =
-            Self::sort_album_release_rev_artist_iter(&sort_artist_lexi, &vec_artist, &vec_album);
-        let sort_album_release_rev_artist_lexi_rev = Self::sort_album_release_rev_artist_iter(
-            &sort_artist_lexi_rev,
-            &vec_artist,
-            &vec_album,
-        );
-        let sort_album_lexi_artist_lexi =
-            Self::sort_album_lexi_artist_iter(&sort_artist_lexi, &vec_artist, &vec_album);
-        let sort_album_lexi_artist_lexi_rev =
-            Self::sort_album_lexi_artist_iter(&sort_artist_lexi_rev, &vec_artist, &vec_album);
-        let sort_album_lexi_rev_artist_lexi =
-            Self::sort_album_lexi_rev_artist_iter(&sort_artist_lexi, &vec_artist, &vec_album);
-        let sort_album_lexi_rev_artist_lexi_rev =
-            Self::sort_album_lexi_rev_artist_iter(&sort_artist_lexi_rev, &vec_artist, &vec_album);
-        let sort_album_lexi = Self::sort_album_lexi(&vec_album);
-        let sort_album_lexi_rev = sort_album_lexi
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[AlbumKey]>>();
-        let sort_album_release = Self::sort_album_release(&vec_album);
-        let sort_album_release_rev = sort_album_release
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[AlbumKey]>>();
-        let sort_album_runtime = Self::sort_album_runtime(&vec_album);
-        let sort_album_runtime_rev = sort_album_runtime
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[AlbumKey]>>();
-        let sort_album_title = Self::sort_album_title(&vec_album);
-        let sort_album_title_rev = sort_album_title
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[AlbumKey]>>();
-
-        let sort_song_album_release_artist_lexi =
-            Self::sort_song(&sort_album_release_artist_lexi, &vec_album);
-        let sort_song_album_release_artist_lexi_rev =
-            Self::sort_song(&sort_album_release_artist_lexi_rev, &vec_album);
-        let sort_song_album_release_rev_artist_lexi =
-            Self::sort_song(&sort_album_release_rev_artist_lexi, &vec_album);
-        let sort_song_album_release_rev_artist_lexi_rev =
-            Self::sort_song(&sort_album_release_rev_artist_lexi_rev, &vec_album);
-        let sort_song_album_lexi_artist_lexi =
-            Self::sort_song(&sort_album_lexi_artist_lexi, &vec_album);
-        let sort_song_album_lexi_artist_lexi_rev =
-            Self::sort_song(&sort_album_lexi_artist_lexi_rev, &vec_album);
-        let sort_song_album_lexi_rev_artist_lexi =
-            Self::sort_song(&sort_album_lexi_rev_artist_lexi, &vec_album);
-        let sort_song_album_lexi_rev_artist_lexi_rev =
-            Self::sort_song(&sort_album_lexi_rev_artist_lexi_rev, &vec_album);
-        let sort_song_release = Self::sort_song(&sort_album_release, &vec_album);
-        let sort_song_release_rev = sort_song_release
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[SongKey]>>();
-        let sort_song_lexi = Self::sort_song_lexi(&vec_song);
-        let sort_song_lexi_rev = sort_song_lexi
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[SongKey]>>();
-        let sort_song_runtime = Self::sort_song_runtime(&vec_song);
-        let sort_song_runtime_rev = sort_song_runtime
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[SongKey]>>();
-        let sort_song_title = Self::sort_song_title(&vec_song);
-        let sort_song_title_rev = sort_song_title
-            .iter()
-            .rev()
-            .copied()
-            .collect::<Box<[SongKey]>>();
-
-        let perf_sort = secs_f32!(now);
-        trace!("CCD [5/14] ... Sort: {perf_sort}");
-
-        //-------------------------------------------------------------------------------- 6
-        let now = now!();
-        send!(to_kernel, CcdToKernel::UpdatePhase((55.00, Phase::Search)));
-        let map = Map::from_3_vecs(&vec_artist, &vec_album, &vec_song);
-        let perf_map = secs_f32!(now);
-        trace!("CCD [6/14] ... Map: {perf_map}");
-
-        //-------------------------------------------------------------------------------- 7
-        let now = now!();
-        send!(to_kernel, CcdToKernel::UpdatePhase((60.00, Phase::Prepare)));
-        let mut collection = Collection {
-            // These will be fixed after construction.
-            empty: false,
-            timestamp: 0,
-            count_artist: Unsigned::zero(),
-            count_album: Unsigned::zero(),
-            count_song: Unsigned::zero(),
-
-            // We calculated this during "The Loop".
-            count_art: Unsigned::from(count_art),
-
-            map,
-
-            artists: Artists::from_vec(vec_artist),
-            albums: Albums::from_vec(vec_album),
-            songs: Songs::from_vec(vec_song),
-
-            sort_artist_lexi,
-            sort_artist_lexi_rev,
-            sort_artist_album_count,
-            sort_artist_album_count_rev,
-            sort_artist_song_count,
-            sort_artist_song_count_rev,
-            sort_artist_runtime,
-            sort_artist_runtime_rev,
-            sort_artist_name,
-            sort_artist_name_rev,
-
-            sort_album_release_artist_lexi,
-            sort_album_release_artist_lexi_rev,
-            sort_album_release_rev_artist_lexi,
-            sort_album_release_rev_artist_lexi_rev,
-            sort_album_lexi_artist_lexi,
-            sort_album_lexi_artist_lexi_rev,
-            sort_album_lexi_rev_artist_lexi,
-            sort_album_lexi_rev_artist_lexi_rev,
-            sort_album_lexi,
-            sort_album_lexi_rev,
-            sort_album_release,
-            sort_album_release_rev,
-            sort_album_runtime,
-            sort_album_runtime_rev,
-            sort_album_title,
-            sort_album_title_rev,
-
-            sort_song_album_release_artist_lexi,
-            sort_song_album_release_artist_lexi_rev,
-            sort_song_album_release_rev_artist_lexi,
-            sort_song_album_release_rev_artist_lexi_rev,
-            sort_song_album_lexi_artist_lexi,
-            sort_song_album_lexi_artist_lexi_rev,
-            sort_song_album_lexi_rev_artist_lexi,
-            sort_song_album_lexi_rev_artist_lexi_rev,
-            sort_song_lexi,
-            sort_song_lexi_rev,
-            sort_song_release,
-            sort_song_release_rev,
-            sort_song_runtime,
-            sort_song_runtime_rev,
-            sort_song_title,
-            sort_song_title_rev,
-        };
-        // Fix metadata.
-        {
-            // Get `Vec` lengths.
-            let artists = collection.artists.len();
-            let albums = collection.albums.len();
-            let songs = collection.songs.len();
-
-            // Set `empty`.
-            if artists == 0 && albums == 0 && songs == 0 {
-                collection.empty = true;
-            } else {
-                collection.empty = false;
-            }
-
-            // Set `count_*`.
-            collection.count_artist = Unsigned::from(artists);
-            collection.count_album = Unsigned::from(albums);
-            collection.count_song = Unsigned::from(songs);
-
-            // Set `timestamp`.
-            collection.timestamp = benri::unix!();
-        }
-        let perf_prepare = secs_f32!(now);
-        trace!("CCD [7/14] ... Prepare: {perf_prepare}");
+        //-------------------------------------------------------------------------------- 5, 6, 7
+        let (mut collection, index, perf_sort, perf_map, perf_prepare) =
+            Self::build(&to_kernel, vec_artist, vec_album, vec_song, count_art);
 
         #[cfg(feature = "gui")]
         let (perf_resize, perf_clone, perf_convert, perf_textures, collection_for_disk) = {
@@ -535,11 +321,19 @@ impl Ccd {
         //-------------------------------------------------------------------------------- 12
         let now = now!();
         crate::state::PLAYLISTS.write().validate(&collection);
+        crate::state::SMART_PLAYLISTS.read().evaluate(
+            &collection,
+            &crate::state::STATS.read(),
+            &mut crate::state::PLAYLISTS.write(),
+        );
         let playlists_for_disk = crate::state::PLAYLISTS.read().clone();
         let perf_playlists = secs_f32!(now);
         info!("CCD [12/14] ... Playlists: {perf_playlists}");
 
         //-------------------------------------------------------------------------------- 13
+        // `Search` picks this up when it gets the new `Collection`.
+        let index = Arc::new(index);
+        *lock!(NEW_INDEX) = Some(Arc::clone(&index));
         send!(to_kernel, CcdToKernel::NewCollection(collection));
         let user_time = secs_f32!(beginning);
         info!("CCD [13/14] ... User time: {user_time}");
@@ -551,34 +345,7 @@ impl Ccd {
         atomic_store!(crate::state::SAVING, true);
 
         // Attempt atomic save.
-        //
-        // SAFETY:
-        // `Collection` is saved to disk via `memmap`.
-        //
-        // We (`CCD`) are the only "entity" that should
-        // be touching `collection.bin` at this point.
-        let total_bytes = match unsafe { collection_for_disk.save_atomic_memmap() } {
-            Ok(md) => {
-                debug!("CCD ... Collection{COLLECTION_VERSION}: {md}");
-                md.size()
-            }
-            Err(e) => {
-                debug_panic!("CCD ... Collection{COLLECTION_VERSION}: {e}");
-                fail!("CCD ... Collection{COLLECTION_VERSION}: {e}");
-                0
-            }
-        };
-
-        // SAFETY: `memmap` used.
-        //
-        // Save the `Playlists`
-        match unsafe { playlists_for_disk.save_atomic_memmap() } {
-            Ok(md) => debug!("CCD ... Playlists{PLAYLIST_VERSION}: {md}"),
-            Err(e) => {
-                debug_panic!("CCD ... Playlists{PLAYLIST_VERSION}: {e}");
-                fail!("CCD ... Playlists{PLAYLIST_VERSION}: {e}");
-            }
-        }
+        let total_bytes = Self::save(&collection_for_disk, &playlists_for_disk, &index);
 
         #[cfg(feature = "gui")]
         {
@@ -648,6 +415,305 @@ impl Ccd {
         //-------------------------------------------------------------------------------- End.
         ok_debug!("CCD");
     }
+
+    //-------------------------------------------------------------------------------- CCD `save()`
+    // Atomically save the `Collection`, `Playlists` and `SearchIndex` to disk.
+    //
+    // Returns the amount of bytes the `Collection` took up (`0` on failure).
+    pub(super) fn save(collection: &Collection, playlists: &Playlists, index: &SearchIndex) -> u64 {
+        // SAFETY:
+        // `Collection` is saved to disk via `memmap`.
+        //
+        // We (`CCD`) are the only "entity" that should
+        // be touching `collection.bin` at this point.
+        let total_bytes = match unsafe { collection.save_atomic_memmap() } {
+            Ok(md) => {
+                debug!("CCD ... Collection{COLLECTION_VERSION}: {md}");
+                md.size()
+            }
+            Err(e) => {
+                debug_panic!("CCD ... Collection{COLLECTION_VERSION}: {e}");
+                fail!("CCD ... Collection{COLLECTION_VERSION}: {e}");
+                0
+            }
+        };
+
+        // SAFETY: `memmap` used.
+        //
+        // Save the `Playlists`
+        match unsafe { playlists.save_atomic_memmap() } {
+            Ok(md) => debug!("CCD ... Playlists{PLAYLIST_VERSION}: {md}"),
+            Err(e) => {
+                debug_panic!("CCD ... Playlists{PLAYLIST_VERSION}: {e}");
+                fail!("CCD ... Playlists{PLAYLIST_VERSION}: {e}");
+            }
+        }
+
+        // Save the `SearchIndex`.
+        //
+        // This isn't fatal, `Search` re-builds it if it's missing.
+        match index.save_atomic() {
+            Ok(md) => debug!("CCD ... SearchIndex{SEARCH_INDEX_VERSION}: {md}"),
+            Err(e) => warn!("CCD ... SearchIndex{SEARCH_INDEX_VERSION}: {e}"),
+        }
+
+        total_bytes
+    }
+
+    //-------------------------------------------------------------------------------- CCD `build()`
+    // Steps `5`, `6`, and `7` of `new_collection()`.
+    //
+    // Takes in the fixed `Vec`'s and creates the
+    // `Collection` (with sort keys and map) and its `SearchIndex`.
+    //
+    // Returns the perf timings of each step as well.
+    pub(super) fn build(
+        to_kernel: &Sender<CcdToKernel>,
+        vec_artist: Vec<Artist>,
+        vec_album: Vec<Album>,
+        vec_song: Vec<Song>,
+        count_art: usize,
+    ) -> (Collection, SearchIndex, f32, f32, f32) {
+        //-------------------------------------------------------------------------------- 5
+        let now = now!();
+        send!(to_kernel, CcdToKernel::UpdatePhase((52.50, Phase::Sort)));
+
+        let sort_artist_lexi = Self::sort_artist_lexi(&vec_artist);
+        let sort_artist_lexi_rev = sort_artist_lexi
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[ArtistKey]>>();
+        let sort_artist_album_count = Self::sort_artist_album_count(&vec_artist);
+        let sort_artist_album_count_rev = sort_artist_album_count
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[ArtistKey]>>();
+        let sort_artist_song_count = Self::sort_artist_song_count(&vec_artist, &vec_album);
+        let sort_artist_song_count_rev = sort_artist_song_count
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[ArtistKey]>>();
+        let sort_artist_runtime = Self::sort_artist_runtime(&vec_artist);
+        let sort_artist_runtime_rev = sort_artist_runtime
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[ArtistKey]>>();
+        let sort_artist_name = Self::sort_artist_name(&vec_artist);
+        let sort_artist_name_rev = sort_artist_name
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[ArtistKey]>>();
+
+        let sort_album_release_artist_lexi =
+            Self::sort_album_release_artist_iter(&sort_artist_lexi, &vec_artist, &vec_album);
+        let sort_album_release_artist_lexi_rev =
+            Self::sort_album_release_artist_iter(&sort_artist_lexi_rev, &vec_artist, &vec_album);
+        let sort_album_release_rev_artist_lexi =
+            Self::sort_album_release_rev_artist_iter(&sort_artist_lexi, &vec_artist, &vec_album);
+        let sort_album_release_rev_artist_lexi_rev = Self::sort_album_release_rev_artist_iter(
+            &sort_artist_lexi_rev,
+            &vec_artist,
+            &vec_album,
+        );
+        let sort_album_lexi_artist_lexi =
+            Self::sort_album_lexi_artist_iter(&sort_artist_lexi, &vec_artist, &vec_album);
+        let sort_album_lexi_artist_lexi_rev =
+            Self::sort_album_lexi_artist_iter(&sort_artist_lexi_rev, &vec_artist, &vec_album);
+        let sort_album_lexi_rev_artist_lexi =
+            Self::sort_album_lexi_rev_artist_iter(&sort_artist_lexi, &vec_artist, &vec_album);
+        let sort_album_lexi_rev_artist_lexi_rev =
+            Self::sort_album_lexi_rev_artist_iter(&sort_artist_lexi_rev, &vec_artist, &vec_album);
+        let sort_album_lexi = Self::sort_album_lexi(&vec_album);
+        let sort_album_lexi_rev = sort_album_lexi
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[AlbumKey]>>();
+        let sort_album_release = Self::sort_album_release(&vec_album);
+        let sort_album_release_rev = sort_album_release
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[AlbumKey]>>();
+        let sort_album_runtime = Self::sort_album_runtime(&vec_album);
+        let sort_album_runtime_rev = sort_album_runtime
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[AlbumKey]>>();
+        let sort_album_title = Self::sort_album_title(&vec_album);
+        let sort_album_title_rev = sort_album_title
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[AlbumKey]>>();
+
+        let sort_song_album_release_artist_lexi =
+            Self::sort_song(&sort_album_release_artist_lexi, &vec_album);
+        let sort_song_album_release_artist_lexi_rev =
+            Self::sort_song(&sort_album_release_artist_lexi_rev, &vec_album);
+        let sort_song_album_release_rev_artist_lexi =
+            Self::sort_song(&sort_album_release_rev_artist_lexi, &vec_album);
+        let sort_song_album_release_rev_artist_lexi_rev =
+            Self::sort_song(&sort_album_release_rev_artist_lexi_rev, &vec_album);
+        let sort_song_album_lexi_artist_lexi =
+            Self::sort_song(&sort_album_lexi_artist_lexi, &vec_album);
+        let sort_song_album_lexi_artist_lexi_rev =
+            Self::sort_song(&sort_album_lexi_artist_lexi_rev, &vec_album);
+        let sort_song_album_lexi_rev_artist_lexi =
+            Self::sort_song(&sort_album_lexi_rev_artist_lexi, &vec_album);
+        let sort_song_album_lexi_rev_artist_lexi_rev =
+            Self::sort_song(&sort_album_lexi_rev_artist_lexi_rev, &vec_album);
+        let sort_song_release = Self::sort_song(&sort_album_release, &vec_album);
+        let sort_song_release_rev = sort_song_release
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[SongKey]>>();
+        let sort_song_lexi = Self::sort_song_lexi(&vec_song);
+        let sort_song_lexi_rev = sort_song_lexi
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[SongKey]>>();
+        let sort_song_runtime = Self::sort_song_runtime(&vec_song);
+        let sort_song_runtime_rev = sort_song_runtime
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[SongKey]>>();
+        let sort_song_title = Self::sort_song_title(&vec_song);
+        let sort_song_title_rev = sort_song_title
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Box<[SongKey]>>();
+
+        let sort_artist_genre =
+            Self::sort_artist_genre(&sort_artist_lexi, &vec_artist, &vec_album, false);
+        let sort_artist_genre_rev =
+            Self::sort_artist_genre(&sort_artist_lexi, &vec_artist, &vec_album, true);
+        let sort_album_genre =
+            Self::sort_album_genre(&sort_album_release_artist_lexi, &vec_album, false);
+        let sort_album_genre_rev =
+            Self::sort_album_genre(&sort_album_release_artist_lexi, &vec_album, true);
+        let genres = Self::genres(&sort_album_release_artist_lexi, &vec_album);
+
+        let perf_sort = secs_f32!(now);
+        trace!("CCD [5/14] ... Sort: {perf_sort}");
+
+        //-------------------------------------------------------------------------------- 6
+        let now = now!();
+        send!(to_kernel, CcdToKernel::UpdatePhase((55.00, Phase::Search)));
+        let map = Map::from_3_vecs(&vec_artist, &vec_album, &vec_song);
+        let mut index = SearchIndex::from_3_vecs(&vec_artist, &vec_album, &vec_song);
+        let perf_map = secs_f32!(now);
+        trace!("CCD [6/14] ... Map: {perf_map}");
+
+        //-------------------------------------------------------------------------------- 7
+        let now = now!();
+        send!(to_kernel, CcdToKernel::UpdatePhase((60.00, Phase::Prepare)));
+        let mut collection = Collection {
+            // These will be fixed after construction.
+            empty: false,
+            timestamp: 0,
+            count_artist: Unsigned::zero(),
+            count_album: Unsigned::zero(),
+            count_song: Unsigned::zero(),
+
+            // We calculated this during "The Loop".
+            count_art: Unsigned::from(count_art),
+
+            map,
+
+            artists: Artists::from_vec(vec_artist),
+            albums: Albums::from_vec(vec_album),
+            songs: Songs::from_vec(vec_song),
+
+            sort_artist_lexi,
+            sort_artist_lexi_rev,
+            sort_artist_album_count,
+            sort_artist_album_count_rev,
+            sort_artist_song_count,
+            sort_artist_song_count_rev,
+            sort_artist_runtime,
+            sort_artist_runtime_rev,
+            sort_artist_name,
+            sort_artist_name_rev,
+
+            sort_album_release_artist_lexi,
+            sort_album_release_artist_lexi_rev,
+            sort_album_release_rev_artist_lexi,
+            sort_album_release_rev_artist_lexi_rev,
+            sort_album_lexi_artist_lexi,
+            sort_album_lexi_artist_lexi_rev,
+            sort_album_lexi_rev_artist_lexi,
+            sort_album_lexi_rev_artist_lexi_rev,
+            sort_album_lexi,
+            sort_album_lexi_rev,
+            sort_album_release,
+            sort_album_release_rev,
+            sort_album_runtime,
+            sort_album_runtime_rev,
+            sort_album_title,
+            sort_album_title_rev,
+
+            sort_song_album_release_artist_lexi,
+            sort_song_album_release_artist_lexi_rev,
+            sort_song_album_release_rev_artist_lexi,
+            sort_song_album_release_rev_artist_lexi_rev,
+            sort_song_album_lexi_artist_lexi,
+            sort_song_album_lexi_artist_lexi_rev,
+            sort_song_album_lexi_rev_artist_lexi,
+            sort_song_album_lexi_rev_artist_lexi_rev,
+            sort_song_lexi,
+            sort_song_lexi_rev,
+            sort_song_release,
+            sort_song_release_rev,
+            sort_song_runtime,
+            sort_song_runtime_rev,
+            sort_song_title,
+            sort_song_title_rev,
+
+            sort_artist_genre,
+            sort_artist_genre_rev,
+            sort_album_genre,
+            sort_album_genre_rev,
+            genres,
+        };
+        // Fix metadata.
+        {
+            // Get `Vec` lengths.
+            let artists = collection.artists.len();
+            let albums = collection.albums.len();
+            let songs = collection.songs.len();
+
+            // Set `empty`.
+            if artists == 0 && albums == 0 && songs == 0 {
+                collection.empty = true;
+            } else {
+                collection.empty = false;
+            }
+
+            // Set `count_*`.
+            collection.count_artist = Unsigned::from(artists);
+            collection.count_album = Unsigned::from(albums);
+            collection.count_song = Unsigned::from(songs);
+
+            // Set `timestamp`.
+            collection.timestamp = benri::unix!();
+        }
+        index.set_collection(&collection);
+        let perf_prepare = secs_f32!(now);
+        trace!("CCD [7/14] ... Prepare: {perf_prepare}");
+
+        (collection, index, perf_sort, perf_map, perf_prepare)
+    }
 }
 
 //---------------------------------------------------------------------------------------------------- TESTS
@@ -0,0 +1,253 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::collection::{Album, Artist, Song, SongKey};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+//---------------------------------------------------------------------------------------------------- Constants
+// Separators between multiple artists, e.g: `Artist A & Artist B`.
+//
+// `,` is not included since it is
+// used within names, e.g: `Tyler, The Creator`.
+const SEPARATORS: [&str; 6] = [" & ", " x ", " × ", " / ", "; ", " vs. "];
+
+// Markers before featured artists, e.g: `Artist A feat. Artist B`.
+//
+// These are matched case-insensitively.
+const FEATURING: [&str; 5] = ["featuring ", "feat. ", "feat ", "ft. ", "ft "];
+
+//---------------------------------------------------------------------------------------------------- Credits
+impl crate::ccd::Ccd {
+    // Split an artist-like credit into the main and featured artists.
+    //
+    // `Artist A & Artist B feat. Artist C` -> (["Artist A", "Artist B"], ["Artist C"])
+    pub(super) fn split_credit(credit: &str) -> (Vec<String>, Vec<String>) {
+        match Self::find_featuring(credit) {
+            Some((main, featured)) => (Self::split_artists(main), Self::split_artists(featured)),
+            None => (Self::split_artists(credit), vec![]),
+        }
+    }
+
+    // The featured artists within a `Song` title.
+    //
+    // `Title (feat. Artist B)` -> ["Artist B"]
+    pub(super) fn split_title(title: &str) -> Vec<String> {
+        match Self::find_featuring(title) {
+            Some((_, featured)) => Self::split_artists(featured),
+            None => vec![],
+        }
+    }
+
+    // Push the names in `new` that aren't already in `vec`.
+    pub(super) fn extend_unique(vec: &mut Vec<String>, new: Vec<String>) {
+        for name in new {
+            if !vec.contains(&name) {
+                vec.push(name);
+            }
+        }
+    }
+
+    // Split `s` into (before, after) the first featuring marker.
+    //
+    // The marker must be after a space or opening bracket, so
+    // names like `Daft Punk` or titles like `Ft. Lauderdale` are left alone.
+    //
+    // If the marker is within `()` or `[]`, `after` ends at the closing bracket.
+    fn find_featuring(s: &str) -> Option<(&str, &str)> {
+        // ASCII lowercase keeps the byte indices the same.
+        let lower = s.to_ascii_lowercase();
+        let bytes = lower.as_bytes();
+
+        let (start, end) = FEATURING
+            .iter()
+            .filter_map(|marker| {
+                lower
+                    .match_indices(marker)
+                    .find(|(i, _)| *i > 0 && matches!(bytes[i - 1], b' ' | b'(' | b'['))
+                    .map(|(i, _)| (i, i + marker.len()))
+            })
+            .min()?;
+
+        let before = s[..start]
+            .trim_end()
+            .trim_end_matches(&['(', '['][..])
+            .trim_end();
+
+        let after = &s[end..];
+        let after = match bytes[start - 1] {
+            b'(' => after.split(')').next().unwrap_or(after),
+            b'[' => after.split(']').next().unwrap_or(after),
+            _ => after,
+        };
+
+        Some((before, after))
+    }
+
+    // Split on `SEPARATORS`, without empty or duplicate names.
+    fn split_artists(s: &str) -> Vec<String> {
+        let mut split = vec![s];
+        for sep in SEPARATORS {
+            split = split.into_iter().flat_map(|s| s.split(sep)).collect();
+        }
+
+        let mut vec = Vec::with_capacity(split.len());
+        Self::extend_unique(
+            &mut vec,
+            split
+                .into_iter()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect(),
+        );
+        vec
+    }
+
+    // Collect the `Song`'s each `Artist` appears on, but doesn't own.
+    //
+    // Names are matched against `Artist` names case-insensitively.
+    //
+    // `Album`'s are walked in release order (and the `Song`'s
+    // should already be in track order), so the keys are as well.
+    pub(super) fn appearances(vec_artist: &mut [Artist], vec_album: &[Album], vec_song: &[Song]) {
+        let memory: HashMap<Arc<str>, usize> = vec_artist
+            .iter()
+            .enumerate()
+            .map(|(i, artist)| (Arc::clone(&artist.name_lowercase), i))
+            .collect();
+
+        let mut appearances: Vec<Vec<SongKey>> = vec![vec![]; vec_artist.len()];
+
+        let mut albums: Vec<&Album> = vec_album.iter().collect();
+        albums.sort_by(|a, b| a.release.cmp(&b.release));
+
+        for album in albums {
+            for key in album.songs.iter() {
+                let song = &vec_song[key.inner()];
+
+                for name in song.artists.iter().chain(song.featured.iter()) {
+                    let Some(&i) = memory.get(&*name.to_lowercase()) else {
+                        continue;
+                    };
+
+                    // Their own `Song`, or already added by another name.
+                    if i == album.artist.inner() || appearances[i].last() == Some(key) {
+                        continue;
+                    }
+
+                    appearances[i].push(*key);
+                }
+            }
+        }
+
+        for (artist, songs) in vec_artist.iter_mut().zip(appearances) {
+            artist.appearances = songs.into_boxed_slice();
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use crate::ccd::Ccd;
+    use crate::collection::{AlbumKey, ArtistKey};
+
+    fn strings(s: &[&str]) -> Vec<String> {
+        s.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    // Main and featured artists are split.
+    fn split_credit() {
+        let cases: [(&str, &[&str], &[&str]); 9] = [
+            ("Artist A", &["Artist A"], &[]),
+            ("Artist A & Artist B", &["Artist A", "Artist B"], &[]),
+            ("Artist A x Artist B", &["Artist A", "Artist B"], &[]),
+            ("Artist A feat. Artist B", &["Artist A"], &["Artist B"]),
+            (
+                "Artist A Ft. Artist B & Artist C",
+                &["Artist A"],
+                &["Artist B", "Artist C"],
+            ),
+            (
+                "Artist A (featuring Artist B)",
+                &["Artist A"],
+                &["Artist B"],
+            ),
+            ("Artist A & Artist A", &["Artist A"], &[]),
+            ("Tyler, The Creator", &["Tyler, The Creator"], &[]),
+            ("Daft Punk", &["Daft Punk"], &[]),
+        ];
+
+        for (input, main, featured) in cases {
+            assert_eq!(
+                Ccd::split_credit(input),
+                (strings(main), strings(featured)),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    // Featured artists are found in titles.
+    fn split_title() {
+        assert_eq!(
+            Ccd::split_title("Title (feat. Artist B) [Remix]"),
+            strings(&["Artist B"])
+        );
+        assert_eq!(
+            Ccd::split_title("Title [ft. Artist B x Artist C]"),
+            strings(&["Artist B", "Artist C"])
+        );
+        assert_eq!(Ccd::split_title("Ft. Lauderdale"), strings(&[]));
+        assert_eq!(Ccd::split_title("Left Behind"), strings(&[]));
+    }
+
+    #[test]
+    // `Artist`'s get the other `Artist`'s `Song`'s they are credited on.
+    fn appearances() {
+        use crate::collection::{Album, Artist, Song, SongKey};
+
+        let artist = |key: usize, name: &str| Artist {
+            key: ArtistKey::from(key),
+            name: name.into(),
+            name_lowercase: name.to_lowercase().into(),
+            ..Default::default()
+        };
+        let song = |key: usize, album: usize, artists: &[&str], featured: &[&str]| Song {
+            key: SongKey::from(key),
+            album: AlbumKey::from(album),
+            artists: artists.iter().map(|s| (*s).into()).collect(),
+            featured: featured.iter().map(|s| (*s).into()).collect(),
+            ..Default::default()
+        };
+
+        let mut artists = vec![artist(0, "Artist A"), artist(1, "Artist B")];
+        let albums = vec![
+            Album {
+                key: AlbumKey::from(0_usize),
+                artist: ArtistKey::from(0_usize),
+                songs: vec![SongKey::from(0_usize), SongKey::from(1_usize)],
+                ..Default::default()
+            },
+            Album {
+                key: AlbumKey::from(1_usize),
+                artist: ArtistKey::from(1_usize),
+                songs: vec![SongKey::from(2_usize)],
+                ..Default::default()
+            },
+        ];
+        let songs = vec![
+            song(0, 0, &["Artist A"], &["artist b"]),
+            song(1, 0, &["Artist A", "Artist B"], &["Artist B"]),
+            song(2, 1, &["Artist B"], &["Artist C"]),
+        ];
+
+        Ccd::appearances(&mut artists, &albums, &songs);
+
+        assert!(artists[0].appearances.is_empty());
+        assert_eq!(
+            &*artists[1].appearances,
+            &[SongKey::from(0_usize), SongKey::from(1_usize)]
+        );
+    }
+}
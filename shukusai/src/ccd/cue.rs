@@ -0,0 +1,356 @@
+//---------------------------------------------------------------------------------------------------- Use
+use log::trace;
+use std::path::Path;
+use symphonia::core::{formats::Cue, meta::Tag};
+
+//---------------------------------------------------------------------------------------------------- Constants
+// CUE sheet timestamps are in `mm:ss:ff`, with 75 frames per second.
+const FRAMES_PER_SECOND: u64 = 75;
+
+// The FLAC `CUESHEET` block's lead-out track number.
+const LEAD_OUT: u32 = 170;
+
+//---------------------------------------------------------------------------------------------------- CueSheet
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+// The parts of a CUE sheet we care about.
+pub(super) struct CueSheet {
+    pub(super) performer: Option<String>,
+    pub(super) title: Option<String>,
+    pub(super) date: Option<String>,
+    pub(super) genre: Option<String>,
+    pub(super) tracks: Vec<CueTrack>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+// A single track within a CUE sheet.
+pub(super) struct CueTrack {
+    pub(super) number: u32,
+    pub(super) title: Option<String>,
+    pub(super) performer: Option<String>,
+    pub(super) songwriter: Option<String>,
+    // The `FILE` this track is in.
+    pub(super) file: Option<String>,
+    // The start of this track (`INDEX 01`) in milliseconds.
+    pub(super) start: u64,
+}
+
+impl CueSheet {
+    // Parse the text of a CUE sheet.
+    //
+    // Unknown commands, non-audio tracks and
+    // tracks without an `INDEX` are ignored.
+    pub(super) fn parse(s: &str) -> Self {
+        let mut sheet = Self::default();
+        let mut file = None;
+        // The current track, and if it is an audio track with an `INDEX 01`.
+        let mut track: Option<(CueTrack, bool)> = None;
+        let mut index_00 = None;
+
+        for line in s.lines() {
+            let line = line.trim();
+            let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let rest = rest.trim();
+
+            match command.to_ascii_uppercase().as_str() {
+                "FILE" => file = Some(Self::file_name(rest)),
+                "TRACK" => {
+                    sheet.push(track.take(), index_00.take());
+                    let mut split = rest.split_whitespace();
+                    let number = split.next().and_then(|n| n.parse().ok());
+                    let audio = split
+                        .next()
+                        .is_some_and(|t| t.eq_ignore_ascii_case("AUDIO"));
+                    if let (Some(number), true) = (number, audio) {
+                        let t = CueTrack {
+                            number,
+                            file: file.clone(),
+                            ..Default::default()
+                        };
+                        track = Some((t, false));
+                    }
+                }
+                "INDEX" => {
+                    let Some((t, found)) = track.as_mut() else {
+                        continue;
+                    };
+                    let mut split = rest.split_whitespace();
+                    let index = split.next().and_then(|i| i.parse::<u32>().ok());
+                    let Some(ms) = split.next().and_then(Self::timestamp) else {
+                        continue;
+                    };
+                    match index {
+                        Some(0) => index_00 = Some(ms),
+                        Some(1) => {
+                            t.start = ms;
+                            *found = true;
+                        }
+                        _ => (),
+                    }
+                }
+                "TITLE" => match track.as_mut() {
+                    Some((t, _)) => t.title = Self::value(rest),
+                    None => sheet.title = Self::value(rest),
+                },
+                "PERFORMER" => match track.as_mut() {
+                    Some((t, _)) => t.performer = Self::value(rest),
+                    None => sheet.performer = Self::value(rest),
+                },
+                "SONGWRITER" => {
+                    if let Some((t, _)) = track.as_mut() {
+                        t.songwriter = Self::value(rest);
+                    }
+                }
+                "REM" => {
+                    let (key, value) = rest.split_once(' ').unwrap_or((rest, ""));
+                    match key.to_ascii_uppercase().as_str() {
+                        "DATE" => sheet.date = Self::value(value),
+                        "GENRE" => sheet.genre = Self::value(value),
+                        _ => (),
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        sheet.push(track, index_00);
+        sheet
+    }
+
+    // Create a `CueSheet` from the FLAC `CUESHEET` block.
+    //
+    // This block only contains the track offsets, no titles, etc.
+    pub(super) fn from_cues(cues: &[Cue], sample_rate: u32) -> Option<Self> {
+        if cues.is_empty() || sample_rate == 0 {
+            return None;
+        }
+
+        let tracks = cues
+            .iter()
+            .filter(|cue| cue.index != 0 && cue.index != LEAD_OUT)
+            .map(|cue| CueTrack {
+                number: cue.index,
+                start: cue.start_ts * 1000 / u64::from(sample_rate),
+                ..Default::default()
+            })
+            .collect();
+
+        Some(Self {
+            tracks,
+            ..Default::default()
+        })
+    }
+
+    // Only keep the tracks that are within the audio file at `path`.
+    //
+    // The extension is ignored since CUE sheets
+    // often still point to the original `.wav`.
+    fn retain_file(&mut self, path: &Path) {
+        let stem = |s: &str| match s.rsplit_once('.') {
+            Some((stem, _)) => stem.to_lowercase(),
+            None => s.to_lowercase(),
+        };
+
+        let Some(name) = path.file_name().map(|n| stem(&n.to_string_lossy())) else {
+            self.tracks.clear();
+            return;
+        };
+
+        self.tracks
+            .retain(|t| t.file.as_deref().is_some_and(|f| stem(f) == name));
+    }
+
+    // Push a finished track.
+    //
+    // `INDEX 00` (the pre-gap) is only used if there is no `INDEX 01`.
+    fn push(&mut self, track: Option<(CueTrack, bool)>, index_00: Option<u64>) {
+        match (track, index_00) {
+            (Some((t, true)), _) => self.tracks.push(t),
+            (Some((mut t, false)), Some(ms)) => {
+                t.start = ms;
+                self.tracks.push(t);
+            }
+            _ => (),
+        }
+    }
+
+    // `mm:ss:ff` -> milliseconds.
+    fn timestamp(s: &str) -> Option<u64> {
+        let mut split = s.split(':').map(|n| n.parse::<u64>().ok());
+        let (Some(Some(m)), Some(Some(s)), Some(Some(f)), None) =
+            (split.next(), split.next(), split.next(), split.next())
+        else {
+            return None;
+        };
+        Some((m * 60 + s) * 1000 + f * 1000 / FRAMES_PER_SECOND)
+    }
+
+    // A (maybe) quoted value.
+    fn value(s: &str) -> Option<String> {
+        let s = s.trim();
+        let s = s
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .unwrap_or(s)
+            .trim();
+
+        if s.is_empty() {
+            None
+        } else {
+            Some(s.to_string())
+        }
+    }
+
+    // The file name within a `FILE "name" TYPE` command.
+    fn file_name(s: &str) -> String {
+        let name = match s.strip_prefix('"') {
+            Some(s) => s.split('"').next().unwrap_or(s),
+            None => s.split_whitespace().next().unwrap_or(s),
+        };
+
+        // Only the name, not any (Windows) directories.
+        name.rsplit(['/', '\\']).next().unwrap_or(name).to_string()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- CUE sheet functions
+impl crate::ccd::Ccd {
+    // Find the CUE sheet of the audio file at `path`, in order of priority:
+    //
+    // 1. A `.cue` file in the same directory with tracks in `path`
+    // 2. A `CUESHEET` tag, the text of a CUE sheet embedded by some rippers
+    // 3. The FLAC `CUESHEET` block (`block`)
+    //
+    // `None` is returned if there are less than 2 tracks,
+    // i.e, the file does not need to be split.
+    pub(super) fn cue_sheet(
+        path: &Path,
+        tags: &[Tag],
+        block: Option<CueSheet>,
+    ) -> Option<CueSheet> {
+        let sheet = Self::cue_file(path)
+            .or_else(|| {
+                tags.iter()
+                    .find(|t| t.key.eq_ignore_ascii_case("CUESHEET"))
+                    .and_then(Self::value_str)
+                    .map(CueSheet::parse)
+            })
+            .filter(|s| s.tracks.len() > 1)
+            .or(block)?;
+
+        if sheet.tracks.len() > 1 {
+            trace!(
+                "CCD - CUE sheet, {} tracks: {}",
+                sheet.tracks.len(),
+                path.display()
+            );
+            Some(sheet)
+        } else {
+            None
+        }
+    }
+
+    // Look for a `.cue` file next to `path` that has tracks in it.
+    fn cue_file(path: &Path) -> Option<CueSheet> {
+        let entries = std::fs::read_dir(path.parent()?).ok()?;
+
+        for entry in entries.filter_map(Result::ok) {
+            let cue = entry.path();
+            if !Self::path_is_cue(&cue) {
+                continue;
+            }
+
+            let Ok(bytes) = std::fs::read(&cue) else {
+                continue;
+            };
+
+            // Strip the BOM, and don't fail on non-UTF-8 sheets.
+            let text = String::from_utf8_lossy(&bytes);
+            let mut sheet = CueSheet::parse(text.trim_start_matches('\u{feff}'));
+            sheet.retain_file(path);
+
+            if sheet.tracks.len() > 1 {
+                return Some(sheet);
+            }
+        }
+
+        None
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUE: &str = r#"REM GENRE Rock
+REM DATE 2018
+PERFORMER "Artist A"
+TITLE "Album A"
+FILE "Album A.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "Song 1"
+    PERFORMER "Artist A"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Song 2"
+    PERFORMER "Artist A feat. Artist B"
+    SONGWRITER "Composer"
+    INDEX 00 03:10:00
+    INDEX 01 03:12:30
+  TRACK 03 AUDIO
+    TITLE "Song 3"
+    INDEX 00 07:00:00
+FILE "Bonus.wav" WAVE
+  TRACK 04 AUDIO
+    TITLE "Song 4"
+    INDEX 01 00:00:00
+"#;
+
+    #[test]
+    // All the metadata is parsed.
+    fn parse() {
+        let sheet = CueSheet::parse(CUE);
+
+        assert_eq!(sheet.performer.as_deref(), Some("Artist A"));
+        assert_eq!(sheet.title.as_deref(), Some("Album A"));
+        assert_eq!(sheet.date.as_deref(), Some("2018"));
+        assert_eq!(sheet.genre.as_deref(), Some("Rock"));
+        assert_eq!(sheet.tracks.len(), 4);
+
+        assert_eq!(
+            sheet.tracks[1],
+            CueTrack {
+                number: 2,
+                title: Some("Song 2".into()),
+                performer: Some("Artist A feat. Artist B".into()),
+                songwriter: Some("Composer".into()),
+                file: Some("Album A.wav".into()),
+                start: 192_400,
+            }
+        );
+
+        // `INDEX 00` is used without an `INDEX 01`.
+        assert_eq!(sheet.tracks[2].start, 420_000);
+        assert_eq!(sheet.tracks[3].file.as_deref(), Some("Bonus.wav"));
+    }
+
+    #[test]
+    // Only the tracks within the file are kept, ignoring the extension.
+    fn retain_file() {
+        let mut sheet = CueSheet::parse(CUE);
+        sheet.retain_file(Path::new("/music/Album A/album a.flac"));
+
+        let numbers: Vec<u32> = sheet.tracks.iter().map(|t| t.number).collect();
+        assert_eq!(numbers, [1, 2, 3]);
+    }
+
+    #[test]
+    // `mm:ss:ff` is converted correctly.
+    fn timestamp() {
+        assert_eq!(CueSheet::timestamp("00:00:00"), Some(0));
+        assert_eq!(CueSheet::timestamp("01:02:75"), Some(63_000));
+        assert_eq!(CueSheet::timestamp("61:00:15"), Some(3_660_200));
+        assert_eq!(CueSheet::timestamp("00:00"), None);
+        assert_eq!(CueSheet::timestamp("aa:00:00"), None);
+    }
+}
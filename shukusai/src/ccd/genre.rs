@@ -0,0 +1,155 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::collection::{Album, AlbumKey, Genre, SongKey};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+//---------------------------------------------------------------------------------------------------- Constants
+// Separators between multiple genres, e.g: `Rock; Electronic`.
+//
+// `\0` is how `ID3v2.4` separates multiple values.
+//
+// A `/` without spaces is not included since it
+// is used within genres, e.g: `Hip-Hop/Rap`, `R&B/Soul`.
+const SEPARATORS: [&str; 4] = [";", ",", "\0", " / "];
+
+//---------------------------------------------------------------------------------------------------- Genres
+impl crate::ccd::Ccd {
+    // Split a genre tag on `SEPARATORS`, without empty or duplicate genres.
+    //
+    // `Rock; Electronic` -> ["Rock", "Electronic"]
+    pub(super) fn split_genre(s: &str) -> Vec<String> {
+        let mut split = vec![s];
+        for sep in SEPARATORS {
+            split = split.into_iter().flat_map(|s| s.split(sep)).collect();
+        }
+
+        let mut vec = Vec::with_capacity(split.len());
+        Self::extend_unique(
+            &mut vec,
+            split
+                .into_iter()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect(),
+        );
+        vec
+    }
+
+    // Create the `Genre`'s of the `Collection`, sorted A-Z.
+    //
+    // Genres are merged case-insensitively, the
+    // spelling of the first `Album` found is kept.
+    //
+    // `sorted_albums` should be `Artist` A-Z, `Album` release order,
+    // so the keys within each `Genre` are in that order as well.
+    pub(super) fn genres(sorted_albums: &[AlbumKey], albums: &[Album]) -> Box<[Genre]> {
+        let mut memory: HashMap<Arc<str>, usize> = HashMap::new();
+        let mut vec: Vec<(Arc<str>, Arc<str>, Vec<AlbumKey>, Vec<SongKey>)> = vec![];
+
+        for key in sorted_albums {
+            let album = &albums[key.inner()];
+
+            for name in album.genres.iter() {
+                let lowercase: Arc<str> = name.to_lowercase().into();
+
+                let i = match memory.get(&lowercase) {
+                    Some(i) => *i,
+                    None => {
+                        memory.insert(Arc::clone(&lowercase), vec.len());
+                        vec.push((Arc::clone(name), lowercase, vec![], vec![]));
+                        vec.len() - 1
+                    }
+                };
+
+                // The same genre with different cases, e.g: `Rock; rock`.
+                let (_, _, keys, songs) = &mut vec[i];
+                if keys.last() == Some(key) {
+                    continue;
+                }
+
+                keys.push(*key);
+                songs.extend_from_slice(&album.songs);
+            }
+        }
+
+        vec.sort_by(|a, b| a.1.cmp(&b.1));
+
+        vec.into_iter()
+            .map(|(name, name_lowercase, albums, songs)| Genre {
+                name,
+                name_lowercase,
+                albums: albums.into_boxed_slice(),
+                songs: songs.into_boxed_slice(),
+            })
+            .collect()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use crate::ccd::Ccd;
+    use crate::collection::{Album, AlbumKey, SongKey};
+
+    fn strings(s: &[&str]) -> Vec<String> {
+        s.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    // Multi-valued genres are split.
+    fn split_genre() {
+        let cases: [(&str, &[&str]); 7] = [
+            ("Rock", &["Rock"]),
+            ("Rock; Electronic", &["Rock", "Electronic"]),
+            ("Rock,Pop, Jazz", &["Rock", "Pop", "Jazz"]),
+            ("Rock\0Pop", &["Rock", "Pop"]),
+            ("Rock / Pop", &["Rock", "Pop"]),
+            ("Hip-Hop/Rap", &["Hip-Hop/Rap"]),
+            ("Rock; ; Rock", &["Rock"]),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(Ccd::split_genre(input), strings(expected), "{input:?}");
+        }
+    }
+
+    #[test]
+    // `Album`'s are merged into `Genre`'s case-insensitively.
+    fn genres() {
+        let album = |key: usize, songs: &[usize], genres: &[&str]| Album {
+            key: AlbumKey::from(key),
+            songs: songs.iter().map(|k| SongKey::from(*k)).collect(),
+            genres: genres.iter().map(|s| (*s).into()).collect(),
+            ..Default::default()
+        };
+
+        let albums = vec![
+            album(0, &[0, 1], &["Rock", "Electronic"]),
+            album(1, &[2], &["electronic", "ELECTRONIC"]),
+            album(2, &[3], &[]),
+        ];
+        let sorted: Vec<AlbumKey> = [1_usize, 0, 2].into_iter().map(AlbumKey::from).collect();
+
+        let genres = Ccd::genres(&sorted, &albums);
+        assert_eq!(genres.len(), 2);
+
+        assert_eq!(&*genres[0].name, "electronic");
+        assert_eq!(&*genres[0].name_lowercase, "electronic");
+        assert_eq!(
+            &*genres[0].albums,
+            &[AlbumKey::from(1_usize), AlbumKey::from(0_usize)]
+        );
+        assert_eq!(
+            &*genres[0].songs,
+            &[
+                SongKey::from(2_usize),
+                SongKey::from(0_usize),
+                SongKey::from(1_usize)
+            ]
+        );
+
+        assert_eq!(&*genres[1].name, "Rock");
+        assert_eq!(&*genres[1].albums, &[AlbumKey::from(0_usize)]);
+    }
+}
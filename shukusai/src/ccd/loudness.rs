@@ -0,0 +1,355 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::collection::{Gain, Song};
+use std::path::Path;
+use symphonia::core::{
+    audio::{AudioBuffer, Channels, Signal},
+    codecs::{DecoderOptions, CODEC_TYPE_NULL},
+    errors::Error,
+};
+
+//---------------------------------------------------------------------------------------------------- Constants
+// Blocks quieter than this are ignored (absolute gate).
+const GATE_ABSOLUTE_LUFS: f64 = -70.0;
+// Blocks this much quieter than the ungated loudness are ignored (relative gate).
+const GATE_RELATIVE_LU: f64 = -10.0;
+
+//---------------------------------------------------------------------------------------------------- Biquad
+// A single `K-weighting` filter stage.
+#[derive(Clone, Debug)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 3],
+    // Direct form II state.
+    z: [f64; 2],
+}
+
+impl Biquad {
+    // The "head" shelving filter from `ITU-R BS.1770`,
+    // re-calculated for `rate` (the spec only lists 48kHz).
+    fn shelf(rate: f64) -> Self {
+        let f0 = 1681.974450955533;
+        let g = 3.999843853973347;
+        let q = 0.7071752369554196;
+
+        let k = (std::f64::consts::PI * f0 / rate).tan();
+        let vh = 10_f64.powf(g / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+
+        Self {
+            b: [
+                (vh + vb * k / q + k * k) / a0,
+                2.0 * (k * k - vh) / a0,
+                (vh - vb * k / q + k * k) / a0,
+            ],
+            a: [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            z: [0.0; 2],
+        }
+    }
+
+    // The "RLB" high-pass filter from `ITU-R BS.1770`.
+    fn highpass(rate: f64) -> Self {
+        let f0 = 38.13547087602444;
+        let q = 0.5003270373238773;
+
+        let k = (std::f64::consts::PI * f0 / rate).tan();
+        let a0 = 1.0 + k / q + k * k;
+
+        Self {
+            b: [1.0, -2.0, 1.0],
+            a: [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            z: [0.0; 2],
+        }
+    }
+
+    #[inline(always)]
+    fn process(&mut self, x: f64) -> f64 {
+        let w = x - self.a[1] * self.z[0] - self.a[2] * self.z[1];
+        let y = self.b[0] * w + self.b[1] * self.z[0] + self.b[2] * self.z[1];
+        self.z[1] = self.z[0];
+        self.z[0] = w;
+        y
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Meter
+// An `EBU R128` integrated loudness meter.
+//
+// Samples are `K-weighted`, then their mean square is taken
+// over `400ms` blocks that overlap by `75%` (a new block
+// every `100ms`), and the blocks are then gated.
+//
+// Reference: `ITU-R BS.1770-4` and `EBU Tech 3341`.
+#[derive(Clone, Debug)]
+pub(super) struct Meter {
+    // Per-channel `(shelf, highpass)` filters.
+    filters: Vec<(Biquad, Biquad)>,
+    // Per-channel weights (surrounds are louder, LFE is ignored).
+    weights: Vec<f64>,
+    // Frames in a `100ms` sub-block.
+    sub_len: usize,
+    // Frames in the current sub-block so far.
+    sub_frames: usize,
+    // Per-channel sum of squares of the current sub-block.
+    sub_sum: Vec<f64>,
+    // The weighted mean square of the last 4 sub-blocks.
+    last: [f64; 4],
+    // How many sub-blocks have been finished.
+    subs: usize,
+    // The weighted mean square of every `400ms` block.
+    blocks: Vec<f64>,
+    // The highest absolute sample seen.
+    peak: f32,
+}
+
+impl Meter {
+    pub(super) fn new(rate: u32, channels: Channels) -> Self {
+        let weights: Vec<f64> = channels
+            .iter()
+            .map(|c| {
+                if c == Channels::LFE1 || c == Channels::LFE2 {
+                    0.0
+                } else if c == Channels::SIDE_LEFT
+                    || c == Channels::SIDE_RIGHT
+                    || c == Channels::REAR_LEFT
+                    || c == Channels::REAR_RIGHT
+                {
+                    1.41
+                } else {
+                    1.0
+                }
+            })
+            .collect();
+
+        let rate_f = f64::from(rate);
+
+        Self {
+            filters: vec![(Biquad::shelf(rate_f), Biquad::highpass(rate_f)); weights.len()],
+            sub_sum: vec![0.0; weights.len()],
+            weights,
+            sub_len: (rate as usize / 10).max(1),
+            sub_frames: 0,
+            last: [0.0; 4],
+            subs: 0,
+            blocks: vec![],
+            peak: 0.0,
+        }
+    }
+
+    // Feed decoded samples into the meter.
+    //
+    // The buffer must have the channels/rate given to `new()`.
+    pub(super) fn push(&mut self, buf: &AudioBuffer<f32>) {
+        let channels = self.weights.len().min(buf.spec().channels.count());
+
+        for frame in 0..buf.frames() {
+            for ch in 0..channels {
+                let sample = buf.chan(ch)[frame];
+                self.peak = self.peak.max(sample.abs());
+
+                let (shelf, highpass) = &mut self.filters[ch];
+                let y = highpass.process(shelf.process(f64::from(sample)));
+                self.sub_sum[ch] += y * y;
+            }
+
+            self.sub_frames += 1;
+            if self.sub_frames == self.sub_len {
+                self.sub_block();
+            }
+        }
+    }
+
+    // Finish the current `100ms` sub-block, and
+    // if there are enough, the `400ms` block.
+    fn sub_block(&mut self) {
+        let power: f64 = self
+            .sub_sum
+            .iter()
+            .zip(&self.weights)
+            .map(|(sum, w)| w * sum / self.sub_len as f64)
+            .sum();
+
+        self.last[self.subs % 4] = power;
+        self.subs += 1;
+        if self.subs >= 4 {
+            self.blocks.push(self.last.iter().sum::<f64>() / 4.0);
+        }
+
+        self.sub_frames = 0;
+        self.sub_sum.iter_mut().for_each(|s| *s = 0.0);
+    }
+
+    // Returns the gated integrated loudness in `LUFS`,
+    // and the sample peak.
+    //
+    // `None` if the audio was too short or silent.
+    pub(super) fn finish(&self) -> Option<(f64, f32)> {
+        let abs: Vec<f64> = self
+            .blocks
+            .iter()
+            .copied()
+            .filter(|p| lufs(*p) > GATE_ABSOLUTE_LUFS)
+            .collect();
+
+        if abs.is_empty() {
+            return None;
+        }
+
+        let relative = lufs(mean(&abs)) + GATE_RELATIVE_LU;
+        let gated: Vec<f64> = abs.into_iter().filter(|p| lufs(*p) > relative).collect();
+
+        if gated.is_empty() {
+            return None;
+        }
+
+        Some((lufs(mean(&gated)), self.peak))
+    }
+}
+
+#[inline(always)]
+// Mean square -> `LUFS`.
+fn lufs(power: f64) -> f64 {
+    -0.691 + 10.0 * power.log10()
+}
+
+#[inline(always)]
+fn mean(slice: &[f64]) -> f64 {
+    slice.iter().sum::<f64>() / slice.len() as f64
+}
+
+//---------------------------------------------------------------------------------------------------- Loudness functions.
+impl crate::ccd::Ccd {
+    // Calculate the track [`Gain`] of an untagged file.
+    //
+    // This decodes the _entire_ file, so it's only used
+    // as a fallback when `REPLAYGAIN_*/R128_*` tags don't exist.
+    pub(super) fn loudness(path: &Path) -> Option<Gain> {
+        let mut format = Self::probe(path).ok()?.format;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)?;
+        let track_id = track.id;
+
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions { verify: false })
+            .ok()?;
+
+        let mut meter: Option<Meter> = None;
+        let mut buf: Option<AudioBuffer<f32>> = None;
+
+        loop {
+            let packet = match format.next_packet() {
+                Ok(p) => p,
+                // EOF, or a broken file, use what we have.
+                Err(_) => break,
+            };
+
+            if packet.track_id() != track_id {
+                continue;
+            }
+
+            let decoded = match decoder.decode(&packet) {
+                Ok(d) => d,
+                Err(Error::DecodeError(_)) => continue,
+                Err(_) => break,
+            };
+
+            // Re-use the buffer if possible.
+            let spec = *decoded.spec();
+            if !matches!(&buf, Some(b) if b.capacity() >= decoded.capacity() && *b.spec() == spec) {
+                buf = Some(decoded.make_equivalent::<f32>());
+            }
+
+            if let Some(b) = buf.as_mut() {
+                decoded.convert(b);
+                meter
+                    .get_or_insert_with(|| Meter::new(spec.rate, spec.channels))
+                    .push(b);
+            }
+        }
+
+        let (lufs, peak) = meter?.finish()?;
+
+        Some(Gain::from_lufs(lufs as f32, Some(peak)))
+    }
+
+    // Calculate an `Album`'s [`Gain`] from its `Song`'s.
+    //
+    // This is the runtime-weighted power mean of each `Song`'s
+    // loudness, which is close to (but not exactly) what measuring
+    // the whole `Album` as 1 stream would give.
+    //
+    // `None` if any `Song` is missing its [`Gain`].
+    pub(super) fn album_gain<'a>(songs: impl Iterator<Item = &'a Song>) -> Option<Gain> {
+        let mut power = 0.0_f64;
+        let mut weight = 0.0_f64;
+        let mut peak: Option<f32> = Some(0.0);
+
+        for song in songs {
+            let gain = song.gain?;
+            let w = f64::from(song.runtime.inner().max(1));
+
+            power += w * 10_f64.powf(f64::from(gain.lufs()) / 10.0);
+            weight += w;
+            peak = match (peak, gain.peak) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                _ => None,
+            };
+        }
+
+        if weight == 0.0 {
+            return None;
+        }
+
+        let lufs = 10.0 * (power / weight).log10();
+
+        Some(Gain::from_lufs(lufs as f32, peak))
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symphonia::core::audio::SignalSpec;
+
+    const RATE: u32 = 48_000;
+
+    // `seconds` of a 1kHz sine wave at `amplitude` in 1 channel.
+    fn sine(amplitude: f32, seconds: usize) -> AudioBuffer<f32> {
+        let frames = RATE as usize * seconds;
+        let spec = SignalSpec::new(RATE, Channels::FRONT_LEFT);
+        let mut buf = AudioBuffer::<f32>::new(frames as u64, spec);
+        buf.render_reserved(Some(frames));
+
+        for (i, s) in buf.chan_mut(0).iter_mut().enumerate() {
+            let t = i as f32 / RATE as f32;
+            *s = amplitude * (2.0 * std::f32::consts::PI * 1000.0 * t).sin();
+        }
+
+        buf
+    }
+
+    #[test]
+    // A `-20dBFS` 1kHz sine in 1 channel should be `-23 LUFS`.
+    //
+    // (`ITU-R BS.1770`: a full scale 1kHz sine in 1 channel is `-3.01 LUFS`).
+    fn sine_1khz() {
+        let mut meter = Meter::new(RATE, Channels::FRONT_LEFT);
+        meter.push(&sine(0.1, 5));
+
+        let (lufs, peak) = meter.finish().unwrap();
+        assert!((lufs - -23.01).abs() < 0.1, "{lufs}");
+        assert!((peak - 0.1).abs() < 0.001, "{peak}");
+    }
+
+    #[test]
+    // Silence is gated out entirely.
+    fn silence() {
+        let mut meter = Meter::new(RATE, Channels::FRONT_LEFT);
+        meter.push(&sine(0.0, 2));
+        assert!(meter.finish().is_none());
+    }
+}
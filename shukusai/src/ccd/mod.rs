@@ -3,6 +3,9 @@ mod mime;
 /// Collection creation performance
 pub mod perf;
 
+/// Folder-structure fallback for untagged files
+pub mod template;
+
 mod thread;
 pub(crate) use thread::*;
 
@@ -13,10 +16,19 @@ mod ccd;
 pub(crate) use ccd::*;
 
 //----- CCD internal functions.
+mod credits;
+mod cue;
+mod genre;
+mod loudness;
 mod sort;
 mod the_loop;
 mod walk;
 
+//----- Incremental `Collection` updates (no `GUI` art support).
+#[cfg(not(feature = "gui"))]
+#[cfg(feature = "daemon")]
+mod update;
+
 //----- Frontend specific.
 mod img;
 pub(crate) use img::*;
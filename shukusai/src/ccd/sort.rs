@@ -196,6 +196,96 @@ impl super::Ccd {
         vec_album.into_iter().map(AlbumKey::from).collect()
     }
 
+    //--------------------------------------------------------------- Genre sorts.
+    // INVARIANT:
+    // These require the `Album::genres` to be filled, and an already sorted
+    // A-Z `ArtistKey`/`AlbumKey` input, since the sort is stable and
+    // keeps that order within the same genre.
+    //
+    // `rev` only reverses the genre order, `Artist`'s/`Album`'s
+    // without a genre are always last.
+    pub(super) fn sort_artist_genre(
+        sorted_artists: &[ArtistKey],
+        artists: &[Artist],
+        albums: &[Album],
+        rev: bool,
+    ) -> Box<[ArtistKey]> {
+        let genres: Vec<Option<String>> = artists
+            .iter()
+            .map(|a| Self::artist_genre(a, albums))
+            .collect();
+
+        let mut vec_artist = sorted_artists.to_vec();
+        vec_artist.sort_by(|a, b| {
+            Self::cmp_genre(
+                genres[a.inner()].as_deref(),
+                genres[b.inner()].as_deref(),
+                rev,
+            )
+        });
+
+        vec_artist.into_boxed_slice()
+    }
+
+    pub(super) fn sort_album_genre(
+        sorted_albums: &[AlbumKey],
+        albums: &[Album],
+        rev: bool,
+    ) -> Box<[AlbumKey]> {
+        let genres: Vec<Option<String>> = albums
+            .iter()
+            .map(|a| a.genres.first().map(|g| g.to_lowercase()))
+            .collect();
+
+        let mut vec_album = sorted_albums.to_vec();
+        vec_album.sort_by(|a, b| {
+            Self::cmp_genre(
+                genres[a.inner()].as_deref(),
+                genres[b.inner()].as_deref(),
+                rev,
+            )
+        });
+
+        vec_album.into_boxed_slice()
+    }
+
+    // The most common primary genre within an `Artist`'s `Album`'s (lowercase).
+    //
+    // Ties go to the genre found first.
+    fn artist_genre(artist: &Artist, albums: &[Album]) -> Option<String> {
+        let mut count: Vec<(String, usize)> = vec![];
+
+        for key in artist.albums.iter() {
+            let Some(genre) = albums[key.inner()].genres.first() else {
+                continue;
+            };
+            let genre = genre.to_lowercase();
+
+            match count.iter_mut().find(|(g, _)| *g == genre) {
+                Some((_, c)) => *c += 1,
+                None => count.push((genre, 1)),
+            }
+        }
+
+        // `max_by_key()` returns the last max, so reverse.
+        count
+            .into_iter()
+            .rev()
+            .max_by_key(|(_, c)| *c)
+            .map(|(g, _)| g)
+    }
+
+    fn cmp_genre(a: Option<&str>, b: Option<&str>, rev: bool) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+        match (a, b) {
+            (Some(a), Some(b)) if rev => b.cmp(a),
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
     //--------------------------------------------------------------- `SongKey` sorts.
     // INVARIANT:
     // Needs an already sorted `Vec<AlbumKey>`.
@@ -0,0 +1,364 @@
+//---------------------------------------------------------------------------------------------------- Use
+use anyhow::{anyhow, bail};
+use benri::sync::*;
+use std::path::{Component, Path};
+use std::sync::RwLock;
+
+//---------------------------------------------------------------------------------------------------- Statics
+// The templates untagged files are matched against, in order.
+//
+// Empty by default, i.e, untagged files are skipped.
+static PATH_TEMPLATES: RwLock<Vec<PathTemplate>> = RwLock::new(Vec::new());
+
+/// Set the [`PathTemplate`]'s used when creating a `Collection`.
+///
+/// If a file is missing its artist or album tag (or has no tags at all),
+/// these templates are tried in order and the first match fills in the
+/// missing metadata. Metadata found in tags is always preferred.
+///
+/// Setting an empty `Vec` (the default) disables this.
+pub fn set_path_templates(templates: Vec<PathTemplate>) {
+    *lockw!(PATH_TEMPLATES) = templates;
+}
+
+/// The [`PathTemplate`]'s used when creating a `Collection`.
+pub fn path_templates() -> Vec<PathTemplate> {
+    lockr!(PATH_TEMPLATES).clone()
+}
+
+// Infer metadata from `path` with the first matching template.
+pub(crate) fn infer(path: &Path) -> Option<PathMetadata> {
+    infer_first(&lockr!(PATH_TEMPLATES), path)
+}
+
+// Same as `infer()`, with any `templates`.
+fn infer_first(templates: &[PathTemplate], path: &Path) -> Option<PathMetadata> {
+    templates.iter().find_map(|t| t.infer(path))
+}
+
+//---------------------------------------------------------------------------------------------------- PathMetadata
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+// Metadata inferred from a PATH.
+pub(crate) struct PathMetadata {
+    pub(crate) artist: String,
+    pub(crate) album: String,
+    pub(crate) release: Option<String>,
+    pub(crate) track: Option<u32>,
+    pub(crate) title: Option<String>,
+}
+
+//---------------------------------------------------------------------------------------------------- PathTemplate
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Field {
+    Artist,
+    Album,
+    Release,
+    Track,
+    Title,
+}
+
+impl Field {
+    const ALL: [(&'static str, Self); 5] = [
+        ("artist", Self::Artist),
+        ("album", Self::Album),
+        ("release", Self::Release),
+        ("track", Self::Track),
+        ("title", Self::Title),
+    ];
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Text(String),
+    Field(Field),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A template describing how metadata is laid out in a PATH
+///
+/// Each `/` separated part matches a directory, the
+/// last part matches the file name (without extension).
+///
+/// The template is matched against the end of the PATH,
+/// so it does not need to include the `Collection` root.
+///
+/// The available fields are:
+/// - `{artist}` (required)
+/// - `{album}` (required)
+/// - `{release}`
+/// - `{track}`
+/// - `{title}`
+///
+/// ```rust,ignore
+/// let t = PathTemplate::new("{artist}/{album} ({release})/{track} - {title}").unwrap();
+///
+/// // Matches:
+/// // `/music/Artist/Album (2018)/01 - Title.flac`
+/// ```
+///
+/// If `{title}` is not in the template, the file name is used.
+pub struct PathTemplate {
+    template: String,
+    parts: Vec<Vec<Token>>,
+}
+
+impl PathTemplate {
+    /// Parse a template.
+    ///
+    /// ## Errors
+    /// This errors if the template:
+    /// - has an unknown, unclosed or duplicate field
+    /// - has two fields next to each other, e.g: `{track}{title}`
+    /// - has an empty part, e.g: `{artist}//{album}`
+    /// - is missing `{artist}` or `{album}`
+    pub fn new(template: &str) -> Result<Self, anyhow::Error> {
+        let mut seen = vec![];
+        let mut parts = vec![];
+
+        for part in template.split('/') {
+            if part.is_empty() {
+                bail!("empty part in path template: {template}");
+            }
+
+            let mut tokens = vec![];
+            let mut rest = part;
+
+            while !rest.is_empty() {
+                let Some(start) = rest.find('{') else {
+                    tokens.push(Token::Text(rest.to_string()));
+                    break;
+                };
+
+                if start > 0 {
+                    tokens.push(Token::Text(rest[..start].to_string()));
+                }
+
+                let end = rest[start..]
+                    .find('}')
+                    .ok_or_else(|| anyhow!("unclosed field in path template: {template}"))?
+                    + start;
+
+                let name = &rest[start + 1..end];
+                let Some((_, field)) = Field::ALL.iter().find(|(n, _)| *n == name) else {
+                    bail!("unknown field {{{name}}} in path template: {template}");
+                };
+
+                if seen.contains(field) {
+                    bail!("duplicate field {{{name}}} in path template: {template}");
+                }
+                if let Some(Token::Field(_)) = tokens.last() {
+                    bail!("adjacent fields in path template: {template}");
+                }
+
+                seen.push(*field);
+                tokens.push(Token::Field(*field));
+                rest = &rest[end + 1..];
+            }
+
+            parts.push(tokens);
+        }
+
+        for (name, field) in [("artist", Field::Artist), ("album", Field::Album)] {
+            if !seen.contains(&field) {
+                bail!("missing field {{{name}}} in path template: {template}");
+            }
+        }
+
+        Ok(Self {
+            template: template.to_string(),
+            parts,
+        })
+    }
+
+    /// The original template `str`.
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    // Attempt to infer metadata from `path`.
+    //
+    // Returns `None` if `path` does not fit the template.
+    pub(crate) fn infer(&self, path: &Path) -> Option<PathMetadata> {
+        // The parent directories + the file name (without extension).
+        let stem = path.file_stem()?.to_str()?;
+        let mut names: Vec<&str> = path
+            .parent()?
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => Some(s.to_str()),
+                _ => None,
+            })
+            .collect::<Option<_>>()?;
+        names.push(stem);
+
+        if names.len() < self.parts.len() {
+            return None;
+        }
+
+        let mut found = vec![];
+        for (tokens, name) in self
+            .parts
+            .iter()
+            .zip(&names[names.len() - self.parts.len()..])
+        {
+            if !Self::matches(tokens, name, &mut found) {
+                return None;
+            }
+        }
+
+        let mut metadata = PathMetadata::default();
+        for (field, value) in found {
+            let value = value.to_string();
+            match field {
+                Field::Artist => metadata.artist = value,
+                Field::Album => metadata.album = value,
+                Field::Release => metadata.release = Some(value),
+                Field::Track => metadata.track = value.parse().ok(),
+                Field::Title => metadata.title = Some(value),
+            }
+        }
+
+        Some(metadata)
+    }
+
+    // Match `tokens` against all of `s`, pushing the field values onto `found`.
+    //
+    // Fields match as little as possible, except the
+    // last one in a part which takes the rest of `s`.
+    fn matches<'a>(tokens: &[Token], s: &'a str, found: &mut Vec<(Field, &'a str)>) -> bool {
+        let Some((first, tokens)) = tokens.split_first() else {
+            return s.is_empty();
+        };
+
+        let field = match first {
+            Token::Text(text) => {
+                return match s.strip_prefix(text.as_str()) {
+                    Some(rest) => Self::matches(tokens, rest, found),
+                    None => false,
+                };
+            }
+            Token::Field(field) => *field,
+        };
+
+        // Fields are never adjacent, so the next token
+        // (if there is one) is always `Token::Text`.
+        let ends: Vec<usize> = match tokens.first() {
+            Some(Token::Text(text)) => s.match_indices(text.as_str()).map(|(i, _)| i).collect(),
+            _ => vec![s.len()],
+        };
+
+        for end in ends {
+            let value = s[..end].trim();
+            if value.is_empty() {
+                continue;
+            }
+            if field == Field::Track && value.parse::<u32>().is_err() {
+                continue;
+            }
+
+            found.push((field, value));
+            if Self::matches(tokens, &s[end..], found) {
+                return true;
+            }
+            found.pop();
+        }
+
+        false
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Invalid templates are rejected.
+    fn new() {
+        for t in [
+            "{artist}/{album}",
+            "{artist}/{album} ({release})/{track} - {title}",
+            "{artist} - {album}",
+        ] {
+            assert!(PathTemplate::new(t).is_ok(), "{t}");
+        }
+
+        for t in [
+            "",
+            "{artist}",
+            "{album}/{title}",
+            "{artist}/{album}/{genre}",
+            "{artist}/{album}/{title",
+            "{artist}/{album}/{title}/{title}",
+            "{artist}/{album}/{track}{title}",
+            "{artist}//{album}",
+        ] {
+            assert!(PathTemplate::new(t).is_err(), "{t}");
+        }
+    }
+
+    #[test]
+    // All fields are inferred.
+    fn infer() {
+        let t = PathTemplate::new("{artist}/{album} ({release})/{track} - {title}").unwrap();
+
+        assert_eq!(
+            t.infer(Path::new(
+                "/music/Artist A/Album - B (2018)/01 - Title - C.flac"
+            )),
+            Some(PathMetadata {
+                artist: "Artist A".into(),
+                album: "Album - B".into(),
+                release: Some("2018".into()),
+                track: Some(1),
+                title: Some("Title - C".into()),
+            }),
+        );
+
+        // Too short, or not fitting the template.
+        assert_eq!(t.infer(Path::new("/Album (2018)/01 - Title.flac")), None);
+        assert_eq!(t.infer(Path::new("/Artist/Album/01 - Title.flac")), None);
+        assert_eq!(t.infer(Path::new("/Artist/Album (2018)/Title.flac")), None);
+    }
+
+    #[test]
+    // `{track}` must be a number.
+    fn infer_track() {
+        let t = PathTemplate::new("{artist}/{album}/{track} {title}").unwrap();
+
+        assert_eq!(
+            t.infer(Path::new("/Artist/Album/5 Title.mp3")),
+            Some(PathMetadata {
+                artist: "Artist".into(),
+                album: "Album".into(),
+                release: None,
+                track: Some(5),
+                title: Some("Title".into()),
+            }),
+        );
+        assert_eq!(t.infer(Path::new("/Artist/Album/Intro Title.mp3")), None);
+    }
+
+    #[test]
+    // The first matching template is used.
+    fn templates() {
+        let t = [
+            PathTemplate::new("{artist}/{album}/{track} - {title}").unwrap(),
+            PathTemplate::new("{artist} - {album}/{title}").unwrap(),
+        ];
+
+        assert_eq!(
+            infer_first(&t, Path::new("/Artist/Album/1 - Title.ogg"))
+                .unwrap()
+                .track,
+            Some(1)
+        );
+        assert_eq!(
+            infer_first(&t, Path::new("/Artist - Album/Title.ogg"))
+                .unwrap()
+                .artist,
+            "Artist"
+        );
+        assert_eq!(infer_first(&t, Path::new("/Title.ogg")), None);
+    }
+}
@@ -1,6 +1,9 @@
 //---------------------------------------------------------------------------------------------------- Use
 use super::CcdToKernel;
-use crate::collection::{Album, AlbumKey, Art, Artist, ArtistKey, Song, SongKey};
+use crate::ccd::cue::CueSheet;
+use crate::ccd::template::PathMetadata;
+use crate::collection::{Album, AlbumKey, Art, Artist, ArtistKey, Gain, Song, SongKey};
+use crate::constants::VARIOUS_ARTISTS;
 use anyhow::{anyhow, bail};
 use benri::sync::*;
 use crossbeam::channel::Sender;
@@ -33,6 +36,21 @@ struct TagMetadata {
     art: Option<Box<[u8]>>,
     release: Option<String>,
     genre: Option<String>,
+    genres: Vec<String>,
+    track_gain: Option<Gain>,
+    album_gain: Option<Gain>,
+    track_artist: Option<String>,
+    artists: Vec<String>,
+    composers: Vec<String>,
+    featured: Vec<String>,
+    path_metadata: bool,
+
+    // The range within the file (milliseconds),
+    // only set for `Song`'s split from a CUE sheet.
+    start: u64,
+    end: Option<u64>,
+    // The file's CUE sheet, this gets split by `cue_split()`.
+    cue: Option<CueSheet>,
 }
 
 //---------------------------------------------------------------------------------------------------- Metadata functions.
@@ -61,6 +79,23 @@ impl crate::ccd::Ccd {
     pub(super) fn the_loop(
         to_kernel: &Sender<CcdToKernel>,
         vec_paths: Vec<(PathBuf, &'static str, &'static str)>,
+    ) -> (Vec<Artist>, Vec<Album>, Vec<Song>, usize) {
+        Self::the_loop_with(to_kernel, vec_paths, vec![], vec![], vec![], 0)
+    }
+
+    // Same as `the_loop()`, but the `Song`'s are added on top of existing `Vec`'s.
+    //
+    // This is used when updating a `Collection`, the `Artist`'s and `Album`'s
+    // already within the `Vec`'s will be re-used if a new `Song` belongs to them.
+    //
+    // The keys within the given `Vec`'s must be valid indices into each other.
+    pub(super) fn the_loop_with(
+        to_kernel: &Sender<CcdToKernel>,
+        vec_paths: Vec<(PathBuf, &'static str, &'static str)>,
+        mut vec_artist: Vec<Artist>,
+        mut vec_album: Vec<Album>,
+        mut vec_song: Vec<Song>,
+        count_art: usize,
     ) -> (Vec<Artist>, Vec<Album>, Vec<Song>, usize) {
         // ResetUpdate.
         //
@@ -101,12 +136,28 @@ impl crate::ccd::Ccd {
         //                            Name   in `Vec<Artist>`   Name   in `Vec<Album>`
         //                              |          |              |         |
         //                              v          v              v         v
-        let memory: Mutex<HashMap<Arc<str>, (usize, HashMap<Arc<str>, usize>)>> =
-            Mutex::new(HashMap::with_capacity(artist_len_maybe));
-        let vec_artist: Mutex<Vec<Artist>> = Mutex::new(Vec::with_capacity(artist_len_maybe));
-        let vec_album: Mutex<Vec<Album>> = Mutex::new(Vec::with_capacity(album_len_maybe));
-        let vec_song: Mutex<Vec<Song>> = Mutex::new(Vec::with_capacity(song_len_maybe));
-        let count_art: Mutex<usize> = Mutex::new(0);
+        let mut memory: HashMap<Arc<str>, (usize, HashMap<Arc<str>, usize>)> =
+            HashMap::with_capacity(vec_artist.len() + artist_len_maybe);
+
+        // Remember the `Artist`'s and `Album`'s we were given.
+        for artist in vec_artist.iter() {
+            let albums = artist
+                .albums
+                .iter()
+                .map(|key| (Arc::clone(&vec_album[key.inner()].title), key.inner()))
+                .collect();
+            memory.insert(Arc::clone(&artist.name), (artist.key.inner(), albums));
+        }
+
+        vec_artist.reserve(artist_len_maybe);
+        vec_album.reserve(album_len_maybe);
+        vec_song.reserve(song_len_maybe);
+
+        let memory = Mutex::new(memory);
+        let vec_artist: Mutex<Vec<Artist>> = Mutex::new(vec_artist);
+        let vec_album: Mutex<Vec<Album>> = Mutex::new(vec_album);
+        let vec_song: Mutex<Vec<Song>> = Mutex::new(vec_song);
+        let count_art: Mutex<usize> = Mutex::new(count_art);
 
         // In this loop, each `PathBuf` represents a new `Song` with metadata.
         // There are 3 logical possibilities with 3 actions associated with them:
@@ -134,33 +185,43 @@ impl crate::ccd::Ccd {
                 // Chunk the total paths for each thread.
                 scope.spawn(|| {
                     // Spawn a thread.
-                    for (path, mime, extension) in paths.iter() {
-                        // Make thread work over the chunked paths.
-
-                        // FIXME:
-                        // Figure out how to take ownership of this instead of cloning.
-                        let path = path.clone();
 
-                        // Get the tags for this `PathBuf`, skip on error.
-                        //
-                        // FIXME:
-                        // `symphonia` doesn't have a partial-`Tag` API. It always reads all
-                        // the data from a file. AKA, the `Picture` data gets allocated
-                        // into an owned `Vec<u8>` for every single file...!
-                        //
-                        // This is obviously not ideal, we only need
-                        // the `Picture` data once per `Album`.
-                        //
-                        // For some reason though, this doesn't affect performance that much.
-                        // Basic tests show maybe `~1.5x-2x` speed improvements upon commenting
-                        // out all picture ops. Not that much faster.
-                        let metadata = match Self::extract(&path) {
+                    // Get the tags for each `PathBuf`, skip on error.
+                    //
+                    // FIXME:
+                    // `symphonia` doesn't have a partial-`Tag` API. It always reads all
+                    // the data from a file. AKA, the `Picture` data gets allocated
+                    // into an owned `Vec<u8>` for every single file...!
+                    //
+                    // This is obviously not ideal, we only need
+                    // the `Picture` data once per `Album`.
+                    //
+                    // For some reason though, this doesn't affect performance that much.
+                    // Basic tests show maybe `~1.5x-2x` speed improvements upon commenting
+                    // out all picture ops. Not that much faster.
+                    //
+                    // Files with a CUE sheet are split into
+                    // multiple `Song`'s, all with the same PATH.
+                    let songs = paths.iter().flat_map(|(path, mime, extension)| {
+                        let metadata = match Self::extract(path).map(Self::cue_split) {
                             Ok(t) => t,
                             Err(e) => {
                                 warn!("{e}: {}", path.display());
-                                continue;
+                                vec![]
                             }
                         };
+                        let increment = increment / metadata.len().max(1) as f64;
+                        metadata
+                            .into_iter()
+                            .map(move |m| (path, mime, extension, m, increment))
+                    });
+
+                    for (path, mime, extension, metadata, increment) in songs {
+                        // Make thread work over the chunked paths.
+
+                        // FIXME:
+                        // Figure out how to take ownership of this instead of cloning.
+                        let path = path.clone();
 
                         // Destructure tag metadata
                         // into individual variables.
@@ -176,6 +237,17 @@ impl crate::ccd::Ccd {
                             art,
                             release,
                             genre,
+                            genres,
+                            track_gain,
+                            album_gain,
+                            track_artist,
+                            artists,
+                            composers,
+                            featured,
+                            path_metadata,
+                            start,
+                            end,
+                            cue: _,
                         } = metadata;
 
                         // Convert `String`'s to `Arc<str>`.
@@ -185,6 +257,16 @@ impl crate::ccd::Ccd {
                         let artist: Arc<str> = artist.into();
                         let album: Arc<str> = album.into();
                         let title: Arc<str> = title.into();
+                        let track_artist_lowercase: Option<Arc<str>> =
+                            track_artist.as_deref().map(|a| a.to_lowercase().into());
+                        let track_artist: Option<Arc<str>> = track_artist.map(Into::into);
+                        let artists: Box<[Arc<str>]> =
+                            artists.into_iter().map(Into::into).collect();
+                        let composers: Box<[Arc<str>]> =
+                            composers.into_iter().map(Into::into).collect();
+                        let featured: Box<[Arc<str>]> =
+                            featured.into_iter().map(Into::into).collect();
+                        let genres: Box<[Arc<str>]> = genres.into_iter().map(Into::into).collect();
                         let mime: Arc<str> = Arc::from(*mime);
                         let extension: Arc<str> = Arc::from(*extension);
 
@@ -218,15 +300,26 @@ impl crate::ccd::Ccd {
                                     mime,
                                     extension,
                                     path,
+                                    gain: track_gain,
+                                    track_artist,
+                                    track_artist_lowercase,
+                                    artists,
+                                    composers,
+                                    featured,
+                                    path_metadata,
+                                    start,
+                                    end,
                                 };
 
                                 // Push to `Vec<Song>`
                                 vec_song.push(song);
 
                                 // Update `Album`.
-                                vec_album[*album_idx]
-                                    .songs
-                                    .push(SongKey::from(vec_song.len() - 1));
+                                let album_struct = &mut vec_album[*album_idx];
+                                album_struct.songs.push(SongKey::from(vec_song.len() - 1));
+                                if album_struct.gain.is_none() {
+                                    album_struct.gain = album_gain;
+                                }
 
                                 continue;
                             }
@@ -280,6 +373,15 @@ impl crate::ccd::Ccd {
                                 extension,
                                 path,
                                 album: AlbumKey::from(vec_album.len()),
+                                gain: track_gain,
+                                track_artist,
+                                track_artist_lowercase,
+                                artists,
+                                composers,
+                                featured,
+                                path_metadata,
+                                start,
+                                end,
                             };
 
                             // Create `Album`.
@@ -293,6 +395,8 @@ impl crate::ccd::Ccd {
                                 songs: vec![SongKey::from(vec_song.len())],
                                 path: path_parent,
                                 genre,
+                                genres,
+                                gain: album_gain,
 
                                 // Needs to be updated later.
                                 runtime: runtime_album,
@@ -374,6 +478,15 @@ impl crate::ccd::Ccd {
                             extension,
                             path,
                             album: AlbumKey::from(vec_album.len()),
+                            gain: track_gain,
+                            track_artist,
+                            track_artist_lowercase,
+                            artists,
+                            composers,
+                            featured,
+                            path_metadata,
+                            start,
+                            end,
                         };
 
                         // Create `Album`.
@@ -387,6 +500,8 @@ impl crate::ccd::Ccd {
                             songs: vec![SongKey::from(vec_song.len())],
                             path: path_parent,
                             genre,
+                            genres,
+                            gain: album_gain,
 
                             // Needs to be updated later.
                             runtime: runtime_album,
@@ -407,6 +522,7 @@ impl crate::ccd::Ccd {
                             runtime: Runtime::zero(),
                             albums: vec![AlbumKey::from(count_album)],
                             songs: Box::new([]),
+                            appearances: Box::new([]),
                         };
 
                         // Push `Artist/Album/Song`.
@@ -473,6 +589,11 @@ impl crate::ccd::Ccd {
                     .sum::<u32>(),
             );
 
+            // Album gain, if it wasn't tagged.
+            if album.gain.is_none() {
+                album.gain = Self::album_gain(album.songs.iter().map(|key| &vec_song[key.inner()]));
+            }
+
             // Sort songs based off `track`.
             album
                 .songs
@@ -497,7 +618,7 @@ impl crate::ccd::Ccd {
         }
 
         // Fix `Album` order in the `Artist` (release order).
-        for artist in vec_artist {
+        for artist in vec_artist.iter_mut() {
             artist.albums.sort_by(|a, b| {
                 vec_album[a.inner()]
                     .release
@@ -519,6 +640,9 @@ impl crate::ccd::Ccd {
                 .flat_map(|k| vec_album[k.inner()].songs.iter().map(|k| *k))
                 .collect();
         }
+
+        // `Song`'s the `Artist`'s appear on.
+        Self::appearances(vec_artist, vec_album, vec_song);
     }
 
     //---------------------------------------------------------------------------------------------------- Private tag functions.
@@ -527,7 +651,7 @@ impl crate::ccd::Ccd {
     //
     // This is the 2nd `heaviest` function within the entire `new_collection()` function.
     // It accounts for around 20% of the total time spent making the `Collection`.
-    fn probe(path: &Path) -> Result<ProbeResult, anyhow::Error> {
+    pub(super) fn probe(path: &Path) -> Result<ProbeResult, anyhow::Error> {
         let file = std::fs::File::open(path)?;
         let mss = MediaSourceStream::new(Box::new(file), Default::default());
 
@@ -636,6 +760,56 @@ impl crate::ccd::Ccd {
         None
     }
 
+    #[inline(always)]
+    // Attempt to get the `Artist` the `Song` will be grouped under,
+    // and the `Song`'s own track artist (if it is different).
+    //
+    // Compilations are grouped under their `AlbumArtist`,
+    // or `VARIOUS_ARTISTS` if there isn't one, and each
+    // `Song` keeps its own `Artist` as the track artist.
+    //
+    // Everything else is grouped with `tag_artist()`.
+    fn tag_artists(tag: &mut [Tag], compilation: bool) -> Option<(String, Option<String>)> {
+        if !compilation {
+            return Self::tag_artist(tag).map(|artist| (artist, None));
+        }
+
+        let album_artist = tag
+            .iter_mut()
+            .find(|i| i.std_key == Some(StandardTagKey::AlbumArtist))
+            .and_then(Self::value)
+            .unwrap_or_else(|| VARIOUS_ARTISTS.to_string());
+
+        // `AlbumArtist` was taken above, so this will
+        // fallback to the other artist-like tags.
+        let track_artist = Self::tag_artist(tag).filter(|a| *a != album_artist);
+
+        Some((album_artist, track_artist))
+    }
+
+    #[inline(always)]
+    // Attempt to get the credits of `key`, split
+    // into the main and featured artists.
+    //
+    // Every tag with `key` is used, since some
+    // formats repeat tags for multiple values.
+    //
+    // This doesn't take the values out of the tags.
+    fn tag_credits(tag: &[Tag], key: StandardTagKey) -> (Vec<String>, Vec<String>) {
+        let mut main = vec![];
+        let mut featured = vec![];
+
+        for t in tag.iter().filter(|i| i.std_key == Some(key)) {
+            if let Some(s) = Self::value_str(t) {
+                let (m, f) = Self::split_credit(s);
+                Self::extend_unique(&mut main, m);
+                Self::extend_unique(&mut featured, f);
+            }
+        }
+
+        (main, featured)
+    }
+
     #[inline(always)]
     // Attempt to get album title.
     fn tag_album(tag: &mut [Tag]) -> Option<String> {
@@ -664,20 +838,12 @@ impl crate::ccd::Ccd {
 
     #[inline(always)]
     // Attempt to get song title.
-    fn tag_title(tag: &mut [Tag], path: &Path) -> Option<String> {
+    fn tag_title(tag: &mut [Tag]) -> Option<String> {
         if let Some(t) = tag
             .iter_mut()
             .find(|i| i.std_key == Some(StandardTagKey::TrackTitle))
         {
-            let o = Self::value(t);
-            if o.is_some() {
-                return o;
-            }
-        }
-
-        // Fallback to file name.
-        if let Some(os_str) = path.file_stem() {
-            Some(os_str.to_string_lossy().into_owned())
+            Self::value(t)
         } else {
             None
         }
@@ -745,6 +911,61 @@ impl crate::ccd::Ccd {
         }
     }
 
+    #[inline(always)]
+    // Attempt to get every genre, split on separators.
+    //
+    // Every genre tag is used, since some
+    // formats repeat tags for multiple values.
+    //
+    // This doesn't take the values out of the tags.
+    fn tag_genres(tag: &[Tag]) -> Vec<String> {
+        let mut genres = vec![];
+
+        for t in tag
+            .iter()
+            .filter(|i| i.std_key == Some(StandardTagKey::Genre))
+        {
+            if let Some(s) = Self::value_str(t) {
+                Self::extend_unique(&mut genres, Self::split_genre(s));
+            }
+        }
+
+        genres
+    }
+
+    #[inline(always)]
+    // Attempt to get `ReplayGain` data.
+    //
+    // `REPLAYGAIN_*` tags are tried first, then `R128_*`.
+    fn tag_gain(
+        tag: &[Tag],
+        gain_key: StandardTagKey,
+        peak_key: StandardTagKey,
+        r128_key: &str,
+    ) -> Option<Gain> {
+        if let Some(gain) = tag
+            .iter()
+            .find(|i| i.std_key == Some(gain_key))
+            .and_then(Self::value_float)
+        {
+            let peak = tag
+                .iter()
+                .find(|i| i.std_key == Some(peak_key))
+                .and_then(Self::value_float);
+            return Some(Gain { gain, peak });
+        }
+
+        // `R128_*` tags are `Q7.8` fixed-point integers
+        // relative to `-23 LUFS`, not `-18 LUFS`, and have no peak.
+        tag.iter()
+            .find(|i| i.key.eq_ignore_ascii_case(r128_key))
+            .and_then(Self::value_float)
+            .map(|q| Gain {
+                gain: q / 256.0 + 5.0,
+                peak: None,
+            })
+    }
+
     #[inline(always)]
     fn art(mut visuals: Vec<Visual>) -> Option<Box<[u8]>> {
         if !visuals.is_empty() {
@@ -801,6 +1022,20 @@ impl crate::ccd::Ccd {
         }
     }
 
+    #[inline(always)]
+    // Borrow a `Tag`'s `Value` as a string.
+    //
+    // Same as `value()`, but the value is left in the `Tag`.
+    pub(super) fn value_str(tag: &Tag) -> Option<&str> {
+        use symphonia::core::meta::Value;
+        let s = match &tag.value {
+            Value::String(s) => s.as_str(),
+            Value::Binary(b) => std::str::from_utf8(b).ok()?,
+            _ => return None,
+        };
+        s.split_whitespace().next().map(|_| s)
+    }
+
     #[inline(always)]
     // Extract a `Tag`'s `Value` to a number.
     //
@@ -841,6 +1076,36 @@ impl crate::ccd::Ccd {
         }
     }
 
+    #[inline(always)]
+    // Extract a `Tag`'s `Value` to a float.
+    //
+    // This expects values like `-6.54 dB` or `0.988553`.
+    fn value_float(tag: &Tag) -> Option<f32> {
+        use symphonia::core::meta::Value;
+
+        fn parse(s: &str) -> Option<f32> {
+            let s = s.trim();
+            let s = s
+                .strip_suffix("dB")
+                .or_else(|| s.strip_suffix("db"))
+                .or_else(|| s.strip_suffix("DB"))
+                .unwrap_or(s);
+            s.trim().parse::<f32>().ok().filter(|f| f.is_finite())
+        }
+
+        match &tag.value {
+            Value::Float(f) => Some(*f as f32),
+            Value::UnsignedInt(u) => Some(*u as f32),
+            Value::SignedInt(s) => Some(*s as f32),
+            Value::String(s) => parse(s),
+            Value::Binary(b) => match std::str::from_utf8(b) {
+                Ok(s) => parse(s),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     #[inline(always)]
     // Extract a `Tag`'s `Value` to a bool
     //
@@ -885,40 +1150,137 @@ impl crate::ccd::Ccd {
             Some(t) => t,
             _ => bail!("Runtime metadata missing"),
         };
-        let metadata = match Self::metadata(probe_result) {
-            Ok(md) => md,
-            Err(e) => bail!(e),
+        let cue_block = CueSheet::from_cues(probe_result.format.cues(), sample_rate);
+
+        // Metadata inferred from the PATH, this is only
+        // used for whatever is missing from the tags.
+        let inferred = super::template::infer(path);
+        let mut path_metadata = false;
+
+        // Untagged files may still get their
+        // metadata from a CUE sheet or their PATH.
+        let (mut tags, visuals) = match Self::metadata(probe_result) {
+            Ok(md) => {
+                let (tags, visuals, _) = md.into_inner();
+                (tags, visuals)
+            }
+            Err(_) => (vec![], vec![]),
         };
-
-        let (mut tags, visuals, _) = metadata.into_inner();
-
-        // SOMEDAY:
-        // We should handle compilations correctly.
-        // But... for now, skip them entirely.
-        if Self::tag_compilation(&tags) {
-            bail!("Compilation not supported");
+        let cue = Self::cue_sheet(path, &tags, cue_block);
+
+        // Credits, these must be read before the tags are taken below.
+        let (mut artists, mut featured) = (vec![], vec![]);
+        for key in [
+            StandardTagKey::Artist,
+            StandardTagKey::AlbumArtist,
+            StandardTagKey::Performer,
+        ] {
+            (artists, featured) = Self::tag_credits(&tags, key);
+            if !artists.is_empty() {
+                break;
+            }
         }
+        let (composers, _) = Self::tag_credits(&tags, StandardTagKey::Composer);
 
         // Attempt to get required metadata.
-        let artist = match Self::tag_artist(&mut tags) {
-            Some(t) => t,
+        let compilation = Self::tag_compilation(&tags);
+        let (artist, track_artist) = match (
+            Self::tag_artists(&mut tags, compilation),
+            cue.as_ref().and_then(|c| c.performer.as_ref()),
+            &inferred,
+        ) {
+            (Some(t), _, _) => t,
+            (None, Some(p), _) => (p.clone(), None),
+            (None, None, Some(i)) => {
+                path_metadata = true;
+                (i.artist.clone(), None)
+            }
             _ => bail!("Artist metadata missing"),
         };
-        let album = match Self::tag_album(&mut tags) {
-            Some(t) => t,
+        let album = match (
+            Self::tag_album(&mut tags),
+            cue.as_ref().and_then(|c| c.title.as_ref()),
+            &inferred,
+        ) {
+            (Some(t), _, _) => t,
+            (None, Some(t), _) => t.clone(),
+            (None, None, Some(i)) => {
+                path_metadata = true;
+                i.album.clone()
+            }
             _ => bail!("Album metadata missing"),
         };
-        let title = match Self::tag_title(&mut tags, path) {
-            Some(t) => t,
-            _ => bail!("Title metadata missing"),
+        let title = match (Self::tag_title(&mut tags), &inferred) {
+            (Some(t), _) => t,
+            (None, Some(PathMetadata { title: Some(t), .. })) => {
+                path_metadata = true;
+                t.clone()
+            }
+            // Fallback to file name.
+            _ => match path.file_stem() {
+                Some(os_str) => os_str.to_string_lossy().into_owned(),
+                None => bail!("Title metadata missing"),
+            },
         };
+        if artists.is_empty() {
+            (artists, featured) = Self::split_credit(&artist);
+        }
+        Self::extend_unique(&mut featured, Self::split_title(&title));
 
         // Optional metadata.
         let art = Self::art(visuals);
-        let track = Self::tag_track(&mut tags);
+        let track = match (Self::tag_track(&mut tags), &inferred) {
+            (Some(t), _) => Some(t),
+            (None, Some(PathMetadata { track: Some(t), .. })) => {
+                path_metadata = true;
+                Some(*t)
+            }
+            _ => None,
+        };
         let disc = Self::tag_disc(&mut tags);
-        let release = Self::tag_release(&mut tags);
-        let genre = Self::tag_genre(&mut tags);
+        let release = match (
+            Self::tag_release(&mut tags),
+            cue.as_ref().and_then(|c| c.date.as_ref()),
+            &inferred,
+        ) {
+            (Some(r), _, _) => Some(r),
+            (None, Some(r), _) => Some(r.clone()),
+            (
+                None,
+                None,
+                Some(PathMetadata {
+                    release: Some(r), ..
+                }),
+            ) => {
+                path_metadata = true;
+                Some(r.clone())
+            }
+            _ => None,
+        };
+        let genres = match Self::tag_genres(&tags) {
+            g if g.is_empty() => cue
+                .as_ref()
+                .and_then(|c| c.genre.as_deref())
+                .map(Self::split_genre)
+                .unwrap_or_default(),
+            g => g,
+        };
+        let genre = Self::tag_genre(&mut tags).or_else(|| cue.as_ref()?.genre.clone());
+        let album_gain = Self::tag_gain(
+            &tags,
+            StandardTagKey::ReplayGainAlbumGain,
+            StandardTagKey::ReplayGainAlbumPeak,
+            "R128_ALBUM_GAIN",
+        );
+        let track_gain = match Self::tag_gain(
+            &tags,
+            StandardTagKey::ReplayGainTrackGain,
+            StandardTagKey::ReplayGainTrackPeak,
+            "R128_TRACK_GAIN",
+        ) {
+            Some(g) => Some(g),
+            None => Self::loudness(path),
+        };
 
         Ok(TagMetadata {
             artist,
@@ -932,8 +1294,99 @@ impl crate::ccd::Ccd {
             art,
             release,
             genre,
+            genres,
+            track_gain,
+            album_gain,
+            track_artist,
+            artists,
+            composers,
+            featured,
+            path_metadata,
+
+            start: 0,
+            end: None,
+            cue,
         })
     }
+
+    // Split a file with a CUE sheet into a `TagMetadata` per track.
+    //
+    // The file's metadata is used for anything the tracks don't have.
+    fn cue_split(mut metadata: TagMetadata) -> Vec<TagMetadata> {
+        let Some(cue) = metadata.cue.take() else {
+            return vec![metadata];
+        };
+
+        // The whole file's runtime in milliseconds.
+        let len = metadata.runtime * 1000;
+        // Only the first `Song` needs the art.
+        let mut art = metadata.art.take();
+
+        let mut vec = Vec::with_capacity(cue.tracks.len());
+        let mut tracks = cue.tracks.iter().peekable();
+
+        while let Some(t) = tracks.next() {
+            let end = tracks.peek().map(|next| next.start);
+            let runtime = end.unwrap_or(len).saturating_sub(t.start);
+            if runtime == 0 {
+                continue;
+            }
+
+            let title = match &t.title {
+                Some(title) => title.clone(),
+                None => format!("Track {}", t.number),
+            };
+
+            // The track's own performer, if it's
+            // not the same as the `Album`'s artist.
+            let (artists, mut featured, track_artist) = match &t.performer {
+                Some(p) if !p.eq_ignore_ascii_case(&metadata.artist) => {
+                    let (artists, featured) = Self::split_credit(p);
+                    (artists, featured, Some(p.clone()))
+                }
+                _ => (
+                    metadata.artists.clone(),
+                    metadata.featured.clone(),
+                    metadata.track_artist.clone(),
+                ),
+            };
+            Self::extend_unique(&mut featured, Self::split_title(&title));
+
+            let composers = match &t.songwriter {
+                Some(s) => Self::split_credit(s).0,
+                None => metadata.composers.clone(),
+            };
+
+            vec.push(TagMetadata {
+                artist: metadata.artist.clone(),
+                album: metadata.album.clone(),
+                title,
+                // Rounded to the nearest second.
+                runtime: (runtime + 500) / 1000,
+                sample_rate: metadata.sample_rate,
+
+                track: Some(t.number),
+                disc: metadata.disc,
+                art: art.take(),
+                release: metadata.release.clone(),
+                genre: metadata.genre.clone(),
+                genres: metadata.genres.clone(),
+                track_gain: metadata.track_gain,
+                album_gain: metadata.album_gain,
+                track_artist,
+                artists,
+                composers,
+                featured,
+                path_metadata: metadata.path_metadata,
+
+                start: t.start,
+                end,
+                cue: None,
+            });
+        }
+
+        vec
+    }
 }
 
 //---------------------------------------------------------------------------------------------------- TESTS
@@ -957,6 +1410,9 @@ mod tests {
         assert_eq!(t.track, Some(1));
         assert_eq!(t.disc, Some(2));
         assert_eq!(t.release, Some(String::from(DATE)));
+        assert_eq!(t.artists, ["artist_1"]);
+        assert!(t.composers.is_empty());
+        assert!(t.featured.is_empty());
         assert!(!t.art.unwrap().is_empty());
 
         // mp3 - 2/7
@@ -1005,6 +1461,7 @@ mod tests {
         assert_eq!(t.track, Some(1));
         assert_eq!(t.disc, None);
         assert_eq!(t.release, Some(String::from(DATE)));
+        assert_eq!(t.artists, ["artist_2"]);
         assert!(!t.art.unwrap().is_empty());
 
         // ogg - 6/7
@@ -1031,4 +1488,55 @@ mod tests {
         assert_eq!(t.release, Some(String::from(DATE)));
         assert!(!t.art.unwrap().is_empty());
     }
+
+    #[test]
+    // Asserts compilations are grouped under their
+    // `AlbumArtist` or `VARIOUS_ARTISTS`, and keep the track artist.
+    fn __tag_artists() {
+        use symphonia::core::meta::Value;
+
+        let tags = |keys: &[(StandardTagKey, &str)]| -> Vec<Tag> {
+            keys.iter()
+                .map(|(k, v)| Tag::new(Some(*k), "", Value::String(v.to_string())))
+                .collect()
+        };
+
+        // Not a compilation.
+        let mut t = tags(&[
+            (StandardTagKey::AlbumArtist, "album_artist"),
+            (StandardTagKey::Artist, "artist"),
+        ]);
+        assert_eq!(
+            crate::ccd::Ccd::tag_artists(&mut t, false),
+            Some(("album_artist".into(), None))
+        );
+
+        // Compilation, with `AlbumArtist`.
+        let mut t = tags(&[
+            (StandardTagKey::AlbumArtist, "album_artist"),
+            (StandardTagKey::Artist, "artist"),
+        ]);
+        assert_eq!(
+            crate::ccd::Ccd::tag_artists(&mut t, true),
+            Some(("album_artist".into(), Some("artist".into())))
+        );
+
+        // Compilation, without `AlbumArtist`.
+        let mut t = tags(&[(StandardTagKey::Artist, "artist")]);
+        assert_eq!(
+            crate::ccd::Ccd::tag_artists(&mut t, true),
+            Some((VARIOUS_ARTISTS.into(), Some("artist".into())))
+        );
+
+        // Compilation, with no artists at all.
+        let mut t = tags(&[]);
+        assert_eq!(
+            crate::ccd::Ccd::tag_artists(&mut t, true),
+            Some((VARIOUS_ARTISTS.into(), None))
+        );
+
+        // Not a compilation, with no artists at all.
+        let mut t = tags(&[]);
+        assert_eq!(crate::ccd::Ccd::tag_artists(&mut t, false), None);
+    }
 }
@@ -0,0 +1,437 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::ccd::msg::CcdToKernel;
+use crate::{
+    collection::{Album, AlbumKey, Art, Artist, ArtistKey, Collection, Image, Song, SongKey},
+    search::NEW_INDEX,
+    state::Phase,
+};
+use benri::{lock, log::*, sync::*, time::*};
+use crossbeam::channel::Sender;
+use disk::Plain;
+use log::{debug, info, trace, warn};
+use readable::Unsigned;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+//---------------------------------------------------------------------------------------------------- CCD `update_collection()`
+impl super::Ccd {
+    // Public facing "front-end" function for updating a `Collection`.
+    //
+    // Instead of scanning everything, only the `changed` PATHs
+    // (files or directories that were added, modified, renamed
+    // or removed) are looked at. Every other `Song` (and its
+    // `Album`/`Artist`) is copied from the old `Collection`
+    // as-is, without probing the file again.
+    //
+    // Keys are _not_ stable across updates, `Kernel` restores
+    // `AUDIO_STATE` and `Playlists` the same way as a full reset.
+    pub(crate) fn update_collection(
+        to_kernel: Sender<CcdToKernel>,
+        old_collection: Arc<Collection>,
+        changed: Vec<PathBuf>,
+    ) {
+        // `update_collection()` high-level overview.
+        //
+        // 1. Figure out which `Song`'s are gone, and which PATHs need probing.
+        // 2. Copy everything else from the old `Collection`.
+        // 3. Probe the new PATHs on top of that.
+        // 4. Make sure `Vec<Album>` metadata matches the songs.
+        // 5. Create sorted `Key`'s, the "Map" and the `Collection`.
+        // 6. Move/save the art images to match the new `AlbumKey`'s.
+        // 7. Validate playlists
+        // 8. Send to `Kernel`
+        // 9. Save `Collection` to disk.
+        let beginning = now!();
+        debug!("CCD ... purpose in life: update_collection()");
+
+        //-------------------------------------------------------------------------------- 1
+        let now = now!();
+        send!(to_kernel, CcdToKernel::UpdatePhase((0.00, Phase::WalkDir)));
+        let (removed, paths) = Self::delta(&old_collection, changed);
+        trace!(
+            "CCD [1/9] ... Delta: {} (removed: {}, probe: {})",
+            secs_f32!(now),
+            removed.len(),
+            paths.len()
+        );
+
+        //-------------------------------------------------------------------------------- 2
+        let now = now!();
+        let (vec_artist, vec_album, vec_song, count_art, orphans) =
+            Self::keep(&old_collection, &removed);
+        drop(old_collection);
+        trace!("CCD [2/9] ... Keep: {}", secs_f32!(now));
+
+        //-------------------------------------------------------------------------------- 3
+        let now = now!();
+        send!(to_kernel, CcdToKernel::UpdatePhase((5.00, Phase::Parse)));
+        let (mut vec_artist, mut vec_album, vec_song, count_art) = Self::the_loop_with(
+            &to_kernel, paths, vec_artist, vec_album, vec_song, count_art,
+        );
+        trace!("CCD [3/9] ... Metadata: {}", secs_f32!(now));
+
+        //-------------------------------------------------------------------------------- 4
+        let now = now!();
+        send!(to_kernel, CcdToKernel::UpdatePhase((50.00, Phase::Fix)));
+        Self::fix_metadata(&mut vec_artist, &mut vec_album, &vec_song);
+        trace!("CCD [4/9] ... Fix: {}", secs_f32!(now));
+
+        //-------------------------------------------------------------------------------- 5
+        let (mut collection, index, perf_sort, perf_map, perf_prepare) =
+            Self::build(&to_kernel, vec_artist, vec_album, vec_song, count_art);
+        trace!("CCD [5/9] ... Sort: {perf_sort}, Map: {perf_map}, Prepare: {perf_prepare}");
+
+        //-------------------------------------------------------------------------------- 6
+        let now = now!();
+        send!(to_kernel, CcdToKernel::UpdatePhase((95.00, Phase::Convert)));
+        atomic_store!(crate::state::SAVING, true);
+        Self::update_images(&mut collection, orphans);
+        trace!("CCD [6/9] ... Images: {}", secs_f32!(now));
+
+        let collection = Arc::new(collection);
+
+        //-------------------------------------------------------------------------------- 7
+        let now = now!();
+        crate::state::PLAYLISTS.write().validate(&collection);
+        crate::state::SMART_PLAYLISTS.read().evaluate(
+            &collection,
+            &crate::state::STATS.read(),
+            &mut crate::state::PLAYLISTS.write(),
+        );
+        let playlists_for_disk = crate::state::PLAYLISTS.read().clone();
+        trace!("CCD [7/9] ... Playlists: {}", secs_f32!(now));
+
+        //-------------------------------------------------------------------------------- 8
+        let index = Arc::new(index);
+        *lock!(NEW_INDEX) = Some(Arc::clone(&index));
+        send!(
+            to_kernel,
+            CcdToKernel::NewCollection(Arc::clone(&collection))
+        );
+        info!("CCD [8/9] ... User time: {}", secs_f32!(beginning));
+
+        //-------------------------------------------------------------------------------- 9
+        let now = now!();
+        Self::save(&collection, &playlists_for_disk, &index);
+        atomic_store!(crate::state::SAVING, false);
+        trace!("CCD [9/9] ... Disk: {}", secs_f32!(now));
+
+        info!("CCD ... Sys time: {}", secs_f32!(beginning));
+        ok_debug!("CCD");
+    }
+
+    //-------------------------------------------------------------------------------- Delta
+    // Returns the `SongKey`'s of the old `Collection` that are
+    // gone (or need to be probed again), and the PATHs to probe.
+    pub(super) fn delta(
+        collection: &Collection,
+        changed: Vec<PathBuf>,
+    ) -> (HashSet<SongKey>, Vec<(PathBuf, &'static str, &'static str)>) {
+        // Every `Song` PATH we currently know about.
+        //
+        // Files split by a CUE sheet have multiple `Song`'s.
+        let mut known: HashMap<&Path, Vec<SongKey>> = HashMap::new();
+        for song in collection.songs.iter() {
+            known.entry(song.path.as_path()).or_default().push(song.key);
+        }
+
+        let mut removed = HashSet::new();
+        let mut paths = vec![];
+
+        for path in changed {
+            if path.is_dir() {
+                // A new (or renamed) directory, probe everything
+                // in it we don't know about. Modified files within
+                // it get their own event, so they're not re-probed here.
+                paths.extend(
+                    Self::walkdir_audio(vec![path])
+                        .into_iter()
+                        .filter(|(p, _, _)| !known.contains_key(p.as_path())),
+                );
+            } else if Self::path_is_cue(&path) {
+                // A new, modified or removed CUE sheet,
+                // the audio files next to it get split again.
+                let Some(parent) = path.parent() else {
+                    continue;
+                };
+                for (p, keys) in known.iter().filter(|(p, _)| p.parent() == Some(parent)) {
+                    removed.extend(keys);
+                    if let Some(entry) = Self::path_is_audio(p.to_path_buf()) {
+                        paths.push(entry);
+                    }
+                }
+            } else if path.is_file() {
+                // A new or modified (re-tagged) file.
+                if let Some(keys) = known.get(path.as_path()) {
+                    removed.extend(keys);
+                }
+                if let Some(entry) = Self::path_is_audio(path) {
+                    paths.push(entry);
+                }
+            } else if let Some(keys) = known.get(path.as_path()) {
+                // A removed (or renamed away) file.
+                removed.extend(keys);
+            } else {
+                // A removed (or renamed away) directory.
+                removed.extend(
+                    known
+                        .iter()
+                        .filter(|(p, _)| p.starts_with(&path))
+                        .flat_map(|(_, keys)| keys),
+                );
+            }
+        }
+
+        paths.sort();
+        paths.dedup();
+
+        (removed, paths)
+    }
+
+    //-------------------------------------------------------------------------------- Keep
+    // Copy every `Song` that wasn't `removed` (and its `Album`/`Artist`)
+    // from the old `Collection`, with new keys.
+    //
+    // `Album`'s and `Artist`'s with no `Song`'s left are not copied,
+    // the PATHs of those `Album`'s saved art is returned as well.
+    fn keep(
+        collection: &Collection,
+        removed: &HashSet<SongKey>,
+    ) -> (Vec<Artist>, Vec<Album>, Vec<Song>, usize, Vec<PathBuf>) {
+        let mut vec_artist = Vec::with_capacity(collection.artists.len());
+        let mut vec_album = Vec::with_capacity(collection.albums.len());
+        let mut vec_song = Vec::with_capacity(collection.songs.len());
+        let mut count_art = 0;
+        let mut orphans = vec![];
+
+        for artist in collection.artists.iter() {
+            let artist_key = ArtistKey::from(vec_artist.len());
+            let mut albums = vec![];
+
+            for album in artist.albums.iter().map(|key| &collection.albums[key]) {
+                let mut songs = album
+                    .songs
+                    .iter()
+                    .map(|key| &collection.songs[key])
+                    .filter(|song| !removed.contains(&song.key))
+                    .peekable();
+
+                if songs.peek().is_none() {
+                    if let Art::Known { path, .. } = &album.art {
+                        orphans.push(path.clone());
+                    }
+                    continue;
+                }
+
+                let album_key = AlbumKey::from(vec_album.len());
+                let song_keys = songs
+                    .map(|song| {
+                        let key = SongKey::from(vec_song.len());
+                        vec_song.push(Song {
+                            key,
+                            album: album_key,
+                            ..song.clone()
+                        });
+                        key
+                    })
+                    .collect();
+
+                if !matches!(album.art, Art::Unknown) {
+                    count_art += 1;
+                }
+
+                vec_album.push(Album {
+                    key: album_key,
+                    artist: artist_key,
+                    songs: song_keys,
+                    // `fix_metadata()` counts these again.
+                    discs: 0,
+                    ..album.clone()
+                });
+                albums.push(album_key);
+            }
+
+            if albums.is_empty() {
+                continue;
+            }
+
+            vec_artist.push(Artist {
+                key: artist_key,
+                albums,
+                // `fix_metadata()` collects these again.
+                songs: Box::new([]),
+                appearances: Box::new([]),
+                ..artist.clone()
+            });
+        }
+
+        (vec_artist, vec_album, vec_song, count_art, orphans)
+    }
+
+    //-------------------------------------------------------------------------------- Images
+    // Make the saved art match the new `AlbumKey`'s.
+    //
+    // - Art of `Album`'s that no longer exist is deleted
+    // - Art of `Album`'s that were kept is renamed to the new `AlbumKey`
+    // - Art of new `Album`'s is saved (same as `new_collection()`)
+    fn update_images(collection: &mut Collection, orphans: Vec<PathBuf>) {
+        let base = match Image::base_path() {
+            Ok(p) => p,
+            Err(e) => {
+                fail!("CCD ... Error, Skipping Image: {e}");
+                for album in collection.albums.0.iter_mut() {
+                    if let Art::Bytes(_) = album.art {
+                        album.art = Art::Unknown;
+                    }
+                }
+                return;
+            }
+        };
+
+        for path in orphans {
+            if let Err(e) = std::fs::remove_file(&path) {
+                warn!("CCD ... Image {e}: {}", path.display());
+            }
+        }
+
+        // Renaming is done in 2 steps, as an
+        // `Album`'s new name may still be in use
+        // by another `Album` that hasn't moved yet.
+        let mut moved = vec![];
+        for album in collection.albums.0.iter_mut() {
+            let key = album.key;
+            let Art::Known {
+                path, extension, ..
+            } = &mut album.art
+            else {
+                continue;
+            };
+
+            let new = base.join(format!("{key}.{extension}"));
+            if *path == new {
+                continue;
+            }
+
+            let tmp = base.join(format!("{key}.{extension}.tmp"));
+            match std::fs::rename(&path, &tmp) {
+                Ok(_) => moved.push((key, tmp, new)),
+                Err(e) => {
+                    warn!("CCD ... Image {e}: {}", path.display());
+                    album.art = Art::Unknown;
+                }
+            }
+        }
+
+        for (key, tmp, new) in moved {
+            let album = &mut collection.albums.0[key.inner()];
+            match std::fs::rename(&tmp, &new) {
+                Ok(_) => {
+                    if let Art::Known { path, .. } = &mut album.art {
+                        *path = new;
+                    }
+                }
+                Err(e) => {
+                    warn!("CCD ... Image {e}: {}", new.display());
+                    album.art = Art::Unknown;
+                }
+            }
+        }
+
+        // New `Album`'s.
+        for (i, album) in collection.albums.0.iter_mut().enumerate() {
+            crate::ccd::img::save_image_and_convert(i, album, &base);
+        }
+
+        if let Err(e) = Image(collection.timestamp).save() {
+            warn!("CCD ... Image timestamp: {e}");
+        }
+
+        collection.count_art = Unsigned::from(
+            collection
+                .albums
+                .iter()
+                .filter(|album| !matches!(album.art, Art::Unknown))
+                .count(),
+        );
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ccd::Ccd;
+
+    // Creates a `Collection` from `assets/` the
+    // same way as `new_collection()`, without the art.
+    fn collection() -> Collection {
+        let (to_kernel, _from_ccd) = crossbeam::channel::unbounded::<CcdToKernel>();
+        let paths = vec![PathBuf::from("../assets").canonicalize().unwrap()];
+        let (mut a, mut b, c, d) = Ccd::the_loop(&to_kernel, Ccd::walkdir_audio(paths));
+        Ccd::fix_metadata(&mut a, &mut b, &c);
+        Ccd::build(&to_kernel, a, b, c, d).0
+    }
+
+    #[test]
+    // A removed `Album` directory removes all its `Song`'s,
+    // and the kept keys are still valid indices into each other.
+    fn delta_keep() {
+        let mut c = collection();
+
+        // Nothing we know about.
+        let unknown = c.albums[AlbumKey::zero()].path.join("does_not_exist");
+        let (removed, paths) = Ccd::delta(&c, vec![unknown]);
+        assert!(removed.is_empty());
+        assert!(paths.is_empty());
+
+        // Pretend the 1st `Album` was in a directory that got deleted.
+        let gone = PathBuf::from("/__festival_deleted__");
+        for key in c.albums[AlbumKey::zero()].songs.clone() {
+            let song = &mut c.songs.0[key.inner()];
+            song.path = gone.join(song.path.file_name().unwrap());
+        }
+
+        let (removed, paths) = Ccd::delta(&c, vec![gone]);
+        assert!(paths.is_empty());
+        assert_eq!(removed.len(), c.albums[AlbumKey::zero()].songs.len());
+
+        let (a, b, s, _, _) = Ccd::keep(&c, &removed);
+        assert_eq!(s.len(), c.songs.len() - removed.len());
+        assert_eq!(b.len(), c.albums.len() - 1);
+
+        for (i, song) in s.iter().enumerate() {
+            assert_eq!(song.key.inner(), i);
+            assert!(b[song.album.inner()].songs.contains(&song.key));
+        }
+        for (i, album) in b.iter().enumerate() {
+            assert_eq!(album.key.inner(), i);
+            assert!(a[album.artist.inner()].albums.contains(&album.key));
+        }
+    }
+
+    #[test]
+    // Updating with no changes results in the same `Collection`.
+    fn keep_all() {
+        let c = collection();
+        let (to_kernel, _from_ccd) = crossbeam::channel::unbounded::<CcdToKernel>();
+
+        let (a, b, s, n, orphans) = Ccd::keep(&c, &HashSet::new());
+        assert!(orphans.is_empty());
+        let (mut a, mut b, s, n) = Ccd::the_loop_with(&to_kernel, vec![], a, b, s, n);
+        Ccd::fix_metadata(&mut a, &mut b, &s);
+        let c2 = Ccd::build(&to_kernel, a, b, s, n).0;
+
+        assert_eq!(c.count_artist, c2.count_artist);
+        assert_eq!(c.count_album, c2.count_album);
+        assert_eq!(c.count_song, c2.count_song);
+
+        let lexi = |c: &Collection| -> Vec<PathBuf> {
+            c.sort_song_lexi
+                .iter()
+                .map(|k| c.songs[k].path.clone())
+                .collect()
+        };
+        assert_eq!(lexi(&c), lexi(&c2));
+    }
+}
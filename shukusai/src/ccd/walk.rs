@@ -129,7 +129,14 @@ impl super::Ccd {
     }
 
     #[inline(always)]
-    fn path_is_audio(path: PathBuf) -> Option<(PathBuf, &'static str, &'static str)> {
+    // If the PATH is a `.cue` file.
+    pub(crate) fn path_is_cue(path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("cue"))
+    }
+
+    #[inline(always)]
+    pub(crate) fn path_is_audio(path: PathBuf) -> Option<(PathBuf, &'static str, &'static str)> {
         trace!("CCD - Walking PATH: {}", path.display());
 
         // Attempt MIME via file magic bytes first.
@@ -1,5 +1,6 @@
 //---------------------------------------------------------------------------------------------------- Use
 use crate::collection::art::Art;
+use crate::collection::gain::Gain;
 use crate::collection::key::{AlbumKey, ArtistKey, SongKey};
 use bincode::{Decode, Encode};
 use readable::{Date, Runtime, Unsigned};
@@ -78,6 +79,21 @@ pub struct Album {
 
     /// This [`Album`]'s genre.
     pub genre: Option<String>,
+
+    #[serde(skip)]
+    /// This [`Album`]'s album loudness normalization data.
+    ///
+    /// `None` if it couldn't be read or calculated.
+    pub gain: Option<Gain>,
+
+    #[serde(skip)]
+    /// Every genre of this [`Album`].
+    ///
+    /// Multi-valued genre tags are split, e.g:
+    /// `Rock; Electronic` -> `["Rock", "Electronic"]`.
+    ///
+    /// The first one is this [`Album`]'s primary genre.
+    pub genres: Box<[Arc<str>]>,
 }
 
 #[cfg(feature = "gui")]
@@ -128,6 +144,8 @@ impl Default for Album {
             path: Default::default(),
             art: Default::default(),
             genre: Default::default(),
+            gain: Default::default(),
+            genres: Box::new([]),
         }
     }
 }
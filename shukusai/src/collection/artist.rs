@@ -36,6 +36,15 @@ pub struct Artist {
     ///
     /// The order is [`Album`] release order, then [`Song`] track order.
     pub songs: Box<[SongKey]>,
+
+    #[serde(skip)]
+    /// Keys to every [`Song`] this [`Artist`] appears on, that isn't their own.
+    ///
+    /// This is every [`Song`] that credits this [`Artist`] in
+    /// [`Song::artists`] or [`Song::featured`], but is owned by another [`Artist`].
+    ///
+    /// The order is [`Album`] release order, then [`Song`] track order.
+    pub appearances: Box<[SongKey]>,
 }
 
 impl Default for Artist {
@@ -47,6 +56,7 @@ impl Default for Artist {
             runtime: Default::default(),
             albums: Vec::with_capacity(0),
             songs: Box::new([]),
+            appearances: Box::new([]),
         }
     }
 }
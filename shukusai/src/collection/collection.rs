@@ -4,7 +4,7 @@ use crate::collection::{
     artist::Artist,
     plural::{Albums, Artists, Songs},
     song::Song,
-    AlbumKey, ArtistKey, Key, Map, SongKey,
+    AlbumKey, ArtistKey, Genre, Key, Map, SongKey,
 };
 use crate::constants::{COLLECTION_VERSION, FESTIVAL, FRONTEND_SUB_DIR, HEADER, STATE_SUB_DIR};
 use crate::sort::{AlbumSort, ArtistSort, SongSort};
@@ -220,6 +220,21 @@ pub struct Collection {
     pub sort_song_title: Box<[SongKey]>,
     /// [`Song`] title longest to shortest.
     pub sort_song_title_rev: Box<[SongKey]>,
+
+    // Sorted by genre.
+    //
+    // These were added in `v9`, after the other sorts.
+    /// [`Artist`] primary genre A-Z, [`Artist`] A-Z.
+    pub sort_artist_genre: Box<[ArtistKey]>,
+    /// [`Artist`] primary genre Z-A, [`Artist`] A-Z.
+    pub sort_artist_genre_rev: Box<[ArtistKey]>,
+    /// [`Album`] primary genre A-Z, [`Artist`] A-Z, [`Album`] oldest-latest.
+    pub sort_album_genre: Box<[AlbumKey]>,
+    /// [`Album`] primary genre Z-A, [`Artist`] A-Z, [`Album`] oldest-latest.
+    pub sort_album_genre_rev: Box<[AlbumKey]>,
+
+    /// Every [`Genre`] in this [`Collection`], A-Z.
+    pub genres: Box<[Genre]>,
 }
 
 impl Collection {
@@ -283,6 +298,12 @@ impl Collection {
             sort_song_runtime_rev: Box::new([]),
             sort_song_title: Box::new([]),
             sort_song_title_rev: Box::new([]),
+
+            sort_artist_genre: Box::new([]),
+            sort_artist_genre_rev: Box::new([]),
+            sort_album_genre: Box::new([]),
+            sort_album_genre_rev: Box::new([]),
+            genres: Box::new([]),
         }
     }
 
@@ -578,6 +599,17 @@ impl Collection {
             .collect()
     }
 
+    /// Get all [`Song`]'s this [`Artist`] appears on, but doesn't own
+    ///
+    /// See [`Artist::appearances`].
+    pub fn artist_appearances<K: Into<ArtistKey>>(&self, key: K) -> Box<[&Song]> {
+        self.artists[key.into()]
+            .appearances
+            .iter()
+            .map(|k| &self.songs[*k])
+            .collect()
+    }
+
     /// Get all [`Song`]'s in this [`Album`]
     pub fn album_songs<K: Into<AlbumKey>>(&self, key: K) -> Box<[&Song]> {
         self.albums[key.into()]
@@ -587,6 +619,30 @@ impl Collection {
             .collect()
     }
 
+    /// Get a [`Genre`] by name, case-insensitively
+    ///
+    /// Returns `None` if no [`Album`] has this genre.
+    pub fn genre(&self, name: &str) -> Option<&Genre> {
+        let name = name.to_lowercase();
+        self.genres
+            .binary_search_by(|g| (*g.name_lowercase).cmp(name.as_str()))
+            .ok()
+            .map(|i| &self.genres[i])
+    }
+
+    /// Get all [`Album`]'s with this [`Genre`]
+    ///
+    /// Returns `None` if no [`Album`] has this genre.
+    pub fn genre_albums(&self, name: &str) -> Option<Box<[&Album]>> {
+        Some(
+            self.genre(name)?
+                .albums
+                .iter()
+                .map(|k| &self.albums[*k])
+                .collect(),
+        )
+    }
+
     //-------------------------------------------------- Key traversal (index).
     #[inline(always)]
     /// Obtain an [`Artist`], but from a [`AlbumKey`].
@@ -675,6 +731,8 @@ impl Collection {
             RuntimeRev => &self.sort_artist_runtime_rev,
             Name => &self.sort_artist_name,
             NameRev => &self.sort_artist_name_rev,
+            Genre => &self.sort_artist_genre,
+            GenreRev => &self.sort_artist_genre_rev,
         }
         .iter()
     }
@@ -699,11 +757,17 @@ impl Collection {
             RuntimeRev => &self.sort_album_runtime_rev,
             Title => &self.sort_album_title,
             TitleRev => &self.sort_album_title_rev,
+            Genre => &self.sort_album_genre,
+            GenreRev => &self.sort_album_genre_rev,
         }
         .iter()
     }
 
     /// Access `sort_song` fields in the [`Collection`] as an iterator via a [`SongSort`].
+    ///
+    /// The play statistic sorts (e.g [`SongSort::PlayCount`]) are not stored
+    /// in the [`Collection`], these return [`Collection::sort_song_lexi`].
+    /// Use [`crate::state::Stats::sort_songs`] for those instead.
     pub fn song_iter(&self, sort: SongSort) -> std::slice::Iter<'_, SongKey> {
         use SongSort::*;
         match sort {
@@ -723,6 +787,7 @@ impl Collection {
             RuntimeRev => &self.sort_song_runtime_rev,
             Title => &self.sort_song_title,
             TitleRev => &self.sort_song_title_rev,
+            PlayCount | PlayCountRev | LastPlayed | LastPlayedRev => &self.sort_song_lexi,
         }
         .iter()
     }
@@ -1057,11 +1122,11 @@ mod tests {
 
     // Empty new `Collection`.
     const C1: Lazy<Collection> = Lazy::new(|| {
-        Collection::from_path("../assets/shukusai/state/collection3_new.bin").unwrap()
+        Collection::from_path("../assets/shukusai/state/collection9_new.bin").unwrap()
     });
     // Filled, user `Collection`.
     const C2: Lazy<Collection> = Lazy::new(|| {
-        Collection::from_path("../assets/shukusai/state/collection3_real.bin").unwrap()
+        Collection::from_path("../assets/shukusai/state/collection9_real.bin").unwrap()
     });
 
     #[test]
@@ -1124,6 +1189,7 @@ mod tests {
         assert_eq!(C2.artists[k].runtime, Runtime::from(4_u8));
         assert_eq!(C2.artists[k].albums.len(), 2);
         assert_eq!(C2.artists[k].songs.len(), 4);
+        assert!(C2.artists[k].appearances.is_empty());
 
         // Artist 2/3
         let k = ArtistKey::from(1_u8);
@@ -1142,6 +1208,7 @@ mod tests {
         // Albums 1/4
         let k = AlbumKey::from(0_u8);
         assert_eq!(C2.albums[k].title, "album_1".into());
+        assert!(C2.albums[k].genres.is_empty());
         assert_eq!(C2.albums[k].release, Date::from_str("2018-04-25").unwrap());
 
         // Albums 2/4
@@ -1159,6 +1226,11 @@ mod tests {
         assert_eq!(C2.albums[k].title, "album_4".into());
         assert_eq!(C2.albums[k].release, Date::from_str("2018-04-25").unwrap());
 
+        // Genres
+        assert!(C2.genres.is_empty());
+        assert_eq!(C2.sort_artist_genre, C2.sort_artist_lexi);
+        assert_eq!(C2.sort_album_genre, C2.sort_album_release_artist_lexi);
+
         // Song 1/7
         let k = SongKey::from(0_u8);
         assert_eq!(C2.songs[k].title, "mp3".into());
@@ -1167,6 +1239,12 @@ mod tests {
             C2.songs[k].path.as_os_str().to_str().unwrap(),
             "/home/main/git/festival/assets/audio/song_1.mp3"
         );
+        assert_eq!(&*C2.songs[k].artists[0], "artist_1");
+        assert!(C2.songs[k].composers.is_empty());
+        assert!(C2.songs[k].featured.is_empty());
+        assert!(!C2.songs[k].path_metadata);
+        assert_eq!(C2.songs[k].start, 0);
+        assert_eq!(C2.songs[k].end, None);
 
         // Song 2/7
         let k = SongKey::from(1_u8);
@@ -1221,6 +1299,9 @@ mod tests {
             C2.songs[k].path.as_os_str().to_str().unwrap(),
             "/home/main/git/festival/assets/audio/song_7.mp3"
         );
+        assert_eq!(&*C2.songs[k].artists[0], "artist_3");
+        assert!(C2.songs[k].composers.is_empty());
+        assert!(C2.songs[k].featured.is_empty());
     }
 
     #[test]
@@ -1239,11 +1320,11 @@ mod tests {
         use crate::collection::{Art, Keychain};
 
         #[cfg(target_os = "linux")]
-        const ALBUM_SIZE: usize = 360;
+        const ALBUM_SIZE: usize = 392;
         #[cfg(target_os = "macos")]
-        const ALBUM_SIZE: usize = 376;
+        const ALBUM_SIZE: usize = 408;
         #[cfg(target_os = "windows")]
-        const ALBUM_SIZE: usize = 384;
+        const ALBUM_SIZE: usize = 416;
 
         #[cfg(target_os = "linux")]
         const ART_SIZE: usize = 128;
@@ -1253,15 +1334,15 @@ mod tests {
         const ART_SIZE: usize = 144;
 
         #[cfg(target_os = "linux")]
-        const SONG_SIZE: usize = 152;
+        const SONG_SIZE: usize = 272;
         #[cfg(target_os = "macos")]
-        const SONG_SIZE: usize = 152;
+        const SONG_SIZE: usize = 272;
         #[cfg(target_os = "windows")]
-        const SONG_SIZE: usize = 160;
+        const SONG_SIZE: usize = 280;
 
         crate::assert_size_of! {
             // Collection
-            Collection       => 976,
+            Collection       => 1056,
             Unsigned         => 48,
             Map              => 48,
             Artists          => 16,
@@ -1272,7 +1353,7 @@ mod tests {
             Box<[SongKey]>   => 16,
 
             // Artist
-            Artist           => 104,
+            Artist           => 120,
             Runtime          => 24,
             Vec<AlbumKey>    => 24,
 
@@ -0,0 +1,103 @@
+//---------------------------------------------------------------------------------------------------- Use
+use bincode::{Decode, Encode};
+use serde::Serialize;
+
+//---------------------------------------------------------------------------------------------------- Constants
+/// The `ReplayGain 2.0` reference loudness in `LUFS`.
+///
+/// A [`Gain`] of `0.0` means the audio is already at this loudness.
+pub const GAIN_REFERENCE_LUFS: f32 = -18.0;
+
+//---------------------------------------------------------------------------------------------------- Gain
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd, Serialize, Encode, Decode)]
+/// Loudness normalization data for a [`Song`] or [`Album`]
+///
+/// This is read from `REPLAYGAIN_*` or `R128_*` tags, or if
+/// those don't exist, calculated from the audio itself.
+pub struct Gain {
+    /// The gain to apply in decibels to reach [`GAIN_REFERENCE_LUFS`].
+    pub gain: f32,
+    /// The peak sample amplitude, where `1.0` is full scale.
+    ///
+    /// `None` if unknown (`R128_*` tags don't have peaks).
+    pub peak: Option<f32>,
+}
+
+impl Gain {
+    #[inline]
+    /// Create a [`Gain`] from an integrated loudness in `LUFS`.
+    pub fn from_lufs(lufs: f32, peak: Option<f32>) -> Self {
+        Self {
+            gain: GAIN_REFERENCE_LUFS - lufs,
+            peak,
+        }
+    }
+
+    #[inline]
+    /// The integrated loudness in `LUFS` this [`Gain`] was created from.
+    pub fn lufs(&self) -> f32 {
+        GAIN_REFERENCE_LUFS - self.gain
+    }
+
+    #[inline]
+    /// Returns the linear factor samples should be multiplied by.
+    ///
+    /// This prevents clipping, the factor is lowered so that the
+    /// `peak` never goes above `1.0`. If the `peak` is unknown,
+    /// the factor is never above `1.0`, i.e, it only lowers volume.
+    pub fn factor(&self) -> f32 {
+        let factor = 10_f32.powf(self.gain / 20.0);
+
+        match self.peak {
+            Some(peak) if peak > 0.0 => factor.min(1.0 / peak),
+            _ => factor.min(1.0),
+        }
+    }
+}
+
+// `f32` isn't `Hash`, but `Song` is.
+impl std::hash::Hash for Gain {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.gain.to_bits().hash(state);
+        self.peak.map(f32::to_bits).hash(state);
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Asserts the factor never clips.
+    fn factor() {
+        // -6dB ~= 0.5
+        let g = Gain {
+            gain: -6.0,
+            peak: Some(1.0),
+        };
+        assert!((g.factor() - 0.501).abs() < 0.001);
+
+        // +6dB would clip a 0.8 peak, limited to 1/0.8.
+        let g = Gain {
+            gain: 6.0,
+            peak: Some(0.8),
+        };
+        assert_eq!(g.factor(), 1.25);
+
+        // +6dB with no peak, no boost.
+        let g = Gain {
+            gain: 6.0,
+            peak: None,
+        };
+        assert_eq!(g.factor(), 1.0);
+    }
+
+    #[test]
+    // Asserts `LUFS` <-> gain.
+    fn lufs() {
+        let g = Gain::from_lufs(-8.0, None);
+        assert_eq!(g.gain, -10.0);
+        assert_eq!(g.lufs(), -8.0);
+    }
+}
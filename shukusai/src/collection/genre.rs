@@ -0,0 +1,42 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::collection::key::{AlbumKey, SongKey};
+use bincode::{Decode, Encode};
+use serde::Serialize;
+use std::sync::Arc;
+
+//---------------------------------------------------------------------------------------------------- Genre
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Encode, Decode)]
+/// Struct holding a genre, with pointers to its [`Album`]\(s\) and [`Song`]\(s\)
+///
+/// Genres are matched case-insensitively, the
+/// spelling of the first [`Album`] found is kept.
+///
+/// An [`Album`] with multiple genres (see [`Album::genres`]) will be in multiple [`Genre`]'s.
+pub struct Genre {
+    /// The [`Genre`]'s name.
+    pub name: Arc<str>,
+    #[serde(skip)]
+    /// The [`Genre`]'s name in "Unicode Derived Core Property" lowercase.
+    pub name_lowercase: Arc<str>,
+
+    /// Keys to every [`Album`] with this [`Genre`].
+    ///
+    /// The order is [`Artist`] A-Z, then [`Album`] release order.
+    pub albums: Box<[AlbumKey]>,
+
+    /// Keys to every [`Song`] with this [`Genre`].
+    ///
+    /// The order is the same as `albums`, then [`Song`] track order.
+    pub songs: Box<[SongKey]>,
+}
+
+impl Default for Genre {
+    fn default() -> Self {
+        Self {
+            name: "".into(),
+            name_lowercase: "".into(),
+            albums: Box::new([]),
+            songs: Box::new([]),
+        }
+    }
+}
@@ -1,5 +1,5 @@
 //---------------------------------------------------------------------------------------------------- Use
-use crate::collection::{Album, AlbumKey, Artist, ArtistKey, Collection, Song, SongKey};
+use crate::collection::{Album, AlbumKey, Artist, ArtistKey, Collection, Genre, Song, SongKey};
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -110,6 +110,18 @@ pub struct CollectionJson<'a> {
     pub sort_song_title: Cow<'a, [SongKey]>,
     #[serde(borrow)]
     pub sort_song_title_rev: Cow<'a, [SongKey]>,
+
+    #[serde(borrow)]
+    pub sort_artist_genre: Cow<'a, [ArtistKey]>,
+    #[serde(borrow)]
+    pub sort_artist_genre_rev: Cow<'a, [ArtistKey]>,
+    #[serde(borrow)]
+    pub sort_album_genre: Cow<'a, [AlbumKey]>,
+    #[serde(borrow)]
+    pub sort_album_genre_rev: Cow<'a, [AlbumKey]>,
+
+    #[serde(borrow)]
+    pub genres: Cow<'a, [GenreJson<'a>]>,
 }
 
 #[allow(missing_docs)]
@@ -162,6 +174,26 @@ pub struct SongJson<'a> {
     pub mime: Cow<'a, str>,
     #[serde(borrow)]
     pub extension: Cow<'a, str>,
+    #[serde(borrow)]
+    pub track_artist: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    pub artists: Box<[Cow<'a, str>]>,
+    #[serde(borrow)]
+    pub composers: Box<[Cow<'a, str>]>,
+    #[serde(borrow)]
+    pub featured: Box<[Cow<'a, str>]>,
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// A struct representation of `Genre`'s JSON serialization output.
+pub struct GenreJson<'a> {
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    #[serde(borrow)]
+    pub albums: Cow<'a, [AlbumKey]>,
+    #[serde(borrow)]
+    pub songs: Cow<'a, [SongKey]>,
 }
 
 //---------------------------------------------------------------------------------------------------- TESTS
@@ -221,7 +253,12 @@ mod tests {
   sort_song_runtime: [],
   sort_song_runtime_rev: [],
   sort_song_title: [],
-  sort_song_title_rev: []
+  sort_song_title_rev: [],
+  sort_artist_genre: [],
+  sort_artist_genre_rev: [],
+  sort_album_genre: [],
+  sort_album_genre_rev: [],
+  genres: []
 }"#;
 
     const EXPECTED_ARTIST: &str = r#"{
@@ -245,6 +282,12 @@ mod tests {
   "genre": null
 }"#;
 
+    const EXPECTED_GENRE: &str = r#"{
+  "name": "",
+  "albums": [],
+  "songs": []
+}"#;
+
     const EXPECTED_SONG: &str = r#"{
   "title": "",
   "key": 0,
@@ -254,7 +297,11 @@ mod tests {
   "track": null,
   "disc": null,
   "mime": "",
-  "extension": ""
+  "extension": "",
+  "track_artist": null,
+  "artists": [],
+  "composers": [],
+  "featured": []
 }"#;
 
     fn serde_json_collection() {
@@ -290,4 +337,12 @@ mod tests {
         let d: SongJson = serde_json::from_str(&s).unwrap();
         assert_eq!(EXPECTED_SONG, serde_json::to_string_pretty(&d).unwrap());
     }
+
+    #[test]
+    fn serde_json_genre() {
+        let s: String = serde_json::to_string_pretty(&Genre::default()).unwrap();
+        assert_eq!(EXPECTED_GENRE, s);
+        let d: GenreJson = serde_json::from_str(&s).unwrap();
+        assert_eq!(EXPECTED_GENRE, serde_json::to_string_pretty(&d).unwrap());
+    }
 }
@@ -3,6 +3,8 @@ mod art;
 mod artist;
 mod collection;
 mod entry;
+mod gain;
+mod genre;
 mod image;
 mod key;
 mod map;
@@ -14,6 +16,8 @@ pub use art::*;
 pub use artist::*;
 pub use collection::*;
 pub use entry::*;
+pub use gain::*;
+pub use genre::*;
 pub use key::*;
 pub use map::*;
 pub use plural::*;
@@ -29,6 +33,18 @@ pub(crate) mod v0;
 pub(crate) mod v1;
 #[cfg(feature = "gui")]
 pub(crate) mod v2;
+#[cfg(feature = "gui")]
+pub(crate) mod v3;
+#[cfg(feature = "gui")]
+pub(crate) mod v4;
+#[cfg(feature = "gui")]
+pub(crate) mod v5;
+#[cfg(feature = "gui")]
+pub(crate) mod v6;
+#[cfg(feature = "gui")]
+pub(crate) mod v7;
+#[cfg(feature = "gui")]
+pub(crate) mod v8;
 
 /// `struct` representations for JSON output
 pub mod json;
@@ -1,5 +1,5 @@
 //---------------------------------------------------------------------------------------------------- Use
-use crate::collection::{AlbumKey, SongKey};
+use crate::collection::{AlbumKey, Gain, SongKey};
 use bincode::{Decode, Encode};
 use readable::Runtime;
 use serde::Serialize;
@@ -44,6 +44,55 @@ pub struct Song {
     #[serde(skip)]
     /// The [`PathBuf`] this [`Song`] is located at.
     pub path: PathBuf,
+
+    #[serde(skip)]
+    /// This [`Song`]'s track loudness normalization data.
+    ///
+    /// `None` if it couldn't be read or calculated.
+    pub gain: Option<Gain>,
+
+    /// The track artist of this [`Song`].
+    ///
+    /// This is only `Some` for [`Song`]'s in compilation [`Album`]'s
+    /// (e.g, soundtracks, "Various Artists"), where the owning [`Artist`]
+    /// is not the actual artist of this [`Song`].
+    pub track_artist: Option<Arc<str>>,
+    #[serde(skip)]
+    /// [`Song::track_artist`] in "Unicode Derived Core Property" lowercase.
+    pub track_artist_lowercase: Option<Arc<str>>,
+
+    /// Every artist credited on this [`Song`].
+    ///
+    /// This is the `Artist` tag, split on separators like `&` and ` x `, e.g:
+    /// `Artist A & Artist B` -> `["Artist A", "Artist B"]`.
+    ///
+    /// Featured artists are not included, see [`Song::featured`].
+    pub artists: Box<[Arc<str>]>,
+    /// Every composer credited on this [`Song`].
+    ///
+    /// This is the `Composer` tag, split the same way as [`Song::artists`].
+    pub composers: Box<[Arc<str>]>,
+    /// Every featured artist on this [`Song`].
+    ///
+    /// These are found after `feat.`, `ft.`, `featuring`
+    /// in either the `Artist` tag or the title.
+    pub featured: Box<[Arc<str>]>,
+
+    /// Was any of this [`Song`]'s metadata inferred from its PATH?
+    ///
+    /// This is `true` if the file was missing tags and
+    /// some were filled in with a [`crate::template::PathTemplate`].
+    pub path_metadata: bool,
+
+    /// Where this [`Song`] starts within its file, in milliseconds.
+    ///
+    /// This is only non-zero for [`Song`]'s split from a CUE sheet,
+    /// where multiple [`Song`]'s share the same [`Song::path`].
+    pub start: u64,
+    /// Where this [`Song`] ends within its file, in milliseconds.
+    ///
+    /// `None` means the end of the file.
+    pub end: Option<u64>,
 }
 
 impl Default for Song {
@@ -60,6 +109,15 @@ impl Default for Song {
             mime: "".into(),
             extension: "".into(),
             path: Default::default(),
+            gain: Default::default(),
+            track_artist: Default::default(),
+            track_artist_lowercase: Default::default(),
+            artists: Box::new([]),
+            composers: Box::new([]),
+            featured: Box::new([]),
+            path_metadata: false,
+            start: 0,
+            end: None,
         }
     }
 }
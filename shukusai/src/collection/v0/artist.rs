@@ -37,6 +37,9 @@ impl Into<crate::collection::Artist> for Artist {
         let name = name.into();
 
         crate::collection::Artist {
+            // Needs the `Song` credits, which this version doesn't have.
+            appearances: Box::new([]),
+
             // INVARIANT: must be set correctly in the broader `Collection::into()`
             key: ArtistKey::zero(),
 
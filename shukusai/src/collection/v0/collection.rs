@@ -215,6 +215,13 @@ impl Into<crate::collection::Collection> for Collection {
         let songs: crate::collection::Songs = songs.into();
         let map = crate::collection::Map::from_3_vecs(&artists.0, &albums.0, &songs.0);
 
+        // No `Album` has genres until re-scanned, so
+        // these are the same as the A-Z sorts.
+        let sort_artist_genre = sort_artist_lexi.clone();
+        let sort_artist_genre_rev = sort_artist_lexi.clone();
+        let sort_album_genre = sort_album_release_artist_lexi.clone();
+        let sort_album_genre_rev = sort_album_release_artist_lexi.clone();
+
         crate::collection::Collection {
             empty,
             timestamp,
@@ -272,6 +279,12 @@ impl Into<crate::collection::Collection> for Collection {
             sort_song_runtime_rev,
             sort_song_title,
             sort_song_title_rev,
+
+            sort_artist_genre,
+            sort_artist_genre_rev,
+            sort_album_genre,
+            sort_album_genre_rev,
+            genres: Box::new([]),
         }
     }
 }
@@ -83,10 +83,13 @@ impl Into<crate::collection::Album> for Album {
         } = self;
 
         crate::collection::Album {
+            // Not re-scanned, `Audio` handles `None`.
+            gain: None,
             // INVARIANT: must be set correctly in the broader `Collection::into()`
             key: AlbumKey::zero(),
             // We can't recover this info, assume user will rescan... eventually...
             genre: None,
+            genres: Box::new([]),
 
             title,
             title_lowercase,
@@ -52,6 +52,9 @@ impl Into<crate::collection::Artist> for Artist {
         } = self;
 
         crate::collection::Artist {
+            // Needs the `Song` credits, which this version doesn't have.
+            appearances: Box::new([]),
+
             // INVARIANT: must be set correctly in the broader `Collection::into()`
             key: ArtistKey::zero(),
 
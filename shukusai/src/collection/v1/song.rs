@@ -47,6 +47,18 @@ impl Into<crate::collection::Song> for Song {
         } = self;
 
         crate::collection::Song {
+            // Not re-scanned, `Audio` handles `None`.
+            gain: None,
+            // Compilations were skipped in `v1`.
+            track_artist: None,
+            track_artist_lowercase: None,
+            // Not re-scanned.
+            artists: Box::new([]),
+            composers: Box::new([]),
+            featured: Box::new([]),
+            path_metadata: false,
+            start: 0,
+            end: None,
             // INVARIANT: must be set correctly in the broader `Collection::into()`
             key: SongKey::zero(),
 
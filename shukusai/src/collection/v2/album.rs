@@ -150,6 +150,10 @@ impl Into<crate::collection::Album> for Album {
         } = self;
 
         crate::collection::Album {
+            // Not split until re-scanned.
+            genres: Box::new([]),
+            // Not re-scanned, `Audio` handles `None`.
+            gain: None,
             genre,
             key,
             title,
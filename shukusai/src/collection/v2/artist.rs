@@ -63,6 +63,9 @@ impl Into<crate::collection::Artist> for Artist {
         } = self;
 
         crate::collection::Artist {
+            // Needs the `Song` credits, which this version doesn't have.
+            appearances: Box::new([]),
+
             key,
             name,
             name_lowercase,
@@ -72,6 +72,18 @@ impl Into<crate::collection::Song> for Song {
         } = self;
 
         crate::collection::Song {
+            // Not re-scanned, `Audio` handles `None`.
+            gain: None,
+            // Compilations were skipped in `v2`.
+            track_artist: None,
+            track_artist_lowercase: None,
+            // Not re-scanned.
+            artists: Box::new([]),
+            composers: Box::new([]),
+            featured: Box::new([]),
+            path_metadata: false,
+            start: 0,
+            end: None,
             // Could chase PATHs and recover this
             // but that's slow and this info isn't crucial.
             mime: "".into(),
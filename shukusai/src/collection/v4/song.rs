@@ -0,0 +1,123 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::collection::{AlbumKey, Gain, SongKey};
+use bincode::{Decode, Encode};
+use readable::Runtime;
+use serde::Serialize;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+//----------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Hash, PartialEq, PartialOrd, Serialize, Encode, Decode)]
+/// Struct holding [`Song`] metadata, with a pointer to the [`Album`] it belongs to
+///
+/// This struct holds all the metadata about a particular [`Song`].
+///
+/// It contains a [`SongKey`] that is the index of the owning [`Album`], in the [`Collection`].
+pub struct Song {
+    /// Title of the [`Song`].
+    pub title: Arc<str>,
+    #[serde(skip)]
+    /// Title of the [`Song`] in "Unicode Derived Core Property" lowercase.
+    pub title_lowercase: Arc<str>,
+
+    /// This [`Song`]'s [`SongKey`].
+    pub key: SongKey,
+    /// Key to the [`Album`].
+    pub album: AlbumKey,
+
+    #[serde(serialize_with = "crate::serde::runtime")]
+    /// Total runtime of this [`Song`].
+    pub runtime: Runtime,
+    /// Sample rate of this [`Song`].
+    pub sample_rate: u32,
+    /// The track number of this [`Song`].
+    pub track: Option<u32>,
+    /// The disc number of this [`Song`].
+    pub disc: Option<u32>,
+
+    /// The `MIME` type of this [`Song`].
+    pub mime: Arc<str>,
+    /// The file extension of this [`Song`].
+    pub extension: Arc<str>,
+
+    #[serde(skip)]
+    /// The [`PathBuf`] this [`Song`] is located at.
+    pub path: PathBuf,
+
+    #[serde(skip)]
+    /// This [`Song`]'s track loudness normalization data.
+    ///
+    /// `None` if it couldn't be read or calculated.
+    pub gain: Option<Gain>,
+}
+
+impl Default for Song {
+    fn default() -> Self {
+        Self {
+            key: SongKey::zero(),
+            title: "".into(),
+            title_lowercase: "".into(),
+            album: Default::default(),
+            runtime: Default::default(),
+            sample_rate: Default::default(),
+            track: Default::default(),
+            disc: Default::default(),
+            mime: "".into(),
+            extension: "".into(),
+            path: Default::default(),
+            gain: Default::default(),
+        }
+    }
+}
+
+impl Into<crate::collection::Song> for Song {
+    fn into(self) -> crate::collection::Song {
+        let Self {
+            title,
+            title_lowercase,
+            album,
+            runtime,
+            sample_rate,
+            track,
+            disc,
+            mime,
+            extension,
+            path,
+            key,
+            gain,
+        } = self;
+
+        crate::collection::Song {
+            // Compilations were skipped in `v4`.
+            track_artist: None,
+            track_artist_lowercase: None,
+            // Not re-scanned.
+            artists: Box::new([]),
+            composers: Box::new([]),
+            featured: Box::new([]),
+            path_metadata: false,
+            start: 0,
+            end: None,
+
+            key,
+            title,
+            title_lowercase,
+            album,
+            runtime,
+            sample_rate,
+            track,
+            disc,
+            mime,
+            extension,
+            path,
+            gain,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+}
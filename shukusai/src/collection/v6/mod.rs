@@ -0,0 +1,10 @@
+mod album;
+pub(crate) use album::*;
+mod artist;
+pub(crate) use artist::*;
+mod collection;
+pub(crate) use collection::*;
+mod song;
+pub(crate) use song::*;
+mod plural;
+pub(crate) use plural::*;
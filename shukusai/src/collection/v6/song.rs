@@ -0,0 +1,159 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::collection::{AlbumKey, Gain, SongKey};
+use bincode::{Decode, Encode};
+use readable::Runtime;
+use serde::Serialize;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+//----------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Hash, PartialEq, PartialOrd, Serialize, Encode, Decode)]
+/// Struct holding [`Song`] metadata, with a pointer to the [`Album`] it belongs to
+///
+/// This struct holds all the metadata about a particular [`Song`].
+///
+/// It contains a [`SongKey`] that is the index of the owning [`Album`], in the [`Collection`].
+pub struct Song {
+    /// Title of the [`Song`].
+    pub title: Arc<str>,
+    #[serde(skip)]
+    /// Title of the [`Song`] in "Unicode Derived Core Property" lowercase.
+    pub title_lowercase: Arc<str>,
+
+    /// This [`Song`]'s [`SongKey`].
+    pub key: SongKey,
+    /// Key to the [`Album`].
+    pub album: AlbumKey,
+
+    #[serde(serialize_with = "crate::serde::runtime")]
+    /// Total runtime of this [`Song`].
+    pub runtime: Runtime,
+    /// Sample rate of this [`Song`].
+    pub sample_rate: u32,
+    /// The track number of this [`Song`].
+    pub track: Option<u32>,
+    /// The disc number of this [`Song`].
+    pub disc: Option<u32>,
+
+    /// The `MIME` type of this [`Song`].
+    pub mime: Arc<str>,
+    /// The file extension of this [`Song`].
+    pub extension: Arc<str>,
+
+    #[serde(skip)]
+    /// The [`PathBuf`] this [`Song`] is located at.
+    pub path: PathBuf,
+
+    #[serde(skip)]
+    /// This [`Song`]'s track loudness normalization data.
+    ///
+    /// `None` if it couldn't be read or calculated.
+    pub gain: Option<Gain>,
+
+    /// The track artist of this [`Song`].
+    ///
+    /// This is only `Some` for [`Song`]'s in compilation [`Album`]'s
+    /// (e.g, soundtracks, "Various Artists"), where the owning [`Artist`]
+    /// is not the actual artist of this [`Song`].
+    pub track_artist: Option<Arc<str>>,
+    #[serde(skip)]
+    /// [`Song::track_artist`] in "Unicode Derived Core Property" lowercase.
+    pub track_artist_lowercase: Option<Arc<str>>,
+
+    /// Every artist credited on this [`Song`].
+    ///
+    /// This is the `Artist` tag, split on separators like `&` and ` x `, e.g:
+    /// `Artist A & Artist B` -> `["Artist A", "Artist B"]`.
+    ///
+    /// Featured artists are not included, see [`Song::featured`].
+    pub artists: Box<[Arc<str>]>,
+    /// Every composer credited on this [`Song`].
+    ///
+    /// This is the `Composer` tag, split the same way as [`Song::artists`].
+    pub composers: Box<[Arc<str>]>,
+    /// Every featured artist on this [`Song`].
+    ///
+    /// These are found after `feat.`, `ft.`, `featuring`
+    /// in either the `Artist` tag or the title.
+    pub featured: Box<[Arc<str>]>,
+}
+
+impl Default for Song {
+    fn default() -> Self {
+        Self {
+            key: SongKey::zero(),
+            title: "".into(),
+            title_lowercase: "".into(),
+            album: Default::default(),
+            runtime: Default::default(),
+            sample_rate: Default::default(),
+            track: Default::default(),
+            disc: Default::default(),
+            mime: "".into(),
+            extension: "".into(),
+            path: Default::default(),
+            gain: Default::default(),
+            track_artist: Default::default(),
+            track_artist_lowercase: Default::default(),
+            artists: Box::new([]),
+            composers: Box::new([]),
+            featured: Box::new([]),
+        }
+    }
+}
+
+impl Into<crate::collection::Song> for Song {
+    fn into(self) -> crate::collection::Song {
+        let Self {
+            title,
+            title_lowercase,
+            album,
+            runtime,
+            sample_rate,
+            track,
+            disc,
+            mime,
+            extension,
+            path,
+            key,
+            gain,
+            track_artist,
+            track_artist_lowercase,
+            artists,
+            composers,
+            featured,
+        } = self;
+
+        crate::collection::Song {
+            // Not re-scanned.
+            path_metadata: false,
+            start: 0,
+            end: None,
+
+            key,
+            title,
+            title_lowercase,
+            album,
+            runtime,
+            sample_rate,
+            track,
+            disc,
+            mime,
+            extension,
+            path,
+            gain,
+            track_artist,
+            track_artist_lowercase,
+            artists,
+            composers,
+            featured,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+}
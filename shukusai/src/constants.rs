@@ -139,7 +139,12 @@ pub const HEADER_STR: &str = match std::str::from_utf8(&HEADER) {
 };
 
 /// Current major version of the [`Collection`]
-pub const COLLECTION_VERSION: u8 = 3;
+pub const COLLECTION_VERSION: u8 = 9;
+
+/// The `Artist` name compilation `Album`'s are grouped under
+///
+/// This is used if a compilation (e.g, a soundtrack) has no `AlbumArtist` tag.
+pub const VARIOUS_ARTISTS: &str = "Various Artists";
 
 /// Current major version of the [`AudioState`]
 pub const AUDIO_VERSION: u8 = 0;
@@ -147,6 +152,15 @@ pub const AUDIO_VERSION: u8 = 0;
 /// Current major version of the [`Playlists`]
 pub const PLAYLIST_VERSION: u8 = 0;
 
+/// Current major version of the [`SmartPlaylists`]
+pub const SMART_PLAYLIST_VERSION: u8 = 0;
+
+/// Current major version of the [`Stats`]
+pub const STATS_VERSION: u8 = 0;
+
+/// Current major version of the search index (`search_index.bin`)
+pub const SEARCH_INDEX_VERSION: u8 = 0;
+
 #[cfg(target_os = "windows")]
 #[cfg(target_arch = "x86_64")]
 /// OS + Arch
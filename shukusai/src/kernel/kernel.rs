@@ -4,12 +4,14 @@ use crate::{
     audio::{Append, Audio, AudioToKernel, KernelToAudio, Volume},
     ccd::{Ccd, CcdToKernel},
     collection::{AlbumKey, ArtistKey, Collection, SongKey, DUMMY_COLLECTION},
-    constants::{AUDIO_VERSION, COLLECTION_VERSION, PLAYLIST_VERSION},
+    constants::{
+        AUDIO_VERSION, COLLECTION_VERSION, PLAYLIST_VERSION, SMART_PLAYLIST_VERSION, STATS_VERSION,
+    },
     logger::INIT_INSTANT,
     search::{KernelToSearch, Search, SearchToKernel},
     state::{
-        AudioState, AudioStateRestore, Phase, Playlists, AUDIO_STATE, PLAYLISTS, RESETTING,
-        RESET_STATE,
+        AudioState, AudioStateRestore, Phase, Playlists, SmartPlaylists, Stats, AUDIO_STATE,
+        PLAYLISTS, RESETTING, RESET_STATE, SMART_PLAYLISTS, STATS,
     },
     watch::{Watch, WatchToKernel},
 };
@@ -159,6 +161,23 @@ impl Kernel {
         // Create `ResetState`, send to `Frontend`.
         RESET_STATE.write().disk();
 
+        // `Stats` are keyed by name, not by `Collection` keys,
+        // so they can be read regardless of the `Collection` state.
+        // SAFETY: memmap is used.
+        match unsafe { Stats::from_file_memmap() } {
+            Ok(stats) => *STATS.write() = stats,
+            Err(e) => debug!("Kernel Init ... Stats{STATS_VERSION} from file error: {e}"),
+        }
+
+        // Same for `SmartPlaylists`, their contents are in `Playlists`.
+        // SAFETY: memmap is used.
+        match unsafe { SmartPlaylists::from_file_memmap() } {
+            Ok(smart) => *SMART_PLAYLISTS.write() = smart,
+            Err(e) => debug!(
+                "Kernel Init ... SmartPlaylists{SMART_PLAYLIST_VERSION} from file error: {e}"
+            ),
+        }
+
         // Attempt to load `Collection` from file.
         debug!("Kernel Init ... Reading Collection{COLLECTION_VERSION} from disk...");
         let now = now!();
@@ -169,6 +188,12 @@ impl Kernel {
             (COLLECTION_VERSION, || unsafe {
                 Collection::from_file_memmap()
             }),
+            (8, crate::collection::v8::Collection::disk_into),
+            (7, crate::collection::v7::Collection::disk_into),
+            (6, crate::collection::v6::Collection::disk_into),
+            (5, crate::collection::v5::Collection::disk_into),
+            (4, crate::collection::v4::Collection::disk_into),
+            (3, crate::collection::v3::Collection::disk_into),
             (2, crate::collection::v2::Collection::disk_into),
             (1, crate::collection::v1::Collection::disk_into),
             (0, crate::collection::v0::Collection::disk_into),
@@ -598,6 +623,9 @@ impl Kernel {
             // Audio settings.
             Repeat(r) => send!(self.to_audio, KernelToAudio::Repeat(r)),
             Volume(volume) => send!(self.to_audio, KernelToAudio::Volume(volume.check())),
+            Crossfade(c) => send!(self.to_audio, KernelToAudio::Crossfade(c)),
+            ReplayGain(r) => send!(self.to_audio, KernelToAudio::ReplayGain(r)),
+            Output(name) => send!(self.to_audio, KernelToAudio::Output(name)),
             Seek(tuple) => send!(self.to_audio, KernelToAudio::Seek(tuple)),
 
             // Queue.
@@ -605,6 +633,7 @@ impl Kernel {
             QueueAddAlbum(tuple) => send!(self.to_audio, KernelToAudio::QueueAddAlbum(tuple)),
             QueueAddArtist(tuple) => send!(self.to_audio, KernelToAudio::QueueAddArtist(tuple)),
             QueueAddPlaylist(tuple) => send!(self.to_audio, KernelToAudio::QueueAddPlaylist(tuple)),
+            QueueAddGenre(tuple) => send!(self.to_audio, KernelToAudio::QueueAddGenre(tuple)),
             Shuffle => send!(self.to_audio, KernelToAudio::Shuffle),
             Clear(play) => send!(self.to_audio, KernelToAudio::Clear(play)),
             Skip(num) => send!(self.to_audio, KernelToAudio::Skip(num)),
@@ -619,6 +648,9 @@ impl Kernel {
 
             // Collection.
             NewCollection(paths) => self.ccd_mode(paths),
+            #[cfg(not(feature = "gui"))]
+            #[cfg(feature = "daemon")]
+            UpdateCollection(paths) => self.ccd_update(paths),
             CachePath(paths) => Self::cache_path(paths),
             Search(string) => send!(self.to_search, KernelToSearch::Search(string)),
 
@@ -727,6 +759,24 @@ impl Kernel {
             }
         }
 
+        // Save `SmartPlaylists`.
+        match SMART_PLAYLISTS.read().save_atomic() {
+            Ok(o) => ok!("Kernel - SmartPlaylists{SMART_PLAYLIST_VERSION} save: {o}"),
+            Err(e) => {
+                fail!("Kernel - SmartPlaylists{SMART_PLAYLIST_VERSION} save: {e}");
+                err = Some(e.to_string());
+            }
+        }
+
+        // Save `Stats`.
+        match STATS.read().save_atomic() {
+            Ok(o) => ok!("Kernel - Stats{STATS_VERSION} save: {o}"),
+            Err(e) => {
+                fail!("Kernel - Stats{STATS_VERSION} save: {e}");
+                err = Some(e.to_string());
+            }
+        }
+
         if let Some(err) = err {
             send!(self.to_frontend, KernelToFrontend::Exit(Err(err)));
         } else {
@@ -794,6 +844,37 @@ impl Kernel {
     // 6. Tell `CCD` to... `Die`
     // 7. Give new `Arc<Collection>` to everyone
     fn ccd_mode(&mut self, paths: Vec<PathBuf>) {
+        // If the provided PATHs is empty,
+        // scan the default Music directory.
+        let paths = match paths.is_empty() {
+            false => paths,
+            true => match dirs::audio_dir() {
+                Some(p) => vec![p],
+                None => vec![],
+            },
+        };
+
+        self.ccd(move |to_kernel, old_collection| {
+            Ccd::new_collection(to_kernel, old_collection, paths)
+        });
+    }
+
+    #[cfg(not(feature = "gui"))]
+    #[cfg(feature = "daemon")]
+    // Same as `ccd_mode()`, but only the changed
+    // PATHs are scanned, the rest is re-used.
+    fn ccd_update(&mut self, paths: Vec<PathBuf>) {
+        self.ccd(move |to_kernel, old_collection| {
+            Ccd::update_collection(to_kernel, old_collection, paths)
+        });
+    }
+
+    // Hand the old `Collection` to `CCD` (running `f`),
+    // and wait for it to send back the new one.
+    fn ccd<F>(&mut self, f: F)
+    where
+        F: FnOnce(Sender<CcdToKernel>, Arc<Collection>) + Send + 'static,
+    {
         atomic_store!(RESETTING, true);
 
         // Set our `ResetState`.
@@ -847,21 +928,11 @@ impl Kernel {
         // Set `ResetState` to `Start` phase.
         RESET_STATE.write().start();
 
-        // If the provided PATHs is empty,
-        // scan the default Music directory.
-        let paths = match paths.is_empty() {
-            false => paths,
-            true => match dirs::audio_dir() {
-                Some(p) => vec![p],
-                None => vec![],
-            },
-        };
-
         // Spawn `CCD`.
         if let Err(e) = std::thread::Builder::new()
             .name("CCD".to_string())
             .stack_size(16_000_000) // 16MB stack.
-            .spawn(move || Ccd::new_collection(ccd_send, old_collection, paths))
+            .spawn(move || f(ccd_send, old_collection))
         {
             panic!("Kernel - failed to spawn CCD: {e}");
         }
@@ -1,6 +1,6 @@
 //---------------------------------------------------------------------------------------------------- Use
 use crate::{
-    audio::{Append, Repeat, Seek, Volume},
+    audio::{Append, Crossfade, Repeat, ReplayGain, Seek, Volume},
     collection::{AlbumKey, ArtistKey, Collection, Keychain, SongKey},
     search::SearchKind,
 };
@@ -46,6 +46,22 @@ pub enum FrontendToKernel {
     ///
     /// Use [`crate::state::VOLUME`] instead.
     Volume(Volume),
+    /// Change the [`Crossfade`] used between songs.
+    ///
+    /// This takes effect on the next song change,
+    /// a crossfade already in progress is cancelled.
+    Crossfade(Crossfade),
+    /// Change the [`ReplayGain`] loudness normalization mode.
+    ///
+    /// This takes effect immediately.
+    ReplayGain(ReplayGain),
+    /// Switch to the [`crate::audio::Output`] with this name.
+    ///
+    /// The name must be one of [`crate::audio::outputs`], unknown names are ignored.
+    ///
+    /// If the new output fails to open, the old one is kept
+    /// and a [`KernelToFrontend::DeviceError`] is sent.
+    Output(String),
 
     // Queue.
     /// - [`SongKey`]: add this `Song` to the queue.
@@ -87,6 +103,10 @@ pub enum FrontendToKernel {
     QueueAddArtist((ArtistKey, Append, bool, bool, usize)),
     /// Add the playlist with this name to the queue.
     QueueAddPlaylist((Arc<str>, Append, bool, bool, usize)),
+    /// Add all the `Song`'s of the genre with this name (case-insensitive) to the queue.
+    ///
+    /// The order is the same as [`Genre::songs`](crate::collection::Genre::songs).
+    QueueAddGenre((Arc<str>, Append, bool, bool, usize)),
     /// Shuffle the _current_ queue.
     Shuffle,
     /// Clear the entire queue.
@@ -132,9 +152,24 @@ pub enum FrontendToKernel {
     // Collection.
     /// I'd like a new [`Collection`], scanning these [`PathBuf`]'s for audio files.
     NewCollection(Vec<PathBuf>),
+    #[cfg(not(feature = "gui"))]
+    #[cfg(feature = "daemon")]
+    /// These [`PathBuf`]'s were added, modified, renamed or removed,
+    /// please update the [`Collection`] without re-scanning everything else.
+    ///
+    /// This is meant to be used with the PATHs sent by [`crate::CollectionWatch`].
+    ///
+    /// # Notes
+    /// This is the same as [`FrontendToKernel::NewCollection`], [`Kernel`]
+    /// will respond with [`KernelToFrontend::NewCollection`] and all the
+    /// same rules apply (drop your pointer, keys may change).
+    UpdateCollection(Vec<PathBuf>),
     /// I'd like to search the [`Collection`] with this [`String`] for similar
     /// [`Artist`]'s, [`Album`]'s, and [`Song`]'s.
     ///
+    /// The [`String`] is parsed as a [`crate::search::Query`], so it
+    /// may contain filters like `artist:radiohead year:>2000`.
+    ///
     /// # Notes
     /// [`Kernel`] will respond with [`KernelToFrontend::SearchResp`].
     Search((String, SearchKind)),
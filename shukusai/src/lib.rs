@@ -83,6 +83,9 @@ pub use const_format::formatcp as const_format;
 /// Collection creation performance.
 pub use ccd::perf;
 
+/// Folder-structure fallback for untagged files.
+pub use ccd::template;
+
 /// Panic.
 pub mod panic;
 
@@ -157,6 +160,15 @@ pub mod sort;
 /// `Kernel` will start audio playback, then delete the file that was created.
 pub mod signal;
 
+#[cfg(not(feature = "gui"))]
+#[cfg(feature = "daemon")]
+/// Filesystem watcher for the `Collection`'s PATHs
+///
+/// This sends batches of changed PATHs that `Kernel` can use to update
+/// the `Collection` with [`kernel::FrontendToKernel::UpdateCollection`]
+/// without re-scanning everything.
+pub use watch::CollectionWatch;
+
 /// `Frontend`-specific compatibility layers
 pub mod frontend;
 
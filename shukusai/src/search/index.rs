@@ -0,0 +1,408 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::collection::{Album, Artist, Collection, Song};
+use crate::constants::{FESTIVAL, FRONTEND_SUB_DIR, HEADER, SEARCH_INDEX_VERSION, STATE_SUB_DIR};
+use bincode::{Decode, Encode};
+use const_format::formatcp;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use unicode_normalization::char::{decompose_compatible, is_combining_mark};
+
+//---------------------------------------------------------------------------------------------------- Statics
+// The `SearchIndex` `CCD` just built alongside a new `Collection`.
+//
+// `CCD` sets this before sending the `Collection` to `Kernel`,
+// `Search` takes it when it receives that `Collection`,
+// so it doesn't have to re-build or read it from disk.
+pub(crate) static NEW_INDEX: Mutex<Option<Arc<SearchIndex>>> = Mutex::new(None);
+
+//---------------------------------------------------------------------------------------------------- SearchIndex
+disk::bincode2!(
+    SearchIndex,
+    disk::Dir::Data,
+    FESTIVAL,
+    formatcp!("{FRONTEND_SUB_DIR}/{STATE_SUB_DIR}"),
+    "search_index",
+    HEADER,
+    SEARCH_INDEX_VERSION
+);
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+// An inverted index of all the words within the `Collection`.
+//
+// Each token (a folded word, or a CJK character/bigram)
+// maps to the `Artist`'s, `Album`'s and `Song`'s it is in.
+//
+// The tokens are sorted, so all tokens starting
+// with a prefix are next to each other.
+pub(crate) struct SearchIndex {
+    // The `Collection` this index is for.
+    timestamp: u64,
+    count_artist: usize,
+    count_album: usize,
+    count_song: usize,
+
+    // Sorted, unique tokens.
+    tokens: Box<[String]>,
+    // The objects each token (of the same index) is in.
+    postings: Box<[Postings]>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+// The sorted `Artist`, `Album` and `Song` indices a token is in.
+struct Postings {
+    artists: Box<[usize]>,
+    albums: Box<[usize]>,
+    songs: Box<[usize]>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+// The sorted `Artist`, `Album` and `Song` indices matching a search.
+pub(crate) struct Hits {
+    pub(crate) artists: Vec<usize>,
+    pub(crate) albums: Vec<usize>,
+    pub(crate) songs: Vec<usize>,
+}
+
+impl SearchIndex {
+    // Create the index for a `Collection`.
+    pub(crate) fn new(collection: &Collection) -> Self {
+        let mut index = Self::from_3_vecs(
+            &collection.artists.0,
+            &collection.albums.0,
+            &collection.songs.0,
+        );
+        index.set_collection(collection);
+        index
+    }
+
+    // Index the "3 Slices" before they are turned into a `Collection`.
+    //
+    // `set_collection()` must be called once the `Collection` exists.
+    //
+    // `Artist`'s are indexed by name, `Album`'s by title and
+    // `Song`'s by title and track artist (for compilations).
+    pub(crate) fn from_3_vecs(artists: &[Artist], albums: &[Album], songs: &[Song]) -> Self {
+        let mut map: HashMap<String, [Vec<usize>; 3]> = HashMap::new();
+
+        let mut insert = |text: &str, kind: usize, i: usize| {
+            for token in Self::tokenize(text, false) {
+                let vec = &mut map.entry(token).or_default()[kind];
+                // The same token twice, e.g: `Love Me, Love Me`.
+                if vec.last() != Some(&i) {
+                    vec.push(i);
+                }
+            }
+        };
+
+        for (i, artist) in artists.iter().enumerate() {
+            insert(&artist.name, 0, i);
+        }
+        for (i, album) in albums.iter().enumerate() {
+            insert(&album.title, 1, i);
+        }
+        for (i, song) in songs.iter().enumerate() {
+            insert(&song.title, 2, i);
+            if let Some(artist) = &song.track_artist {
+                insert(artist, 2, i);
+            }
+        }
+
+        let mut vec: Vec<(String, [Vec<usize>; 3])> = map.into_iter().collect();
+        vec.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        let (tokens, postings) = vec
+            .into_iter()
+            .map(|(token, [artists, albums, songs])| {
+                let postings = Postings {
+                    artists: artists.into_boxed_slice(),
+                    albums: albums.into_boxed_slice(),
+                    songs: songs.into_boxed_slice(),
+                };
+                (token, postings)
+            })
+            .unzip::<_, _, Vec<String>, Vec<Postings>>();
+
+        Self {
+            tokens: tokens.into_boxed_slice(),
+            postings: postings.into_boxed_slice(),
+            ..Default::default()
+        }
+    }
+
+    // Mark this index as being for `collection`.
+    pub(crate) fn set_collection(&mut self, collection: &Collection) {
+        self.timestamp = collection.timestamp;
+        self.count_artist = collection.count_artist.usize();
+        self.count_album = collection.count_album.usize();
+        self.count_song = collection.count_song.usize();
+    }
+
+    // Is this index for `collection`?
+    pub(crate) fn is_for(&self, collection: &Collection) -> bool {
+        self.timestamp == collection.timestamp
+            && self.count_artist == collection.count_artist.usize()
+            && self.count_album == collection.count_album.usize()
+            && self.count_song == collection.count_song.usize()
+    }
+
+    // The objects that contain all the tokens of `text`, each
+    // token matched as a prefix, e.g: `dark moo` matches
+    // `The Dark Side of the Moon`.
+    //
+    // Returns `None` if `text` has no tokens.
+    pub(crate) fn lookup(&self, text: &str) -> Option<Hits> {
+        let mut hits: Option<Hits> = None;
+
+        for token in Self::tokenize(text, true) {
+            let found = self.prefix(&token);
+            hits = Some(match hits {
+                Some(hits) => hits.intersect(found),
+                None => found,
+            });
+        }
+
+        hits
+    }
+
+    // All objects that have a token starting with `prefix`.
+    fn prefix(&self, prefix: &str) -> Hits {
+        let start = self.tokens.partition_point(|t| t.as_str() < prefix);
+        let mut hits = Hits::default();
+
+        for (token, postings) in self.tokens[start..].iter().zip(&self.postings[start..]) {
+            if !token.starts_with(prefix) {
+                break;
+            }
+            hits.artists.extend_from_slice(&postings.artists);
+            hits.albums.extend_from_slice(&postings.albums);
+            hits.songs.extend_from_slice(&postings.songs);
+        }
+
+        for vec in [&mut hits.artists, &mut hits.albums, &mut hits.songs] {
+            vec.sort_unstable();
+            vec.dedup();
+        }
+
+        hits
+    }
+
+    //-------------------------------------------------- Tokenization.
+    // Split `text` into tokens.
+    //
+    // Words are lowercased with their diacritics
+    // removed, e.g: `Beyoncé` -> `beyonce`.
+    //
+    // CJK text has no spaces between words, so each character
+    // is a token when indexing (as well as each pair of
+    // characters, "bigrams"). When searching (`query`), only
+    // the bigrams are used so `東京事変` must match `東京`, `京事`
+    // and `事変`, instead of any of the 4 characters.
+    pub(crate) fn tokenize(text: &str, query: bool) -> Vec<String> {
+        let mut tokens = vec![];
+
+        for (word, cjk) in Self::words(text) {
+            if !cjk {
+                tokens.push(word);
+                continue;
+            }
+
+            let chars: Vec<char> = word.chars().collect();
+            if !query || chars.len() == 1 {
+                tokens.extend(chars.iter().map(char::to_string));
+            }
+            tokens.extend(chars.windows(2).map(|w| w.iter().collect::<String>()));
+        }
+
+        tokens
+    }
+
+    // Split `text` into folded words, and if they are CJK.
+    fn words(text: &str) -> Vec<(String, bool)> {
+        let mut words = vec![];
+        let mut word = String::new();
+        let mut cjk = String::new();
+        let mut decomposed = vec![];
+
+        let flush = |s: &mut String, words: &mut Vec<(String, bool)>, is_cjk: bool| {
+            if !s.is_empty() {
+                words.push((std::mem::take(s), is_cjk));
+            }
+        };
+
+        for c in text.chars() {
+            // Hangul would decompose into Jamo, so check before decomposing.
+            if is_cjk(c) {
+                flush(&mut word, &mut words, false);
+                cjk.push(c);
+                continue;
+            }
+
+            decomposed.clear();
+            decompose_compatible(c, |d| decomposed.push(d));
+
+            for &d in decomposed.iter() {
+                if is_combining_mark(d) || matches!(d, '\'' | '’') {
+                    // `é` -> `e`, `Don't` -> `dont`.
+                    continue;
+                } else if is_cjk(d) {
+                    // e.g: half-width Katakana.
+                    flush(&mut word, &mut words, false);
+                    cjk.push(d);
+                } else if d.is_alphanumeric() {
+                    flush(&mut cjk, &mut words, true);
+                    match d {
+                        'ß' => word.push_str("ss"),
+                        'æ' | 'Æ' => word.push_str("ae"),
+                        'œ' | 'Œ' => word.push_str("oe"),
+                        'ø' | 'Ø' => word.push('o'),
+                        'đ' | 'Đ' => word.push('d'),
+                        'ł' | 'Ł' => word.push('l'),
+                        _ => word.extend(d.to_lowercase()),
+                    }
+                } else {
+                    flush(&mut word, &mut words, false);
+                    flush(&mut cjk, &mut words, true);
+                }
+            }
+        }
+
+        flush(&mut word, &mut words, false);
+        flush(&mut cjk, &mut words, true);
+        words
+    }
+}
+
+impl Hits {
+    // Only keep the objects that are in both.
+    fn intersect(mut self, other: Self) -> Self {
+        self.artists
+            .retain(|i| other.artists.binary_search(i).is_ok());
+        self.albums
+            .retain(|i| other.albums.binary_search(i).is_ok());
+        self.songs.retain(|i| other.songs.binary_search(i).is_ok());
+        self
+    }
+}
+
+// Hiragana, Katakana, CJK ideographs and Hangul.
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(s: &[&str]) -> Vec<String> {
+        s.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn index() -> SearchIndex {
+        let artist = |name: &str| Artist {
+            name: name.into(),
+            ..Default::default()
+        };
+        let album = |title: &str| Album {
+            title: title.into(),
+            ..Default::default()
+        };
+        let song = |title: &str, artist: Option<&str>| Song {
+            title: title.into(),
+            track_artist: artist.map(Into::into),
+            ..Default::default()
+        };
+
+        SearchIndex::from_3_vecs(
+            &[artist("Beyoncé"), artist("椎名林檎")],
+            &[album("The Dark Side of the Moon"), album("教育")],
+            &[
+                song("Don't Stop Me Now", None),
+                song("Moonlight", Some("Beyoncé")),
+                song("東京事変", None),
+            ],
+        )
+    }
+
+    #[test]
+    // Diacritics are folded, punctuation splits words.
+    fn tokenize() {
+        let cases: [(&str, &[&str]); 5] = [
+            ("Beyoncé", &["beyonce"]),
+            ("Don't Stop", &["dont", "stop"]),
+            ("AC/DC", &["ac", "dc"]),
+            ("Straße Ærø", &["strasse", "aero"]),
+            ("ＡＢＣ 123", &["abc", "123"]),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(SearchIndex::tokenize(input, false), strings(expected));
+            assert_eq!(SearchIndex::tokenize(input, true), strings(expected));
+        }
+    }
+
+    #[test]
+    // CJK characters are indexed as uni/bigrams, searched as bigrams.
+    fn tokenize_cjk() {
+        assert_eq!(
+            SearchIndex::tokenize("林檎abc", false),
+            strings(&["林", "檎", "林檎", "abc"]),
+        );
+        assert_eq!(
+            SearchIndex::tokenize("林檎abc", true),
+            strings(&["林檎", "abc"]),
+        );
+        assert_eq!(SearchIndex::tokenize("林", true), strings(&["林"]));
+        assert_eq!(SearchIndex::tokenize("한국", true), strings(&["한국"]));
+    }
+
+    #[test]
+    // Prefixes and words in the middle match.
+    fn lookup() {
+        let index = index();
+
+        let hits = index.lookup("moo").unwrap();
+        assert_eq!(hits.artists, []);
+        assert_eq!(hits.albums, [0]);
+        assert_eq!(hits.songs, [1]);
+
+        let hits = index.lookup("side dark").unwrap();
+        assert_eq!(hits.albums, [0]);
+
+        let hits = index.lookup("beyonce").unwrap();
+        assert_eq!(hits.artists, [0]);
+        assert_eq!(hits.songs, [1]);
+
+        let hits = index.lookup("dont").unwrap();
+        assert_eq!(hits.songs, [0]);
+
+        let hits = index.lookup("林檎").unwrap();
+        assert_eq!(hits.artists, [1]);
+
+        let hits = index.lookup("京事").unwrap();
+        assert_eq!(hits.songs, [2]);
+
+        assert_eq!(index.lookup("moon xyz").unwrap(), Hits::default());
+        assert_eq!(index.lookup("  !? "), None);
+    }
+
+    #[test]
+    // The index is only used for its own `Collection`.
+    fn is_for() {
+        let mut collection = Collection::new();
+        collection.timestamp = 1;
+
+        let mut index = SearchIndex::default();
+        assert!(!index.is_for(&collection));
+
+        index.set_collection(&collection);
+        assert!(index.is_for(&collection));
+    }
+}
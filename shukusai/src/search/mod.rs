@@ -6,3 +6,9 @@ pub(crate) use search::*;
 
 mod kind;
 pub use kind::*;
+
+mod query;
+pub use query::*;
+
+mod index;
+pub(crate) use index::*;
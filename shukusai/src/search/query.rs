@@ -0,0 +1,548 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::collection::{Album, Artist, Collection, Song};
+use std::cmp::Ordering;
+
+//---------------------------------------------------------------------------------------------------- Cmp
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+// The comparison of a number filter, e.g: `year:>2000`.
+enum Cmp {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Cmp {
+    // Split the comparison off the front of `s`, no prefix is `Cmp::Eq`.
+    fn parse(s: &str) -> (Self, &str) {
+        for (prefix, cmp) in [
+            (">=", Self::Ge),
+            ("<=", Self::Le),
+            (">", Self::Gt),
+            ("<", Self::Lt),
+            ("=", Self::Eq),
+        ] {
+            if let Some(rest) = s.strip_prefix(prefix) {
+                return (cmp, rest);
+            }
+        }
+        (Self::Eq, s)
+    }
+
+    fn matches(self, a: u32, b: u32) -> bool {
+        let ord = a.cmp(&b);
+        match self {
+            Self::Eq => ord == Ordering::Equal,
+            Self::Lt => ord == Ordering::Less,
+            Self::Le => ord != Ordering::Greater,
+            Self::Gt => ord == Ordering::Greater,
+            Self::Ge => ord != Ordering::Less,
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Eq => "",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Filter
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+// A single `field:value` filter.
+//
+// Text values are lowercase, runtimes are in seconds.
+enum Filter {
+    Artist(String),
+    Album(String),
+    Song(String),
+    Genre(String),
+    Year(Cmp, u32),
+    Runtime(Cmp, u32),
+}
+
+impl Filter {
+    // Parse a `field` and its `value`.
+    //
+    // `None` is returned for unknown fields or bad
+    // values, these are treated as normal text.
+    fn parse(field: &str, value: &str) -> Option<Self> {
+        let value = normalize(value);
+        if value.is_empty() {
+            return None;
+        }
+
+        let filter = match field.to_lowercase().as_str() {
+            "artist" => Self::Artist(value),
+            "album" => Self::Album(value),
+            "song" | "title" => Self::Song(value),
+            "genre" => Self::Genre(value),
+            "year" => {
+                let (cmp, year) = Cmp::parse(&value);
+                Self::Year(cmp, year.parse().ok()?)
+            }
+            "runtime" => {
+                let (cmp, runtime) = Cmp::parse(&value);
+                Self::Runtime(cmp, parse_runtime(runtime)?)
+            }
+            _ => return None,
+        };
+
+        Some(filter)
+    }
+
+    // The normalized `field:value` string.
+    fn normalized(&self) -> String {
+        match self {
+            Self::Artist(s) => format!("artist:{}", quote(s)),
+            Self::Album(s) => format!("album:{}", quote(s)),
+            Self::Song(s) => format!("song:{}", quote(s)),
+            Self::Genre(s) => format!("genre:{}", quote(s)),
+            Self::Year(cmp, y) => format!("year:{}{y}", cmp.as_str()),
+            Self::Runtime(cmp, r) => format!("runtime:{}{r}s", cmp.as_str()),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Query
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// A parsed search query
+///
+/// Besides plain text (which is fuzzy matched like before),
+/// a query can contain `"quoted phrases"` and `field:value` filters:
+///
+/// | Filter     | Example                                          | Matches |
+/// |------------|--------------------------------------------------|---------|
+/// | `artist:`  | `artist:radiohead`, `artist:"pink floyd"`        | The `Artist` name contains the value
+/// | `album:`   | `album:"ok computer"`                            | The `Album` title contains the value
+/// | `song:`    | `song:airbag` (or `title:`)                      | The `Song` title contains the value
+/// | `genre:`   | `genre:rock`                                     | One of the `Album` genres contains the value
+/// | `year:`    | `year:2000`, `year:>2000`, `year:<=1999`         | The `Album` release year
+/// | `runtime:` | `runtime:<5m`, `runtime:>=1h30m`, `runtime:3:30` | The runtime (no unit means seconds)
+///
+/// Quoted phrases must be found (case-insensitively) in the name/title.
+///
+/// Filters apply to the object itself and its parents, e.g, `artist:`
+/// on a `Song` checks the `Song`'s `Artist`. Filters for children
+/// (e.g, `song:` on an `Artist`) match if _any_ child matches.
+///
+/// All filters and phrases must match, the remaining
+/// text is used for the similarity score. With only filters
+/// and/or phrases, all matching objects are equally similar.
+///
+/// Unknown fields (e.g, `re:zero`) and invalid values (e.g, `year:soon`) are plain text.
+///
+/// ```rust
+/// # use shukusai::search::Query;
+/// let query = Query::new("Year:>2000  ARTIST:\"Pink  Floyd\" hello");
+/// assert_eq!(query.text(), "hello");
+/// assert_eq!(query.normalized(), "hello artist:\"pink floyd\" year:>2000");
+/// ```
+pub struct Query {
+    text: String,
+    phrases: Vec<String>,
+    filters: Vec<Filter>,
+}
+
+impl Query {
+    /// Parse a search query.
+    pub fn new(input: &str) -> Self {
+        let mut words = vec![];
+        let mut phrases = vec![];
+        let mut filters = vec![];
+
+        let mut rest = input.trim_start();
+        while !rest.is_empty() {
+            // A `"quoted phrase"`.
+            if let Some(quoted) = rest.strip_prefix('"') {
+                let (phrase, r) = split_quote(quoted);
+                let phrase = normalize(phrase);
+                if !phrase.is_empty() && !phrases.contains(&phrase) {
+                    phrases.push(phrase);
+                }
+                rest = r.trim_start();
+                continue;
+            }
+
+            // A word, or a `field:value` filter (maybe quoted).
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let mut word = &rest[..end];
+            let mut after = &rest[end..];
+
+            if let Some((field, value)) = word.split_once(':') {
+                let value = if value.starts_with('"') {
+                    let (value, r) = split_quote(&rest[field.len() + 2..]);
+                    // The whole `field:"quoted value"` is a single word.
+                    word = &rest[..rest.len() - r.len()];
+                    after = r;
+                    value
+                } else {
+                    value
+                };
+
+                if let Some(filter) = Filter::parse(field, value) {
+                    if !filters.contains(&filter) {
+                        filters.push(filter);
+                    }
+                    rest = after.trim_start();
+                    continue;
+                }
+            }
+
+            words.push(word);
+            rest = after.trim_start();
+        }
+
+        filters.sort();
+
+        Self {
+            text: normalize(&words.join(" ")),
+            phrases,
+            filters,
+        }
+    }
+
+    /// The plain text of the query, without phrases or filters.
+    ///
+    /// This is lowercase and whitespace is collapsed.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// If this query is only plain text, no phrases or filters.
+    pub fn is_plain(&self) -> bool {
+        self.phrases.is_empty() && self.filters.is_empty()
+    }
+
+    /// The normalized query string.
+    ///
+    /// Queries that have the same result
+    /// will have the same normalized string, e.g:
+    /// - `year:>2000 artist:a` and `ARTIST:A  year:>2000`
+    /// - `runtime:<5m` and `runtime:<300`
+    pub fn normalized(&self) -> String {
+        let mut parts: Vec<String> =
+            Vec::with_capacity(1 + self.phrases.len() + self.filters.len());
+
+        if !self.text.is_empty() {
+            parts.push(self.text.clone());
+        }
+        parts.extend(self.phrases.iter().map(|p| format!("\"{p}\"")));
+        parts.extend(self.filters.iter().map(Filter::normalized));
+
+        parts.join(" ")
+    }
+
+    // The similarity of `s` to the plain text.
+    //
+    // If there's no text but there are filters/phrases,
+    // everything that matched is equally similar.
+    pub(crate) fn score(&self, s: &str) -> f64 {
+        if self.text.is_empty() && !self.is_plain() {
+            1.0
+        } else {
+            strsim::jaro(s, &self.text)
+        }
+    }
+
+    //-------------------------------------------------- Matching
+    // If the `Artist` matches all phrases and filters.
+    pub(crate) fn matches_artist(&self, c: &Collection, artist: &Artist) -> bool {
+        let albums = || artist.albums.iter().map(|k| &c.albums[k]);
+
+        self.phrases
+            .iter()
+            .all(|p| artist.name_lowercase.contains(p.as_str()))
+            && self.filters.iter().all(|f| match f {
+                Filter::Artist(s) => artist.name_lowercase.contains(s.as_str()),
+                Filter::Album(s) => albums().any(|a| a.title_lowercase.contains(s.as_str())),
+                Filter::Song(s) => artist
+                    .songs
+                    .iter()
+                    .any(|k| c.songs[k].title_lowercase.contains(s.as_str())),
+                Filter::Genre(s) => albums().any(|a| genre_matches(a, s)),
+                Filter::Year(cmp, y) => albums().any(|a| year_matches(a, *cmp, *y)),
+                Filter::Runtime(cmp, r) => cmp.matches(artist.runtime.inner(), *r),
+            })
+    }
+
+    // If the `Album` matches all phrases and filters.
+    pub(crate) fn matches_album(&self, c: &Collection, album: &Album) -> bool {
+        self.phrases
+            .iter()
+            .all(|p| album.title_lowercase.contains(p.as_str()))
+            && self.filters.iter().all(|f| match f {
+                Filter::Artist(s) => c.artists[album.artist].name_lowercase.contains(s.as_str()),
+                Filter::Album(s) => album.title_lowercase.contains(s.as_str()),
+                Filter::Song(s) => album
+                    .songs
+                    .iter()
+                    .any(|k| c.songs[k].title_lowercase.contains(s.as_str())),
+                Filter::Genre(s) => genre_matches(album, s),
+                Filter::Year(cmp, y) => year_matches(album, *cmp, *y),
+                Filter::Runtime(cmp, r) => cmp.matches(album.runtime.inner(), *r),
+            })
+    }
+
+    // If the `Song` matches all phrases and filters.
+    //
+    // `Song`'s also match on their track artist and credited artists.
+    pub(crate) fn matches_song(&self, c: &Collection, song: &Song) -> bool {
+        let album = &c.albums[song.album];
+
+        self.phrases.iter().all(|p| {
+            song.title_lowercase.contains(p.as_str())
+                || song
+                    .track_artist
+                    .as_ref()
+                    .is_some_and(|a| a.to_lowercase().contains(p.as_str()))
+        }) && self.filters.iter().all(|f| match f {
+            Filter::Artist(s) => {
+                c.artists[album.artist].name_lowercase.contains(s.as_str())
+                    || song
+                        .track_artist
+                        .iter()
+                        .chain(song.artists.iter())
+                        .any(|a| a.to_lowercase().contains(s.as_str()))
+            }
+            Filter::Album(s) => album.title_lowercase.contains(s.as_str()),
+            Filter::Song(s) => song.title_lowercase.contains(s.as_str()),
+            Filter::Genre(s) => genre_matches(album, s),
+            Filter::Year(cmp, y) => year_matches(album, *cmp, *y),
+            Filter::Runtime(cmp, r) => cmp.matches(song.runtime.inner(), *r),
+        })
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Free functions
+// Lowercase, and collapse all whitespace into a single space.
+fn normalize(s: &str) -> String {
+    s.to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+// Quote a value if it has spaces.
+fn quote(s: &str) -> String {
+    if s.contains(' ') {
+        format!("\"{s}\"")
+    } else {
+        s.to_string()
+    }
+}
+
+// Split `s` (after an opening `"`) into the quoted part and the rest.
+//
+// An unclosed quote takes the rest of `s`.
+fn split_quote(s: &str) -> (&str, &str) {
+    match s.split_once('"') {
+        Some((quoted, rest)) => (quoted, rest),
+        None => (s, ""),
+    }
+}
+
+// Parse a runtime into seconds, e.g: `300`, `5m`, `1h30m`, `90s`, `3:30`, `1:02:03`.
+fn parse_runtime(s: &str) -> Option<u32> {
+    if s.contains(':') {
+        return s.split(':').try_fold(0_u32, |acc, n| {
+            acc.checked_mul(60)?.checked_add(n.parse().ok()?)
+        });
+    }
+
+    let mut seconds = 0_u32;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        let n: u32 = std::mem::take(&mut number).parse().ok()?;
+        seconds = seconds.checked_add(n.checked_mul(unit)?)?;
+    }
+
+    // No unit means seconds.
+    if !number.is_empty() {
+        seconds = seconds.checked_add(number.parse().ok()?)?;
+    }
+
+    Some(seconds)
+}
+
+// If any of the `Album`'s genres contain `s`.
+//
+// The unsplit `genre` is used for `Album`'s
+// that don't have `genres` (not re-scanned yet).
+fn genre_matches(album: &Album, s: &str) -> bool {
+    if album.genres.is_empty() {
+        album
+            .genre
+            .as_ref()
+            .is_some_and(|g| g.to_lowercase().contains(s))
+    } else {
+        album.genres.iter().any(|g| g.to_lowercase().contains(s))
+    }
+}
+
+// If the `Album`'s release year matches.
+//
+// `Album`'s with an unknown release never match.
+fn year_matches(album: &Album, cmp: Cmp, year: u32) -> bool {
+    album
+        .release
+        .as_str()
+        .get(..4)
+        .and_then(|y| y.parse().ok())
+        .is_some_and(|y| cmp.matches(y, year))
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::{AlbumKey, SongKey};
+    use readable::{Date, Runtime};
+
+    #[test]
+    // Text, phrases and filters are all parsed.
+    fn new() {
+        let q = Query::new(
+            r#"artist:radiohead year:>2000 genre:rock runtime:<5m "exact phrase" hello  WORLD"#,
+        );
+        assert_eq!(q.text(), "hello world");
+        assert_eq!(q.phrases, ["exact phrase"]);
+        assert_eq!(
+            q.filters,
+            [
+                Filter::Artist("radiohead".into()),
+                Filter::Genre("rock".into()),
+                Filter::Year(Cmp::Gt, 2000),
+                Filter::Runtime(Cmp::Lt, 300),
+            ]
+        );
+        assert!(!q.is_plain());
+
+        // Quoted values, unclosed quotes.
+        let q = Query::new(r#"album:"ok  computer" "unclosed phrase"#);
+        assert_eq!(q.text(), "");
+        assert_eq!(q.phrases, ["unclosed phrase"]);
+        assert_eq!(q.filters, [Filter::Album("ok computer".into())]);
+    }
+
+    #[test]
+    // Unknown fields and bad values are plain text.
+    fn plain() {
+        for (input, text) in [
+            ("re:zero", "re:zero"),
+            ("year:soon", "year:soon"),
+            ("runtime:<5x hello", "runtime:<5x hello"),
+            (r#"foo:"bar baz" end"#, r#"foo:"bar baz" end"#),
+            ("artist:", "artist:"),
+            ("  Hello   World ", "hello world"),
+        ] {
+            let q = Query::new(input);
+            assert!(q.is_plain(), "{input}");
+            assert_eq!(q.text(), text, "{input}");
+        }
+    }
+
+    #[test]
+    // The same query written differently normalizes to the same string.
+    fn normalized() {
+        let a = Query::new(r#"year:>2000 artist:"Pink Floyd" runtime:<5m  Hello"#);
+        let b = Query::new(r#"hello ARTIST:"pink   floyd" runtime:<300 year:>2000"#);
+        assert_eq!(a.normalized(), b.normalized());
+        assert_eq!(
+            a.normalized(),
+            r#"hello artist:"pink floyd" year:>2000 runtime:<300s"#
+        );
+    }
+
+    #[test]
+    // Runtimes in all the formats.
+    fn runtime() {
+        for (input, expected) in [
+            ("300", Some(300)),
+            ("5m", Some(300)),
+            ("90s", Some(90)),
+            ("1h30m", Some(5400)),
+            ("3:30", Some(210)),
+            ("1:02:03", Some(3723)),
+            ("5x", None),
+            ("m", None),
+            ("3:", None),
+        ] {
+            assert_eq!(parse_runtime(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    // Filters apply to parents, and children with _any_.
+    fn matches() {
+        let mut c = Collection::new();
+        let mut artist = Artist::default();
+        artist.name_lowercase = "radiohead".into();
+        artist.albums = vec![AlbumKey::zero()];
+        artist.songs = Box::new([SongKey::zero()]);
+        artist.runtime = Runtime::from(400_u32);
+        let mut album = Album::default();
+        album.title_lowercase = "kid a".into();
+        album.release = Date::from_str_silent("2000-10-02");
+        album.genres = Box::new(["Electronic".into(), "Rock".into()]);
+        album.songs = vec![SongKey::zero()];
+        let mut song = Song::default();
+        song.title_lowercase = "idioteque".into();
+        song.runtime = Runtime::from(309_u32);
+        c.artists.0 = Box::new([artist]);
+        c.albums.0 = Box::new([album]);
+        c.songs.0 = Box::new([song]);
+
+        let artist = &c.artists.0[0];
+        let album = &c.albums.0[0];
+        let song = &c.songs.0[0];
+
+        let q = Query::new("artist:radio genre:rock year:>=2000");
+        assert!(q.matches_artist(&c, artist));
+        assert!(q.matches_album(&c, album));
+        assert!(q.matches_song(&c, song));
+
+        let q = Query::new("song:idio runtime:<6m");
+        assert!(q.matches_artist(&c, artist));
+        assert!(q.matches_album(&c, album));
+        assert!(q.matches_song(&c, song));
+
+        let q = Query::new("runtime:<5m");
+        assert!(!q.matches_artist(&c, artist));
+        assert!(!q.matches_song(&c, song));
+
+        let q = Query::new(r#"year:<2000 "kid""#);
+        assert!(!q.matches_album(&c, album));
+        let q = Query::new(r#""kid""#);
+        assert!(q.matches_album(&c, album));
+        assert!(!q.matches_song(&c, song));
+    }
+
+    #[test]
+    // Comparisons.
+    fn cmp() {
+        assert!(Cmp::Eq.matches(1, 1));
+        assert!(Cmp::Lt.matches(0, 1));
+        assert!(!Cmp::Lt.matches(1, 1));
+        assert!(Cmp::Le.matches(1, 1));
+        assert!(Cmp::Gt.matches(2, 1));
+        assert!(!Cmp::Gt.matches(1, 1));
+        assert!(Cmp::Ge.matches(1, 1));
+    }
+}
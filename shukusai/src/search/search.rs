@@ -1,11 +1,12 @@
 //---------------------------------------------------------------------------------------------------- Use
-use crate::collection::{AlbumKey, ArtistKey, Collection, Keychain, SongKey};
+use crate::collection::{AlbumKey, ArtistKey, Collection, Keychain, Song, SongKey};
 use crate::search::msg::{KernelToSearch, SearchToKernel};
-use crate::search::SearchKind;
+use crate::search::{Query, SearchIndex, SearchKind, NEW_INDEX};
 use benri::time::{now, secs_f32};
-use benri::{debug_panic, log::*, sync::*};
+use benri::{debug_panic, lock, log::*, sync::*};
 use crossbeam::channel::{Receiver, Sender};
-use log::{debug, error, trace};
+use disk::Bincode2;
+use log::{debug, error, trace, warn};
 use rayon::prelude::*;
 use std::{collections::HashMap, sync::Arc};
 
@@ -29,6 +30,7 @@ pub(crate) struct Search {
     cache_s70: HashMap<String, Keychain>,  // Search index cache (Sim70),
     cache_s80: HashMap<String, Keychain>,  // Search index cache (Sim80),
     collection: Arc<Collection>,           // Pointer to `Collection`
+    index: Arc<SearchIndex>,               // Inverted index of the `Collection`
     total_count: usize,                    // Local cache of all total `Collection` objects
     to_kernel: Sender<SearchToKernel>,     // Channel TO `Kernel`
     from_kernel: Receiver<KernelToSearch>, // Channel FROM `Kernel`
@@ -43,6 +45,7 @@ impl Search {
         from_kernel: Receiver<KernelToSearch>,
     ) {
         // Init data.
+        let index = Self::load_index(&collection);
         let search = Self {
             cache: HashMap::with_capacity(DEFAULT_CACHE_SIZE),
             cache_t1: HashMap::with_capacity(DEFAULT_CACHE_SIZE),
@@ -52,6 +55,7 @@ impl Search {
             cache_s70: HashMap::with_capacity(DEFAULT_CACHE_SIZE),
             cache_s80: HashMap::with_capacity(DEFAULT_CACHE_SIZE),
             collection,
+            index,
             total_count: DEFAULT_CACHE_SIZE,
             to_kernel,
             from_kernel,
@@ -62,123 +66,200 @@ impl Search {
     }
 
     #[inline]
-    fn search_sim(&self, input: &str, sim: f64) -> Keychain {
-        let mut artists: Vec<(f64, ArtistKey)> = self
-            .collection
-            .artists
-            .0
-            .par_iter()
-            .enumerate()
-            .map(|(i, x)| (strsim::jaro(&x.name_lowercase, input), ArtistKey::from(i)))
-            .filter(|(f, _)| *f >= sim)
-            .collect();
-        let mut albums: Vec<(f64, AlbumKey)> = self
-            .collection
-            .albums
-            .0
-            .par_iter()
-            .enumerate()
-            .map(|(i, x)| (strsim::jaro(&x.title_lowercase, input), AlbumKey::from(i)))
-            .filter(|(f, _)| *f >= sim)
-            .collect();
-        let mut songs: Vec<(f64, SongKey)> = self
-            .collection
-            .songs
-            .0
-            .par_iter()
-            .enumerate()
-            .map(|(i, x)| (strsim::jaro(&x.title_lowercase, input), SongKey::from(i)))
-            .filter(|(f, _)| *f >= sim)
-            .collect();
-
-        // Sort by highest-to-lowest similarity value.
-        artists.par_sort_by(|a, b| Self::cmp_f64(b.0, a.0));
-        albums.par_sort_by(|a, b| Self::cmp_f64(b.0, a.0));
-        songs.par_sort_by(|a, b| Self::cmp_f64(b.0, a.0));
+    fn search_sim(&self, query: &Query, sim: f64) -> Keychain {
+        let (artists, albums, songs) = self.search_base(query, sim, 1);
 
         // Collect just the Keys
-        let artists: Vec<ArtistKey> = artists.into_par_iter().map(|tuple| tuple.1).collect();
-        let albums: Vec<AlbumKey> = albums.into_par_iter().map(|tuple| tuple.1).collect();
-        let songs: Vec<SongKey> = songs.into_par_iter().map(|tuple| tuple.1).collect();
+        let artists: Vec<ArtistKey> = artists
+            .into_par_iter()
+            .map(|tuple| tuple.1.into())
+            .collect();
+        let albums: Vec<AlbumKey> = albums.into_par_iter().map(|tuple| tuple.1.into()).collect();
+        let songs: Vec<SongKey> = songs.into_par_iter().map(|tuple| tuple.1.into()).collect();
 
         // Return keychain.
         Keychain::from_vecs(artists, albums, songs)
     }
 
     #[inline]
-    fn search_top<const N: usize>(&self, input: &str) -> Keychain {
-        let (artists, albums, songs) = self.search_base(input);
+    fn search_top<const N: usize>(&self, query: &Query) -> Keychain {
+        let (artists, albums, songs) = self.search_base(query, 0.0, N);
 
         // Sort by highest-to-lowest similarity value.
         let artists: Vec<ArtistKey> = artists
             .into_par_iter()
-            .map(|tuple| tuple.1)
+            .map(|tuple| tuple.1.into())
             .take(N)
             .collect();
         let albums: Vec<AlbumKey> = albums
             .into_par_iter()
-            .map(|tuple| tuple.1)
+            .map(|tuple| tuple.1.into())
+            .take(N)
+            .collect();
+        let songs: Vec<SongKey> = songs
+            .into_par_iter()
+            .map(|tuple| tuple.1.into())
             .take(N)
             .collect();
-        let songs: Vec<SongKey> = songs.into_par_iter().map(|tuple| tuple.1).take(N).collect();
 
         // Return keychain.
         Keychain::from_vecs(artists, albums, songs)
     }
 
     #[inline]
-    fn search_all(&self, input: &str) -> Keychain {
-        let (artists, albums, songs) = self.search_base(input);
+    fn search_all(&self, query: &Query) -> Keychain {
+        let (artists, albums, songs) = self.search_base(query, 0.0, usize::MAX);
 
         // Sort by highest-to-lowest similarity value.
-        let artists: Vec<ArtistKey> = artists.into_par_iter().map(|tuple| tuple.1).collect();
-        let albums: Vec<AlbumKey> = albums.into_par_iter().map(|tuple| tuple.1).collect();
-        let songs: Vec<SongKey> = songs.into_par_iter().map(|tuple| tuple.1).collect();
+        let artists: Vec<ArtistKey> = artists
+            .into_par_iter()
+            .map(|tuple| tuple.1.into())
+            .collect();
+        let albums: Vec<AlbumKey> = albums.into_par_iter().map(|tuple| tuple.1.into()).collect();
+        let songs: Vec<SongKey> = songs.into_par_iter().map(|tuple| tuple.1.into()).collect();
 
         // Return keychain.
         Keychain::from_vecs(artists, albums, songs)
     }
 
     #[inline]
+    // Score all objects that match the `query`'s filters/phrases
+    // and are at least `sim` similar, sorted by similarity.
+    //
+    // Objects found in the `SearchIndex` (every word of the text
+    // is the prefix of a word in the object) get `+1.0`, so they
+    // always come first. If there are at least `min_hits` of them,
+    // only they are scored, else every object is scored with
+    // `strsim::jaro()` as a fallback for typos.
+    //
+    // Filter-only queries give all matching objects `1.0`.
     fn search_base(
         &self,
-        input: &str,
-    ) -> (
-        Vec<(f64, ArtistKey)>,
-        Vec<(f64, AlbumKey)>,
-        Vec<(f64, SongKey)>,
-    ) {
-        let mut artists: Vec<(f64, ArtistKey)> = self
-            .collection
-            .artists
-            .0
-            .par_iter()
-            .enumerate()
-            .map(|(i, x)| (strsim::jaro(&x.name_lowercase, input), ArtistKey::from(i)))
-            .collect();
-        let mut albums: Vec<(f64, AlbumKey)> = self
-            .collection
-            .albums
-            .0
-            .par_iter()
-            .enumerate()
-            .map(|(i, x)| (strsim::jaro(&x.title_lowercase, input), AlbumKey::from(i)))
-            .collect();
-        let mut songs: Vec<(f64, SongKey)> = self
-            .collection
-            .songs
-            .0
-            .par_iter()
-            .enumerate()
-            .map(|(i, x)| (strsim::jaro(&x.title_lowercase, input), SongKey::from(i)))
-            .collect();
+        query: &Query,
+        sim: f64,
+        min_hits: usize,
+    ) -> (Vec<(f64, usize)>, Vec<(f64, usize)>, Vec<(f64, usize)>) {
+        let c = &self.collection;
+        let plain = query.is_plain();
+        let hits = self.index.lookup(query.text()).unwrap_or_default();
+
+        let artists = Self::score(
+            &c.artists.0,
+            &hits.artists,
+            min_hits,
+            sim,
+            |x| plain || query.matches_artist(c, x),
+            |x| query.score(&x.name_lowercase),
+        );
+        let albums = Self::score(
+            &c.albums.0,
+            &hits.albums,
+            min_hits,
+            sim,
+            |x| plain || query.matches_album(c, x),
+            |x| query.score(&x.title_lowercase),
+        );
+        let songs = Self::score(
+            &c.songs.0,
+            &hits.songs,
+            min_hits,
+            sim,
+            |x| plain || query.matches_song(c, x),
+            |x| Self::song_sim(x, query),
+        );
+
+        (artists, albums, songs)
+    }
+
+    #[inline]
+    // Score the `objects` (or just the `hits` if there's at least
+    // `min_hits`) that pass the `filter`, sorted by similarity.
+    fn score<T: Sync>(
+        objects: &[T],
+        hits: &[usize],
+        min_hits: usize,
+        sim: f64,
+        filter: impl Fn(&T) -> bool + Sync + Send,
+        score: impl Fn(&T) -> f64 + Sync + Send,
+    ) -> Vec<(f64, usize)> {
+        let f = |i: usize, x: &T| {
+            if !filter(x) {
+                return None;
+            }
+            let hit = if hits.binary_search(&i).is_ok() {
+                1.0
+            } else {
+                0.0
+            };
+            let f = score(x) + hit;
+            (f >= sim).then_some((f, i))
+        };
+
+        let mut vec: Vec<(f64, usize)> = if !hits.is_empty() && hits.len() >= min_hits {
+            hits.par_iter()
+                .filter_map(|i| f(*i, &objects[*i]))
+                .collect()
+        } else {
+            objects
+                .par_iter()
+                .enumerate()
+                .filter_map(|(i, x)| f(i, x))
+                .collect()
+        };
 
         // Sort by highest-to-lowest similarity value.
-        artists.par_sort_by(|a, b| Self::cmp_f64(b.0, a.0));
-        albums.par_sort_by(|a, b| Self::cmp_f64(b.0, a.0));
-        songs.par_sort_by(|a, b| Self::cmp_f64(b.0, a.0));
+        vec.par_sort_by(|a, b| Self::cmp_f64(b.0, a.0));
+        vec
+    }
 
-        (artists, albums, songs)
+    // Get the `SearchIndex` of `collection`, either:
+    //
+    // 1. The one `CCD` just built
+    // 2. The one saved on disk
+    // 3. A new one (that gets saved to disk)
+    fn load_index(collection: &Collection) -> Arc<SearchIndex> {
+        if collection.empty {
+            return Arc::new(SearchIndex::default());
+        }
+
+        if let Some(index) = lock!(NEW_INDEX).take() {
+            if index.is_for(collection) {
+                ok_debug!("Search - SearchIndex from CCD");
+                return index;
+            }
+        }
+
+        match SearchIndex::from_file() {
+            Ok(index) if index.is_for(collection) => {
+                ok_debug!("Search - SearchIndex from disk");
+                return Arc::new(index);
+            }
+            Ok(_) => debug!("Search - SearchIndex on disk is for another Collection"),
+            Err(e) => debug!("Search - SearchIndex from disk: {e}"),
+        }
+
+        let now = now!();
+        let index = SearchIndex::new(collection);
+        debug!("Search - SearchIndex build ... {}", secs_f32!(now));
+        match index.save_atomic() {
+            Ok(md) => debug!("Search - SearchIndex: {md}"),
+            Err(e) => warn!("Search - SearchIndex: {e}"),
+        }
+        Arc::new(index)
+    }
+
+    #[inline]
+    // A `Song`'s similarity to `input`.
+    //
+    // `Song`'s in compilations are also
+    // matched against their track artist.
+    fn song_sim(song: &Song, query: &Query) -> f64 {
+        let title = query.score(&song.title_lowercase);
+
+        match &song.track_artist_lowercase {
+            Some(artist) => title.max(query.score(artist)),
+            None => title,
+        }
     }
 
     #[inline]
@@ -286,7 +367,11 @@ impl Search {
     #[inline(always)]
     fn msg_sim(&mut self, input: String, kind: SearchKind) {
         let now = now!();
-        let input = input.to_lowercase();
+
+        // Results are cached by the normalized query, so
+        // e.g `year:>2000 a` and `A  YEAR:>2000` are the same.
+        let query = Query::new(&input);
+        let input = query.normalized();
 
         let keychain = match self.get_cache(&input, kind) {
             Some(k) => {
@@ -295,13 +380,13 @@ impl Search {
             }
             None => {
                 let k = match kind {
-                    SearchKind::Sim70 => self.search_sim(&input, 0.7),
-                    SearchKind::Sim60 => self.search_sim(&input, 0.6),
-                    SearchKind::Sim80 => self.search_sim(&input, 0.8),
-                    SearchKind::Top25 => self.search_top::<25>(&input),
-                    SearchKind::Top5 => self.search_top::<5>(&input),
-                    SearchKind::Top1 => self.search_top::<1>(&input),
-                    SearchKind::All => self.search_all(&input),
+                    SearchKind::Sim70 => self.search_sim(&query, 0.7),
+                    SearchKind::Sim60 => self.search_sim(&query, 0.6),
+                    SearchKind::Sim80 => self.search_sim(&query, 0.8),
+                    SearchKind::Top25 => self.search_top::<25>(&query),
+                    SearchKind::Top5 => self.search_top::<5>(&query),
+                    SearchKind::Top1 => self.search_top::<1>(&query),
+                    SearchKind::All => self.search_all(&query),
                 };
                 self.check_cache(kind);
                 self.insert_cache(input, k.clone(), kind);
@@ -335,11 +420,18 @@ impl Search {
 
     #[inline(always)]
     fn msg_drop(&mut self) {
-        // Drop pointer.
+        // Drop pointers.
         self.collection = Collection::dummy();
+        self.index = Arc::new(SearchIndex::default());
 
         // Reset cache.
         self.cache.clear();
+        self.cache_t1.clear();
+        self.cache_t5.clear();
+        self.cache_t25.clear();
+        self.cache_s60.clear();
+        self.cache_s70.clear();
+        self.cache_s80.clear();
 
         // Hang until we get the new one.
         debug!("Search - Dropped Collection, waiting...");
@@ -351,6 +443,7 @@ impl Search {
                 KernelToSearch::NewCollection(arc) => {
                     ok_debug!("Search - New Collection received");
                     self.collection = arc;
+                    self.index = Self::load_index(&self.collection);
                     self.total_count = {
                         self.collection.count_artist.usize()
                             + self.collection.count_album.usize()
@@ -384,6 +477,36 @@ mod tests {
         assert_eq!(Search::cmp_f64(1.0, f64::INFINITY), Ordering::Less);
     }
 
+    #[test]
+    // `Song`'s are matched against their track artist as well.
+    fn song_sim() {
+        let mut song = Song::default();
+        song.title_lowercase = "aaaa".into();
+        assert_eq!(Search::song_sim(&song, &Query::new("bbbb")), 0.0);
+
+        song.track_artist_lowercase = Some("bbbb".into());
+        assert_eq!(Search::song_sim(&song, &Query::new("bbbb")), 1.0);
+        assert_eq!(Search::song_sim(&song, &Query::new("aaaa")), 1.0);
+    }
+
+    #[test]
+    // `SearchIndex` hits come first, and are the only
+    // objects scored if there are enough of them.
+    fn score() {
+        let objects = ["aaaa", "aaab", "bbbb"];
+        let score = |x: &&str| strsim::jaro(x, "aaaa");
+        let keys = |vec: Vec<(f64, usize)>| vec.into_iter().map(|t| t.1).collect::<Vec<usize>>();
+
+        let vec = Search::score(&objects, &[1], 2, 0.0, |_| true, score);
+        assert_eq!(keys(vec), [1, 0, 2]);
+
+        let vec = Search::score(&objects, &[1, 2], 2, 0.0, |_| true, score);
+        assert_eq!(keys(vec), [1, 2]);
+
+        let vec = Search::score(&objects, &[], 1, 0.7, |_| true, score);
+        assert_eq!(keys(vec), [0, 1]);
+    }
+
     #[test]
     // Tests all search functions, asserting the result output is correct.
     fn search() {
@@ -24,6 +24,10 @@ pub const ARTIST_RUNTIME_REV: &str = "Artists runtime longest-shortest";
 pub const ARTIST_NAME: &str = "Artist name shortest-longest";
 /// [`ArtistSort::NameRev`]
 pub const ARTIST_NAME_REV: &str = "Artist name longest-shortest";
+/// [`ArtistSort::Genre`]
+pub const ARTIST_GENRE: &str = "Artists per genre A-Z";
+/// [`ArtistSort::GenreRev`]
+pub const ARTIST_GENRE_REV: &str = "Artists per genre Z-A";
 
 /// [`AlbumSort::ReleaseArtistLexi`]
 pub const ALBUM_RELEASE_ARTIST_LEXI: &str = "Artists A-Z, albums oldest-latest";
@@ -59,6 +63,10 @@ pub const ALBUM_RUNTIME_REV: &str = "Albums longest-shortest";
 pub const ALBUM_TITLE: &str = "Album title shortest-longest";
 /// [`AlbumSort::TitleRev`]
 pub const ALBUM_TITLE_REV: &str = "Album title longest-shortest";
+/// [`AlbumSort::Genre`]
+pub const ALBUM_GENRE: &str = "Albums per genre A-Z, artists A-Z, albums oldest-latest";
+/// [`AlbumSort::GenreRev`]
+pub const ALBUM_GENRE_REV: &str = "Albums per genre Z-A, artists A-Z, albums oldest-latest";
 
 /// [`SongSort::AlbumReleaseArtistLexi`]
 pub const SONG_ALBUM_RELEASE_ARTIST_LEXI: &str =
@@ -99,6 +107,14 @@ pub const SONG_RUNTIME_REV: &str = "Songs longest-shortest";
 pub const SONG_TITLE: &str = "Song title shortest-longest";
 /// [`SongSort::TitleRev`]
 pub const SONG_TITLE_REV: &str = "Song title longest-shortest";
+/// [`SongSort::PlayCount`]
+pub const SONG_PLAY_COUNT: &str = "Songs most-least played";
+/// [`SongSort::PlayCountRev`]
+pub const SONG_PLAY_COUNT_REV: &str = "Songs least-most played";
+/// [`SongSort::LastPlayed`]
+pub const SONG_LAST_PLAYED: &str = "Songs recently played first";
+/// [`SongSort::LastPlayedRev`]
+pub const SONG_LAST_PLAYED_REV: &str = "Songs least recently played first";
 
 //---------------------------------------------------------------------------------------------------- Sort
 #[derive(
@@ -152,6 +168,12 @@ pub enum ArtistSort {
     Name,
     /// [`Artist`] name longest-shortest. Field: [`Collection::sort_artist_name_rev`].
     NameRev,
+    /// [`Artist`] primary genre A-Z, then [`Artist`] A-Z. Field: [`Collection::sort_artist_genre`].
+    ///
+    /// An [`Artist`]'s primary genre is the most common one within their `Album`'s.
+    Genre,
+    /// [`Artist`] primary genre Z-A, then [`Artist`] A-Z. Field: [`Collection::sort_artist_genre_rev`].
+    GenreRev,
 }
 
 #[derive(
@@ -217,6 +239,10 @@ pub enum AlbumSort {
     Title,
     /// [`Album`] longest title, shortest title. Field: [`Collection::sort_album_title_rev`].
     TitleRev,
+    /// [`Album`] primary genre A-Z, [`Artist`] A-Z, [`Album`] oldest-latest. Field: [`Collection::sort_album_genre`].
+    Genre,
+    /// [`Album`] primary genre Z-A, [`Artist`] A-Z, [`Album`] oldest-latest. Field: [`Collection::sort_album_genre_rev`].
+    GenreRev,
 }
 
 #[derive(
@@ -282,6 +308,22 @@ pub enum SongSort {
     Title,
     /// [`Song`] longest title, shortest title. Field: [`Collection::sort_song_title_rev`].
     TitleRev,
+    /// [`Song`] most played, least played.
+    ///
+    /// This is not stored in the [`Collection`], see [`crate::state::Stats::sort_songs`].
+    PlayCount,
+    /// [`Song`] least played, most played.
+    ///
+    /// This is not stored in the [`Collection`], see [`crate::state::Stats::sort_songs`].
+    PlayCountRev,
+    /// [`Song`] recently played, never played.
+    ///
+    /// This is not stored in the [`Collection`], see [`crate::state::Stats::sort_songs`].
+    LastPlayed,
+    /// [`Song`] never played, recently played.
+    ///
+    /// This is not stored in the [`Collection`], see [`crate::state::Stats::sort_songs`].
+    LastPlayedRev,
 }
 
 impl ArtistSort {
@@ -302,6 +344,8 @@ impl ArtistSort {
             RuntimeRev => ARTIST_RUNTIME_REV,
             Name => ARTIST_NAME,
             NameRev => ARTIST_NAME_REV,
+            Genre => ARTIST_GENRE,
+            GenreRev => ARTIST_GENRE_REV,
         }
     }
 
@@ -319,7 +363,9 @@ impl ArtistSort {
             Self::Runtime => Self::RuntimeRev,
             Self::RuntimeRev => Self::Name,
             Self::Name => Self::NameRev,
-            Self::NameRev => Self::Lexi,
+            Self::NameRev => Self::Genre,
+            Self::Genre => Self::GenreRev,
+            Self::GenreRev => Self::Lexi,
         }
     }
 
@@ -328,7 +374,7 @@ impl ArtistSort {
     /// This returns the _last_ if at the _first_.
     pub const fn previous(&self) -> Self {
         match self {
-            Self::Lexi => Self::NameRev,
+            Self::Lexi => Self::GenreRev,
             Self::LexiRev => Self::Lexi,
             Self::AlbumCount => Self::LexiRev,
             Self::AlbumCountRev => Self::AlbumCount,
@@ -338,6 +384,8 @@ impl ArtistSort {
             Self::RuntimeRev => Self::Runtime,
             Self::Name => Self::RuntimeRev,
             Self::NameRev => Self::Name,
+            Self::Genre => Self::NameRev,
+            Self::GenreRev => Self::Genre,
         }
     }
 }
@@ -366,6 +414,8 @@ impl AlbumSort {
             RuntimeRev => ALBUM_RUNTIME_REV,
             Title => ALBUM_TITLE,
             TitleRev => ALBUM_TITLE_REV,
+            Genre => ALBUM_GENRE,
+            GenreRev => ALBUM_GENRE_REV,
         }
     }
 
@@ -389,7 +439,9 @@ impl AlbumSort {
             Self::Runtime => Self::RuntimeRev,
             Self::RuntimeRev => Self::Title,
             Self::Title => Self::TitleRev,
-            Self::TitleRev => Self::ReleaseArtistLexi,
+            Self::TitleRev => Self::Genre,
+            Self::Genre => Self::GenreRev,
+            Self::GenreRev => Self::ReleaseArtistLexi,
         }
     }
 
@@ -398,7 +450,7 @@ impl AlbumSort {
     /// This returns the _last_ if at the _first_.
     pub const fn previous(&self) -> Self {
         match self {
-            Self::ReleaseArtistLexi => Self::TitleRev,
+            Self::ReleaseArtistLexi => Self::GenreRev,
             Self::ReleaseArtistLexiRev => Self::ReleaseArtistLexi,
             Self::ReleaseRevArtistLexi => Self::ReleaseArtistLexiRev,
             Self::ReleaseRevArtistLexiRev => Self::ReleaseRevArtistLexi,
@@ -414,6 +466,8 @@ impl AlbumSort {
             Self::RuntimeRev => Self::Runtime,
             Self::Title => Self::RuntimeRev,
             Self::TitleRev => Self::Title,
+            Self::Genre => Self::TitleRev,
+            Self::GenreRev => Self::Genre,
         }
     }
 }
@@ -442,6 +496,10 @@ impl SongSort {
             RuntimeRev => SONG_RUNTIME_REV,
             Title => SONG_TITLE,
             TitleRev => SONG_TITLE_REV,
+            PlayCount => SONG_PLAY_COUNT,
+            PlayCountRev => SONG_PLAY_COUNT_REV,
+            LastPlayed => SONG_LAST_PLAYED,
+            LastPlayedRev => SONG_LAST_PLAYED_REV,
         }
     }
 
@@ -465,7 +523,11 @@ impl SongSort {
             Self::Runtime => Self::RuntimeRev,
             Self::RuntimeRev => Self::Title,
             Self::Title => Self::TitleRev,
-            Self::TitleRev => Self::AlbumReleaseArtistLexi,
+            Self::TitleRev => Self::PlayCount,
+            Self::PlayCount => Self::PlayCountRev,
+            Self::PlayCountRev => Self::LastPlayed,
+            Self::LastPlayed => Self::LastPlayedRev,
+            Self::LastPlayedRev => Self::AlbumReleaseArtistLexi,
         }
     }
 
@@ -474,7 +536,7 @@ impl SongSort {
     /// This returns the _last_ if at the _first_.
     pub const fn previous(&self) -> Self {
         match self {
-            Self::AlbumReleaseArtistLexi => Self::TitleRev,
+            Self::AlbumReleaseArtistLexi => Self::LastPlayedRev,
             Self::AlbumReleaseArtistLexiRev => Self::AlbumReleaseArtistLexi,
             Self::AlbumReleaseRevArtistLexi => Self::AlbumReleaseArtistLexiRev,
             Self::AlbumReleaseRevArtistLexiRev => Self::AlbumReleaseRevArtistLexi,
@@ -490,6 +552,10 @@ impl SongSort {
             Self::RuntimeRev => Self::Runtime,
             Self::Title => Self::RuntimeRev,
             Self::TitleRev => Self::Title,
+            Self::PlayCount => Self::TitleRev,
+            Self::PlayCountRev => Self::PlayCount,
+            Self::LastPlayed => Self::PlayCountRev,
+            Self::LastPlayedRev => Self::LastPlayed,
         }
     }
 }
@@ -171,6 +171,32 @@ impl AudioState {
         None
     }
 
+    // Returns the `SongKey` and `queue_idx` that will
+    // be played after the current song finishes,
+    // taking the `Repeat` mode into account.
+    //
+    // This does not mutate anything, it's
+    // used for pre-opening the next song.
+    //
+    // Returns `None` if playback will finish.
+    pub(crate) fn peek_next(&self) -> Option<(SongKey, usize)> {
+        let i = self.queue_idx?;
+
+        if self.repeat == Repeat::Song {
+            return self.song.map(|key| (key, i));
+        }
+
+        if let Some(key) = self.queue.get(i + 1) {
+            return Some((*key, i + 1));
+        }
+
+        if matches!(self.repeat, Repeat::Queue | Repeat::QueuePause) {
+            return self.queue.front().map(|key| (*key, 0));
+        }
+
+        None
+    }
+
     // - Decrements the `queue_idx`
     // - Sets current song to the new index
     //
@@ -416,6 +442,47 @@ mod tests {
         assert_eq!(a.queue_idx, Some(2));
     }
 
+    #[test]
+    // Tests `peek_next()` and asserts the following behavior:
+    //
+    // 1. `queue_idx` is `None` => Returns `None`
+    // 2. Next key exists => Returns it without mutating
+    // 3. At the end with `Repeat::Off` => Returns `None`
+    // 4. At the end with `Repeat::Queue` => Returns index `0`
+    // 5. `Repeat::Song` => Returns the current song
+    fn peek_next() {
+        let mut a = AudioState::new();
+        let other = SongKey::from(1_u8);
+
+        // 1
+        assert!(a.peek_next().is_none());
+
+        // 2
+        a.queue.push_back(SONG);
+        a.queue.push_back(other);
+        a.queue_idx = Some(0);
+        a.song = Some(SONG);
+        a.repeat = Repeat::Off;
+        assert_eq!(a.peek_next(), Some((other, 1)));
+        assert_eq!(a.queue_idx, Some(0));
+        assert_eq!(a.song, Some(SONG));
+
+        // 3
+        a.queue_idx = Some(1);
+        a.song = Some(other);
+        assert!(a.peek_next().is_none());
+
+        // 4
+        a.repeat = Repeat::Queue;
+        assert_eq!(a.peek_next(), Some((SONG, 0)));
+        a.repeat = Repeat::QueuePause;
+        assert_eq!(a.peek_next(), Some((SONG, 0)));
+
+        // 5
+        a.repeat = Repeat::Song;
+        assert_eq!(a.peek_next(), Some((other, 1)));
+    }
+
     #[test]
     // Tests `prev()` and asserts the following behavior:
     //
@@ -12,3 +12,9 @@ pub use phase::*;
 
 mod playlist;
 pub use playlist::*;
+
+mod stats;
+pub use stats::*;
+
+mod smart;
+pub use smart::*;
@@ -1,7 +1,7 @@
 //---------------------------------------------------------------------------------------------------- Use
 use crate::{
     audio::Append,
-    collection::{AlbumKey, ArtistKey, Collection, SongKey},
+    collection::{AlbumKey, ArtistKey, Collection, Genre, SongKey},
     constants::{FESTIVAL, FRONTEND_SUB_DIR, HEADER, PLAYLIST_VERSION, STATE_SUB_DIR},
 };
 use benri::{lockr, lockw};
@@ -12,11 +12,13 @@ use log::{debug, error, info, trace, warn};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
 use std::{
-    collections::{BTreeMap, VecDeque},
+    collections::{BTreeMap, HashMap, VecDeque},
     sync::Arc,
 };
+use strum::{AsRefStr, Display, EnumCount, EnumIter, EnumString, EnumVariantNames, IntoStaticStr};
 
 //---------------------------------------------------------------------------------------------------- Lazy
 /// This is the single, global copy of `Playlists` that `Kernel` uses.
@@ -485,6 +487,80 @@ impl Playlists {
         (existed, old_len, v.len())
     }
 
+    /// Add all the songs of this genre to this playlist.
+    ///
+    /// Creates playlist if it did not exist.
+    ///
+    /// # Return
+    /// - `bool`  => did the playlist already existed?
+    /// - `usize` => playlist old length
+    /// - `usize` => playlist new length
+    ///
+    /// # INVARIANT
+    /// - Assumes `Append` index is not out-of-bounds
+    /// - Assumes the `Genre` is from `collection`
+    pub fn playlist_add_genre(
+        &mut self,
+        playlist: Arc<str>,
+        genre: &Genre,
+        append: Append,
+        collection: &Arc<Collection>,
+    ) -> (bool, usize, usize) {
+        let keys = &genre.songs;
+        let iter = keys.iter();
+
+        let mut existed = true;
+
+        let v = self.entry(playlist).or_insert_with(|| {
+            existed = false;
+            VecDeque::with_capacity(keys.len())
+        });
+
+        let old_len = v.len();
+
+        match append {
+            Append::Back => iter.for_each(|k| {
+                let (artist, album, song) = collection.walk(k);
+                let entry = Entry::Valid {
+                    key_artist: artist.key,
+                    key_album: album.key,
+                    key_song: *k,
+                    artist: Arc::clone(&artist.name),
+                    album: Arc::clone(&album.title),
+                    song: Arc::clone(&song.title),
+                };
+                v.push_back(entry);
+            }),
+            Append::Front => iter.rev().for_each(|k| {
+                let (artist, album, song) = collection.walk(k);
+                let entry = Entry::Valid {
+                    key_artist: artist.key,
+                    key_album: album.key,
+                    key_song: *k,
+                    artist: Arc::clone(&artist.name),
+                    album: Arc::clone(&album.title),
+                    song: Arc::clone(&song.title),
+                };
+                v.push_front(entry);
+            }),
+            Append::Index(mut i) => iter.for_each(|k| {
+                let (artist, album, song) = collection.walk(k);
+                let entry = Entry::Valid {
+                    key_artist: artist.key,
+                    key_album: album.key,
+                    key_song: *k,
+                    artist: Arc::clone(&artist.name),
+                    album: Arc::clone(&album.title),
+                    song: Arc::clone(&song.title),
+                };
+                v.insert(i, entry);
+                i += 1;
+            }),
+        }
+
+        (existed, old_len, v.len())
+    }
+
     //-------------------------------------------------- Misc.
     /// INVARIANT: this assumes the playlist's validity is already correct.
     ///
@@ -667,6 +743,502 @@ impl Playlists {
     }
 }
 
+//---------------------------------------------------------------------------------------------------- Import/Export
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    AsRefStr,
+    Display,
+    EnumCount,
+    EnumIter,
+    EnumString,
+    EnumVariantNames,
+    IntoStaticStr,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+/// Playlist file formats of other players that can be imported/exported.
+///
+/// See [`Playlists::import`] and [`Playlists::export`].
+pub enum PlaylistFormat {
+    #[default]
+    /// Extended `M3U` in `UTF-8`, with `#EXTINF` lines.
+    ///
+    /// Plain `M3U` files (only `PATH`'s) can also be imported with this.
+    M3u8,
+    /// [XML Shareable Playlist Format](https://xspf.org).
+    Xspf,
+    /// `PLS`, an `INI`-like format.
+    Pls,
+}
+
+impl PlaylistFormat {
+    /// The file extension of this format, without a `.`
+    pub const fn extension(&self) -> &'static str {
+        match self {
+            Self::M3u8 => "m3u8",
+            Self::Xspf => "xspf",
+            Self::Pls => "pls",
+        }
+    }
+
+    /// The `MIME` type of this format.
+    pub const fn mime(&self) -> &'static str {
+        match self {
+            Self::M3u8 => "audio/x-mpegurl",
+            Self::Xspf => "application/xspf+xml",
+            Self::Pls => "audio/x-scpls",
+        }
+    }
+
+    /// Get the format from a file's extension, e.g `my_playlist.m3u` -> `M3u8`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "m3u" | "m3u8" => Some(Self::M3u8),
+            "xspf" => Some(Self::Xspf),
+            "pls" => Some(Self::Pls),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from the start of a file's contents.
+    pub fn detect(s: &str) -> Option<Self> {
+        let s = s.trim_start_matches('\u{feff}').trim_start();
+        if s.starts_with("<?xml") || s.starts_with("<playlist") {
+            Some(Self::Xspf)
+        } else if s.len() >= 10 && s[..10].eq_ignore_ascii_case("[playlist]") {
+            Some(Self::Pls)
+        } else if s.starts_with("#EXTM3U") {
+            Some(Self::M3u8)
+        } else {
+            None
+        }
+    }
+}
+
+// A single row of a playlist file, before resolving it into an `Entry`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Row {
+    path: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    title: Option<String>,
+}
+
+impl Playlists {
+    /// Import a playlist file's contents.
+    ///
+    /// Each row is resolved into a `Song` by its `PATH` first
+    /// (relative `PATH`'s are joined onto `base`), then by its
+    /// artist name, album title and song title with [`Collection::song`].
+    ///
+    /// Rows that can't be resolved are kept as [`Entry::Invalid`]
+    /// with whatever metadata the file had.
+    pub fn import(
+        s: &str,
+        format: PlaylistFormat,
+        base: Option<&Path>,
+        collection: &Arc<Collection>,
+    ) -> VecDeque<Entry> {
+        let rows = match format {
+            PlaylistFormat::M3u8 => rows_m3u8(s),
+            PlaylistFormat::Xspf => rows_xspf(s),
+            PlaylistFormat::Pls => rows_pls(s),
+        };
+
+        // Songs split from a CUE sheet share a `PATH`,
+        // those can only be resolved by their metadata.
+        let mut paths: HashMap<&Path, Option<SongKey>> = HashMap::new();
+        for song in collection.songs.iter() {
+            paths
+                .entry(song.path.as_path())
+                .and_modify(|k| *k = None)
+                .or_insert(Some(song.key));
+        }
+
+        rows.into_iter()
+            .map(|row| row.resolve(base, &paths, collection))
+            .collect()
+    }
+
+    /// Export a playlist into a file of `format`.
+    ///
+    /// [`Entry::Invalid`]'s have no `PATH` so they are
+    /// skipped, except in [`PlaylistFormat::Xspf`] where
+    /// they are written with only their metadata.
+    pub fn export(
+        name: &str,
+        playlist: &VecDeque<Entry>,
+        format: PlaylistFormat,
+        collection: &Arc<Collection>,
+    ) -> String {
+        let mut s = String::new();
+
+        match format {
+            PlaylistFormat::M3u8 => {
+                s += "#EXTM3U\n";
+                s += &format!("#PLAYLIST:{name}\n");
+                for key in playlist.iter().filter_map(valid_key) {
+                    let (artist, album, song) = collection.walk(key);
+                    s += &format!(
+                        "#EXTINF:{},{} - {}\n",
+                        song.runtime.usize(),
+                        artist.name,
+                        song.title
+                    );
+                    s += &format!("#EXTALB:{}\n", album.title);
+                    s += &format!("#EXTART:{}\n", artist.name);
+                    s += &format!("{}\n", song.path.display());
+                }
+            }
+            PlaylistFormat::Xspf => {
+                s += "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+                s += "<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n";
+                s += &format!("  <title>{}</title>\n", xml_escape(name));
+                s += "  <trackList>\n";
+                for entry in playlist.iter() {
+                    s += "    <track>\n";
+                    let (artist, album, title) = match entry {
+                        Entry::Valid { key_song, .. } => {
+                            let (artist, album, song) = collection.walk(*key_song);
+                            s += &format!(
+                                "      <location>{}</location>\n",
+                                xml_escape(&path_to_uri(&song.path))
+                            );
+                            s += &format!(
+                                "      <duration>{}</duration>\n",
+                                song.runtime.usize() * 1000
+                            );
+                            (&artist.name, &album.title, &song.title)
+                        }
+                        Entry::Invalid {
+                            artist,
+                            album,
+                            song,
+                        } => (artist, album, song),
+                    };
+                    s += &format!("      <creator>{}</creator>\n", xml_escape(artist));
+                    s += &format!("      <album>{}</album>\n", xml_escape(album));
+                    s += &format!("      <title>{}</title>\n", xml_escape(title));
+                    s += "    </track>\n";
+                }
+                s += "  </trackList>\n";
+                s += "</playlist>\n";
+            }
+            PlaylistFormat::Pls => {
+                s += "[playlist]\n";
+                let mut n = 0;
+                for key in playlist.iter().filter_map(valid_key) {
+                    n += 1;
+                    let (artist, _, song) = collection.walk(key);
+                    s += &format!("File{n}={}\n", song.path.display());
+                    s += &format!("Title{n}={} - {}\n", artist.name, song.title);
+                    s += &format!("Length{n}={}\n", song.runtime.usize());
+                }
+                s += &format!("NumberOfEntries={n}\n");
+                s += "Version=2\n";
+            }
+        }
+
+        s
+    }
+}
+
+fn valid_key(entry: &Entry) -> Option<SongKey> {
+    match entry {
+        Entry::Valid { key_song, .. } => Some(*key_song),
+        Entry::Invalid { .. } => None,
+    }
+}
+
+impl Row {
+    // Resolve this row into an `Entry`, by `PATH` then by metadata.
+    fn resolve(
+        self,
+        base: Option<&Path>,
+        paths: &HashMap<&Path, Option<SongKey>>,
+        collection: &Arc<Collection>,
+    ) -> Entry {
+        if let Some(path) = &self.path {
+            let path = uri_to_path(path);
+            let path = match base {
+                Some(base) if path.is_relative() => base.join(path),
+                _ => path,
+            };
+            if let Some(Some(key)) = paths.get(path.as_path()) {
+                return Entry::valid_from_song(*key, collection);
+            }
+        }
+
+        if let (Some(artist), Some(title)) = (&self.artist, &self.title) {
+            let key = match &self.album {
+                Some(album) => collection.song(artist, album, title).map(|(_, k)| k),
+                None => collection.artist(artist).and_then(|(a, _)| {
+                    a.songs
+                        .iter()
+                        .find(|k| &*collection.songs[**k].title == title.as_str())
+                        .copied()
+                }),
+            };
+            if let Some(key) = key {
+                return Entry::valid_from_song(key, collection);
+            }
+        }
+
+        // If there's no title, the file name is the next best thing.
+        let title = self.title.or_else(|| {
+            self.path.as_ref().and_then(|p| {
+                uri_to_path(p)
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+            })
+        });
+
+        Entry::Invalid {
+            artist: self.artist.unwrap_or_default().into(),
+            album: self.album.unwrap_or_default().into(),
+            song: title.unwrap_or_default().into(),
+        }
+    }
+}
+
+// Split `Artist - Title` display strings (`#EXTINF`, `PLS` titles).
+fn split_display(s: &str) -> (Option<String>, Option<String>) {
+    let s = s.trim();
+    if s.is_empty() {
+        return (None, None);
+    }
+    match s.split_once(" - ") {
+        Some((artist, title)) => (Some(artist.to_string()), Some(title.to_string())),
+        None => (None, Some(s.to_string())),
+    }
+}
+
+fn rows_m3u8(s: &str) -> Vec<Row> {
+    let mut rows = vec![];
+    let mut row = Row::default();
+
+    for line in s.lines() {
+        let line = line.trim_start_matches('\u{feff}').trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            // `#EXTINF:$SECONDS,$ARTIST - $TITLE`
+            if let Some((_, display)) = info.split_once(',') {
+                let (artist, title) = split_display(display);
+                row.artist = artist.or(row.artist);
+                row.title = title;
+            }
+        } else if let Some(album) = line.strip_prefix("#EXTALB:") {
+            row.album = Some(album.trim().to_string());
+        } else if let Some(artist) = line.strip_prefix("#EXTART:") {
+            row.artist = Some(artist.trim().to_string());
+        } else if !line.starts_with('#') {
+            row.path = Some(line.to_string());
+            rows.push(std::mem::take(&mut row));
+        }
+    }
+
+    rows
+}
+
+fn rows_pls(s: &str) -> Vec<Row> {
+    let mut rows: BTreeMap<usize, Row> = BTreeMap::new();
+
+    for line in s.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+
+        let (field, n) = if let Some(n) = key.strip_prefix("file") {
+            ("file", n)
+        } else if let Some(n) = key.strip_prefix("title") {
+            ("title", n)
+        } else {
+            continue;
+        };
+        let Ok(n) = n.parse::<usize>() else {
+            continue;
+        };
+
+        let row = rows.entry(n).or_default();
+        if field == "file" {
+            row.path = Some(value.to_string());
+        } else {
+            (row.artist, row.title) = split_display(value);
+        }
+    }
+
+    rows.into_values().filter(|r| r.path.is_some()).collect()
+}
+
+fn rows_xspf(s: &str) -> Vec<Row> {
+    let mut rows = vec![];
+    let mut rest = s;
+
+    while let Some(start) = rest.find("<track>") {
+        rest = &rest[start + "<track>".len()..];
+        let end = rest.find("</track>").unwrap_or(rest.len());
+        let track = &rest[..end];
+        rest = &rest[end..];
+
+        let row = Row {
+            path: xml_element(track, "location"),
+            artist: xml_element(track, "creator"),
+            album: xml_element(track, "album"),
+            title: xml_element(track, "title"),
+        };
+        if row != Row::default() {
+            rows.push(row);
+        }
+    }
+
+    rows
+}
+
+// The unescaped text of the first `<$tag>` element, if it's not empty.
+fn xml_element(s: &str, tag: &str) -> Option<String> {
+    let open = format!("<{tag}>");
+    let start = s.find(&open)? + open.len();
+    let end = start + s[start..].find(&format!("</{tag}>"))?;
+    let text = s[start..end].trim();
+    let text = text
+        .strip_prefix("<![CDATA[")
+        .and_then(|t| t.strip_suffix("]]>"))
+        .map(str::to_string)
+        .unwrap_or_else(|| xml_unescape(text));
+
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out += "&amp;",
+            '<' => out += "&lt;",
+            '>' => out += "&gt;",
+            '"' => out += "&quot;",
+            '\'' => out += "&apos;",
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn xml_unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(i) = rest.find('&') {
+        out += &rest[..i];
+        rest = &rest[i..];
+
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let c = match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            e => e
+                .strip_prefix("#x")
+                .map(|n| u32::from_str_radix(n, 16).ok())
+                .unwrap_or_else(|| e.strip_prefix('#').and_then(|n| n.parse().ok()))
+                .and_then(char::from_u32),
+        };
+
+        match c {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out += rest;
+    out
+}
+
+// `PATH` -> `file://` URI, percent-encoding everything but unreserved characters.
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut s = String::from("file://");
+    if !path.starts_with('/') {
+        s.push('/');
+    }
+    for b in path.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                s.push(b as char)
+            }
+            b => s += &format!("%{b:02X}"),
+        }
+    }
+    s
+}
+
+// `file://` URI -> `PATH`, anything else is returned as a `PATH` as-is.
+fn uri_to_path(s: &str) -> PathBuf {
+    let Some(path) = s.strip_prefix("file://") else {
+        return PathBuf::from(s);
+    };
+    // Strip the (empty or `localhost`) host.
+    let path = path.strip_prefix("localhost").unwrap_or(path);
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut iter = path.bytes();
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex = [iter.next(), iter.next()];
+            if let [Some(h), Some(l)] = hex {
+                if let Ok(b) = u8::from_str_radix(&format!("{}{}", h as char, l as char), 16) {
+                    bytes.push(b);
+                    continue;
+                }
+            }
+            bytes.push(b'%');
+            bytes.extend(hex.into_iter().flatten());
+        } else {
+            bytes.push(b);
+        }
+    }
+    let path = String::from_utf8_lossy(&bytes).into_owned();
+
+    // `/C:/...` -> `C:/...` on Windows.
+    if cfg!(windows) && path.as_bytes().get(2) == Some(&b':') {
+        return PathBuf::from(&path[1..]);
+    }
+
+    PathBuf::from(path)
+}
+
 //---------------------------------------------------------------------------------------------------- JSON Representation
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -713,9 +1285,133 @@ pub enum EntryJson<'a> {
 }
 
 //---------------------------------------------------------------------------------------------------- TESTS
-//#[cfg(test)]
-//mod tests {
-//	#[test]
-//		fn __TEST__() {
-//	}
-//}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(path: &str, artist: &str, album: &str, title: &str) -> Row {
+        let f = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        Row {
+            path: f(path),
+            artist: f(artist),
+            album: f(album),
+            title: f(title),
+        }
+    }
+
+    #[test]
+    // Formats are detected by extension and contents.
+    fn format() {
+        assert_eq!(
+            PlaylistFormat::from_path(Path::new("a.M3U")),
+            Some(PlaylistFormat::M3u8)
+        );
+        assert_eq!(
+            PlaylistFormat::from_path(Path::new("a.xspf")),
+            Some(PlaylistFormat::Xspf)
+        );
+        assert_eq!(PlaylistFormat::from_path(Path::new("a.txt")), None);
+
+        assert_eq!(
+            PlaylistFormat::detect("#EXTM3U\n"),
+            Some(PlaylistFormat::M3u8)
+        );
+        assert_eq!(
+            PlaylistFormat::detect("\n[Playlist]\n"),
+            Some(PlaylistFormat::Pls)
+        );
+        assert_eq!(
+            PlaylistFormat::detect("<?xml version=\"1.0\"?>"),
+            Some(PlaylistFormat::Xspf)
+        );
+        assert_eq!(PlaylistFormat::detect("/a.mp3"), None);
+        assert_eq!("xspf".parse::<PlaylistFormat>(), Ok(PlaylistFormat::Xspf));
+    }
+
+    #[test]
+    fn m3u8() {
+        let s = "#EXTM3U
+#PLAYLIST:hello
+#EXTINF:123,Artist - Song - Title
+#EXTALB:Album
+/a.mp3
+
+b.flac
+#EXTINF:-1,Title
+c.ogg
+";
+        assert_eq!(
+            rows_m3u8(s),
+            [
+                row("/a.mp3", "Artist", "Album", "Song - Title"),
+                row("b.flac", "", "", ""),
+                row("c.ogg", "", "", "Title"),
+            ]
+        );
+    }
+
+    #[test]
+    fn pls() {
+        let s = "[playlist]
+File2=/b.flac
+File1=/a.mp3
+Title1=Artist - Title
+Length1=123
+Title3=No file
+NumberOfEntries=2
+Version=2
+";
+        assert_eq!(
+            rows_pls(s),
+            [
+                row("/a.mp3", "Artist", "", "Title"),
+                row("/b.flac", "", "", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn xspf() {
+        let s = r#"<?xml version="1.0" encoding="UTF-8"?>
+<playlist version="1" xmlns="http://xspf.org/ns/0/">
+  <title>hello</title>
+  <trackList>
+    <track>
+      <location>file:///a%20b/c.mp3</location>
+      <creator>A &amp; B</creator>
+      <album><![CDATA[<Album>]]></album>
+      <title>&#x41;&#66;</title>
+    </track>
+    <track>
+      <title>Invalid</title>
+    </track>
+    <track></track>
+  </trackList>
+</playlist>"#;
+        assert_eq!(
+            rows_xspf(s),
+            [
+                row("file:///a%20b/c.mp3", "A & B", "<Album>", "AB"),
+                row("", "", "", "Invalid"),
+            ]
+        );
+    }
+
+    #[test]
+    fn escape() {
+        let s = r#"<a & 'b' "c">"#;
+        assert_eq!(xml_unescape(&xml_escape(s)), s);
+        assert_eq!(xml_unescape("&unknown; & &#xZZ;"), "&unknown; & &#xZZ;");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn uri() {
+        let path = Path::new("/a b/ä?#.mp3");
+        let uri = path_to_uri(path);
+        assert_eq!(uri, "file:///a%20b/%C3%A4%3F%23.mp3");
+        assert_eq!(uri_to_path(&uri), path);
+        assert_eq!(uri_to_path("file://localhost/a"), Path::new("/a"));
+        assert_eq!(uri_to_path("relative/a"), Path::new("relative/a"));
+    }
+}
@@ -0,0 +1,434 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::{
+    collection::{Collection, SongKey},
+    constants::{FESTIVAL, FRONTEND_SUB_DIR, HEADER, SMART_PLAYLIST_VERSION, STATE_SUB_DIR},
+    sort::SongSort,
+    state::{Entry, Playlists, Stats},
+};
+use benri::{lockr, lockw};
+use bincode::{Decode, Encode};
+use const_format::formatcp;
+use disk::Bincode2;
+use log::trace;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
+
+//---------------------------------------------------------------------------------------------------- Lazy
+/// This is the single, global copy of `SmartPlaylists` that `Kernel` uses.
+///
+/// To obtain a read-only lock, use `SMART_PLAYLISTS.read()`.
+pub static SMART_PLAYLISTS: SmartPlaylistsLock =
+    SmartPlaylistsLock(RwLock::new(SmartPlaylists::new()));
+
+//---------------------------------------------------------------------------------------------------- SmartPlaylistsLock
+/// There is only a single, global copy of `SmartPlaylists` that `Kernel` uses: [`SMART_PLAYLISTS`].
+///
+/// To obtain a read-only lock, use `SMART_PLAYLISTS.read()`.
+pub struct SmartPlaylistsLock(RwLock<SmartPlaylists>);
+
+impl SmartPlaylistsLock {
+    #[inline(always)]
+    /// Obtain a read-only lock to the global [`SmartPlaylists`].
+    pub fn read(&'static self) -> RwLockReadGuard<'static, SmartPlaylists> {
+        lockr!(self.0)
+    }
+
+    #[inline(always)]
+    /// Call the non-blocking `.try_read()` on the global [`SmartPlaylists`].
+    pub fn try_read(
+        &'static self,
+    ) -> Result<
+        RwLockReadGuard<'static, SmartPlaylists>,
+        TryLockError<RwLockReadGuard<'static, SmartPlaylists>>,
+    > {
+        self.0.try_read()
+    }
+
+    #[inline(always)]
+    /// Obtain a write lock to the global [`SmartPlaylists`].
+    pub fn write(&'static self) -> RwLockWriteGuard<'static, SmartPlaylists> {
+        lockw!(self.0)
+    }
+
+    #[inline(always)]
+    /// Call the non-blocking `.try_write()` on the global [`SmartPlaylists`].
+    pub fn try_write(
+        &'static self,
+    ) -> Result<
+        RwLockWriteGuard<'static, SmartPlaylists>,
+        TryLockError<RwLockWriteGuard<'static, SmartPlaylists>>,
+    > {
+        self.0.try_write()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- SmartPlaylists
+disk::bincode2!(
+    SmartPlaylists,
+    disk::Dir::Data,
+    FESTIVAL,
+    formatcp!("{FRONTEND_SUB_DIR}/{STATE_SUB_DIR}"),
+    "smart_playlists",
+    HEADER,
+    SMART_PLAYLIST_VERSION
+);
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+#[serde(transparent)]
+#[repr(transparent)]
+/// Smart playlist implementation.
+///
+/// A smart playlist is a playlist whose contents are computed from [`Rule`]'s.
+///
+/// This only holds the definitions, the contents are written into
+/// [`Playlists`] as a normal playlist of the same name when evaluated,
+/// so anything that works with a normal playlist works with these.
+///
+/// Smart playlists are re-evaluated on every new [`Collection`],
+/// any manual edits to their contents will be overwritten.
+pub struct SmartPlaylists(pub SmartPlaylistsInner);
+
+/// The internal type of [`SmartPlaylists`].
+///
+/// [`SmartPlaylists`] is just a wrapper so methods/traits can be implemented on it.
+pub type SmartPlaylistsInner = BTreeMap<Arc<str>, SmartPlaylist>;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+/// The definition of a single smart playlist.
+pub struct SmartPlaylist {
+    /// All of these must match for a `Song` to be included.
+    pub rules: Vec<Rule>,
+    /// The maximum amount of `Song`'s, all if [`None`].
+    pub limit: Option<usize>,
+    /// The order of the `Song`'s.
+    pub sort: SongSort,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+#[serde(rename_all = "snake_case")]
+/// A single rule of a [`SmartPlaylist`].
+///
+/// Ranges are inclusive, a [`None`] side is unbounded.
+///
+/// The string form (used by `festival-cli`) is `field:value`, e.g:
+/// `genre:rock`, `release:2000..2010`, `runtime:..300`, `play_count:3..`, `added:30`.
+pub enum Rule {
+    /// The `Album` has this genre (case-insensitive).
+    Genre(Arc<str>),
+    /// The `Artist`, or an `Artist` credited on the `Song` (case-insensitive).
+    Artist(Arc<str>),
+    /// The `Album` release year, `Album`'s with an unknown release never match.
+    Release {
+        /// Earliest year
+        from: Option<u32>,
+        /// Latest year
+        to: Option<u32>,
+    },
+    /// The `Song` runtime in seconds.
+    Runtime {
+        /// Shortest runtime
+        min: Option<u32>,
+        /// Longest runtime
+        max: Option<u32>,
+    },
+    /// The `Song` play count, see [`Stats`].
+    PlayCount {
+        /// Least plays
+        min: Option<u64>,
+        /// Most plays
+        max: Option<u64>,
+    },
+    /// The `Song` file was added (modified) within this many days.
+    Added(u64),
+}
+
+impl std::ops::Deref for SmartPlaylists {
+    type Target = SmartPlaylistsInner;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for SmartPlaylists {
+    fn deref_mut(&mut self) -> &mut SmartPlaylistsInner {
+        &mut self.0
+    }
+}
+
+impl SmartPlaylists {
+    /// Create an empty `Self` with no allocation.
+    pub const fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    /// Evaluate all smart playlists, overwriting their contents in `playlists`.
+    pub fn evaluate(&self, collection: &Arc<Collection>, stats: &Stats, playlists: &mut Playlists) {
+        let now = benri::unix!();
+        for (name, smart) in self.iter() {
+            let entries = smart.evaluate(collection, stats, now);
+            trace!("SmartPlaylists - {name}: {} entries", entries.len());
+            playlists.insert(Arc::clone(name), entries);
+        }
+    }
+}
+
+impl SmartPlaylist {
+    /// Compute the contents of this smart playlist.
+    ///
+    /// `now` is the current UNIX timestamp, used by [`Rule::Added`].
+    pub fn evaluate(
+        &self,
+        collection: &Arc<Collection>,
+        stats: &Stats,
+        now: u64,
+    ) -> VecDeque<Entry> {
+        // `Rule::Added` touches the filesystem, so check it last.
+        let (added, rules): (Vec<&Rule>, Vec<&Rule>) =
+            self.rules.iter().partition(|r| matches!(r, Rule::Added(_)));
+
+        stats
+            .sort_songs(self.sort, collection)
+            .iter()
+            .filter(|key| {
+                rules
+                    .iter()
+                    .chain(added.iter())
+                    .all(|r| r.matches(**key, collection, stats, now))
+            })
+            .take(self.limit.unwrap_or(usize::MAX))
+            .map(|key| Entry::valid_from_song(*key, collection))
+            .collect()
+    }
+}
+
+impl Rule {
+    /// If the `Song` matches this rule.
+    ///
+    /// INVARIANT: assumes `key` is valid.
+    pub fn matches(
+        &self,
+        key: SongKey,
+        collection: &Arc<Collection>,
+        stats: &Stats,
+        now: u64,
+    ) -> bool {
+        let (artist, album, song) = collection.walk(key);
+
+        match self {
+            Self::Genre(g) => album.genres.iter().any(|x| eq_ignore_case(x, g)),
+            Self::Artist(a) => {
+                eq_ignore_case(&artist.name, a) || song.artists.iter().any(|x| eq_ignore_case(x, a))
+            }
+            Self::Release { from, to } => album
+                .release
+                .as_str()
+                .get(..4)
+                .and_then(|y| y.parse().ok())
+                .is_some_and(|y| in_range(y, *from, *to)),
+            Self::Runtime { min, max } => in_range(song.runtime.inner(), *min, *max),
+            Self::PlayCount { min, max } => {
+                let count = stats.song(key, collection).map_or(0, |s| s.play_count);
+                in_range(count, *min, *max)
+            }
+            Self::Added(days) => std::fs::metadata(&song.path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .is_some_and(|t| now.saturating_sub(t.as_secs()) <= days.saturating_mul(86_400)),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Rule <-> String
+impl FromStr for Rule {
+    type Err = anyhow::Error;
+
+    /// Parse the `field:value` form of a [`Rule`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((field, value)) = s.split_once(':') else {
+            return Err(anyhow::anyhow!(
+                "rule must be in the form `field:value`: {s}"
+            ));
+        };
+        let value = value.trim();
+
+        let rule = match field.trim() {
+            "genre" => Self::Genre(value.into()),
+            "artist" => Self::Artist(value.into()),
+            "release" => {
+                let (from, to) = parse_range(value)?;
+                Self::Release { from, to }
+            }
+            "runtime" => {
+                let (min, max) = parse_range(value)?;
+                Self::Runtime { min, max }
+            }
+            "play_count" => {
+                let (min, max) = parse_range(value)?;
+                Self::PlayCount { min, max }
+            }
+            "added" => Self::Added(value.parse()?),
+            _ => return Err(anyhow::anyhow!("unknown rule: {field}")),
+        };
+
+        Ok(rule)
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Genre(g) => write!(f, "genre:{g}"),
+            Self::Artist(a) => write!(f, "artist:{a}"),
+            Self::Release { from, to } => write!(f, "release:{}", Range(from, to)),
+            Self::Runtime { min, max } => write!(f, "runtime:{}", Range(min, max)),
+            Self::PlayCount { min, max } => write!(f, "play_count:{}", Range(min, max)),
+            Self::Added(days) => write!(f, "added:{days}"),
+        }
+    }
+}
+
+// Formats an inclusive range, e.g: `1..5`, `1..`, `..5`, `3` (if equal).
+struct Range<'a, T>(&'a Option<T>, &'a Option<T>);
+
+impl<T: std::fmt::Display + PartialEq> std::fmt::Display for Range<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.0, self.1) {
+            (Some(a), Some(b)) if a == b => write!(f, "{a}"),
+            (a, b) => {
+                if let Some(a) = a {
+                    write!(f, "{a}")?;
+                }
+                f.write_str("..")?;
+                if let Some(b) = b {
+                    write!(f, "{b}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Parse an inclusive range, e.g: `1..5`, `1..`, `..5`, `3` (exactly 3).
+fn parse_range<T: FromStr + Copy>(s: &str) -> Result<(Option<T>, Option<T>), anyhow::Error>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let parse = |s: &str| -> Result<Option<T>, anyhow::Error> {
+        match s.trim() {
+            "" => Ok(None),
+            s => Ok(Some(s.parse()?)),
+        }
+    };
+
+    match s.split_once("..") {
+        Some((a, b)) => Ok((parse(a)?, parse(b)?)),
+        None => match parse(s)? {
+            Some(v) => Ok((Some(v), Some(v))),
+            None => Err(anyhow::anyhow!("empty range")),
+        },
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Free functions
+// If `t` is within the inclusive range.
+fn in_range<T: PartialOrd>(t: T, min: Option<T>, max: Option<T>) -> bool {
+    min.map_or(true, |min| t >= min) && max.map_or(true, |max| t <= max)
+}
+
+// Case-insensitive `==` without allocating.
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .eq(b.chars().flat_map(char::to_lowercase))
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // `Rule` -> `String` -> `Rule`.
+    fn rule_string() {
+        let rules = [
+            ("genre:Rock", Rule::Genre("Rock".into())),
+            ("artist:Pink Floyd", Rule::Artist("Pink Floyd".into())),
+            (
+                "release:2000..2010",
+                Rule::Release {
+                    from: Some(2000),
+                    to: Some(2010),
+                },
+            ),
+            (
+                "runtime:..300",
+                Rule::Runtime {
+                    min: None,
+                    max: Some(300),
+                },
+            ),
+            (
+                "play_count:3..",
+                Rule::PlayCount {
+                    min: Some(3),
+                    max: None,
+                },
+            ),
+            (
+                "release:1999",
+                Rule::Release {
+                    from: Some(1999),
+                    to: Some(1999),
+                },
+            ),
+            ("added:30", Rule::Added(30)),
+        ];
+
+        for (string, rule) in rules {
+            assert_eq!(string.parse::<Rule>().unwrap(), rule);
+            assert_eq!(rule.to_string(), string);
+        }
+
+        for bad in [
+            "genre",
+            "year:2000",
+            "release:abc",
+            "runtime:",
+            "added:soon",
+        ] {
+            assert!(bad.parse::<Rule>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    // `Rule`'s in JSON.
+    fn rule_json() {
+        let rules = vec![
+            Rule::Genre("Rock".into()),
+            Rule::Release {
+                from: Some(2000),
+                to: None,
+            },
+            Rule::Added(30),
+        ];
+        let json = r#"[{"genre":"Rock"},{"release":{"from":2000,"to":null}},{"added":30}]"#;
+
+        assert_eq!(serde_json::to_string(&rules).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Vec<Rule>>(json).unwrap(), rules);
+    }
+
+    #[test]
+    fn helpers() {
+        assert!(in_range(5, Some(1), Some(5)));
+        assert!(in_range(5, None, None));
+        assert!(!in_range(6, None, Some(5)));
+        assert!(!in_range(0, Some(1), None));
+
+        assert!(eq_ignore_case("ROCK", "rock"));
+        assert!(eq_ignore_case("Ärzte", "ärzte"));
+        assert!(!eq_ignore_case("rock", "rocks"));
+    }
+}
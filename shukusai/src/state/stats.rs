@@ -0,0 +1,327 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::{
+    collection::{Collection, MapKey, SongKey},
+    constants::{FESTIVAL, FRONTEND_SUB_DIR, HEADER, STATE_SUB_DIR, STATS_VERSION},
+    sort::SongSort,
+};
+use benri::{lockr, lockw};
+use bincode::{Decode, Encode};
+use const_format::formatcp;
+use disk::Bincode2;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
+
+//---------------------------------------------------------------------------------------------------- Lazy
+/// This is the single, global copy of `Stats` that `Kernel` uses.
+///
+/// To obtain a read-only lock, use `STATS.read()`.
+pub static STATS: StatsLock = StatsLock(RwLock::new(Stats::new()), AtomicU64::new(0));
+
+//---------------------------------------------------------------------------------------------------- StatsLock
+/// There is only a single, global copy of `Stats` that `Kernel` uses: [`STATS`].
+///
+/// To obtain a read-only lock, use `STATS.read()`.
+pub struct StatsLock(RwLock<Stats>, AtomicU64);
+
+impl StatsLock {
+    #[inline(always)]
+    /// Obtain a read-only lock to the global [`Stats`].
+    pub fn read(&'static self) -> RwLockReadGuard<'static, Stats> {
+        lockr!(self.0)
+    }
+
+    #[inline(always)]
+    /// Call the non-blocking `.try_read()` on the global [`Stats`].
+    pub fn try_read(
+        &'static self,
+    ) -> Result<RwLockReadGuard<'static, Stats>, TryLockError<RwLockReadGuard<'static, Stats>>>
+    {
+        self.0.try_read()
+    }
+
+    #[inline(always)]
+    /// Obtain a write lock to the global [`Stats`].
+    pub fn write(&'static self) -> RwLockWriteGuard<'static, Stats> {
+        let guard = lockw!(self.0);
+        self.1.fetch_add(1, Ordering::Release);
+        guard
+    }
+
+    #[inline(always)]
+    /// Call the non-blocking `.try_write()` on the global [`Stats`].
+    pub fn try_write(
+        &'static self,
+    ) -> Result<RwLockWriteGuard<'static, Stats>, TryLockError<RwLockWriteGuard<'static, Stats>>>
+    {
+        let guard = self.0.try_write()?;
+        self.1.fetch_add(1, Ordering::Release);
+        Ok(guard)
+    }
+
+    #[inline(always)]
+    /// How many times the global [`Stats`] were locked for writing.
+    ///
+    /// If this hasn't changed, neither have the [`Stats`], so
+    /// anything computed from them (e.g [`Stats::sort_songs`])
+    /// can be cached. Read this while holding a read lock.
+    pub fn generation(&'static self) -> u64 {
+        self.1.load(Ordering::Acquire)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Stats
+disk::bincode2!(
+    Stats,
+    disk::Dir::Data,
+    FESTIVAL,
+    formatcp!("{FRONTEND_SUB_DIR}/{STATE_SUB_DIR}"),
+    "stats",
+    HEADER,
+    STATS_VERSION
+);
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+#[serde(transparent)]
+#[repr(transparent)]
+/// Play statistics of all [`Song`](crate::collection::Song)'s ever played.
+///
+/// This is keyed by [`MapKey`] (artist name, album title, song title)
+/// instead of [`SongKey`] so that the statistics survive
+/// the [`Collection`] being reset.
+///
+/// A [`Song`](crate::collection::Song) that was never played has no entry.
+pub struct Stats(pub StatsInner);
+
+/// The internal type of [`Stats`].
+///
+/// [`Stats`] is just a wrapper so methods/traits can be implemented on it.
+pub type StatsInner = BTreeMap<MapKey, SongStats>;
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+/// The play statistics of a single [`Song`](crate::collection::Song).
+pub struct SongStats {
+    /// How many times this song was played until the end.
+    pub play_count: u64,
+    /// How many times this song was skipped before the end.
+    pub skip_count: u64,
+    /// UNIX timestamp of when this song was first played.
+    pub first_played: u64,
+    /// UNIX timestamp of when this song was last played.
+    pub last_played: u64,
+    /// Total time listened to this song in seconds.
+    pub listened: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// How a song that was playing ended.
+pub(crate) enum SongEnd {
+    /// Played until the end.
+    Finished,
+    /// Another song was set before the end.
+    Skipped,
+    /// Playback was stopped, this doesn't count as a play or skip.
+    Stopped,
+}
+
+impl std::ops::Deref for Stats {
+    type Target = StatsInner;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Stats {
+    #[inline]
+    /// Create empty [`Stats`].
+    pub const fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    #[inline]
+    /// Get the [`SongStats`] of a [`Song`](crate::collection::Song).
+    ///
+    /// Returns [`None`] if the song was never played.
+    ///
+    /// INVARIANT: assumes `key` is valid.
+    pub fn song(&self, key: SongKey, collection: &Arc<Collection>) -> Option<&SongStats> {
+        self.0.get(&MapKey::from_song_key(key, collection))
+    }
+
+    /// A song started playing at the UNIX timestamp `now`.
+    pub(crate) fn start(&mut self, key: MapKey, now: u64) {
+        let stats = self.0.entry(key).or_default();
+        if stats.first_played == 0 {
+            stats.first_played = now;
+        }
+        stats.last_played = now;
+    }
+
+    /// A song that was listened to for `listened` seconds ended.
+    pub(crate) fn end(&mut self, key: MapKey, listened: u64, end: SongEnd) {
+        let stats = self.0.entry(key).or_default();
+        match end {
+            SongEnd::Finished => stats.play_count += 1,
+            SongEnd::Skipped => stats.skip_count += 1,
+            SongEnd::Stopped => (),
+        }
+        stats.listened += listened;
+    }
+
+    // All songs with statistics that exist in the `Collection`.
+    fn played(&self, collection: &Arc<Collection>) -> Vec<(SongKey, SongStats)> {
+        self.0
+            .iter()
+            .filter_map(|(map_key, stats)| map_key.to_key(collection).map(|key| (key, *stats)))
+            .collect()
+    }
+
+    // Sort by play count, most played first, ties are broken by last played.
+    fn sort_play_count(vec: &mut [(SongKey, SongStats)]) {
+        vec.sort_by(|(_, a), (_, b)| {
+            b.play_count
+                .cmp(&a.play_count)
+                .then(b.last_played.cmp(&a.last_played))
+        });
+    }
+
+    // Sort by last played, most recent first.
+    fn sort_last_played(vec: &mut [(SongKey, SongStats)]) {
+        vec.sort_by(|(_, a), (_, b)| b.last_played.cmp(&a.last_played));
+    }
+
+    /// All songs in the [`Collection`] that were played
+    /// until the end at least once, most played first.
+    pub fn most_played(&self, collection: &Arc<Collection>) -> Vec<(SongKey, SongStats)> {
+        let mut vec = self.played(collection);
+        vec.retain(|(_, s)| s.play_count != 0);
+        Self::sort_play_count(&mut vec);
+        vec
+    }
+
+    /// All songs in the [`Collection`] that were played, most recent first.
+    pub fn recently_played(&self, collection: &Arc<Collection>) -> Vec<(SongKey, SongStats)> {
+        let mut vec = self.played(collection);
+        vec.retain(|(_, s)| s.last_played != 0);
+        Self::sort_last_played(&mut vec);
+        vec
+    }
+
+    /// Get all [`SongKey`]'s of the [`Collection`] sorted by a [`SongSort`].
+    ///
+    /// This is [`Collection::song_iter`], except it also handles the
+    /// play statistic sorts (e.g [`SongSort::PlayCount`]).
+    ///
+    /// For those, songs without statistics are appended
+    /// in [`SongSort::Lexi`] order (or prepended, for the `Rev` variants).
+    pub fn sort_songs<'a>(
+        &self,
+        sort: SongSort,
+        collection: &'a Arc<Collection>,
+    ) -> Cow<'a, [SongKey]> {
+        let rev = match sort {
+            SongSort::PlayCount | SongSort::LastPlayed => false,
+            SongSort::PlayCountRev | SongSort::LastPlayedRev => true,
+            _ => return Cow::Borrowed(collection.song_iter(sort).as_slice()),
+        };
+
+        let mut played = self.played(collection);
+        match sort {
+            SongSort::PlayCount | SongSort::PlayCountRev => Self::sort_play_count(&mut played),
+            _ => Self::sort_last_played(&mut played),
+        }
+
+        let set: HashSet<SongKey> = played.iter().map(|(k, _)| *k).collect();
+        let mut vec: Vec<SongKey> = Vec::with_capacity(collection.count_song.usize());
+        vec.extend(played.into_iter().map(|(k, _)| k));
+        vec.extend(
+            collection
+                .sort_song_lexi
+                .iter()
+                .filter(|k| !set.contains(k)),
+        );
+
+        if rev {
+            vec.reverse();
+        }
+
+        Cow::Owned(vec)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> MapKey {
+        MapKey {
+            artist: "artist".into(),
+            album: "album".into(),
+            song: s.into(),
+        }
+    }
+
+    #[test]
+    // `start()` and `end()` update the right fields.
+    fn start_end() {
+        let mut stats = Stats::new();
+
+        stats.start(key("a"), 100);
+        stats.end(key("a"), 30, SongEnd::Finished);
+        stats.start(key("a"), 200);
+        stats.end(key("a"), 5, SongEnd::Skipped);
+        stats.start(key("a"), 300);
+        stats.end(key("a"), 1, SongEnd::Stopped);
+
+        assert_eq!(
+            stats[&key("a")],
+            SongStats {
+                play_count: 1,
+                skip_count: 1,
+                first_played: 100,
+                last_played: 300,
+                listened: 36,
+            }
+        );
+        assert_eq!(stats.get(&key("b")), None);
+    }
+
+    #[test]
+    // Play count ties are broken by the last played.
+    fn sort() {
+        let stats = |play_count, last_played| SongStats {
+            play_count,
+            last_played,
+            ..Default::default()
+        };
+        let mut vec = vec![
+            (SongKey::from(0_usize), stats(1, 10)),
+            (SongKey::from(1_usize), stats(3, 5)),
+            (SongKey::from(2_usize), stats(1, 20)),
+        ];
+
+        Stats::sort_play_count(&mut vec);
+        let keys: Vec<usize> = vec.iter().map(|(k, _)| k.inner()).collect();
+        assert_eq!(keys, [1, 2, 0]);
+
+        Stats::sort_last_played(&mut vec);
+        let keys: Vec<usize> = vec.iter().map(|(k, _)| k.inner()).collect();
+        assert_eq!(keys, [2, 0, 1]);
+    }
+}
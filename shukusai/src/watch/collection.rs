@@ -0,0 +1,154 @@
+//---------------------------------------------------------------------------------------------------- Use
+use crate::ccd::Ccd;
+use crossbeam::channel::{Receiver, RecvTimeoutError, Sender};
+use log::{debug, error, trace, warn};
+use notify::{
+    event::ModifyKind, Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+//---------------------------------------------------------------------------------------------------- Constants
+// How long the filesystem must be quiet before changes are sent.
+//
+// Copying an album in creates a burst of events,
+// this makes sure they end up in a single update.
+const QUIET: Duration = Duration::from_secs(3);
+
+// The maximum amount of time changes are held onto,
+// even if the filesystem is never quiet.
+const MAX_WAIT: Duration = Duration::from_secs(30);
+
+//---------------------------------------------------------------------------------------------------- CollectionWatch
+#[derive(Debug)]
+/// Filesystem watcher for the PATHs a `Collection` was created from
+///
+/// This watches the given directories (recursively) and sends
+/// batches of changed PATHs, which can be given to `Kernel` with
+/// [`crate::kernel::FrontendToKernel::UpdateCollection`].
+///
+/// Each batch contains the files and directories
+/// that were created, modified, renamed or removed.
+///
+/// Changes are "debounced", i.e, a batch is only sent once
+/// the filesystem has been quiet for a few seconds, so copying
+/// in a whole album only results in a single update.
+pub struct CollectionWatch {
+    // Channel to the frontend.
+    to_frontend: Sender<Vec<PathBuf>>,
+    // Channel from `notify`.
+    from_notify: Receiver<Result<Event, notify::Error>>,
+}
+
+impl CollectionWatch {
+    /// Start watching `paths`.
+    ///
+    /// This spawns a thread that sends batches of changed PATHs
+    /// to the returned channel, forever, or until it is dropped.
+    ///
+    /// # Errors
+    /// This errors if the watcher could not be created, or if
+    /// _none_ of the `paths` could be watched (e.g, they don't exist).
+    pub fn spawn(paths: Vec<PathBuf>) -> Result<Receiver<Vec<PathBuf>>, notify::Error> {
+        let (tx, from_notify) = crossbeam::channel::unbounded();
+        let mut watcher = RecommendedWatcher::new(tx, Config::default())?;
+
+        let mut last_err = None;
+        let mut watching = 0;
+        for path in paths.iter() {
+            match watcher.watch(path, RecursiveMode::Recursive) {
+                Ok(_) => {
+                    debug!("CollectionWatch - Watching PATH: {}", path.display());
+                    watching += 1;
+                }
+                Err(e) => {
+                    warn!("CollectionWatch - Failed to watch {}: {e}", path.display());
+                    last_err = Some(e);
+                }
+            }
+        }
+
+        if watching == 0 {
+            return Err(last_err.unwrap_or_else(|| notify::Error::generic("no PATHs given")));
+        }
+
+        let (to_frontend, from_watch) = crossbeam::channel::unbounded();
+        let this = Self {
+            to_frontend,
+            from_notify,
+        };
+
+        std::thread::Builder::new()
+            .name("CollectionWatch".into())
+            // `watcher` must live as long as the thread.
+            .spawn(move || this.main(watcher))?;
+
+        Ok(from_watch)
+    }
+
+    fn main(self, _watcher: RecommendedWatcher) {
+        let mut changed = vec![];
+
+        loop {
+            // Wait for the first change.
+            match self.from_notify.recv() {
+                Ok(event) => self.push(&mut changed, event),
+                Err(_) => break,
+            }
+
+            // Wait for things to calm down.
+            let start = Instant::now();
+            while start.elapsed() < MAX_WAIT {
+                match self.from_notify.recv_timeout(QUIET) {
+                    Ok(event) => self.push(&mut changed, event),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+
+            if changed.is_empty() {
+                continue;
+            }
+
+            changed.sort();
+            changed.dedup();
+            debug!("CollectionWatch - {} changed PATHs", changed.len());
+
+            // The frontend doesn't want updates anymore.
+            if self.to_frontend.send(std::mem::take(&mut changed)).is_err() {
+                break;
+            }
+        }
+
+        debug!("CollectionWatch - exiting");
+    }
+
+    // Add the relevant PATHs of an event.
+    fn push(&self, changed: &mut Vec<PathBuf>, event: Result<Event, notify::Error>) {
+        let event = match event {
+            Ok(e) => e,
+            Err(e) => {
+                error!("CollectionWatch - {e}");
+                return;
+            }
+        };
+
+        match event.kind {
+            EventKind::Create(_)
+            | EventKind::Remove(_)
+            | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Name(_) | ModifyKind::Any) => (),
+            _ => return,
+        }
+
+        trace!("CollectionWatch - {event:?}");
+
+        // Directories and removed PATHs are always relevant,
+        // files are only relevant if they are audio or CUE sheets.
+        changed.extend(event.paths.into_iter().filter(|p| {
+            !p.exists()
+                || p.is_dir()
+                || Ccd::path_is_cue(p)
+                || Ccd::path_is_audio(p.to_path_buf()).is_some()
+        }));
+    }
+}
@@ -3,3 +3,10 @@ pub(crate) use watch::*;
 
 mod msg;
 pub(crate) use msg::*;
+
+#[cfg(not(feature = "gui"))]
+#[cfg(feature = "daemon")]
+mod collection;
+#[cfg(not(feature = "gui"))]
+#[cfg(feature = "daemon")]
+pub use collection::*;